@@ -1,9 +1,10 @@
 use std::sync::Arc;
+use transport::schema::SchemaVersions;
 use transport::{Envelope, Mailbox, MsgRing, SlotPool, SlotPoolConfig, SlotPoolHandle};
 
 use crate::codec::{Codec, PortClass};
 use crate::endpoint::{EndpointHandle, WorkerEndpoint};
-use crate::error::FabricResult;
+use crate::error::{FabricError, FabricResult};
 use crate::layout::EndpointLayout;
 #[cfg(target_arch = "wasm32")]
 use crate::layout::PortRole;
@@ -11,8 +12,6 @@ use crate::port::{make_port_pair_mailbox, make_port_pair_ring};
 #[cfg(target_arch = "wasm32")]
 use crate::port::{shared_from_consumer, shared_from_producer};
 
-const SCHEMA_VER: u8 = transport::schema::SCHEMA_VERSION_V1;
-
 /// Specification for a ring-based transport channel.
 pub struct RingSpec {
     pub capacity_bytes: usize,
@@ -28,6 +27,24 @@ pub struct MailboxSpec {
 /// Specification for a slot pool (frame/audio/bulk data).
 pub struct SlotPoolSpec {
     pub config: SlotPoolConfig,
+    /// Pool already attached to another endpoint. When set it is reused
+    /// instead of allocating a new pool from `config`, so slot spans written
+    /// by one service can be read through another.
+    pub shared: Option<Arc<SlotPoolHandle>>,
+}
+
+impl SlotPoolSpec {
+    /// Reuses `pool`, typically one of another endpoint's `slot_pools()`.
+    pub fn shared(pool: Arc<SlotPoolHandle>) -> Self {
+        let config = pool.with_ref(|pool| SlotPoolConfig {
+            slot_count: pool.slot_count(),
+            slot_size: pool.slot_size(),
+        });
+        Self {
+            config,
+            shared: Some(pool),
+        }
+    }
 }
 
 /// Complete specification for building a service endpoint pair.
@@ -39,21 +56,39 @@ pub struct ServiceSpec<C: Codec> {
     pub replies: RingSpec,
     pub reply_policy: PortClass,
     pub slot_pools: Vec<SlotPoolSpec>,
+    /// Schema versions the worker side advertises; the endpoint speaks the
+    /// highest version shared with the codec.
+    pub peer_versions: SchemaVersions,
 }
 
 /// Builds a bidirectional service endpoint pair from a specification.
 ///
 /// Returns the scheduler-side handle, worker-side handle, and layout metadata.
+/// Fails when the codec and the peer share no schema version.
 pub fn build_service<C: Codec>(
     spec: ServiceSpec<C>,
 ) -> FabricResult<(EndpointHandle<C>, WorkerEndpoint<C>, EndpointLayout)> {
+    let ring_capacities = (
+        spec.lossless.as_ref().map_or(0, |ring| ring.capacity_bytes),
+        spec.replies.capacity_bytes,
+    );
+    let version =
+        spec.codec
+            .versions()
+            .negotiate(spec.peer_versions)
+            .ok_or(FabricError::InvalidConfig(
+                "no schema version in common with peer",
+            ))?;
     #[allow(unused_mut)]
-    let mut layout = EndpointLayout::default();
+    let mut layout = EndpointLayout {
+        version,
+        ..EndpointLayout::default()
+    };
 
     let lossless_pair = if let Some(ring_spec) = spec.lossless {
         let ring = MsgRing::new(
             ring_spec.capacity_bytes,
-            Envelope::new(ring_spec.envelope_tag, SCHEMA_VER),
+            Envelope::new(ring_spec.envelope_tag, version),
         )?;
         let pair = make_port_pair_ring(PortClass::Lossless, ring);
         #[cfg(target_arch = "wasm32")]
@@ -69,7 +104,7 @@ pub fn build_service<C: Codec>(
     let besteffort_pair = if let Some(ring_spec) = spec.besteffort {
         let ring = MsgRing::new(
             ring_spec.capacity_bytes,
-            Envelope::new(ring_spec.envelope_tag, SCHEMA_VER),
+            Envelope::new(ring_spec.envelope_tag, version),
         )?;
         let pair = make_port_pair_ring(PortClass::BestEffort, ring);
         #[cfg(target_arch = "wasm32")]
@@ -85,7 +120,7 @@ pub fn build_service<C: Codec>(
     let coalesce_pair = if let Some(mailbox_spec) = spec.coalesce {
         let mailbox = Mailbox::new(
             mailbox_spec.payload_bytes,
-            Envelope::new(mailbox_spec.envelope_tag, SCHEMA_VER),
+            Envelope::new(mailbox_spec.envelope_tag, version),
         )?;
         let pair = make_port_pair_mailbox(mailbox);
         #[cfg(target_arch = "wasm32")]
@@ -100,7 +135,7 @@ pub fn build_service<C: Codec>(
 
     let replies_ring = MsgRing::new(
         spec.replies.capacity_bytes,
-        Envelope::new(spec.replies.envelope_tag, SCHEMA_VER),
+        Envelope::new(spec.replies.envelope_tag, version),
     )?;
     let replies_pair = make_port_pair_ring(spec.reply_policy, replies_ring);
     #[cfg(target_arch = "wasm32")]
@@ -125,23 +160,32 @@ pub fn build_service<C: Codec>(
         #[cfg(not(target_arch = "wasm32"))]
         let pool_spec = pool_item;
 
-        let pool = SlotPool::new(pool_spec.config)?;
+        let pool = match pool_spec.shared {
+            Some(pool) => pool,
+            None => Arc::new(SlotPoolHandle::new(SlotPool::new(pool_spec.config)?)),
+        };
         #[cfg(target_arch = "wasm32")]
         {
             use crate::layout::PortLayout;
             let role = PortRole::SlotPool(idx);
-            layout.push_port(role, PortLayout::SlotPool(pool.wasm_layout()));
+            let pool_layout = pool.with_ref(|pool| pool.wasm_layout());
+            layout.push_port(role, PortLayout::SlotPool(pool_layout));
         }
-        slot_pools.push(Arc::new(SlotPoolHandle::new(pool)));
+        slot_pools.push(pool);
     }
 
+    let mut codec = spec.codec;
+    codec.attach_slot_pools(&slot_pools);
+    codec.attach_ring_capacities(ring_capacities.0, ring_capacities.1);
+
     let endpoint = EndpointHandle {
         lossless: lossless_pair.as_ref().map(|p| p.producer.clone()),
         besteffort: besteffort_pair.as_ref().map(|p| p.producer.clone()),
         coalesce: coalesce_pair.as_ref().map(|p| p.producer.clone()),
         replies: replies_pair.consumer.clone(),
         slot_pools: slot_pools.clone(),
-        codec: spec.codec.clone(),
+        codec: codec.clone(),
+        version,
     };
 
     let worker_endpoint = WorkerEndpoint {
@@ -150,7 +194,8 @@ pub fn build_service<C: Codec>(
         coalesce: coalesce_pair.as_ref().map(|p| p.consumer.clone()),
         replies: replies_pair.producer.clone(),
         slot_pools,
-        codec: spec.codec,
+        codec,
+        version,
     };
 
     Ok((endpoint, worker_endpoint, layout))
@@ -1,6 +1,8 @@
-use transport::Envelope;
+use std::sync::Arc;
+use transport::schema::{SchemaVersions, SCHEMA_VERSION};
+use transport::{Envelope, SlotPoolHandle};
 
-use crate::error::FabricResult;
+use crate::error::{FabricError, FabricResult};
 
 /// Port class determines which queue type to use for message delivery.
 /// This is purely a transport-level concept with no app-level semantics.
@@ -38,4 +40,47 @@ pub trait Codec: Clone + Send + Sync + 'static {
     fn decode_cmd(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Cmd>;
     fn encode_rep(&self, rep: &Self::Rep) -> FabricResult<Encoded>;
     fn decode_rep(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Rep>;
+
+    /// Hands the codec its endpoint's slot pools once they are allocated, so
+    /// it can stage bulk payloads there instead of inlining them.
+    fn attach_slot_pools(&mut self, _pools: &[Arc<SlotPoolHandle>]) {}
+
+    /// Hands the codec the capacity in bytes of its endpoint's lossless command
+    /// ring and report ring, the most a payload that misses its slot pool could
+    /// still take inline.
+    fn attach_ring_capacities(&mut self, _cmd_bytes: usize, _rep_bytes: usize) {}
+
+    /// Schema versions this codec can encode and decode.
+    ///
+    /// The default claims only the current version, the one `encode_cmd` and
+    /// `encode_rep` are expected to emit; codecs that also speak older versions
+    /// must override this together with `encode_cmd_as` and `encode_rep_as`.
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::new(SCHEMA_VERSION, SCHEMA_VERSION)
+    }
+
+    /// Encodes `cmd` at schema version `ver`, as negotiated with the peer.
+    ///
+    /// The default only succeeds when `ver` is the version `encode_cmd` emits.
+    fn encode_cmd_as(&self, cmd: &Self::Cmd, ver: u8) -> FabricResult<Encoded> {
+        expect_version(self.encode_cmd(cmd)?, ver)
+    }
+
+    /// Encodes `rep` at schema version `ver`, as negotiated with the peer.
+    ///
+    /// The default only succeeds when `ver` is the version `encode_rep` emits.
+    fn encode_rep_as(&self, rep: &Self::Rep, ver: u8) -> FabricResult<Encoded> {
+        expect_version(self.encode_rep(rep)?, ver)
+    }
+}
+
+fn expect_version(encoded: Encoded, ver: u8) -> FabricResult<Encoded> {
+    if encoded.envelope.ver == ver {
+        Ok(encoded)
+    } else {
+        Err(FabricError::codec(format!(
+            "cannot encode schema version {ver} (codec emits {})",
+            encoded.envelope.ver
+        )))
+    }
 }
@@ -16,11 +16,15 @@ pub struct EndpointHandle<C: Codec> {
     pub(crate) replies: ConsumerPort,
     pub(crate) slot_pools: Vec<Arc<SlotPoolHandle>>,
     pub(crate) codec: C,
+    pub(crate) version: u8,
 }
 
 impl<C: Codec> EndpointHandle<C> {
     pub fn submit(&self, cmd: &C::Cmd) -> FabricResult<SubmitOutcome> {
-        let encoded = self.codec.encode_cmd(cmd)?;
+        let encoded = match self.codec.encode_cmd_as(cmd, self.version) {
+            Err(FabricError::WouldBlock) => return Ok(SubmitOutcome::WouldBlock),
+            encoded => encoded?,
+        };
         let port = match encoded.class {
             PortClass::Lossless => self.lossless.as_ref(),
             PortClass::BestEffort => self.besteffort.as_ref(),
@@ -51,6 +55,11 @@ impl<C: Codec> EndpointHandle<C> {
         &self.slot_pools
     }
 
+    /// Returns the schema version negotiated for this endpoint.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
     /// Returns per-port metrics for this endpoint.
     pub fn metrics(&self) -> EndpointMetrics {
         EndpointMetrics {
@@ -71,6 +80,7 @@ pub struct WorkerEndpoint<C: Codec> {
     pub(crate) replies: ProducerPort,
     pub(crate) slot_pools: Vec<Arc<SlotPoolHandle>>,
     pub(crate) codec: C,
+    pub(crate) version: u8,
 }
 
 impl<C: Codec> WorkerEndpoint<C> {
@@ -81,6 +91,7 @@ impl<C: Codec> WorkerEndpoint<C> {
         replies: ProducerPort,
         slot_pools: Vec<Arc<SlotPoolHandle>>,
         codec: C,
+        version: u8,
     ) -> Self {
         Self {
             lossless,
@@ -89,6 +100,7 @@ impl<C: Codec> WorkerEndpoint<C> {
             replies,
             slot_pools,
             codec,
+            version,
         }
     }
 
@@ -113,7 +125,10 @@ impl<C: Codec> WorkerEndpoint<C> {
     }
 
     pub fn publish_report(&self, rep: &C::Rep) -> FabricResult<SubmitOutcome> {
-        let encoded = self.codec.encode_rep(rep)?;
+        let encoded = match self.codec.encode_rep_as(rep, self.version) {
+            Err(FabricError::WouldBlock) => return Ok(SubmitOutcome::WouldBlock),
+            encoded => encoded?,
+        };
         self.replies
             .try_send(encoded.envelope, encoded.payload.as_slice())
     }
@@ -123,6 +138,11 @@ impl<C: Codec> WorkerEndpoint<C> {
         &self.slot_pools
     }
 
+    /// Returns the schema version negotiated for this endpoint.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
     fn drain_port<F>(
         &self,
         max: usize,
@@ -17,6 +17,11 @@ pub enum FabricError {
 
     #[error("unsupported operation: {0}")]
     Unsupported(&'static str),
+
+    /// A bulk payload found its slot pool full and is too large for the ring;
+    /// endpoints report it as `SubmitOutcome::WouldBlock`.
+    #[error("bulk pool full and payload exceeds the ring")]
+    WouldBlock,
 }
 
 impl FabricError {
@@ -26,6 +26,8 @@ pub enum PortLayout {
 #[derive(Clone, Debug, Default, Archive, Deserialize, Serialize)]
 pub struct EndpointLayout {
     pub ports: Vec<(PortRole, PortLayout)>,
+    /// Schema version negotiated for the endpoint when it was built.
+    pub version: u8,
 }
 
 impl EndpointLayout {
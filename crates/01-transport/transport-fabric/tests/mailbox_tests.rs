@@ -10,11 +10,11 @@ use std::sync::{
 use std::thread;
 use std::time::{Duration, Instant};
 
-use transport::schema::SCHEMA_VERSION_V1;
-use transport::Envelope;
+use transport::schema::{SchemaVersions, SCHEMA_VERSION, SCHEMA_VERSION_V1};
+use transport::{Envelope, SlotPoolConfig};
 use transport_fabric::{
     build_service, Codec, Encoded, EndpointHandle, FabricError, FabricResult, MailboxSpec,
-    PortClass, RingSpec, ServiceSpec, SubmitOutcome, WorkerEndpoint, WorkerRuntime,
+    PortClass, RingSpec, ServiceSpec, SlotPoolSpec, SubmitOutcome, WorkerEndpoint, WorkerRuntime,
 };
 
 const CMD_TAG: u8 = 0xE1;
@@ -38,69 +38,137 @@ enum MbxRep {
     Ack(u32),
 }
 
+/// Test codec that speaks schema V1 only and says so.
 #[derive(Clone, Default)]
 struct MbxCodec;
 
+/// Test codec that emits the current schema and keeps the default `versions`.
+#[derive(Clone, Default)]
+struct CurrentCodec;
+
+fn encode_mbx_cmd(cmd: &MbxCmd, ver: u8) -> FabricResult<Encoded> {
+    let (variant, value, class) = match *cmd {
+        MbxCmd::Set(x) => (0u8, x, PortClass::Coalesce),
+        MbxCmd::SetMany(x) => (1, x, PortClass::Coalesce),
+        MbxCmd::Lossless(x) => (2, x, PortClass::Lossless),
+    };
+    let mut payload = Vec::with_capacity(1 + std::mem::size_of::<u32>());
+    payload.push(variant);
+    payload.extend_from_slice(&value.to_le_bytes());
+    Ok(Encoded::new(class, Envelope::new(CMD_TAG, ver), payload))
+}
+
+fn decode_mbx_cmd(envelope: Envelope, payload: &[u8], ver: u8) -> FabricResult<MbxCmd> {
+    if envelope.tag != CMD_TAG || envelope.ver != ver {
+        return Err(FabricError::codec("unexpected command envelope"));
+    }
+    if payload.len() != 1 + std::mem::size_of::<u32>() {
+        return Err(FabricError::codec("invalid command payload length"));
+    }
+    let variant = payload[0];
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&payload[1..]);
+    let value = u32::from_le_bytes(buf);
+    match variant {
+        0 => Ok(MbxCmd::Set(value)),
+        1 => Ok(MbxCmd::SetMany(value)),
+        2 => Ok(MbxCmd::Lossless(value)),
+        _ => Err(FabricError::codec("unknown command variant")),
+    }
+}
+
+fn encode_mbx_rep(rep: &MbxRep, ver: u8) -> FabricResult<Encoded> {
+    let MbxRep::Ack(value) = *rep;
+    let mut payload = Vec::with_capacity(std::mem::size_of::<u32>());
+    payload.extend_from_slice(&value.to_le_bytes());
+    Ok(Encoded::new(
+        PortClass::Lossless,
+        Envelope::new(REP_TAG, ver),
+        payload,
+    ))
+}
+
+fn decode_mbx_rep(envelope: Envelope, payload: &[u8], ver: u8) -> FabricResult<MbxRep> {
+    if envelope.tag != REP_TAG || envelope.ver != ver {
+        return Err(FabricError::codec("unexpected report envelope"));
+    }
+    if payload.len() != std::mem::size_of::<u32>() {
+        return Err(FabricError::codec("invalid report payload length"));
+    }
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(payload);
+    Ok(MbxRep::Ack(u32::from_le_bytes(buf)))
+}
+
 impl Codec for MbxCodec {
     type Cmd = MbxCmd;
     type Rep = MbxRep;
 
     fn encode_cmd(&self, cmd: &Self::Cmd) -> FabricResult<Encoded> {
-        let (variant, value, class) = match *cmd {
-            MbxCmd::Set(x) => (0u8, x, PortClass::Coalesce),
-            MbxCmd::SetMany(x) => (1, x, PortClass::Coalesce),
-            MbxCmd::Lossless(x) => (2, x, PortClass::Lossless),
-        };
-        let mut payload = Vec::with_capacity(1 + std::mem::size_of::<u32>());
-        payload.push(variant);
-        payload.extend_from_slice(&value.to_le_bytes());
-        Ok(Encoded::new(
-            class,
-            Envelope::new(CMD_TAG, SCHEMA_VERSION_V1),
-            payload,
-        ))
+        encode_mbx_cmd(cmd, SCHEMA_VERSION_V1)
     }
 
     fn decode_cmd(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Cmd> {
-        if envelope.tag != CMD_TAG || envelope.ver != SCHEMA_VERSION_V1 {
-            return Err(FabricError::codec("unexpected command envelope"));
-        }
-        if payload.len() != 1 + std::mem::size_of::<u32>() {
-            return Err(FabricError::codec("invalid command payload length"));
-        }
-        let variant = payload[0];
-        let mut buf = [0u8; 4];
-        buf.copy_from_slice(&payload[1..]);
-        let value = u32::from_le_bytes(buf);
-        match variant {
-            0 => Ok(MbxCmd::Set(value)),
-            1 => Ok(MbxCmd::SetMany(value)),
-            2 => Ok(MbxCmd::Lossless(value)),
-            _ => Err(FabricError::codec("unknown command variant")),
-        }
+        decode_mbx_cmd(envelope, payload, SCHEMA_VERSION_V1)
     }
 
     fn encode_rep(&self, rep: &Self::Rep) -> FabricResult<Encoded> {
-        let MbxRep::Ack(value) = *rep;
-        let mut payload = Vec::with_capacity(std::mem::size_of::<u32>());
-        payload.extend_from_slice(&value.to_le_bytes());
-        Ok(Encoded::new(
-            PortClass::Lossless,
-            Envelope::new(REP_TAG, SCHEMA_VERSION_V1),
-            payload,
-        ))
+        encode_mbx_rep(rep, SCHEMA_VERSION_V1)
     }
 
     fn decode_rep(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Rep> {
-        if envelope.tag != REP_TAG || envelope.ver != SCHEMA_VERSION_V1 {
-            return Err(FabricError::codec("unexpected report envelope"));
-        }
-        if payload.len() != std::mem::size_of::<u32>() {
-            return Err(FabricError::codec("invalid report payload length"));
-        }
-        let mut buf = [0u8; 4];
-        buf.copy_from_slice(payload);
-        Ok(MbxRep::Ack(u32::from_le_bytes(buf)))
+        decode_mbx_rep(envelope, payload, SCHEMA_VERSION_V1)
+    }
+
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::new(SCHEMA_VERSION_V1, SCHEMA_VERSION_V1)
+    }
+}
+
+impl Codec for CurrentCodec {
+    type Cmd = MbxCmd;
+    type Rep = MbxRep;
+
+    fn encode_cmd(&self, cmd: &Self::Cmd) -> FabricResult<Encoded> {
+        encode_mbx_cmd(cmd, SCHEMA_VERSION)
+    }
+
+    fn decode_cmd(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Cmd> {
+        decode_mbx_cmd(envelope, payload, SCHEMA_VERSION)
+    }
+
+    fn encode_rep(&self, rep: &Self::Rep) -> FabricResult<Encoded> {
+        encode_mbx_rep(rep, SCHEMA_VERSION)
+    }
+
+    fn decode_rep(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Rep> {
+        decode_mbx_rep(envelope, payload, SCHEMA_VERSION)
+    }
+}
+
+fn mbx_spec(peer_versions: SchemaVersions) -> ServiceSpec<MbxCodec> {
+    spec_with(MbxCodec, peer_versions)
+}
+
+fn spec_with<C: Codec>(codec: C, peer_versions: SchemaVersions) -> ServiceSpec<C> {
+    ServiceSpec {
+        codec,
+        lossless: Some(RingSpec {
+            capacity_bytes: 1024,
+            envelope_tag: CMD_TAG,
+        }),
+        besteffort: None,
+        coalesce: Some(MailboxSpec {
+            payload_bytes: 64,
+            envelope_tag: CMD_TAG,
+        }),
+        replies: RingSpec {
+            capacity_bytes: 1024,
+            envelope_tag: REP_TAG,
+        },
+        reply_policy: PortClass::Lossless,
+        slot_pools: Vec::new(),
+        peer_versions,
     }
 }
 
@@ -112,24 +180,7 @@ struct Harness {
 
 impl Harness {
     fn new() -> Self {
-        let spec = ServiceSpec {
-            codec: MbxCodec,
-            lossless: Some(RingSpec {
-                capacity_bytes: 1024,
-                envelope_tag: CMD_TAG,
-            }),
-            besteffort: None,
-            coalesce: Some(MailboxSpec {
-                payload_bytes: 64,
-                envelope_tag: CMD_TAG,
-            }),
-            replies: RingSpec {
-                capacity_bytes: 1024,
-                envelope_tag: REP_TAG,
-            },
-            reply_policy: PortClass::Lossless,
-            slot_pools: Vec::new(),
-        };
+        let spec = mbx_spec(SchemaVersions::CURRENT);
         let (handle, worker_endpoint, _layout) =
             build_service(spec).expect("build_service succeeded");
         let drain_log = Arc::new(Mutex::new(Vec::new()));
@@ -346,6 +397,77 @@ fn mailbox_visibility_and_ordering() {
     assert!(reps.is_empty());
 }
 
+#[test]
+fn build_negotiates_highest_common_version() {
+    let (handle, worker, layout) =
+        build_service(mbx_spec(SchemaVersions::CURRENT)).expect("build_service succeeded");
+    assert_eq!(handle.version(), SCHEMA_VERSION_V1);
+    assert_eq!(worker.version(), SCHEMA_VERSION_V1);
+    assert_eq!(layout.version, SCHEMA_VERSION_V1);
+
+    let newer_peer = SchemaVersions::new(SCHEMA_VERSION_V1 + 1, SCHEMA_VERSION_V1 + 2);
+    assert!(matches!(
+        build_service(mbx_spec(newer_peer)),
+        Err(FabricError::InvalidConfig(_))
+    ));
+}
+
+/// A codec that keeps the default `versions` only claims the version it
+/// encodes, so a V1-only peer is refused when the service is built instead of
+/// failing on the first mailbox write.
+#[test]
+fn default_codec_versions_refuse_v1_only_peer() {
+    let v1_only = SchemaVersions::new(SCHEMA_VERSION_V1, SCHEMA_VERSION_V1);
+    assert!(matches!(
+        build_service(spec_with(CurrentCodec, v1_only)),
+        Err(FabricError::InvalidConfig(_))
+    ));
+
+    let (handle, worker, layout) = build_service(spec_with(CurrentCodec, SchemaVersions::CURRENT))
+        .expect("build_service succeeded");
+    assert_eq!(layout.version, SCHEMA_VERSION);
+    let outcome = handle.submit(&MbxCmd::Set(5)).expect("submit");
+    assert!(matches!(outcome, SubmitOutcome::Accepted));
+    let mut drained = Vec::new();
+    worker
+        .drain_commands(8, |cmd| drained.push(*cmd))
+        .expect("drain commands");
+    assert_eq!(drained, vec![MbxCmd::Set(5)]);
+}
+
+#[test]
+fn shared_slot_pool_is_attached_to_both_endpoints() {
+    let mut producer = mbx_spec(SchemaVersions::CURRENT);
+    producer.slot_pools.push(SlotPoolSpec {
+        config: SlotPoolConfig {
+            slot_count: 2,
+            slot_size: 1024,
+        },
+        shared: None,
+    });
+    let (producer, _, _) = build_service(producer).expect("build producer");
+    let pool = Arc::clone(&producer.slot_pools()[0]);
+
+    let mut consumer = mbx_spec(SchemaVersions::CURRENT);
+    consumer
+        .slot_pools
+        .push(SlotPoolSpec::shared(Arc::clone(&pool)));
+    assert_eq!(consumer.slot_pools[0].config.slot_size, 1024);
+    let (consumer, worker, _) = build_service(consumer).expect("build consumer");
+    assert!(Arc::ptr_eq(&consumer.slot_pools()[0], &pool));
+    assert!(Arc::ptr_eq(&worker.slot_pools()[0], &pool));
+}
+
+#[test]
+fn schema_versions_negotiate_overlap() {
+    let current = SchemaVersions::new(1, 2);
+    assert_eq!(current.negotiate(SchemaVersions::new(1, 1)), Some(1));
+    assert_eq!(current.negotiate(SchemaVersions::new(2, 3)), Some(2));
+    assert_eq!(current.negotiate(current), Some(2));
+    assert_eq!(current.negotiate(SchemaVersions::new(3, 4)), None);
+    assert!(current.contains(1) && current.contains(2) && !current.contains(3));
+}
+
 #[cfg(feature = "proptest")]
 mod prop {
     use super::*;
@@ -7,11 +7,13 @@
 //! This module exposes the foundational pieces described in the transport spec:
 //! * [`SharedRegion`] – contiguous, aligned memory slices which back the rings.
 //! * [`MsgRing`] – single-producer/single-consumer command/report queue encoded with rkyv.
+//! * [`MpscRing`] – multi-producer variant of [`MsgRing`] for fan-in from several workers.
 //! * [`ProducerGrant`] / [`Record`] – ergonomic producer/consumer views that avoid callbacks.
 //! * [`TransportError`] – lightweight error surface for allocation/config failures.
 
 mod error;
 mod mailbox;
+mod mpsc_ring;
 mod msg_ring;
 mod region;
 pub mod schema;
@@ -21,6 +23,7 @@ pub mod wasm;
 
 pub use error::{TransportError, TransportResult};
 pub use mailbox::{Mailbox, MailboxRecord, MailboxSend};
+pub use mpsc_ring::{MpscConsumer, MpscProducer, MpscRing};
 pub use msg_ring::{Envelope, MsgRing, ProducerGrant, Record};
 pub use region::{SharedRegion, Uninit, Zeroed};
 pub use schema::*;
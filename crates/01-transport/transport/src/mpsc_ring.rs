@@ -0,0 +1,455 @@
+//! Multi-producer/single-consumer message ring.
+//!
+//! Shares the [`MsgRing`] header, record format and wasm layout, so a consumer
+//! attached with [`MsgRing::from_wasm_layout`] drains it unchanged. The header
+//! word after the tail holds a reservation cursor: producers claim space by
+//! advancing it with a CAS, write their record, then wait until every earlier
+//! reservation is committed before publishing their own. Each producer only
+//! waits for records reserved ahead of it, which take one copy to finish; a
+//! reservation dropped without commit is published as padding instead.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::msg_ring::{Envelope, MsgRing, ProducerGrant, Record};
+use crate::TransportResult;
+
+/// Message ring that several producers, e.g. kernel workers, can feed at once.
+///
+/// [`MpscRing::split`] hands out a cloneable [`MpscProducer`] for the workers
+/// and the one [`MpscConsumer`] that drains the ring.
+pub struct MpscRing {
+    ring: MsgRing,
+}
+
+// SAFETY: `MsgRing` is not `Sync` only because of the consumer's peek state. Through a shared
+// `MpscRing` producers reserve and publish via the header atomics and write into the ranges they
+// reserved; the peek state is reached only through the single, non-`Sync` `MpscConsumer`.
+unsafe impl Send for MpscRing {}
+// SAFETY: See above.
+unsafe impl Sync for MpscRing {}
+
+impl MpscRing {
+    /// Creates a new ring with `capacity_bytes` usable for payload storage.
+    pub fn new(capacity_bytes: usize, default_envelope: Envelope) -> TransportResult<Self> {
+        Ok(Self {
+            ring: MsgRing::new(capacity_bytes, default_envelope)?,
+        })
+    }
+
+    /// Returns the maximum payload capacity of the ring.
+    pub fn capacity_bytes(&self) -> usize {
+        self.ring.capacity_bytes()
+    }
+
+    /// Splits the ring into its producer and consumer handles.
+    pub fn split(self) -> (MpscProducer, MpscConsumer) {
+        let ring = Arc::new(self);
+        (
+            MpscProducer {
+                ring: Arc::clone(&ring),
+            },
+            MpscConsumer {
+                ring,
+                _not_sync: PhantomData,
+            },
+        )
+    }
+}
+
+/// Producer side of an [`MpscRing`]; clone it once per producer.
+#[derive(Clone)]
+pub struct MpscProducer {
+    ring: Arc<MpscRing>,
+}
+
+impl MpscProducer {
+    #[cfg(target_arch = "wasm32")]
+    /// Unsafely attaches a producer to a ring that already lives in shared linear memory.
+    ///
+    /// Each producer worker attaches its own view; the same safety requirements as
+    /// [`MsgRing::from_wasm_layout`] apply.
+    pub unsafe fn from_wasm_layout(
+        layout: impl crate::wasm::IntoNativeLayout<Native = crate::wasm::MsgRingLayout>,
+        default_envelope: Envelope,
+    ) -> Self {
+        Self {
+            ring: Arc::new(MpscRing {
+                ring: MsgRing::from_wasm_layout(layout, default_envelope),
+            }),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    /// Describes the header/data regions backing this ring inside shared linear memory.
+    pub fn wasm_layout(&self) -> crate::wasm::MsgRingLayout {
+        self.ring.ring.wasm_layout()
+    }
+
+    /// Returns the maximum payload capacity of the ring.
+    pub fn capacity_bytes(&self) -> usize {
+        self.ring.capacity_bytes()
+    }
+
+    /// Attempts to reserve space for `need` archived bytes.
+    ///
+    /// Producers may reserve concurrently and publish in reservation order. A
+    /// grant dropped without commit is published as padding the consumer skips.
+    pub fn try_reserve(&self, need: usize) -> Option<ProducerGrant<'_>> {
+        self.try_reserve_with(self.ring.ring.default_envelope(), need)
+    }
+
+    /// Variant of `try_reserve` allowing the caller to override the envelope.
+    pub fn try_reserve_with(&self, envelope: Envelope, need: usize) -> Option<ProducerGrant<'_>> {
+        self.ring.ring.try_reserve_shared(envelope, need)
+    }
+
+    /// Parks the caller until the consumer tail advances, indicating free space.
+    pub fn wait_for_space(&self) {
+        self.ring.ring.wait_for_space();
+    }
+}
+
+/// Consumer side of an [`MpscRing`]; there is exactly one per ring.
+pub struct MpscConsumer {
+    ring: Arc<MpscRing>,
+    /// Peeks update per-consumer state, so the handle must not be shared.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl MpscConsumer {
+    /// Returns the maximum payload capacity of the ring.
+    pub fn capacity_bytes(&self) -> usize {
+        self.ring.capacity_bytes()
+    }
+
+    /// Returns the next committed payload without advancing the consumer tail.
+    pub fn consumer_peek(&self) -> Option<Record<'_>> {
+        self.ring.ring.consumer_peek()
+    }
+
+    /// Advances the consumer tail past the record returned by the last `consumer_peek`.
+    pub fn consumer_pop_advance(&mut self) {
+        self.ring.ring.consumer_advance();
+    }
+
+    /// Returns the envelope captured during the most recent peek.
+    pub fn consumer_last_envelope(&self) -> Option<Envelope> {
+        self.ring.ring.consumer_last_envelope()
+    }
+}
+
+#[cfg(all(test, not(feature = "loom")))]
+mod tests {
+    //! Unit coverage for the multi-producer ring.
+    use super::*;
+    use std::collections::HashMap;
+    use std::thread;
+
+    fn ring(capacity: usize) -> (MpscProducer, MpscConsumer) {
+        MpscRing::new(capacity, Envelope::new(0x11, 2))
+            .expect("create ring")
+            .split()
+    }
+
+    fn push(producer: &MpscProducer, payload: &[u8]) -> bool {
+        match producer.try_reserve(payload.len()) {
+            Some(mut grant) => {
+                grant.payload().copy_from_slice(payload);
+                grant.commit(payload.len());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pop(consumer: &mut MpscConsumer) -> Option<Vec<u8>> {
+        let payload = consumer.consumer_peek()?.payload.to_vec();
+        consumer.consumer_pop_advance();
+        Some(payload)
+    }
+
+    /// Producers can be cloned across workers; the consumer can only move.
+    #[test]
+    fn handles_are_thread_safe() {
+        fn producer<P: Send + Sync + Clone>() {}
+        fn consumer<C: Send>() {}
+        producer::<MpscProducer>();
+        consumer::<MpscConsumer>();
+    }
+
+    /// Smoke test: records and envelopes round-trip exactly as through `MsgRing`.
+    #[test]
+    fn single_record_round_trip() {
+        let (producer, mut consumer) = ring(256);
+        let mut grant = producer
+            .try_reserve_with(Envelope::new(0x12, 2), 5)
+            .unwrap();
+        grant.payload().copy_from_slice(b"hello");
+        grant.commit(5);
+
+        let record = consumer.consumer_peek().expect("record");
+        assert_eq!(record.payload, b"hello");
+        assert_eq!(record.envelope, Envelope::new(0x12, 2));
+        consumer.consumer_pop_advance();
+        assert!(consumer.consumer_peek().is_none());
+    }
+
+    /// Wrap and backpressure follow the single-producer rules.
+    #[test]
+    fn wraps_and_reports_full() {
+        let (producer, mut consumer) = ring(128);
+        while push(&producer, &[0xAB; 40]) {}
+        assert!(producer.try_reserve(40).is_none());
+
+        assert_eq!(pop(&mut consumer), Some(vec![0xAB; 40]));
+        assert_eq!(pop(&mut consumer), Some(vec![0xAB; 40]));
+        assert!(
+            push(&producer, &[0xCD; 40]),
+            "freed space is reused after wrap"
+        );
+        assert_eq!(pop(&mut consumer), Some(vec![0xCD; 40]));
+        assert_eq!(pop(&mut consumer), None);
+    }
+
+    /// A later reservation stays invisible until every earlier one commits.
+    #[test]
+    fn commits_publish_in_reservation_order() {
+        let (producer, mut consumer) = ring(256);
+        let mut first = producer.try_reserve(1).expect("reserve first");
+        first.payload()[0] = 1;
+
+        thread::scope(|scope| {
+            let second = scope.spawn(|| push(&producer, &[2]));
+            thread::sleep(std::time::Duration::from_millis(20));
+            assert!(consumer.consumer_peek().is_none());
+            first.commit(1);
+            assert!(second.join().unwrap());
+        });
+
+        assert_eq!(pop(&mut consumer), Some(vec![1]));
+        assert_eq!(pop(&mut consumer), Some(vec![2]));
+    }
+
+    /// A grant dropped without commit becomes padding instead of wedging later producers.
+    #[test]
+    fn dropped_grant_is_skipped() {
+        let (producer, mut consumer) = ring(128);
+        let abandoned = producer.try_reserve(24).expect("reserve abandoned");
+
+        thread::scope(|scope| {
+            let later = scope.spawn(|| push(&producer, &[7; 16]));
+            thread::sleep(std::time::Duration::from_millis(20));
+            drop(abandoned);
+            assert!(later.join().unwrap());
+        });
+        assert_eq!(pop(&mut consumer), Some(vec![7; 16]));
+        assert_eq!(pop(&mut consumer), None);
+
+        // The skipped space is reclaimed, including across a wrap.
+        for round in 0..8u8 {
+            drop(producer.try_reserve(24).expect("reserve padding"));
+            assert!(push(&producer, &[round; 40]));
+            assert_eq!(pop(&mut consumer), Some(vec![round; 40]));
+        }
+        assert_eq!(pop(&mut consumer), None);
+    }
+
+    /// Concurrent producers: every record arrives intact and in per-producer order.
+    #[test]
+    fn concurrent_producers_fan_in() {
+        const PRODUCERS: u8 = 4;
+        const RECORDS: u32 = 5_000;
+        let (producer, mut consumer) = ring(1024);
+
+        thread::scope(|scope| {
+            for id in 0..PRODUCERS {
+                let producer = producer.clone();
+                scope.spawn(move || {
+                    for seq in 0..RECORDS {
+                        let len = 5 + (seq as usize * 7 + id as usize) % 60;
+                        let mut payload = vec![id; len];
+                        payload[1..5].copy_from_slice(&seq.to_le_bytes());
+                        while !push(&producer, &payload) {
+                            thread::yield_now();
+                        }
+                    }
+                });
+            }
+
+            let mut next = HashMap::new();
+            let mut received = 0;
+            while received < PRODUCERS as u32 * RECORDS {
+                let Some(payload) = pop(&mut consumer) else {
+                    thread::yield_now();
+                    continue;
+                };
+                let id = payload[0];
+                let seq = u32::from_le_bytes(payload[1..5].try_into().unwrap());
+                let expected = next.entry(id).or_insert(0u32);
+                assert_eq!(seq, *expected, "producer {id} out of order");
+                assert!(payload[5..].iter().all(|&byte| byte == id));
+                *expected += 1;
+                received += 1;
+            }
+        });
+        assert!(consumer.consumer_peek().is_none());
+    }
+}
+
+#[cfg(all(test, feature = "loom"))]
+mod loom_tests {
+    use super::*;
+    use loom::thread;
+
+    fn ring(capacity: usize) -> (MpscProducer, MpscConsumer) {
+        MpscRing::new(capacity, Envelope::new(0xAB, 2))
+            .expect("create mpsc ring")
+            .split()
+    }
+
+    fn produce(producer: &MpscProducer, payload: &[u8]) {
+        loop {
+            if let Some(mut grant) = producer.try_reserve(payload.len()) {
+                grant.payload().copy_from_slice(payload);
+                grant.commit(payload.len());
+                return;
+            }
+            thread::yield_now();
+        }
+    }
+
+    fn consume(consumer: &mut MpscConsumer) -> Vec<u8> {
+        loop {
+            if let Some(record) = consumer.consumer_peek() {
+                let payload = record.payload.to_vec();
+                consumer.consumer_pop_advance();
+                return payload;
+            }
+            thread::yield_now();
+        }
+    }
+
+    fn drain(consumer: &mut MpscConsumer) -> Vec<Vec<u8>> {
+        std::iter::from_fn(|| {
+            let payload = consumer.consumer_peek()?.payload.to_vec();
+            consumer.consumer_pop_advance();
+            Some(payload)
+        })
+        .collect()
+    }
+
+    // Loom does not schedule fairly, so a model with two threads spinning at once (a producer
+    // waiting for its commit turn plus a polling consumer) can starve the third forever. The
+    // producer races are therefore drained after the join.
+
+    /// Loom: two producers racing for reservations never lose or tear a record.
+    #[test]
+    #[ignore]
+    fn slow_loom_mpsc_ring_two_producers() {
+        loom::model(|| {
+            let (producer, mut consumer) = ring(128);
+            let producers: Vec<_> = [1u8, 2]
+                .into_iter()
+                .map(|id| {
+                    let producer = producer.clone();
+                    thread::spawn(move || {
+                        for seq in 0u8..2 {
+                            produce(&producer, &[id, seq, id]);
+                        }
+                    })
+                })
+                .collect();
+            for producer in producers {
+                producer.join().unwrap();
+            }
+
+            let mut next = [0u8; 3];
+            for payload in drain(&mut consumer) {
+                let id = payload[0] as usize;
+                assert_eq!(payload[2], payload[0]);
+                assert_eq!(payload[1], next[id], "producer {id} out of order");
+                next[id] += 1;
+            }
+            assert_eq!(next, [0, 2, 2]);
+        });
+    }
+
+    /// Loom: a wrapping reservation racing a regular one keeps the sentinel consistent.
+    #[test]
+    #[ignore]
+    fn slow_loom_mpsc_ring_wrap_under_contention() {
+        loom::model(|| {
+            let (producer, mut consumer) = ring(64);
+            produce(&producer, &[0; 24]);
+            assert_eq!(consume(&mut consumer).len(), 24);
+
+            let producers: Vec<_> = [16usize, 12]
+                .into_iter()
+                .map(|len| {
+                    let producer = producer.clone();
+                    thread::spawn(move || produce(&producer, &vec![len as u8; len]))
+                })
+                .collect();
+            for producer in producers {
+                producer.join().unwrap();
+            }
+
+            let mut lens: Vec<_> = drain(&mut consumer)
+                .into_iter()
+                .map(|payload| {
+                    assert!(payload.iter().all(|&byte| byte as usize == payload.len()));
+                    payload.len()
+                })
+                .collect();
+            lens.sort_unstable();
+            assert_eq!(lens, vec![12, 16]);
+        });
+    }
+
+    /// Loom: a grant dropped without commit is skipped as padding and its space reclaimed.
+    #[test]
+    #[ignore]
+    fn slow_loom_mpsc_ring_abandoned_grant() {
+        loom::model(|| {
+            let (producer, mut consumer) = ring(64);
+            let abandoning = producer.clone();
+            let abandoner = thread::spawn(move || loop {
+                if let Some(grant) = abandoning.try_reserve(16) {
+                    drop(grant);
+                    return;
+                }
+                thread::yield_now();
+            });
+            let committing = producer.clone();
+            let committer = thread::spawn(move || produce(&committing, &[7; 12]));
+            abandoner.join().unwrap();
+            committer.join().unwrap();
+
+            assert_eq!(drain(&mut consumer), vec![vec![7; 12]]);
+            // The next record wraps past the skipped space.
+            produce(&producer, &[9; 16]);
+            assert_eq!(consume(&mut consumer), vec![9; 16]);
+        });
+    }
+
+    /// Loom: a shared-path producer and a polling consumer hand records over in order.
+    #[test]
+    #[ignore]
+    fn slow_loom_mpsc_ring_producer_and_consumer() {
+        loom::model(|| {
+            let (producer, mut consumer) = ring(64);
+            let producer = thread::spawn(move || {
+                for len in [16usize, 20, 12] {
+                    produce(&producer, &vec![len as u8; len]);
+                }
+            });
+
+            for len in [16usize, 20, 12] {
+                assert_eq!(consume(&mut consumer), vec![len as u8; len]);
+            }
+            producer.join().unwrap();
+        });
+    }
+}
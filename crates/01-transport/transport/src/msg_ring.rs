@@ -10,6 +10,7 @@
 //!                          [u32 total_len][u8 tag][u8 ver][u16 flags]
 //!                          [rkyv archived payload ...][pad → 8 bytes]
 //!                          Sentinel (wrap): total_len == 0xFFFF_FFFF
+//!                          Padding: [u32 0xFFFF_FFFE][u32 record_len]
 //! ```
 //!
 //! Producers reserve space via [`ProducerGrant`], write archived payloads
@@ -30,6 +31,9 @@ const ALIGN: usize = 8;
 const ENVELOPE_LEN: usize = 8;
 const SENTINEL: u32 = u32::MAX;
 const SENTINEL_BYTES: usize = 4;
+/// Marks a reservation abandoned by a shared producer; the following word
+/// holds the record length the consumer skips.
+const PADDING: u32 = u32::MAX - 1;
 const HEADER_SIZE: usize = size_of::<MsgRingHeader>();
 const MIN_CAPACITY: usize = 64;
 
@@ -41,9 +45,12 @@ const MSG_RING_MAGIC: u64 = 0;
 #[repr(C, align(8))]
 struct MsgRingHeader {
     capacity_bytes: u32,
+    /// End of the last committed record.
     head_bytes: AtomicU32,
     tail_bytes: AtomicU32,
-    flags_or_pad: u32,
+    /// End of the last reserved record; only advanced when producers share the
+    /// ring (see [`crate::MpscRing`]), otherwise it stays zero.
+    reserve_bytes: AtomicU32,
     magic: u64,
     reserved: u64,
 }
@@ -54,7 +61,7 @@ impl MsgRingHeader {
             capacity_bytes,
             head_bytes: AtomicU32::new(0),
             tail_bytes: AtomicU32::new(0),
-            flags_or_pad: 0,
+            reserve_bytes: AtomicU32::new(0),
             magic: MSG_RING_MAGIC,
             reserved: 0,
         }
@@ -100,11 +107,14 @@ pub struct Record<'a> {
 
 /// Reservation object handed to producers once capacity has been secured.
 pub struct ProducerGrant<'a> {
-    ring: &'a mut MsgRing,
+    ring: &'a MsgRing,
     offset: usize,
     payload_capacity: usize,
     record_len: usize,
     new_head: usize,
+    /// Reservation cursor the grant started from when producers share the
+    /// ring; the commit waits until every earlier reservation is published.
+    shared_from: Option<usize>,
     envelope: Envelope,
     committed: bool,
 }
@@ -113,8 +123,12 @@ impl<'a> ProducerGrant<'a> {
     /// Returns the writable slice reserved for the payload.
     pub fn payload(&mut self) -> &mut [u8] {
         let start = self.offset + ENVELOPE_LEN;
-        let end = start + self.payload_capacity;
-        &mut self.ring.data_slice_mut()[start..end]
+        // SAFETY: `start..start + payload_capacity` lies inside the data region and was reserved
+        // for this grant alone, so neither the consumer nor other producers touch it until commit;
+        // `&mut self` keeps the returned slice unique.
+        unsafe {
+            std::slice::from_raw_parts_mut(self.ring.data_ptr().add(start), self.payload_capacity)
+        }
     }
 
     /// Maximum number of bytes that can be written into this grant.
@@ -143,6 +157,7 @@ impl<'a> ProducerGrant<'a> {
             written,
             self.record_len,
             self.new_head,
+            self.shared_from,
         );
         self.committed = true;
         written
@@ -151,10 +166,21 @@ impl<'a> ProducerGrant<'a> {
 
 impl Drop for ProducerGrant<'_> {
     fn drop(&mut self) {
-        debug_assert!(
-            self.committed,
-            "ProducerGrant dropped without commit; ring state would be inconsistent"
-        );
+        if self.committed {
+            return;
+        }
+        match self.shared_from {
+            // Later producers wait for this reservation, so publish it as padding the consumer
+            // skips rather than leave a hole that wedges the ring.
+            Some(from) => {
+                self.ring
+                    .abandon_shared(self.offset, self.record_len, self.new_head, from)
+            }
+            None => debug_assert!(
+                false,
+                "ProducerGrant dropped without commit; ring state would be inconsistent"
+            ),
+        }
     }
 }
 
@@ -235,6 +261,10 @@ impl MsgRing {
         self.capacity as usize
     }
 
+    pub(crate) fn default_envelope(&self) -> Envelope {
+        self.default_envelope
+    }
+
     #[cfg(target_arch = "wasm32")]
     /// Describes the header/data regions backing this ring inside shared linear memory.
     pub fn wasm_layout(&self) -> crate::wasm::MsgRingLayout {
@@ -283,7 +313,10 @@ impl MsgRing {
         let head = self.load_head_relaxed();
         let tail = self.load_tail_acquire();
 
-        let (write_offset, new_head) = self.reserve_offset(head, tail, record_len, capacity)?;
+        let (write_offset, new_head) = reserve_offset(head, tail, record_len, capacity)?;
+        if write_offset != head {
+            self.emit_sentinel(head);
+        }
 
         Some(ProducerGrant {
             ring: self,
@@ -291,11 +324,62 @@ impl MsgRing {
             payload_capacity: need,
             record_len,
             new_head,
+            shared_from: None,
             envelope,
             committed: false,
         })
     }
 
+    /// Reserves space for one of several producers sharing the ring.
+    ///
+    /// Producers claim space by advancing the reservation cursor with a CAS and
+    /// publish in reservation order, so the consumer side is unchanged.
+    pub(crate) fn try_reserve_shared(
+        &self,
+        envelope: Envelope,
+        need: usize,
+    ) -> Option<ProducerGrant<'_>> {
+        let total_len = ENVELOPE_LEN.saturating_add(need);
+        let record_len = align_up(total_len, ALIGN);
+        let capacity = self.capacity_bytes();
+        if record_len >= capacity {
+            return None;
+        }
+
+        let header = self.header();
+        let mut head = header.reserve_bytes.load(Ordering::Relaxed) as usize;
+        loop {
+            let tail = self.load_tail_acquire();
+            let (write_offset, new_head) = reserve_offset(head, tail, record_len, capacity)?;
+            match header.reserve_bytes.compare_exchange_weak(
+                head as u32,
+                new_head as u32,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    if write_offset != head {
+                        self.emit_sentinel(head);
+                    }
+                    return Some(ProducerGrant {
+                        ring: self,
+                        offset: write_offset,
+                        payload_capacity: need,
+                        record_len,
+                        new_head,
+                        shared_from: Some(head),
+                        envelope,
+                        committed: false,
+                    });
+                }
+                Err(current) => {
+                    head = current as usize;
+                    spin_wait();
+                }
+            }
+        }
+    }
+
     /// Returns the next payload without advancing the consumer tail.
     ///
     /// The peek loop performs four steps:
@@ -335,6 +419,21 @@ impl MsgRing {
                 continue;
             }
 
+            if total_len == PADDING {
+                let record_len = self
+                    .read_u32_at(data, tail + SENTINEL_BYTES)
+                    .unwrap_or_else(|| panic!("corrupt padding at {tail}"))
+                    as usize;
+                tail += record_len;
+                if tail >= capacity {
+                    tail -= capacity;
+                }
+                // SAFETY: Skipping the padding only touches the atomic tail inside the header.
+                unsafe { (*header).tail_bytes.store(tail as u32, Ordering::Release) };
+                self.notify_tail();
+                continue;
+            }
+
             let total_len = total_len as usize;
             if total_len < ENVELOPE_LEN {
                 panic!("invalid record length {total_len} (< envelope)");
@@ -363,6 +462,13 @@ impl MsgRing {
 
     /// Advances the consumer tail past the record returned by the last `consumer_peek`.
     pub fn consumer_pop_advance(&mut self) {
+        self.consumer_advance();
+    }
+
+    /// Shared-reference form of [`Self::consumer_pop_advance`] for consumer
+    /// handles that share the ring with producers; callers must be the only
+    /// consumer.
+    pub(crate) fn consumer_advance(&self) {
         let header = self.header_ptr();
         // SAFETY: The header pointer originates from `self.region`; atomic loads are safe here.
         let tail = unsafe { (*header).tail_bytes.load(Ordering::Relaxed) as usize };
@@ -414,6 +520,12 @@ impl MsgRing {
         self.region.as_ptr() as *const MsgRingHeader
     }
 
+    fn header(&self) -> &MsgRingHeader {
+        // SAFETY: The header was initialised when the ring was created (or by the host for wasm
+        // views) and lives as long as `self.region`.
+        unsafe { &*self.header_ptr() }
+    }
+
     fn notify_head(&self) {
         // SAFETY: Header pointer is derived from the owned shared region; forming an immutable
         // reference is safe for the lifetime of `self`.
@@ -436,38 +548,61 @@ impl MsgRing {
         unsafe { std::slice::from_raw_parts(ptr, self.capacity_bytes()) }
     }
 
-    fn data_slice_mut(&mut self) -> &mut [u8] {
-        // SAFETY: Allocation is header + data; the offset respects bounds and alignment, and
-        // `&mut self` guarantees exclusive access.
-        let ptr = unsafe { self.region.as_mut_ptr().add(HEADER_SIZE) };
-        // SAFETY: The resulting slice spans only the data portion; the SPSC contract prevents any
-        // concurrent `&mut` aliases.
-        unsafe { std::slice::from_raw_parts_mut(ptr, self.capacity_bytes()) }
+    /// Base pointer of the data region. Producers write only into the ranges
+    /// they reserved, which nothing else reads or writes until committed.
+    fn data_ptr(&self) -> *mut u8 {
+        // SAFETY: Allocation is header + data, so offsetting by the header size stays in bounds.
+        unsafe { self.region.as_ptr().add(HEADER_SIZE) as *mut u8 }
     }
 
     fn finish_producer(
-        &mut self,
+        &self,
         offset: usize,
         envelope: Envelope,
         payload_len: usize,
         record_len: usize,
         new_head: usize,
+        shared_from: Option<usize>,
     ) {
         self.write_envelope(offset, envelope, payload_len, record_len);
+        self.publish(new_head, shared_from);
+    }
+
+    /// Publishes an abandoned shared reservation as a padding record.
+    fn abandon_shared(&self, offset: usize, record_len: usize, new_head: usize, from: usize) {
+        // SAFETY: `offset..offset + record_len` is the abandoned reservation, which no other
+        // party accesses until the head moves past it; every record spans at least 8 bytes.
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(self.data_ptr().add(offset), record_len) };
+        data[..SENTINEL_BYTES].copy_from_slice(&PADDING.to_le_bytes());
+        data[SENTINEL_BYTES..ENVELOPE_LEN].copy_from_slice(&(record_len as u32).to_le_bytes());
+        self.publish(new_head, Some(from));
+    }
+
+    fn publish(&self, new_head: usize, shared_from: Option<usize>) {
+        if let Some(from) = shared_from {
+            // Earlier reservations publish first so the committed head never skips a record.
+            while self.header().head_bytes.load(Ordering::Acquire) as usize != from {
+                spin_wait();
+            }
+        }
         self.store_head_release(new_head as u32);
         self.notify_head();
     }
 
     fn write_envelope(
-        &mut self,
+        &self,
         offset: usize,
         envelope: Envelope,
         payload_len: usize,
         record_len: usize,
     ) {
         let total_len = (ENVELOPE_LEN + payload_len) as u32;
-        let data = self.data_slice_mut();
-        let mut cursor = offset;
+        // SAFETY: `offset..offset + record_len` is the range reserved for the committing grant,
+        // which no other party accesses until the head moves past it.
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(self.data_ptr().add(offset), record_len) };
+        let mut cursor = 0;
 
         data[cursor..cursor + 4].copy_from_slice(&total_len.to_le_bytes());
         cursor += 4;
@@ -477,9 +612,8 @@ impl MsgRing {
         cursor += 4;
 
         let payload_end = cursor + payload_len;
-        let record_end = offset + record_len;
-        if payload_end < record_end {
-            data[payload_end..record_end].fill(0);
+        if payload_end < record_len {
+            data[payload_end..record_len].fill(0);
         }
     }
 
@@ -501,69 +635,24 @@ impl MsgRing {
         unsafe { (*header).head_bytes.store(value, Ordering::Release) };
     }
 
-    fn reserve_offset(
-        &mut self,
-        head: usize,
-        tail: usize,
-        record_len: usize,
-        capacity: usize,
-    ) -> Option<(usize, usize)> {
-        let mut head_pos = head;
-        if head_pos >= capacity || tail >= capacity {
-            return None;
-        }
-
-        if head_pos >= tail {
-            let space_at_end = capacity - head_pos;
-            if space_at_end >= record_len {
-                let mut new_head = head_pos + record_len;
-                if new_head == capacity {
-                    new_head = 0;
-                }
-                if new_head == tail {
-                    return None;
-                }
-                Some((head_pos, new_head))
-            } else {
-                let space_at_start = tail;
-                if space_at_start <= record_len {
-                    return None;
-                }
-                if space_at_end < SENTINEL_BYTES {
-                    return None;
-                }
-                self.emit_sentinel(head_pos);
-                head_pos = 0;
-                let new_head = record_len;
-                if new_head == tail {
-                    return None;
-                }
-                Some((head_pos, new_head))
-            }
-        } else {
-            if record_len >= (tail - head_pos) {
-                return None;
-            }
-            let new_head = head_pos + record_len;
-            Some((head_pos, new_head))
-        }
-    }
-
-    fn emit_sentinel(&mut self, offset: usize) {
-        let data = self.data_slice_mut();
-        let end = offset + SENTINEL_BYTES;
-        data[offset..end].copy_from_slice(&SENTINEL.to_le_bytes());
+    /// Marks the unused end of the data region so the consumer wraps to zero.
+    fn emit_sentinel(&self, offset: usize) {
+        let capacity = self.capacity_bytes();
+        // SAFETY: The wrapping reservation owns `offset..capacity` until it commits.
+        let data = unsafe {
+            std::slice::from_raw_parts_mut(self.data_ptr().add(offset), capacity - offset)
+        };
+        data[..SENTINEL_BYTES].copy_from_slice(&SENTINEL.to_le_bytes());
 
-        let pad_end = offset + ENVELOPE_LEN;
-        if pad_end <= data.len() {
-            data[end..pad_end].fill(0);
+        if ENVELOPE_LEN <= data.len() {
+            data[SENTINEL_BYTES..ENVELOPE_LEN].fill(0);
         }
     }
 
     fn peek_envelope_at(&self, tail: usize) -> Option<RecordMeta> {
         let data = self.data_slice();
         let total = self.read_u32_at(data, tail)?;
-        if total == SENTINEL {
+        if total == SENTINEL || total == PADDING {
             return None;
         }
         Some(RecordMeta {
@@ -587,6 +676,65 @@ impl MsgRing {
     }
 }
 
+/// Plans a reservation of `record_len` bytes at producer cursor `head`.
+///
+/// Returns the write offset and the new head; a write offset of zero with a
+/// non-zero `head` means the record wraps and a sentinel must be written at
+/// `head`.
+fn reserve_offset(
+    head: usize,
+    tail: usize,
+    record_len: usize,
+    capacity: usize,
+) -> Option<(usize, usize)> {
+    if head >= capacity || tail >= capacity {
+        return None;
+    }
+
+    if head >= tail {
+        let space_at_end = capacity - head;
+        if space_at_end >= record_len {
+            let mut new_head = head + record_len;
+            if new_head == capacity {
+                new_head = 0;
+            }
+            if new_head == tail {
+                return None;
+            }
+            Some((head, new_head))
+        } else {
+            let space_at_start = tail;
+            if space_at_start <= record_len {
+                return None;
+            }
+            if space_at_end < SENTINEL_BYTES {
+                return None;
+            }
+            let new_head = record_len;
+            if new_head == tail {
+                return None;
+            }
+            Some((0, new_head))
+        }
+    } else {
+        if record_len >= (tail - head) {
+            return None;
+        }
+        Some((head, head + record_len))
+    }
+}
+
+/// Backs off while another producer holds the ring's reservation or commit turn.
+///
+/// Yields rather than spinning so the producer ahead of us can finish its copy
+/// even when there are more workers than cores.
+fn spin_wait() {
+    #[cfg(feature = "loom")]
+    loom::thread::yield_now();
+    #[cfg(not(feature = "loom"))]
+    std::thread::yield_now();
+}
+
 fn align_up(value: usize, align: usize) -> usize {
     assert!(align.is_power_of_two());
     (value + (align - 1)) & !(align - 1)
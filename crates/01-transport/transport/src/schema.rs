@@ -16,6 +16,46 @@ use std::vec::Vec;
 
 /// Schema version for transport-visible messages.
 pub const SCHEMA_VERSION_V1: u8 = 1;
+/// Schema version that carries the kernel group on every kernel message.
+pub const SCHEMA_VERSION_V2: u8 = 2;
+/// Schema version stamped on envelopes by current encoders.
+pub const SCHEMA_VERSION: u8 = SCHEMA_VERSION_V2;
+/// Oldest schema version current decoders still accept (one version back).
+pub const SCHEMA_VERSION_MIN: u8 = SCHEMA_VERSION - 1;
+
+/// Inclusive range of schema versions an endpoint peer can encode and decode.
+///
+/// Each side of an endpoint advertises its range when the endpoint is built and
+/// both sides then speak the highest version they have in common, so a
+/// scheduler and a worker one schema version apart still interoperate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchemaVersions {
+    /// Oldest supported version.
+    pub min: u8,
+    /// Newest supported version.
+    pub max: u8,
+}
+
+impl SchemaVersions {
+    /// Versions supported by this build: the current schema and the one before it.
+    pub const CURRENT: Self = Self::new(SCHEMA_VERSION_MIN, SCHEMA_VERSION);
+
+    /// Constructs a range covering `min..=max`.
+    pub const fn new(min: u8, max: u8) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns whether `ver` lies within the range.
+    pub const fn contains(self, ver: u8) -> bool {
+        self.min <= ver && ver <= self.max
+    }
+
+    /// Returns the highest version supported by both `self` and `peer`.
+    pub fn negotiate(self, peer: Self) -> Option<u8> {
+        let max = self.max.min(peer.max);
+        (max >= self.min.max(peer.min)).then_some(max)
+    }
+}
 
 /// Envelope tag for kernel commands.
 pub const TAG_KERNEL_CMD: u8 = 0x01;
@@ -88,6 +128,121 @@ pub struct KernelLoadRomCmdV1 {
     pub bytes: Vec<u8>,
 }
 
+/// Kernel command to load a ROM and seed its battery-backed cartridge RAM.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelLoadRomWithSaveCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelLoadRomWithSaveCmdV1 {
+    /// Length of the ROM prefix within `bytes`.
+    pub rom_len: u32,
+    /// ROM bytes followed by the persisted cartridge RAM contents.
+    ///
+    /// Packed into one buffer so the variant fits the existing `KernelCmdV1`
+    /// layout and earlier fixtures stay byte-identical.
+    pub bytes: Vec<u8>,
+}
+
+/// Kernel command to capture a lane's save state.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelSaveStateCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelSaveStateCmdV1 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Lane to capture.
+    pub lane: u16,
+}
+
+/// Kernel command to restore a lane from a save state.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelLoadStateCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelLoadStateCmdV1 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Lane to restore.
+    pub lane: u16,
+    /// Encoded save-state image.
+    pub bytes: Vec<u8>,
+}
+
+/// Kernel command to reset a single lane.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelResetLaneCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelResetLaneCmdV1 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Lane to reset.
+    pub lane: u16,
+    /// Replacement cartridge ROM, if any.
+    pub rom: Option<Vec<u8>>,
+    /// Cartridge RAM to seed after the reset, if any.
+    pub save_ram: Option<Vec<u8>>,
+}
+
+/// Kernel command to replace the active cheat codes.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelSetCheatsCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelSetCheatsCmdV1 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Game Genie or GameShark codes.
+    pub codes: Vec<String>,
+}
+
+/// Kernel command to branch a lane into a new group driven by input scripts.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelForkCmdV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelForkCmdV1 {
+    /// Kernel group holding the source lane.
+    pub group: u16,
+    /// Lane whose state seeds the forked lanes.
+    pub source_lane: u16,
+    /// Kernel group created for the forked lanes.
+    pub target_group: u16,
+    /// Joypad state per frame, one script per forked lane.
+    pub input_scripts: Vec<Vec<u8>>,
+}
+
 /// Kernel command to update input state.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
@@ -140,6 +295,15 @@ pub enum KernelDebugMemSpaceV1 {
     Oam,
     /// I/O register window (0xFF00-0xFF7F).
     Io,
+    /// High RAM followed by the IE register (0xFF80-0xFFFF).
+    Hram,
+    /// A cartridge ROM bank regardless of mapping; the bank travels next to
+    /// the space so the enum stays a single byte on the wire.
+    Rom,
+    /// An external cartridge RAM bank regardless of mapping.
+    CartRam,
+    /// Flat CPU address map read without side effects.
+    Bus,
 }
 
 /// CPU snapshot payload for debug snapshots.
@@ -204,6 +368,22 @@ pub struct KernelDebugTimersVmV1 {
     pub tac: u8,
 }
 
+/// SIMD lane divergence counters reported after debug snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugLanesVmV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugLanesVmV1 {
+    pub split_mask: u32,
+    pub splits: u64,
+    pub merges: u64,
+    pub split_cycles: u64,
+}
+
 /// Snapshot payload emitted by debug reports.
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
 #[rkyv(
@@ -234,6 +414,115 @@ pub enum KernelDebugStepKindV1 {
     Frame,
 }
 
+/// One disassembled instruction in a debug code listing.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugDisasmLineV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugDisasmLineV1 {
+    pub addr: u16,
+    pub bytes: Vec<u8>,
+    pub text: String,
+}
+
+/// Memory access kind trapped by a debug watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugWatchAccessV1`."
+    ),
+    bytecheck()
+)]
+pub enum KernelDebugWatchAccessV1 {
+    Read,
+    Write,
+}
+
+/// Reason a kernel group paused on a breakpoint or watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugBreakReasonV1`."
+    ),
+    bytecheck()
+)]
+pub enum KernelDebugBreakReasonV1 {
+    Breakpoint,
+    Watchpoint {
+        addr: u16,
+        access: KernelDebugWatchAccessV1,
+    },
+}
+
+/// Register values written by a debug register patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugRegistersPatchV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugRegistersPatchV1 {
+    pub a: Option<u8>,
+    pub f: Option<u8>,
+    pub b: Option<u8>,
+    pub c: Option<u8>,
+    pub d: Option<u8>,
+    pub e: Option<u8>,
+    pub h: Option<u8>,
+    pub l: Option<u8>,
+    pub sp: Option<u16>,
+    pub pc: Option<u16>,
+    pub ime: Option<bool>,
+}
+
+/// Data access recorded with a trace entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugMemAccessV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugMemAccessV1 {
+    pub addr: u16,
+    pub value: u8,
+    pub access: KernelDebugWatchAccessV1,
+}
+
+/// CPU state recorded before a traced instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugTraceEntryV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugTraceEntryV1 {
+    pub cycle: u64,
+    pub pc: u16,
+    pub pcmem: [u8; 4],
+    pub a: u8,
+    pub f: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+    pub e: u8,
+    pub h: u8,
+    pub l: u8,
+    pub sp: u16,
+    pub accesses: Vec<KernelDebugMemAccessV1>,
+}
+
 /// Debug command variants sent to the kernel.
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
 #[rkyv(
@@ -252,6 +541,7 @@ pub enum KernelDebugCmdV1 {
         space: KernelDebugMemSpaceV1,
         base: u16,
         len: u16,
+        bank: u16,
     },
     StepInstruction {
         group: u16,
@@ -260,6 +550,80 @@ pub enum KernelDebugCmdV1 {
     StepFrame {
         group: u16,
     },
+    SetBreakpoint {
+        group: u16,
+        pc: u16,
+    },
+    ClearBreakpoint {
+        group: u16,
+        pc: u16,
+    },
+    SetWatchpoint {
+        group: u16,
+        start: u16,
+        end: u16,
+        access: KernelDebugWatchAccessV1,
+    },
+    ClearWatchpoint {
+        group: u16,
+        start: u16,
+        end: u16,
+        access: KernelDebugWatchAccessV1,
+    },
+    Continue {
+        group: u16,
+    },
+    Disassemble {
+        group: u16,
+        base: u16,
+        count: u16,
+    },
+    SetTracing {
+        group: u16,
+        enabled: bool,
+        capacity: u32,
+    },
+    ReadTrace {
+        group: u16,
+        max: u16,
+    },
+    WriteMem(Box<KernelDebugWriteMemV1>),
+    SetRegisters(Box<KernelDebugSetRegistersV1>),
+}
+
+/// Payload of [`KernelDebugCmdV1::WriteMem`], boxed so the command enum keeps
+/// its original archived size.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugWriteMemV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugWriteMemV1 {
+    pub group: u16,
+    pub lane: u16,
+    pub space: KernelDebugMemSpaceV1,
+    pub base: u16,
+    pub bytes: Vec<u8>,
+    pub bank: u16,
+}
+
+/// Payload of [`KernelDebugCmdV1::SetRegisters`], boxed so the command enum
+/// keeps its original archived size.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugSetRegistersV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugSetRegistersV1 {
+    pub group: u16,
+    pub lane: u16,
+    pub regs: KernelDebugRegistersPatchV1,
 }
 
 /// Debug report variants generated by the kernel.
@@ -277,6 +641,7 @@ pub enum KernelDebugRepV1 {
         space: KernelDebugMemSpaceV1,
         base: u16,
         bytes: Vec<u8>,
+        bank: u16,
     },
     Stepped {
         kind: KernelDebugStepKindV1,
@@ -284,6 +649,34 @@ pub enum KernelDebugRepV1 {
         pc: u16,
         disasm: Option<String>,
     },
+    BreakpointHit {
+        group: u16,
+        pc: u16,
+        reason: KernelDebugBreakReasonV1,
+    },
+    Disassembly {
+        base: u16,
+        lines: Vec<KernelDebugDisasmLineV1>,
+    },
+    Trace(Box<KernelDebugTraceChunkV1>),
+    /// Boxed so the counters do not widen every archived report.
+    Lanes(Box<KernelDebugLanesVmV1>),
+}
+
+/// Payload of [`KernelDebugRepV1::Trace`]. Boxed because its `u64` would
+/// otherwise raise the alignment of every archived debug report.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugTraceChunkV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugTraceChunkV1 {
+    pub entries: Vec<KernelDebugTraceEntryV1>,
+    pub remaining: u32,
+    pub dropped: u64,
 }
 
 /// Command sent to the kernel service.
@@ -304,6 +697,18 @@ pub enum KernelCmdV1 {
     Terminate(KernelTerminateCmdV1),
     /// Execute a debug/inspector command.
     Debug(KernelDebugCmdV1),
+    /// Load a ROM together with persisted cartridge RAM.
+    LoadRomWithSave(KernelLoadRomWithSaveCmdV1),
+    /// Capture a lane's save state.
+    SaveState(KernelSaveStateCmdV1),
+    /// Restore a lane from a save state.
+    LoadState(KernelLoadStateCmdV1),
+    /// Replace the active cheat codes.
+    SetCheats(KernelSetCheatsCmdV1),
+    /// Reset a single lane; boxed to keep the archived command at 16 bytes.
+    ResetLane(Box<KernelResetLaneCmdV1>),
+    /// Branch a lane into a new scripted group; boxed like `ResetLane`.
+    Fork(Box<KernelForkCmdV1>),
 }
 
 /// Filesystem command to persist data.
@@ -323,6 +728,18 @@ pub struct FsPersistCmdV1 {
     pub payload: Vec<u8>,
 }
 
+/// Filesystem command to read persisted data back.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(allow(missing_docs), doc = "Archived representation of `FsLoadCmdV1`."),
+    bytecheck()
+)]
+pub struct FsLoadCmdV1 {
+    /// Storage key identifier.
+    pub key: String,
+}
+
 /// Command sent to the filesystem service.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
@@ -333,6 +750,8 @@ pub struct FsPersistCmdV1 {
 pub enum FsCmdV1 {
     /// Persist data to storage.
     Persist(FsPersistCmdV1),
+    /// Read persisted data back from storage.
+    Load(FsLoadCmdV1),
 }
 
 /// Command sent to the GPU service.
@@ -368,6 +787,53 @@ pub enum AudioCmdV1 {
     },
 }
 
+/// End state of one forked lane.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelForkedLaneV1`."
+    ),
+    bytecheck()
+)]
+pub struct KernelForkedLaneV1 {
+    /// Lane of the forked group.
+    pub lane: u16,
+    /// Encoded save-state image.
+    pub state: Vec<u8>,
+    /// Thumbnail width in pixels.
+    pub thumb_width: u16,
+    /// Thumbnail height in pixels.
+    pub thumb_height: u16,
+    /// Thumbnail RGBA pixels.
+    pub thumbnail: Vec<u8>,
+}
+
+/// Reason a fork was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelForkRejectionV1`."
+    ),
+    bytecheck()
+)]
+pub enum KernelForkRejectionV1 {
+    /// Source group or lane does not exist.
+    SourceMissing,
+    /// Target group is the source group.
+    TargetIsSource,
+    /// Target group is already running.
+    TargetInUse,
+    /// No scripts, or more than fit in one group.
+    ScriptCount,
+    /// A script is longer than a single fork may run.
+    ScriptTooLong,
+    /// The forked lanes refused the source lane's save state.
+    StateRejected,
+}
+
 /// Report generated by the kernel service.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
@@ -408,6 +874,67 @@ pub enum KernelRepV1 {
     },
     /// Inspector/debug payload emitted by the kernel.
     Debug(KernelDebugRepV1),
+    /// Battery-backed cartridge RAM changed and should be persisted.
+    SaveRamDirty {
+        /// Kernel group identifier.
+        group: u16,
+        /// Current cartridge RAM contents.
+        bytes: Vec<u8>,
+    },
+    /// Save-state capture finished.
+    StateSaved {
+        /// Kernel group identifier.
+        group: u16,
+        /// Captured lane.
+        lane: u16,
+        /// Whether the lane could be captured.
+        ok: bool,
+        /// Encoded save-state image; empty when `ok` is false.
+        bytes: Vec<u8>,
+    },
+    /// Save-state restore finished.
+    StateLoaded {
+        /// Kernel group identifier.
+        group: u16,
+        /// Restored lane.
+        lane: u16,
+        /// Whether the image was accepted.
+        ok: bool,
+    },
+    /// Cheat codes replaced.
+    CheatsSet {
+        /// Kernel group identifier.
+        group: u16,
+        /// Number of active codes.
+        active: u16,
+        /// Codes that failed to decode.
+        rejected: Vec<String>,
+    },
+    /// Lane reset finished.
+    LaneReset {
+        /// Kernel group identifier.
+        group: u16,
+        /// Reset lane.
+        lane: u16,
+        /// Whether the lane exists and was reset.
+        ok: bool,
+    },
+    /// Fork finished running its input scripts.
+    Forked {
+        /// Kernel group created by the fork.
+        group: u16,
+        /// Frames every lane ran.
+        frames: u32,
+        /// End state of each scripted lane.
+        lanes: Vec<KernelForkedLaneV1>,
+    },
+    /// Fork refused before running any script.
+    ForkRejected {
+        /// Kernel group the fork would have created.
+        group: u16,
+        /// Why the fork was refused.
+        reason: KernelForkRejectionV1,
+    },
 }
 
 /// Slot span descriptor used by kernel reports.
@@ -424,6 +951,23 @@ pub struct SlotSpanV1 {
     pub count: u32,
 }
 
+/// Filesystem report carrying data read back from storage.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `FsLoadedRepV1`."
+    ),
+    bytecheck()
+)]
+pub struct FsLoadedRepV1 {
+    /// Storage key that was read.
+    pub key: String,
+    /// Stored payload, or `None` when nothing was stored under `key`.
+    pub payload: Option<Vec<u8>>,
+}
+
 /// Report generated by the filesystem service.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
@@ -439,6 +983,8 @@ pub enum FsRepV1 {
         /// Whether the save succeeded.
         ok: bool,
     },
+    /// Data read completed; boxed to keep the archived report at 12 bytes.
+    Loaded(Box<FsLoadedRepV1>),
 }
 
 /// Report generated by the GPU service.
@@ -474,3 +1020,382 @@ pub enum AudioRepV1 {
     /// Audio buffer underrun occurred.
     Underrun,
 }
+
+/// Kernel tick command payload addressed to a group.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelTickCmdV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelTickCmdV2 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Purpose of this tick (display or exploration).
+    pub purpose: TickPurposeV1,
+    /// Instruction budget for this tick.
+    pub budget: u32,
+}
+
+/// Kernel command to load a ROM into a group, optionally with cartridge RAM.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelLoadRomCmdV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelLoadRomCmdV2 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Raw ROM bytes.
+    pub bytes: Vec<u8>,
+    /// Persisted cartridge RAM to seed, if any.
+    pub save_ram: Option<Vec<u8>>,
+}
+
+/// Payload staged in a slot pool instead of travelling inline.
+///
+/// The bytes fill `span.count` slots, pushed to the pool's ready ring in order
+/// starting with `span.start_idx`; the last slot holds the remainder of `len`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(allow(missing_docs), doc = "Archived representation of `BulkSpanV2`."),
+    bytecheck()
+)]
+pub struct BulkSpanV2 {
+    /// Slots holding the payload.
+    pub span: SlotSpanV1,
+    /// Payload length in bytes.
+    pub len: u32,
+}
+
+/// Pooled variant of [`KernelLoadRomCmdV2`] for ROMs too large to send inline.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelLoadRomPooledCmdV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelLoadRomPooledCmdV2 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Staged ROM bytes.
+    pub rom: BulkSpanV2,
+    /// Staged cartridge RAM to seed, if any.
+    pub save_ram: Option<BulkSpanV2>,
+}
+
+/// Pooled variant of [`KernelLoadStateCmdV1`] for large state blobs.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelLoadStatePooledCmdV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelLoadStatePooledCmdV2 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Lane to restore.
+    pub lane: u16,
+    /// Staged state blob.
+    pub bytes: BulkSpanV2,
+}
+
+/// Pooled variant of [`KernelForkedLaneV1`] whose state blob is staged.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelForkedLanePooledV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelForkedLanePooledV2 {
+    /// Lane of the forked group.
+    pub lane: u16,
+    /// Staged save-state image.
+    pub state: BulkSpanV2,
+    /// Thumbnail width in pixels.
+    pub thumb_width: u16,
+    /// Thumbnail height in pixels.
+    pub thumb_height: u16,
+    /// Thumbnail RGBA pixels.
+    pub thumbnail: Vec<u8>,
+}
+
+/// Memory window read from one lane; the V1 debug command always reads lane 0.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `KernelDebugMemWindowCmdV2`."
+    ),
+    bytecheck()
+)]
+pub struct KernelDebugMemWindowCmdV2 {
+    /// Kernel group identifier.
+    pub group: u16,
+    /// Lane to read.
+    pub lane: u16,
+    /// Address space to read.
+    pub space: KernelDebugMemSpaceV1,
+    /// First address of the window.
+    pub base: u16,
+    /// Window length in bytes.
+    pub len: u16,
+    /// Cartridge bank for banked spaces.
+    pub bank: u16,
+}
+
+/// Command sent to the kernel service, schema version 2.
+///
+/// Every variant names its kernel group. Variants whose V1 payload already
+/// carried the group reuse the V1 payload types.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(allow(missing_docs), doc = "Archived representation of `KernelCmdV2`."),
+    bytecheck()
+)]
+pub enum KernelCmdV2 {
+    /// Execute an emulation tick.
+    Tick(KernelTickCmdV2),
+    /// Load a ROM, optionally with persisted cartridge RAM.
+    LoadRom(KernelLoadRomCmdV2),
+    /// Update joypad inputs.
+    SetInputs(KernelSetInputsCmdV1),
+    /// Terminate a kernel group.
+    Terminate(KernelTerminateCmdV1),
+    /// Execute a debug/inspector command.
+    Debug(KernelDebugCmdV1),
+    /// Capture a lane's save state.
+    SaveState(KernelSaveStateCmdV1),
+    /// Restore a lane from a save state.
+    LoadState(KernelLoadStateCmdV1),
+    /// Replace the active cheat codes.
+    SetCheats(KernelSetCheatsCmdV1),
+    /// Reset a single lane.
+    ResetLane(KernelResetLaneCmdV1),
+    /// Branch a lane into a new scripted group.
+    Fork(KernelForkCmdV1),
+    /// Load ROM staged in the command bulk pool.
+    LoadRomPooled(KernelLoadRomPooledCmdV2),
+    /// Load a save state staged in the command bulk pool.
+    LoadStatePooled(KernelLoadStatePooledCmdV2),
+    /// Read a memory window from one lane.
+    DebugMemWindow(KernelDebugMemWindowCmdV2),
+}
+
+/// Report generated by the kernel service, schema version 2.
+///
+/// Every variant names the kernel group it came from.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(allow(missing_docs), doc = "Archived representation of `KernelRepV2`."),
+    bytecheck()
+)]
+pub enum KernelRepV2 {
+    /// Tick completed.
+    TickDone {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lanes that participated in the tick.
+        lanes_mask: u32,
+        /// CPU cycles executed during the tick.
+        cycles_done: u32,
+    },
+    /// A frame is ready on a display lane.
+    LaneFrame {
+        /// Kernel group identifier.
+        group: u16,
+        /// Display lane identifier.
+        lane: LaneId,
+        /// Unique frame identifier.
+        frame_id: FrameId,
+        /// Slot span carrying the frame payload.
+        span: SlotSpanV1,
+        /// Embedded RGBA pixels (optional when slot span not used).
+        pixels: Vec<u8>,
+    },
+    /// ROM loading completed with the number of bytes.
+    RomLoaded {
+        /// Kernel group identifier.
+        group: u16,
+        /// Size of the loaded ROM in bytes.
+        bytes_len: u32,
+    },
+    /// Audio buffer ready for playback.
+    AudioReady {
+        /// Kernel group identifier.
+        group: u16,
+        /// Slot span carrying the audio payload.
+        span: SlotSpanV1,
+    },
+    /// Thumbnails dropped under back-pressure.
+    DroppedThumb {
+        /// Kernel group identifier.
+        group: u16,
+        /// Count of dropped thumbnails.
+        count: u32,
+    },
+    /// Inspector/debug payload emitted by the kernel.
+    Debug {
+        /// Kernel group identifier.
+        group: u16,
+        /// Debug report body.
+        rep: KernelDebugRepV1,
+    },
+    /// Battery-backed cartridge RAM changed and should be persisted.
+    SaveRamDirty {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane whose cartridge RAM changed.
+        lane: u16,
+        /// Current cartridge RAM contents.
+        bytes: Vec<u8>,
+    },
+    /// Save-state capture finished.
+    StateSaved {
+        /// Kernel group identifier.
+        group: u16,
+        /// Captured lane.
+        lane: u16,
+        /// Whether the lane could be captured.
+        ok: bool,
+        /// Encoded save-state image; empty when `ok` is false.
+        bytes: Vec<u8>,
+    },
+    /// Save-state restore finished.
+    StateLoaded {
+        /// Kernel group identifier.
+        group: u16,
+        /// Restored lane.
+        lane: u16,
+        /// Whether the image was accepted.
+        ok: bool,
+    },
+    /// Cheat codes replaced.
+    CheatsSet {
+        /// Kernel group identifier.
+        group: u16,
+        /// Number of active codes.
+        active: u16,
+        /// Codes that failed to decode.
+        rejected: Vec<String>,
+    },
+    /// Lane reset finished.
+    LaneReset {
+        /// Kernel group identifier.
+        group: u16,
+        /// Reset lane.
+        lane: u16,
+        /// Whether the lane exists and was reset.
+        ok: bool,
+    },
+    /// Fork finished running its input scripts.
+    Forked {
+        /// Kernel group created by the fork.
+        group: u16,
+        /// Frames every lane ran.
+        frames: u32,
+        /// End state of each scripted lane.
+        lanes: Vec<KernelForkedLaneV1>,
+    },
+    /// Battery RAM staged in the report bulk pool.
+    SaveRamDirtyPooled {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane whose cartridge RAM changed.
+        lane: u16,
+        /// Staged cartridge RAM.
+        bytes: BulkSpanV2,
+    },
+    /// Save state staged in the report bulk pool.
+    StateSavedPooled {
+        /// Kernel group identifier.
+        group: u16,
+        /// Saved lane.
+        lane: u16,
+        /// Staged state blob.
+        bytes: BulkSpanV2,
+    },
+    /// Audio buffer written into the audio slot pool.
+    AudioReadyPooled {
+        /// Kernel group identifier.
+        group: u16,
+        /// Slots holding the samples.
+        audio: AudioSlotSpanV2,
+    },
+    /// Fork refused before running any script.
+    ForkRejected {
+        /// Kernel group the fork would have created.
+        group: u16,
+        /// Why the fork was refused.
+        reason: KernelForkRejectionV1,
+    },
+    /// Fork finished, with each lane's state staged in the report bulk pool.
+    ForkedPooled {
+        /// Kernel group created by the fork.
+        group: u16,
+        /// Frames every lane ran.
+        frames: u32,
+        /// End state of each scripted lane.
+        lanes: Vec<KernelForkedLanePooledV2>,
+    },
+}
+
+/// Audio samples held in the audio slot pool.
+///
+/// Samples are interleaved little-endian `f32`, filling `span.count` slots in
+/// ready-ring order from `span.start_idx`; the last slot holds the remainder.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(
+        allow(missing_docs),
+        doc = "Archived representation of `AudioSlotSpanV2`."
+    ),
+    bytecheck()
+)]
+pub struct AudioSlotSpanV2 {
+    /// Slots holding the samples.
+    pub span: SlotSpanV1,
+    /// Sample frames across all channels.
+    pub frames: u32,
+    /// Interleaved channel count.
+    pub channels: u8,
+    /// Sample rate in hertz.
+    pub sample_rate_hz: u32,
+}
+
+/// Command sent to the audio service (schema version 2).
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize)]
+#[rkyv(
+    attr(allow(missing_docs), doc = "Archived representation of `AudioCmdV2`."),
+    bytecheck()
+)]
+pub enum AudioCmdV2 {
+    /// Submit audio sample frames for playback.
+    SubmitSamples {
+        /// Number of sample frames to submit.
+        frames: u32,
+    },
+    /// Submit samples already written into the audio slot pool.
+    SubmitPooled {
+        /// Slots holding the samples.
+        audio: AudioSlotSpanV2,
+    },
+}
@@ -22,7 +22,7 @@ mod wasm {
     use super::types::{self, *};
     use std::cell::RefCell;
     use std::sync::Arc;
-    use transport::schema::SCHEMA_VERSION_V1;
+    use transport::schema::SchemaVersions;
     use transport::wasm::IntoNativeLayout;
     use transport::{Envelope, Mailbox, MsgRing, SlotPool, SlotPoolHandle, SlotPush};
     use transport_fabric::{
@@ -40,6 +40,7 @@ mod wasm {
     const ERR_ALREADY_INIT: i32 = -2;
     const ERR_NOT_INIT: i32 = -3;
     const ERR_INVALID_TEST_TYPE: i32 = -5;
+    const ERR_SCHEMA_VERSION: i32 = -6;
 
     const EVENT_ENVELOPE: Envelope = Envelope {
         tag: EVENT_TAG,
@@ -54,6 +55,7 @@ mod wasm {
         pub mailbox: Option<types::MailboxLayout>,
         pub replies: types::MsgRingLayout,
         pub slot_pools: Vec<types::SlotPoolLayout>,
+        pub version: u8,
     }
 
     struct WasmFabricHandle {
@@ -169,7 +171,7 @@ mod wasm {
     // Expose build_worker_endpoint for use by app-layer service registration
     pub fn build_worker_endpoint<C>(
         layout: &EndpointLayouts,
-        codec: C,
+        mut codec: C,
         cmd_tag: u8,
         rep_tag: u8,
     ) -> Result<WorkerEndpoint<C>, i32>
@@ -178,36 +180,30 @@ mod wasm {
     {
         unsafe {
             let lossless = layout.lossless.map(|ring_layout| {
-                let ring = MsgRing::from_wasm_layout(
-                    ring_layout,
-                    Envelope::new(cmd_tag, SCHEMA_VERSION_V1),
-                );
+                let ring =
+                    MsgRing::from_wasm_layout(ring_layout, Envelope::new(cmd_tag, layout.version));
                 make_port_pair_ring(PortClass::Lossless, ring).consumer
             });
 
             let besteffort = layout.besteffort.map(|ring_layout| {
-                let ring = MsgRing::from_wasm_layout(
-                    ring_layout,
-                    Envelope::new(cmd_tag, SCHEMA_VERSION_V1),
-                );
+                let ring =
+                    MsgRing::from_wasm_layout(ring_layout, Envelope::new(cmd_tag, layout.version));
                 make_port_pair_ring(PortClass::BestEffort, ring).consumer
             });
 
             let coalesce = layout.mailbox.map(|mailbox_layout| {
                 let mailbox = Mailbox::from_wasm_layout(
                     mailbox_layout,
-                    Envelope::new(cmd_tag, SCHEMA_VERSION_V1),
+                    Envelope::new(cmd_tag, layout.version),
                 );
                 make_port_pair_mailbox(mailbox).consumer
             });
 
-            let replies_ring = MsgRing::from_wasm_layout(
-                layout.replies,
-                Envelope::new(rep_tag, SCHEMA_VERSION_V1),
-            );
+            let replies_ring =
+                MsgRing::from_wasm_layout(layout.replies, Envelope::new(rep_tag, layout.version));
             let replies = make_port_pair_ring(PortClass::Lossless, replies_ring).producer;
 
-            let slot_pools = layout
+            let slot_pools: Vec<Arc<SlotPoolHandle>> = layout
                 .slot_pools
                 .iter()
                 .map(|pool_layout| {
@@ -216,8 +212,22 @@ mod wasm {
                 })
                 .collect();
 
+            codec.attach_slot_pools(&slot_pools);
+            codec.attach_ring_capacities(
+                layout
+                    .lossless
+                    .map_or(0, |ring_layout| ring_layout.capacity_bytes as usize),
+                layout.replies.capacity_bytes as usize,
+            );
+
             Ok(WorkerEndpoint::new(
-                lossless, besteffort, coalesce, replies, slot_pools, codec,
+                lossless,
+                besteffort,
+                coalesce,
+                replies,
+                slot_pools,
+                codec,
+                layout.version,
             ))
         }
     }
@@ -245,6 +255,11 @@ mod wasm {
             for endpoint_layout in archived_layout.endpoints.iter() {
                 use rkyv::Archived;
 
+                let version = endpoint_layout.version;
+                if !SchemaVersions::CURRENT.contains(version) {
+                    return ERR_SCHEMA_VERSION;
+                }
+
                 let mut lossless = None;
                 let mut besteffort = None;
                 let mut mailbox = None;
@@ -303,6 +318,7 @@ mod wasm {
                         mailbox,
                         replies: replies_layout,
                         slot_pools: pool_layouts,
+                        version,
                     });
                 }
             }
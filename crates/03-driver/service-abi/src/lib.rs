@@ -6,6 +6,7 @@
 #![allow(missing_docs)]
 
 use serde::Serialize;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -76,6 +77,8 @@ pub struct AudioSpan {
     pub sample_rate_hz: u32,
     /// Optional slot span referencing transport-managed memory.
     pub slot_span: Option<SlotSpan>,
+    /// Sample frames held in `slot_span`; unused for inline samples.
+    pub slot_frames: u32,
 }
 
 impl AudioSpan {
@@ -86,6 +89,16 @@ impl AudioSpan {
             channels: 2,
             sample_rate_hz: 48_000,
             slot_span: None,
+            slot_frames: 0,
+        }
+    }
+
+    /// Number of sample frames, whether inline or held in `slot_span`.
+    pub fn frames(&self) -> usize {
+        match self.slot_span {
+            Some(_) => self.slot_frames as usize,
+            None if self.channels == 0 => 0,
+            None => self.samples.len() / usize::from(self.channels),
         }
     }
 }
@@ -116,6 +129,15 @@ pub enum MemSpace {
     Oam,
     /// I/O register window (0xFF00-0xFF7F).
     Io,
+    /// High RAM followed by the IE register (0xFF80-0xFFFF).
+    Hram,
+    /// A cartridge ROM bank regardless of mapping; bank 0 is addressed at
+    /// 0x0000-0x3FFF, every other bank at 0x4000-0x7FFF.
+    Rom { bank: u16 },
+    /// An external cartridge RAM bank regardless of mapping (0xA000-0xBFFF).
+    CartRam { bank: u8 },
+    /// The CPU's view of the whole address map, read without side effects.
+    Bus,
 }
 
 /// CPU register snapshot used by inspector view models.
@@ -159,6 +181,22 @@ pub struct TimersVM {
     pub tac: u8,
 }
 
+/// SIMD lane divergence counters exposed to inspector view models.
+///
+/// Reported by [`DebugRep::Lanes`] after every snapshot; scalar groups
+/// always report zeros.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LanesVM {
+    /// Lanes currently running on their own scalar core, one bit per lane.
+    pub split_mask: u32,
+    /// Times a lane split off the SIMD path.
+    pub splits: u64,
+    /// Times a split lane rejoined the SIMD path.
+    pub merges: u64,
+    /// CPU cycles executed by split lanes.
+    pub split_cycles: u64,
+}
+
 /// Minimal inspector payload emitted with snapshots.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct InspectorVMMinimal {
@@ -185,14 +223,87 @@ pub struct TraceVM {
     pub cycles: u32,
 }
 
+/// One disassembled instruction in a code listing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DisasmLineVM {
+    pub addr: u16,
+    pub bytes: Vec<u8>,
+    pub text: String,
+}
+
+/// CPU memory access a watchpoint traps on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum WatchAccess {
+    /// The CPU reads from the watched range.
+    Read,
+    /// The CPU writes to the watched range.
+    Write,
+}
+
+/// Register values written by `DebugCmd::SetRegisters`; `None` leaves a register as is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RegistersPatch {
+    pub a: Option<u8>,
+    /// Flags; the low nibble is ignored.
+    pub f: Option<u8>,
+    pub b: Option<u8>,
+    pub c: Option<u8>,
+    pub d: Option<u8>,
+    pub e: Option<u8>,
+    pub h: Option<u8>,
+    pub l: Option<u8>,
+    pub sp: Option<u16>,
+    pub pc: Option<u16>,
+    /// Interrupt master enable; shared by every lane of a SIMD group.
+    pub ime: Option<bool>,
+}
+
+/// Data access made by a traced instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MemAccessVM {
+    pub addr: u16,
+    pub value: u8,
+    pub access: WatchAccess,
+}
+
+/// CPU state recorded just before a traced instruction executed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceEntryVM {
+    /// CPU cycles elapsed since tracing was enabled.
+    pub cycle: u64,
+    pub pc: u16,
+    /// The four bytes at `pc`, opcode first.
+    pub pcmem: [u8; 4],
+    pub a: u8,
+    pub f: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+    pub e: u8,
+    pub h: u8,
+    pub l: u8,
+    pub sp: u16,
+    pub accesses: Vec<MemAccessVM>,
+}
+
+/// Why a kernel group paused on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum BreakReason {
+    /// The PC reached a breakpoint; the instruction there has not run yet.
+    Breakpoint,
+    /// The previous instruction accessed a watched address.
+    Watchpoint { addr: u16, access: WatchAccess },
+}
+
 /// Debug command variants routed to the kernel.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DebugCmd {
     /// Capture a fresh snapshot of CPU/PPU/timer state.
     Snapshot { group: u16 },
-    /// Fetch a memory window from the requested address space.
+    /// Fetch a memory window from the requested address space of one lane.
     MemWindow {
         group: u16,
+        lane: u16,
         space: MemSpace,
         base: u16,
         len: u16,
@@ -201,6 +312,49 @@ pub enum DebugCmd {
     StepInstruction { group: u16, count: u32 },
     /// Step exactly one frame worth of cycles.
     StepFrame { group: u16 },
+    /// Pause the group before the instruction at `pc` runs.
+    SetBreakpoint { group: u16, pc: u16 },
+    /// Remove a breakpoint previously set at `pc`.
+    ClearBreakpoint { group: u16, pc: u16 },
+    /// Pause the group after an instruction accesses `addr_range`.
+    SetWatchpoint {
+        group: u16,
+        addr_range: RangeInclusive<u16>,
+        access: WatchAccess,
+    },
+    /// Remove a watchpoint previously set with the same range and access.
+    ClearWatchpoint {
+        group: u16,
+        addr_range: RangeInclusive<u16>,
+        access: WatchAccess,
+    },
+    /// Resume a group paused by a breakpoint or watchpoint.
+    Continue { group: u16 },
+    /// Disassemble `count` instructions starting at `base`.
+    Disassemble { group: u16, base: u16, count: u16 },
+    /// Start recording executed instructions into a ring of `capacity`
+    /// entries, or stop recording when `enabled` is false.
+    SetTracing {
+        group: u16,
+        enabled: bool,
+        capacity: u32,
+    },
+    /// Drain up to `max` of the oldest recorded trace entries.
+    ReadTrace { group: u16, max: u16 },
+    /// Overwrite memory in `space` starting at `base` on one lane.
+    WriteMem {
+        group: u16,
+        lane: u16,
+        space: MemSpace,
+        base: u16,
+        bytes: Arc<[u8]>,
+    },
+    /// Overwrite CPU registers on one lane.
+    SetRegisters {
+        group: u16,
+        lane: u16,
+        regs: RegistersPatch,
+    },
 }
 
 impl DebugCmd {
@@ -209,17 +363,34 @@ impl DebugCmd {
         match self {
             DebugCmd::StepInstruction { .. } | DebugCmd::StepFrame { .. } => SubmitPolicy::Lossless,
             DebugCmd::Snapshot { .. } => SubmitPolicy::Coalesce,
-            DebugCmd::MemWindow { .. } => SubmitPolicy::Lossless,
+            DebugCmd::MemWindow { .. } | DebugCmd::Disassemble { .. } => SubmitPolicy::Lossless,
+            DebugCmd::SetTracing { .. } | DebugCmd::ReadTrace { .. } => SubmitPolicy::Lossless,
+            DebugCmd::WriteMem { .. } | DebugCmd::SetRegisters { .. } => SubmitPolicy::Lossless,
+            DebugCmd::SetBreakpoint { .. }
+            | DebugCmd::ClearBreakpoint { .. }
+            | DebugCmd::SetWatchpoint { .. }
+            | DebugCmd::ClearWatchpoint { .. }
+            | DebugCmd::Continue { .. } => SubmitPolicy::Lossless,
         }
     }
 
     /// Returns the expected number of reports produced by the command.
     pub fn expected_reports(&self) -> usize {
         match self {
-            DebugCmd::Snapshot { .. } => 1,
+            DebugCmd::Snapshot { .. } => 2,
             DebugCmd::MemWindow { .. } => 1,
+            DebugCmd::Disassemble { .. } => 1,
+            DebugCmd::ReadTrace { .. } => 1,
             DebugCmd::StepInstruction { .. } => 1,
             DebugCmd::StepFrame { .. } => 3,
+            DebugCmd::SetBreakpoint { .. }
+            | DebugCmd::ClearBreakpoint { .. }
+            | DebugCmd::SetWatchpoint { .. }
+            | DebugCmd::ClearWatchpoint { .. }
+            | DebugCmd::Continue { .. }
+            | DebugCmd::SetTracing { .. }
+            | DebugCmd::WriteMem { .. }
+            | DebugCmd::SetRegisters { .. } => 0,
         }
     }
 
@@ -229,7 +400,17 @@ impl DebugCmd {
             DebugCmd::Snapshot { group }
             | DebugCmd::MemWindow { group, .. }
             | DebugCmd::StepInstruction { group, .. }
-            | DebugCmd::StepFrame { group } => *group,
+            | DebugCmd::StepFrame { group }
+            | DebugCmd::SetBreakpoint { group, .. }
+            | DebugCmd::ClearBreakpoint { group, .. }
+            | DebugCmd::SetWatchpoint { group, .. }
+            | DebugCmd::ClearWatchpoint { group, .. }
+            | DebugCmd::Continue { group }
+            | DebugCmd::Disassemble { group, .. }
+            | DebugCmd::SetTracing { group, .. }
+            | DebugCmd::ReadTrace { group, .. }
+            | DebugCmd::WriteMem { group, .. }
+            | DebugCmd::SetRegisters { group, .. } => *group,
         }
     }
 }
@@ -252,12 +433,14 @@ pub enum KernelCmd {
         group: u16,
         /// ROM payload.
         bytes: Arc<[u8]>,
+        /// Battery-backed cartridge RAM to seed after loading, if any.
+        save_ram: Option<Arc<[u8]>>,
     },
-    /// Update joypad inputs for a group.
+    /// Update joypad inputs for the selected lanes of a group.
     SetInputs {
         /// Kernel group identifier.
         group: u16,
-        /// Bitmask indicating active lanes.
+        /// Bitmask of lanes receiving the inputs; other lanes keep theirs.
         lanes_mask: u32,
         /// Raw joypad state.
         joypad: u8,
@@ -269,6 +452,60 @@ pub enum KernelCmd {
     },
     /// Inspector/debug command routed to the kernel.
     Debug(DebugCmd),
+    /// Capture a lane's emulator state as a versioned save-state image.
+    SaveState {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to capture.
+        lane: u16,
+    },
+    /// Restore a lane from a save-state image produced by `SaveState`.
+    LoadState {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to restore.
+        lane: u16,
+        /// Encoded save-state image.
+        bytes: Arc<[u8]>,
+    },
+    /// Return one lane to its post-boot state without touching the other lanes.
+    ResetLane {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to reset.
+        lane: u16,
+        /// Cartridge to insert into the lane first; `None` keeps the current one
+        /// along with its battery RAM.
+        rom: Option<Arc<[u8]>>,
+        /// Battery-backed cartridge RAM to seed after the reset, if any.
+        save_ram: Option<Arc<[u8]>>,
+    },
+    /// Replace the Game Genie and GameShark codes active on every lane of a group.
+    ///
+    /// An empty list disables cheats; loading a ROM clears them as well.
+    SetCheats {
+        /// Kernel group identifier.
+        group: u16,
+        /// Codes such as `00A-17B-C49` or `010F3CC1`.
+        codes: Vec<String>,
+    },
+    /// Branch one lane into a new group and play a scripted input sequence per lane.
+    ///
+    /// Every lane of `target_group` starts from a copy of `source_lane` and reads
+    /// one joypad byte per frame from its script, holding the last byte once the
+    /// script runs out, until the longest script ends. `target_group` must not be
+    /// running; the kernel answers with `ForkRejected` instead of replacing it, and
+    /// likewise refuses scripts too many or too long to run in one command.
+    Fork {
+        /// Kernel group holding the lane to branch from.
+        group: u16,
+        /// Lane whose state seeds every forked lane.
+        source_lane: u16,
+        /// Kernel group created for the forked lanes.
+        target_group: u16,
+        /// Raw joypad state per frame, one script per forked lane.
+        input_scripts: Vec<Arc<[u8]>>,
+    },
 }
 
 /// Kernel report variants.
@@ -316,7 +553,101 @@ pub enum KernelRep {
         count: u32,
     },
     /// Inspector/debug payload emitted by the kernel.
-    Debug(DebugRep),
+    Debug {
+        /// Kernel group the debug command addressed.
+        group: u16,
+        /// Debug report body.
+        rep: DebugRep,
+    },
+    /// Battery-backed cartridge RAM changed since the last report.
+    SaveRamDirty {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane whose cartridge RAM changed.
+        lane: u16,
+        /// Current cartridge RAM contents.
+        bytes: Arc<[u8]>,
+    },
+    /// Result of a `SaveState` command.
+    StateSaved {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane that was captured.
+        lane: u16,
+        /// Encoded save-state image, or `None` when the lane could not be captured.
+        bytes: Option<Arc<[u8]>>,
+    },
+    /// Result of a `LoadState` command.
+    StateLoaded {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane that was restored.
+        lane: u16,
+        /// Whether the image was accepted; rejected images leave the lane untouched.
+        ok: bool,
+    },
+    /// Result of a `ResetLane` command.
+    LaneReset {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane that was reset.
+        lane: u16,
+        /// Whether the lane exists and was reset.
+        ok: bool,
+    },
+    /// Result of a `SetCheats` command.
+    CheatsSet {
+        /// Kernel group identifier.
+        group: u16,
+        /// Number of codes now active.
+        active: u16,
+        /// Codes that failed to decode and were skipped.
+        rejected: Vec<String>,
+    },
+    /// Result of a `Fork` command.
+    Forked {
+        /// Kernel group created by the fork.
+        group: u16,
+        /// Frames every lane ran, the length of the longest script.
+        frames: u32,
+        /// End state of each scripted lane.
+        lanes: Vec<ForkedLane>,
+    },
+    /// A `Fork` command was refused without touching any group.
+    ForkRejected {
+        /// Kernel group the fork would have created.
+        group: u16,
+        /// Why the fork was refused.
+        reason: ForkRejection,
+    },
+}
+
+/// Reason the kernel refused a `Fork` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForkRejection {
+    /// The source group or lane does not exist.
+    SourceMissing,
+    /// The target group is the source group.
+    TargetIsSource,
+    /// The target group is already running; terminate it first.
+    TargetInUse,
+    /// No scripts, or more than fit in a single group.
+    ScriptCount,
+    /// A script is longer than a single fork may run.
+    ScriptTooLong,
+    /// The forked lanes refused the source lane's save state.
+    StateRejected,
+}
+
+/// End state of one lane after a `Fork` finished its script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForkedLane {
+    /// Lane of the forked group, matching the script index.
+    pub lane: u16,
+    /// Encoded save-state image of the lane.
+    pub state: Arc<[u8]>,
+    /// Downscaled copy of the lane's last frame.
+    pub thumbnail: FrameSpan,
 }
 
 /// Debug report emitted by the kernel service.
@@ -337,6 +668,25 @@ pub enum DebugRep {
         pc: u16,
         disasm: Option<String>,
     },
+    /// Code listing produced by `DebugCmd::Disassemble`.
+    Disassembly { base: u16, lines: Vec<DisasmLineVM> },
+    /// Chunk of the execution trace produced by `DebugCmd::ReadTrace`.
+    Trace {
+        entries: Vec<TraceEntryVM>,
+        /// Entries still buffered after this chunk.
+        remaining: u32,
+        /// Entries evicted unread since tracing was enabled.
+        dropped: u64,
+    },
+    /// A breakpoint or watchpoint paused the group; it stays paused until
+    /// `DebugCmd::Continue`.
+    BreakpointHit {
+        group: u16,
+        pc: u16,
+        reason: BreakReason,
+    },
+    /// SIMD lane divergence counters, reported right after each snapshot.
+    Lanes(LanesVM),
 }
 
 /// Command directed at the GPU service.
@@ -395,6 +745,11 @@ pub enum FsCmd {
         /// Serialized payload bytes.
         bytes: Arc<[u8]>,
     },
+    /// Read a previously persisted payload back from disk.
+    Load {
+        /// Path to read.
+        path: PathBuf,
+    },
 }
 
 /// Filesystem report variants.
@@ -407,6 +762,13 @@ pub enum FsRep {
         /// Whether the persistence succeeded.
         ok: bool,
     },
+    /// Result of a `Load` command.
+    Loaded {
+        /// Path that was read.
+        path: PathBuf,
+        /// Payload contents; `None` when the file is missing or unreadable.
+        bytes: Option<Arc<[u8]>>,
+    },
 }
 
 // Service handle type aliases for convenience
@@ -5,11 +5,11 @@ use std::sync::Arc;
 use anyhow::Result;
 use service_abi::{AudioServiceHandle, FsServiceHandle, GpuServiceHandle, KernelServiceHandle};
 use transport::schema::{
-    TAG_AUDIO_CMD, TAG_AUDIO_REP, TAG_FS_CMD, TAG_FS_REP, TAG_GPU_CMD, TAG_GPU_REP, TAG_KERNEL_CMD,
-    TAG_KERNEL_REP,
+    SchemaVersions, TAG_AUDIO_CMD, TAG_AUDIO_REP, TAG_FS_CMD, TAG_FS_REP, TAG_GPU_CMD, TAG_GPU_REP,
+    TAG_KERNEL_CMD, TAG_KERNEL_REP,
 };
 use transport::SlotPoolConfig;
-use transport_codecs::{AudioCodec, FsCodec, GpuCodec, KernelCodec};
+use transport_codecs::{AudioCodec, FsCodec, GpuCodec, KernelCodec, KERNEL_AUDIO_POOL};
 use transport_fabric::{
     build_service, EndpointHandle, FabricLayout, MailboxSpec, PortClass, RingSpec, ServiceAdapter,
     ServiceSpec, SlotPoolSpec, WorkerEndpoint,
@@ -47,7 +47,7 @@ impl TransportServices {
         let mut layout = FabricLayout::default();
 
         let kernel_spec = ServiceSpec {
-            codec: KernelCodec,
+            codec: KernelCodec::default(),
             lossless: Some(RingSpec {
                 capacity_bytes: 128 * 1024,
                 envelope_tag: TAG_KERNEL_CMD,
@@ -72,15 +72,34 @@ impl TransportServices {
                         slot_count: 8,
                         slot_size: 128 * 1024,
                     },
+                    shared: None,
                 },
-                // Audio pool (index 1)
+                // Audio pool (index 1, KERNEL_AUDIO_POOL), shared with the audio endpoint
                 SlotPoolSpec {
                     config: SlotPoolConfig {
-                        slot_count: 6,
-                        slot_size: 16 * 1024,
+                        slot_count: 16,
+                        slot_size: 32 * 1024,
                     },
+                    shared: None,
+                },
+                // Command bulk pool (index 2, KERNEL_CMD_BULK_POOL)
+                SlotPoolSpec {
+                    config: SlotPoolConfig {
+                        slot_count: 32,
+                        slot_size: 256 * 1024,
+                    },
+                    shared: None,
+                },
+                // Report bulk pool (index 3, KERNEL_REP_BULK_POOL)
+                SlotPoolSpec {
+                    config: SlotPoolConfig {
+                        slot_count: 8,
+                        slot_size: 128 * 1024,
+                    },
+                    shared: None,
                 },
             ],
+            peer_versions: SchemaVersions::CURRENT,
         };
         let (kernel_endpoint, kernel_worker, _kernel_layout) = build_service(kernel_spec)?;
         #[cfg(target_arch = "wasm32")]
@@ -100,6 +119,7 @@ impl TransportServices {
             },
             reply_policy: PortClass::Lossless,
             slot_pools: vec![],
+            peer_versions: SchemaVersions::CURRENT,
         };
         let (fs_endpoint, fs_worker, _fs_layout) = build_service(fs_spec)?;
         #[cfg(target_arch = "wasm32")]
@@ -119,13 +139,14 @@ impl TransportServices {
             },
             reply_policy: PortClass::Lossless,
             slot_pools: vec![],
+            peer_versions: SchemaVersions::CURRENT,
         };
         let (gpu_endpoint, gpu_worker, _gpu_layout) = build_service(gpu_spec)?;
         #[cfg(target_arch = "wasm32")]
         layout.add_endpoint(_gpu_layout);
 
         let audio_spec = ServiceSpec {
-            codec: AudioCodec,
+            codec: AudioCodec::default(),
             lossless: Some(RingSpec {
                 capacity_bytes: 32 * 1024,
                 envelope_tag: TAG_AUDIO_CMD,
@@ -137,7 +158,11 @@ impl TransportServices {
                 envelope_tag: TAG_AUDIO_REP,
             },
             reply_policy: PortClass::Lossless,
-            slot_pools: vec![],
+            // Audio pool (index 0, AUDIO_POOL)
+            slot_pools: vec![SlotPoolSpec::shared(Arc::clone(
+                &kernel_endpoint.slot_pools()[KERNEL_AUDIO_POOL],
+            ))],
+            peer_versions: SchemaVersions::CURRENT,
         };
         let (audio_endpoint, audio_worker, _audio_layout) = build_service(audio_spec)?;
         #[cfg(target_arch = "wasm32")]
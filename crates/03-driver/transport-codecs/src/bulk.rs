@@ -0,0 +1,158 @@
+//! Bulk payload staging through slot pools.
+//!
+//! ROMs, save states and battery RAM are copied into a slot pool and referenced
+//! from the message by a [`BulkSpanV2`], so they never pass through the command
+//! or report ring. Payloads larger than one slot span several; the producer
+//! pushes them to the ready ring in order and the consumer pops them back in
+//! the same order, as frames do.
+
+use std::sync::Arc;
+use transport::schema::{ArchivedBulkSpanV2, ArchivedSlotSpanV1, BulkSpanV2, SlotSpanV1};
+use transport::{SlotPoolHandle, SlotPop, SlotPush};
+use transport_fabric::{FabricError, FabricResult};
+
+/// Payloads up to this size stay inline in the message.
+pub const BULK_INLINE_MAX: usize = 4 * 1024;
+
+/// Stages `parts` like [`stage`], deciding what happens when the pool is full.
+///
+/// Returns `None` when the bytes can go inline instead: the pool is full but
+/// together they are smaller than `ring_bytes`, or no ring capacity is known.
+/// Larger payloads get [`FabricError::WouldBlock`] while they would fit the
+/// pool once it drains, and a codec error if they never could.
+pub(crate) fn stage_or_block(
+    pool: &SlotPoolHandle,
+    parts: &[&[u8]],
+    ring_bytes: Option<usize>,
+) -> FabricResult<Option<Vec<BulkSpanV2>>> {
+    if let Some(spans) = stage(pool, parts) {
+        return Ok(Some(spans));
+    }
+    let len: usize = parts.iter().map(|part| part.len()).sum();
+    match ring_bytes {
+        Some(ring_bytes) if len >= ring_bytes => {
+            let (slot_size, slot_count) =
+                pool.with_ref(|pool| (pool.slot_size(), pool.slot_count()));
+            let needed: usize = parts
+                .iter()
+                .map(|part| part.len().div_ceil(slot_size))
+                .sum();
+            if needed <= slot_count as usize {
+                Err(FabricError::WouldBlock)
+            } else {
+                Err(FabricError::codec(
+                    "bulk payload exceeds both its slot pool and the ring",
+                ))
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Single-part form of [`stage_or_block`].
+pub(crate) fn stage_one_or_block(
+    pool: &SlotPoolHandle,
+    bytes: &[u8],
+    ring_bytes: Option<usize>,
+) -> FabricResult<Option<BulkSpanV2>> {
+    Ok(stage_or_block(pool, &[bytes], ring_bytes)?.and_then(|mut spans| spans.pop()))
+}
+
+/// Copies each of `parts` into free slots of `pool` and marks them ready.
+///
+/// Returns one span per part, or `None` when the pool cannot take all of them.
+pub(crate) fn stage(pool: &SlotPoolHandle, parts: &[&[u8]]) -> Option<Vec<BulkSpanV2>> {
+    pool.with_mut(|pool| {
+        let slot_size = pool.slot_size();
+        let needed: usize = parts
+            .iter()
+            .map(|part| part.len().div_ceil(slot_size))
+            .sum();
+        let mut slots = Vec::with_capacity(needed);
+        while slots.len() < needed {
+            let Some(idx) = pool.try_acquire_free() else {
+                for idx in slots {
+                    pool.release_free(idx);
+                }
+                return None;
+            };
+            slots.push(idx);
+        }
+
+        let mut spans = Vec::with_capacity(parts.len());
+        let mut next = 0;
+        for part in parts {
+            let chunks = part.chunks(slot_size);
+            let span = SlotSpanV1 {
+                start_idx: slots.get(next).copied().unwrap_or(0),
+                count: chunks.len() as u32,
+            };
+            for chunk in chunks {
+                pool.slot_mut(slots[next])[..chunk.len()].copy_from_slice(chunk);
+                next += 1;
+            }
+            spans.push(BulkSpanV2 {
+                span,
+                len: part.len() as u32,
+            });
+        }
+
+        for (pushed, &idx) in slots.iter().enumerate() {
+            if pool.push_ready(idx) == SlotPush::WouldBlock {
+                // Slots already pushed are skipped by the consumer's resync.
+                for &idx in &slots[pushed..] {
+                    pool.release_free(idx);
+                }
+                return None;
+            }
+        }
+        Some(spans)
+    })
+}
+
+/// Pops the slots of `bulk` from `pool`, copies the payload out and frees them.
+pub(crate) fn take(pool: &SlotPoolHandle, bulk: &ArchivedBulkSpanV2) -> FabricResult<Arc<[u8]>> {
+    let bytes = take_span(pool, &bulk.span, bulk.len.to_native() as usize)?;
+    Ok(Arc::from(bytes))
+}
+
+/// Pops the `len` bytes held by `span` from `pool` and frees its slots.
+///
+/// Ready slots queued ahead of `span` belong to payloads whose message was
+/// never delivered (e.g. the send hit backpressure after staging) and are
+/// released.
+pub(crate) fn take_span(
+    pool: &SlotPoolHandle,
+    span: &ArchivedSlotSpanV1,
+    len: usize,
+) -> FabricResult<Vec<u8>> {
+    let start_idx = span.start_idx.to_native();
+    let count = span.count.to_native();
+    pool.with_mut(|pool| {
+        let slot_size = pool.slot_size();
+        if len > count as usize * slot_size {
+            return Err(FabricError::codec("pooled payload exceeds its slot span"));
+        }
+        let mut bytes = Vec::with_capacity(len);
+        let mut stale = 0;
+        for chunk in 0..count {
+            let idx = loop {
+                let SlotPop::Ok { slot_idx } = pool.pop_ready() else {
+                    return Err(FabricError::codec("pooled payload slots are not ready"));
+                };
+                if chunk > 0 || slot_idx == start_idx {
+                    break slot_idx;
+                }
+                pool.release_free(slot_idx);
+                stale += 1;
+                if stale >= pool.slot_count() {
+                    return Err(FabricError::codec("pooled payload slots are not ready"));
+                }
+            };
+            let take = (len - bytes.len()).min(slot_size);
+            bytes.extend_from_slice(&pool.slot_mut(idx)[..take]);
+            pool.release_free(idx);
+        }
+        Ok(bytes)
+    })
+}
@@ -6,6 +6,10 @@
 
 #![allow(missing_docs)]
 
+mod bulk;
+
+pub use bulk::BULK_INLINE_MAX;
+
 use rkyv::{
     api::high::{access, to_bytes, HighSerializer, HighValidator},
     bytecheck::CheckBytes,
@@ -15,181 +19,949 @@ use rkyv::{
     Archive, Serialize,
 };
 use service_abi::{
-    AudioCmd, AudioRep, AudioSpan, CpuVM, DebugCmd, DebugRep, FsCmd, FsRep, GpuCmd, GpuRep,
-    InspectorVMMinimal, KernelCmd, KernelRep, MemSpace, PpuVM, SlotSpan, StepKind, SubmitPolicy,
-    TickPurpose, TimersVM,
+    AudioCmd, AudioRep, AudioSpan, BreakReason, CpuVM, DebugCmd, DebugRep, DisasmLineVM,
+    ForkRejection, ForkedLane, FrameSpan, FsCmd, FsRep, GpuCmd, GpuRep, InspectorVMMinimal,
+    KernelCmd, KernelRep, LanesVM, MemAccessVM, MemSpace, PpuVM, RegistersPatch, SlotSpan,
+    StepKind, SubmitPolicy, TickPurpose, TimersVM, TraceEntryVM, WatchAccess,
 };
 use std::sync::Arc;
 use transport::schema::*;
-use transport::Envelope;
+use transport::{Envelope, SlotPoolHandle};
 use transport_fabric::{Codec, Encoded, FabricError, FabricResult, PortClass};
 
+/// Kernel endpoint slot pool staging large command payloads.
+pub const KERNEL_CMD_BULK_POOL: usize = 2;
+/// Kernel endpoint slot pool staging large report payloads.
+pub const KERNEL_REP_BULK_POOL: usize = 3;
+/// Kernel endpoint slot pool the kernel writes audio into.
+pub const KERNEL_AUDIO_POOL: usize = 1;
+/// Audio endpoint slot pool; the same pool as the kernel's [`KERNEL_AUDIO_POOL`].
+pub const AUDIO_POOL: usize = 0;
+
 /// Codec for kernel service commands and reports.
+///
+/// Once the endpoint's bulk pools are attached, ROMs, save states and battery
+/// RAM larger than [`BULK_INLINE_MAX`] travel through them instead of the rings.
+/// A payload that finds its pool full is sent inline if it fits the ring, and
+/// otherwise refused with [`FabricError::WouldBlock`] until the pool drains.
 #[derive(Clone, Default)]
-pub struct KernelCodec;
+pub struct KernelCodec {
+    cmd_bulk: Option<Arc<SlotPoolHandle>>,
+    rep_bulk: Option<Arc<SlotPoolHandle>>,
+    /// Capacity of the lossless command ring, once the endpoint reports it.
+    cmd_ring_bytes: Option<usize>,
+    /// Capacity of the report ring, once the endpoint reports it.
+    rep_ring_bytes: Option<usize>,
+}
+
+impl KernelCodec {
+    /// Stages large ROM and save-state loads in the command bulk pool.
+    fn stage_cmd(&self, cmd: &KernelCmd, ver: u8) -> FabricResult<Option<KernelCmdV2>> {
+        let Some(pool) = self
+            .cmd_bulk
+            .as_deref()
+            .filter(|_| ver >= SCHEMA_VERSION_V2)
+        else {
+            return Ok(None);
+        };
+        let ring = self.cmd_ring_bytes;
+        Ok(match cmd {
+            KernelCmd::LoadRom {
+                group,
+                bytes,
+                save_ram,
+            } if bytes.len() > BULK_INLINE_MAX => {
+                let mut parts: Vec<&[u8]> = vec![bytes];
+                parts.extend(save_ram.as_deref());
+                bulk::stage_or_block(pool, &parts, ring)?.map(|spans| {
+                    let mut spans = spans.into_iter();
+                    KernelCmdV2::LoadRomPooled(KernelLoadRomPooledCmdV2 {
+                        group: *group,
+                        rom: spans.next().expect("one span per part"),
+                        save_ram: spans.next(),
+                    })
+                })
+            }
+            KernelCmd::LoadState { group, lane, bytes } if bytes.len() > BULK_INLINE_MAX => {
+                bulk::stage_one_or_block(pool, bytes, ring)?.map(|span| {
+                    KernelCmdV2::LoadStatePooled(KernelLoadStatePooledCmdV2 {
+                        group: *group,
+                        lane: *lane,
+                        bytes: span,
+                    })
+                })
+            }
+            _ => None,
+        })
+    }
+
+    /// Stages large save states, forked lane states and battery RAM in the
+    /// report bulk pool.
+    fn stage_rep(&self, rep: &KernelRep, ver: u8) -> FabricResult<Option<KernelRepV2>> {
+        let Some(pool) = self
+            .rep_bulk
+            .as_deref()
+            .filter(|_| ver >= SCHEMA_VERSION_V2)
+        else {
+            return Ok(None);
+        };
+        let ring = self.rep_ring_bytes;
+        Ok(match rep {
+            KernelRep::SaveRamDirty { group, lane, bytes } if bytes.len() > BULK_INLINE_MAX => {
+                bulk::stage_one_or_block(pool, bytes, ring)?.map(|span| {
+                    KernelRepV2::SaveRamDirtyPooled {
+                        group: *group,
+                        lane: *lane,
+                        bytes: span,
+                    }
+                })
+            }
+            KernelRep::StateSaved {
+                group,
+                lane,
+                bytes: Some(bytes),
+            } if bytes.len() > BULK_INLINE_MAX => {
+                bulk::stage_one_or_block(pool, bytes, ring)?.map(|span| {
+                    KernelRepV2::StateSavedPooled {
+                        group: *group,
+                        lane: *lane,
+                        bytes: span,
+                    }
+                })
+            }
+            KernelRep::Forked {
+                group,
+                frames,
+                lanes,
+            } if lanes.iter().any(|lane| lane.state.len() > BULK_INLINE_MAX) => {
+                let parts: Vec<&[u8]> = lanes.iter().map(|lane| &*lane.state).collect();
+                bulk::stage_or_block(pool, &parts, ring)?.map(|spans| KernelRepV2::ForkedPooled {
+                    group: *group,
+                    frames: *frames,
+                    lanes: lanes
+                        .iter()
+                        .zip(spans)
+                        .map(|(lane, state)| KernelForkedLanePooledV2 {
+                            lane: lane.lane,
+                            state,
+                            thumb_width: lane.thumbnail.width,
+                            thumb_height: lane.thumbnail.height,
+                            thumbnail: lane.thumbnail.pixels.to_vec(),
+                        })
+                        .collect(),
+                })
+            }
+            _ => None,
+        })
+    }
+}
+
+fn bulk_pool(pool: &Option<Arc<SlotPoolHandle>>) -> FabricResult<&SlotPoolHandle> {
+    pool.as_deref()
+        .ok_or_else(|| FabricError::codec("pooled payload without an attached bulk pool"))
+}
 
 impl Codec for KernelCodec {
     type Cmd = KernelCmd;
     type Rep = KernelRep;
 
+    fn attach_slot_pools(&mut self, pools: &[Arc<SlotPoolHandle>]) {
+        self.cmd_bulk = pools.get(KERNEL_CMD_BULK_POOL).cloned();
+        self.rep_bulk = pools.get(KERNEL_REP_BULK_POOL).cloned();
+    }
+
+    fn attach_ring_capacities(&mut self, cmd_bytes: usize, rep_bytes: usize) {
+        self.cmd_ring_bytes = Some(cmd_bytes);
+        self.rep_ring_bytes = Some(rep_bytes);
+    }
+
     fn encode_cmd(&self, cmd: &Self::Cmd) -> FabricResult<Encoded> {
+        self.encode_cmd_as(cmd, SCHEMA_VERSION)
+    }
+
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::CURRENT
+    }
+
+    fn encode_cmd_as(&self, cmd: &Self::Cmd, ver: u8) -> FabricResult<Encoded> {
         let tag = TAG_KERNEL_CMD;
         let policy = default_kernel_policy(cmd);
-        let schema = match cmd {
-            KernelCmd::Tick {
-                purpose, budget, ..
-            } => KernelCmdV1::Tick(KernelTickCmdV1 {
-                purpose: match purpose {
-                    TickPurpose::Display => TickPurposeV1::Display,
-                    TickPurpose::Exploration => TickPurposeV1::Exploration,
-                },
-                budget: *budget,
-            }),
-            KernelCmd::LoadRom { bytes, .. } => KernelCmdV1::LoadRom(KernelLoadRomCmdV1 {
-                bytes: bytes.iter().copied().collect(),
-            }),
-            KernelCmd::SetInputs {
-                group,
-                lanes_mask,
-                joypad,
-            } => KernelCmdV1::SetInputs(KernelSetInputsCmdV1 {
-                group: *group,
-                lanes_mask: *lanes_mask,
-                joypad: *joypad,
-            }),
-            KernelCmd::Terminate { group } => {
-                KernelCmdV1::Terminate(KernelTerminateCmdV1 { group: *group })
-            }
-            KernelCmd::Debug(debug) => KernelCmdV1::Debug(encode_debug_cmd(debug)),
+        let schema = match self.stage_cmd(cmd, ver)? {
+            Some(pooled) => pooled,
+            None => match cmd {
+                KernelCmd::Tick {
+                    group,
+                    purpose,
+                    budget,
+                } => KernelCmdV2::Tick(KernelTickCmdV2 {
+                    group: *group,
+                    purpose: match purpose {
+                        TickPurpose::Display => TickPurposeV1::Display,
+                        TickPurpose::Exploration => TickPurposeV1::Exploration,
+                    },
+                    budget: *budget,
+                }),
+                KernelCmd::LoadRom {
+                    group,
+                    bytes,
+                    save_ram,
+                } => KernelCmdV2::LoadRom(KernelLoadRomCmdV2 {
+                    group: *group,
+                    bytes: bytes.to_vec(),
+                    save_ram: save_ram.as_deref().map(<[u8]>::to_vec),
+                }),
+                KernelCmd::SetInputs {
+                    group,
+                    lanes_mask,
+                    joypad,
+                } => KernelCmdV2::SetInputs(KernelSetInputsCmdV1 {
+                    group: *group,
+                    lanes_mask: *lanes_mask,
+                    joypad: *joypad,
+                }),
+                KernelCmd::Terminate { group } => {
+                    KernelCmdV2::Terminate(KernelTerminateCmdV1 { group: *group })
+                }
+                KernelCmd::Debug(DebugCmd::MemWindow {
+                    group,
+                    lane,
+                    space,
+                    base,
+                    len,
+                }) => {
+                    let (space, bank) = encode_mem_space(*space);
+                    KernelCmdV2::DebugMemWindow(KernelDebugMemWindowCmdV2 {
+                        group: *group,
+                        lane: *lane,
+                        space,
+                        base: *base,
+                        len: *len,
+                        bank,
+                    })
+                }
+                KernelCmd::Debug(debug) => KernelCmdV2::Debug(encode_debug_cmd(debug)),
+                KernelCmd::SaveState { group, lane } => {
+                    KernelCmdV2::SaveState(KernelSaveStateCmdV1 {
+                        group: *group,
+                        lane: *lane,
+                    })
+                }
+                KernelCmd::LoadState { group, lane, bytes } => {
+                    KernelCmdV2::LoadState(KernelLoadStateCmdV1 {
+                        group: *group,
+                        lane: *lane,
+                        bytes: bytes.to_vec(),
+                    })
+                }
+                KernelCmd::SetCheats { group, codes } => {
+                    KernelCmdV2::SetCheats(KernelSetCheatsCmdV1 {
+                        group: *group,
+                        codes: codes.clone(),
+                    })
+                }
+                KernelCmd::ResetLane {
+                    group,
+                    lane,
+                    rom,
+                    save_ram,
+                } => KernelCmdV2::ResetLane(KernelResetLaneCmdV1 {
+                    group: *group,
+                    lane: *lane,
+                    rom: rom.as_deref().map(<[u8]>::to_vec),
+                    save_ram: save_ram.as_deref().map(<[u8]>::to_vec),
+                }),
+                KernelCmd::Fork {
+                    group,
+                    source_lane,
+                    target_group,
+                    input_scripts,
+                } => KernelCmdV2::Fork(KernelForkCmdV1 {
+                    group: *group,
+                    source_lane: *source_lane,
+                    target_group: *target_group,
+                    input_scripts: input_scripts.iter().map(|script| script.to_vec()).collect(),
+                }),
+            },
         };
-        let payload = serialize(&schema)?;
-        Ok(Encoded::new(
-            policy,
-            Envelope::new(tag, SCHEMA_VERSION_V1),
-            payload,
-        ))
+        let payload = match ver {
+            SCHEMA_VERSION_V1 => serialize(&kernel_cmd_v1(schema)?)?,
+            SCHEMA_VERSION_V2 => serialize(&schema)?,
+            _ => return Err(unsupported_version(ver)),
+        };
+        Ok(Encoded::new(policy, Envelope::new(tag, ver), payload))
     }
 
     fn decode_cmd(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Cmd> {
         ensure_tag(envelope, TAG_KERNEL_CMD)?;
-        let archived = archived_root::<KernelCmdV1>(payload)?;
+        if envelope.ver == SCHEMA_VERSION_V1 {
+            return decode_kernel_cmd_v1(payload);
+        }
+        let archived = archived_root::<KernelCmdV2>(payload)?;
         match archived {
-            ArchivedKernelCmdV1::Tick(tick) => Ok(KernelCmd::Tick {
-                group: 0,
-                purpose: match tick.purpose {
-                    ArchivedTickPurposeV1::Display => TickPurpose::Display,
-                    ArchivedTickPurposeV1::Exploration => TickPurpose::Exploration,
-                },
+            ArchivedKernelCmdV2::Tick(tick) => Ok(KernelCmd::Tick {
+                group: tick.group.to_native(),
+                purpose: decode_tick_purpose(&tick.purpose),
                 budget: tick.budget.to_native(),
             }),
-            ArchivedKernelCmdV1::LoadRom(load) => Ok(KernelCmd::LoadRom {
-                group: 0,
+            ArchivedKernelCmdV2::LoadRom(load) => Ok(KernelCmd::LoadRom {
+                group: load.group.to_native(),
                 bytes: load.bytes.as_slice().into(),
+                save_ram: load.save_ram.as_ref().map(|ram| ram.as_slice().into()),
+            }),
+            ArchivedKernelCmdV2::LoadRomPooled(load) => {
+                let pool = bulk_pool(&self.cmd_bulk)?;
+                Ok(KernelCmd::LoadRom {
+                    group: load.group.to_native(),
+                    bytes: bulk::take(pool, &load.rom)?,
+                    save_ram: load
+                        .save_ram
+                        .as_ref()
+                        .map(|ram| bulk::take(pool, ram))
+                        .transpose()?,
+                })
+            }
+            ArchivedKernelCmdV2::LoadStatePooled(load) => Ok(KernelCmd::LoadState {
+                group: load.group.to_native(),
+                lane: load.lane.to_native(),
+                bytes: bulk::take(bulk_pool(&self.cmd_bulk)?, &load.bytes)?,
             }),
-            ArchivedKernelCmdV1::SetInputs(inputs) => Ok(KernelCmd::SetInputs {
+            ArchivedKernelCmdV2::SetInputs(inputs) => Ok(KernelCmd::SetInputs {
                 group: inputs.group.to_native(),
                 lanes_mask: inputs.lanes_mask.to_native(),
                 joypad: inputs.joypad,
             }),
-            ArchivedKernelCmdV1::Terminate(term) => Ok(KernelCmd::Terminate {
+            ArchivedKernelCmdV2::Terminate(term) => Ok(KernelCmd::Terminate {
                 group: term.group.to_native(),
             }),
-            ArchivedKernelCmdV1::Debug(debug) => Ok(KernelCmd::Debug(decode_debug_cmd(debug))),
+            ArchivedKernelCmdV2::Debug(debug) => Ok(KernelCmd::Debug(decode_debug_cmd(debug))),
+            ArchivedKernelCmdV2::DebugMemWindow(window) => {
+                Ok(KernelCmd::Debug(DebugCmd::MemWindow {
+                    group: window.group.to_native(),
+                    lane: window.lane.to_native(),
+                    space: decode_mem_space(&window.space, window.bank.to_native()),
+                    base: window.base.to_native(),
+                    len: window.len.to_native(),
+                }))
+            }
+            ArchivedKernelCmdV2::SaveState(save) => Ok(KernelCmd::SaveState {
+                group: save.group.to_native(),
+                lane: save.lane.to_native(),
+            }),
+            ArchivedKernelCmdV2::LoadState(load) => Ok(KernelCmd::LoadState {
+                group: load.group.to_native(),
+                lane: load.lane.to_native(),
+                bytes: load.bytes.as_slice().into(),
+            }),
+            ArchivedKernelCmdV2::SetCheats(cheats) => Ok(KernelCmd::SetCheats {
+                group: cheats.group.to_native(),
+                codes: cheats.codes.iter().map(|code| code.to_string()).collect(),
+            }),
+            ArchivedKernelCmdV2::ResetLane(reset) => Ok(KernelCmd::ResetLane {
+                group: reset.group.to_native(),
+                lane: reset.lane.to_native(),
+                rom: reset.rom.as_ref().map(|rom| rom.as_slice().into()),
+                save_ram: reset.save_ram.as_ref().map(|ram| ram.as_slice().into()),
+            }),
+            ArchivedKernelCmdV2::Fork(fork) => Ok(KernelCmd::Fork {
+                group: fork.group.to_native(),
+                source_lane: fork.source_lane.to_native(),
+                target_group: fork.target_group.to_native(),
+                input_scripts: fork
+                    .input_scripts
+                    .iter()
+                    .map(|script| script.as_slice().into())
+                    .collect(),
+            }),
         }
     }
 
     fn encode_rep(&self, rep: &Self::Rep) -> FabricResult<Encoded> {
+        self.encode_rep_as(rep, SCHEMA_VERSION)
+    }
+
+    fn encode_rep_as(&self, rep: &Self::Rep, ver: u8) -> FabricResult<Encoded> {
         let tag = TAG_KERNEL_REP;
-        let (class, schema) = match rep {
-            KernelRep::TickDone { .. } => (
-                PortClass::Lossless,
-                KernelRepV1::TickDone {
-                    purpose: TickPurposeV1::Display,
-                    budget: 0,
-                },
-            ),
-            KernelRep::LaneFrame {
-                lane,
-                frame_id,
-                span,
-                ..
-            } => (
-                PortClass::Lossless,
-                KernelRepV1::LaneFrame {
-                    lane: *lane,
-                    frame_id: *frame_id,
-                    span: encode_slot_span(span),
-                    pixels: span.pixels.to_vec(),
-                },
-            ),
-            KernelRep::RomLoaded { bytes_len, .. } => (
-                PortClass::Lossless,
-                KernelRepV1::RomLoaded {
-                    bytes_len: *bytes_len as u32,
-                },
-            ),
-            KernelRep::AudioReady { group, span, .. } => (
-                PortClass::Lossless,
-                KernelRepV1::AudioReady {
-                    group: *group,
-                    span: encode_audio_slot_span(span),
-                },
-            ),
-            KernelRep::DroppedThumb { .. } => {
-                return Err(FabricError::Unsupported(
-                    "kernel codec does not yet support thumbnail reports",
-                ))
-            }
-            KernelRep::Debug(rep) => {
-                let class = match rep {
-                    DebugRep::Snapshot(_) => PortClass::Coalesce,
-                    DebugRep::MemWindow { .. } | DebugRep::Stepped { .. } => PortClass::Lossless,
-                };
-                (class, KernelRepV1::Debug(encode_debug_rep(rep)))
-            }
+        let (class, schema) = match self.stage_rep(rep, ver)? {
+            Some(pooled) => (PortClass::Lossless, pooled),
+            None => match rep {
+                KernelRep::TickDone {
+                    group,
+                    lanes_mask,
+                    cycles_done,
+                } => (
+                    PortClass::Lossless,
+                    KernelRepV2::TickDone {
+                        group: *group,
+                        lanes_mask: *lanes_mask,
+                        cycles_done: *cycles_done,
+                    },
+                ),
+                KernelRep::LaneFrame {
+                    group,
+                    lane,
+                    frame_id,
+                    span,
+                } => (
+                    PortClass::Lossless,
+                    KernelRepV2::LaneFrame {
+                        group: *group,
+                        lane: *lane,
+                        frame_id: *frame_id,
+                        span: encode_slot_span(span),
+                        pixels: span.pixels.to_vec(),
+                    },
+                ),
+                KernelRep::RomLoaded { group, bytes_len } => (
+                    PortClass::Lossless,
+                    KernelRepV2::RomLoaded {
+                        group: *group,
+                        bytes_len: *bytes_len as u32,
+                    },
+                ),
+                KernelRep::AudioReady { group, span } => (
+                    PortClass::Lossless,
+                    match encode_audio_slots(span) {
+                        Some(audio) => KernelRepV2::AudioReadyPooled {
+                            group: *group,
+                            audio,
+                        },
+                        None => KernelRepV2::AudioReady {
+                            group: *group,
+                            span: encode_audio_slot_span(span),
+                        },
+                    },
+                ),
+                KernelRep::DroppedThumb { group, count } => (
+                    PortClass::Lossless,
+                    KernelRepV2::DroppedThumb {
+                        group: *group,
+                        count: *count,
+                    },
+                ),
+                KernelRep::Debug { group, rep } => {
+                    let class = match rep {
+                        DebugRep::Snapshot(_) | DebugRep::Lanes(_) => PortClass::Coalesce,
+                        DebugRep::MemWindow { .. }
+                        | DebugRep::Stepped { .. }
+                        | DebugRep::BreakpointHit { .. }
+                        | DebugRep::Disassembly { .. }
+                        | DebugRep::Trace { .. } => PortClass::Lossless,
+                    };
+                    (
+                        class,
+                        KernelRepV2::Debug {
+                            group: *group,
+                            rep: encode_debug_rep(rep),
+                        },
+                    )
+                }
+                KernelRep::SaveRamDirty { group, lane, bytes } => (
+                    PortClass::Lossless,
+                    KernelRepV2::SaveRamDirty {
+                        group: *group,
+                        lane: *lane,
+                        bytes: bytes.to_vec(),
+                    },
+                ),
+                KernelRep::StateSaved { group, lane, bytes } => (
+                    PortClass::Lossless,
+                    KernelRepV2::StateSaved {
+                        group: *group,
+                        lane: *lane,
+                        ok: bytes.is_some(),
+                        bytes: bytes.as_deref().map(<[u8]>::to_vec).unwrap_or_default(),
+                    },
+                ),
+                KernelRep::StateLoaded { group, lane, ok } => (
+                    PortClass::Lossless,
+                    KernelRepV2::StateLoaded {
+                        group: *group,
+                        lane: *lane,
+                        ok: *ok,
+                    },
+                ),
+                KernelRep::CheatsSet {
+                    group,
+                    active,
+                    rejected,
+                } => (
+                    PortClass::Lossless,
+                    KernelRepV2::CheatsSet {
+                        group: *group,
+                        active: *active,
+                        rejected: rejected.clone(),
+                    },
+                ),
+                KernelRep::LaneReset { group, lane, ok } => (
+                    PortClass::Lossless,
+                    KernelRepV2::LaneReset {
+                        group: *group,
+                        lane: *lane,
+                        ok: *ok,
+                    },
+                ),
+                KernelRep::Forked {
+                    group,
+                    frames,
+                    lanes,
+                } => (
+                    PortClass::Lossless,
+                    KernelRepV2::Forked {
+                        group: *group,
+                        frames: *frames,
+                        lanes: lanes.iter().map(encode_forked_lane).collect(),
+                    },
+                ),
+                KernelRep::ForkRejected { group, reason } => (
+                    PortClass::Lossless,
+                    KernelRepV2::ForkRejected {
+                        group: *group,
+                        reason: encode_fork_rejection(*reason),
+                    },
+                ),
+            },
         };
-        let payload = serialize(&schema)?;
-        Ok(Encoded::new(
-            class,
-            Envelope::new(tag, SCHEMA_VERSION_V1),
-            payload,
-        ))
+        let payload = match ver {
+            SCHEMA_VERSION_V1 => serialize(&kernel_rep_v1(schema)?)?,
+            SCHEMA_VERSION_V2 => serialize(&schema)?,
+            _ => return Err(unsupported_version(ver)),
+        };
+        Ok(Encoded::new(class, Envelope::new(tag, ver), payload))
     }
 
     fn decode_rep(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Rep> {
         ensure_tag(envelope, TAG_KERNEL_REP)?;
-        let archived = archived_root::<KernelRepV1>(payload)?;
+        if envelope.ver == SCHEMA_VERSION_V1 {
+            return decode_kernel_rep_v1(payload);
+        }
+        let archived = archived_root::<KernelRepV2>(payload)?;
         let rep = match archived {
-            ArchivedKernelRepV1::TickDone { .. } => KernelRep::TickDone {
-                group: 0,
-                lanes_mask: 0,
-                cycles_done: 0,
+            ArchivedKernelRepV2::TickDone {
+                group,
+                lanes_mask,
+                cycles_done,
+            } => KernelRep::TickDone {
+                group: group.to_native(),
+                lanes_mask: lanes_mask.to_native(),
+                cycles_done: cycles_done.to_native(),
             },
-            ArchivedKernelRepV1::LaneFrame {
+            ArchivedKernelRepV2::LaneFrame {
+                group,
                 lane,
                 frame_id,
                 span,
                 pixels,
             } => KernelRep::LaneFrame {
-                group: 0,
+                group: group.to_native(),
                 lane: lane.to_native(),
                 span: frame_span_from_parts(span, pixels),
                 frame_id: frame_id.to_native(),
             },
-            ArchivedKernelRepV1::RomLoaded { bytes_len } => KernelRep::RomLoaded {
-                group: 0,
+            ArchivedKernelRepV2::RomLoaded { group, bytes_len } => KernelRep::RomLoaded {
+                group: group.to_native(),
                 bytes_len: bytes_len.to_native() as usize,
             },
-            ArchivedKernelRepV1::AudioReady { group, span } => KernelRep::AudioReady {
+            ArchivedKernelRepV2::DroppedThumb { group, count } => KernelRep::DroppedThumb {
+                group: group.to_native(),
+                count: count.to_native(),
+            },
+            ArchivedKernelRepV2::AudioReady { group, span } => KernelRep::AudioReady {
                 group: group.to_native(),
                 span: audio_span_from_slot(span),
             },
-            ArchivedKernelRepV1::Debug(rep) => KernelRep::Debug(decode_debug_rep(rep)),
+            ArchivedKernelRepV2::AudioReadyPooled { group, audio } => KernelRep::AudioReady {
+                group: group.to_native(),
+                span: audio_span_from_slots(audio),
+            },
+            ArchivedKernelRepV2::Debug { group, rep } => KernelRep::Debug {
+                group: group.to_native(),
+                rep: decode_debug_rep(rep),
+            },
+            ArchivedKernelRepV2::SaveRamDirty { group, lane, bytes } => KernelRep::SaveRamDirty {
+                group: group.to_native(),
+                lane: lane.to_native(),
+                bytes: bytes.as_slice().into(),
+            },
+            ArchivedKernelRepV2::SaveRamDirtyPooled { group, lane, bytes } => {
+                KernelRep::SaveRamDirty {
+                    group: group.to_native(),
+                    lane: lane.to_native(),
+                    bytes: bulk::take(bulk_pool(&self.rep_bulk)?, bytes)?,
+                }
+            }
+            ArchivedKernelRepV2::StateSavedPooled { group, lane, bytes } => KernelRep::StateSaved {
+                group: group.to_native(),
+                lane: lane.to_native(),
+                bytes: Some(bulk::take(bulk_pool(&self.rep_bulk)?, bytes)?),
+            },
+            ArchivedKernelRepV2::StateSaved {
+                group,
+                lane,
+                ok,
+                bytes,
+            } => KernelRep::StateSaved {
+                group: group.to_native(),
+                lane: lane.to_native(),
+                bytes: ok.then(|| bytes.as_slice().into()),
+            },
+            ArchivedKernelRepV2::StateLoaded { group, lane, ok } => KernelRep::StateLoaded {
+                group: group.to_native(),
+                lane: lane.to_native(),
+                ok: *ok,
+            },
+            ArchivedKernelRepV2::CheatsSet {
+                group,
+                active,
+                rejected,
+            } => KernelRep::CheatsSet {
+                group: group.to_native(),
+                active: active.to_native(),
+                rejected: rejected.iter().map(|code| code.to_string()).collect(),
+            },
+            ArchivedKernelRepV2::LaneReset { group, lane, ok } => KernelRep::LaneReset {
+                group: group.to_native(),
+                lane: lane.to_native(),
+                ok: *ok,
+            },
+            ArchivedKernelRepV2::Forked {
+                group,
+                frames,
+                lanes,
+            } => KernelRep::Forked {
+                group: group.to_native(),
+                frames: frames.to_native(),
+                lanes: lanes.iter().map(decode_forked_lane).collect(),
+            },
+            ArchivedKernelRepV2::ForkRejected { group, reason } => KernelRep::ForkRejected {
+                group: group.to_native(),
+                reason: decode_fork_rejection(reason),
+            },
+            ArchivedKernelRepV2::ForkedPooled {
+                group,
+                frames,
+                lanes,
+            } => {
+                let pool = bulk_pool(&self.rep_bulk)?;
+                KernelRep::Forked {
+                    group: group.to_native(),
+                    frames: frames.to_native(),
+                    lanes: lanes
+                        .iter()
+                        .map(|lane| {
+                            Ok(ForkedLane {
+                                lane: lane.lane.to_native(),
+                                state: bulk::take(pool, &lane.state)?,
+                                thumbnail: FrameSpan {
+                                    width: lane.thumb_width.to_native(),
+                                    height: lane.thumb_height.to_native(),
+                                    pixels: lane.thumbnail.as_slice().into(),
+                                    slot_span: None,
+                                },
+                            })
+                        })
+                        .collect::<FabricResult<_>>()?,
+                }
+            }
         };
         Ok(rep)
     }
 }
 
+/// Lowers a V2 command for a V1 peer; ticks and ROM loads lose their group.
+fn kernel_cmd_v1(cmd: KernelCmdV2) -> FabricResult<KernelCmdV1> {
+    Ok(match cmd {
+        KernelCmdV2::Tick(tick) => KernelCmdV1::Tick(KernelTickCmdV1 {
+            purpose: tick.purpose,
+            budget: tick.budget,
+        }),
+        KernelCmdV2::LoadRom(KernelLoadRomCmdV2 {
+            bytes,
+            save_ram: None,
+            ..
+        }) => KernelCmdV1::LoadRom(KernelLoadRomCmdV1 { bytes }),
+        KernelCmdV2::LoadRom(KernelLoadRomCmdV2 {
+            mut bytes,
+            save_ram: Some(save_ram),
+            ..
+        }) => {
+            let rom_len = bytes.len() as u32;
+            bytes.extend_from_slice(&save_ram);
+            KernelCmdV1::LoadRomWithSave(KernelLoadRomWithSaveCmdV1 { rom_len, bytes })
+        }
+        KernelCmdV2::SetInputs(inputs) => KernelCmdV1::SetInputs(inputs),
+        KernelCmdV2::Terminate(term) => KernelCmdV1::Terminate(term),
+        KernelCmdV2::Debug(debug) => KernelCmdV1::Debug(debug),
+        KernelCmdV2::SaveState(save) => KernelCmdV1::SaveState(save),
+        KernelCmdV2::LoadState(load) => KernelCmdV1::LoadState(load),
+        KernelCmdV2::SetCheats(cheats) => KernelCmdV1::SetCheats(cheats),
+        KernelCmdV2::ResetLane(reset) => KernelCmdV1::ResetLane(Box::new(reset)),
+        KernelCmdV2::Fork(fork) => KernelCmdV1::Fork(Box::new(fork)),
+        KernelCmdV2::DebugMemWindow(window) if window.lane == 0 => {
+            KernelCmdV1::Debug(KernelDebugCmdV1::MemWindow {
+                group: window.group,
+                space: window.space,
+                base: window.base,
+                len: window.len,
+                bank: window.bank,
+            })
+        }
+        KernelCmdV2::DebugMemWindow(_) => {
+            return Err(FabricError::Unsupported(
+                "schema v1 reads memory windows from lane 0 only",
+            ))
+        }
+        KernelCmdV2::LoadRomPooled(_) | KernelCmdV2::LoadStatePooled(_) => {
+            return Err(FabricError::Unsupported(
+                "schema v1 cannot reference pooled payloads",
+            ))
+        }
+    })
+}
+
+/// Lowers a V2 report for a V1 peer; tick, frame, ROM and debug reports lose
+/// their group and tick totals.
+fn kernel_rep_v1(rep: KernelRepV2) -> FabricResult<KernelRepV1> {
+    Ok(match rep {
+        KernelRepV2::TickDone { .. } => KernelRepV1::TickDone {
+            purpose: TickPurposeV1::Display,
+            budget: 0,
+        },
+        KernelRepV2::LaneFrame {
+            lane,
+            frame_id,
+            span,
+            pixels,
+            ..
+        } => KernelRepV1::LaneFrame {
+            lane,
+            frame_id,
+            span,
+            pixels,
+        },
+        KernelRepV2::RomLoaded { bytes_len, .. } => KernelRepV1::RomLoaded { bytes_len },
+        KernelRepV2::AudioReady { group, span } => KernelRepV1::AudioReady { group, span },
+        KernelRepV2::AudioReadyPooled { group, audio } => KernelRepV1::AudioReady {
+            group,
+            span: audio.span,
+        },
+        KernelRepV2::DroppedThumb { .. } => {
+            return Err(FabricError::Unsupported(
+                "schema v1 cannot carry thumbnail reports",
+            ))
+        }
+        KernelRepV2::Debug { rep, .. } => KernelRepV1::Debug(rep),
+        KernelRepV2::SaveRamDirty {
+            group,
+            lane: 0,
+            bytes,
+        } => KernelRepV1::SaveRamDirty { group, bytes },
+        KernelRepV2::SaveRamDirty { .. } => {
+            return Err(FabricError::Unsupported(
+                "schema v1 only reports lane 0 battery RAM",
+            ))
+        }
+        KernelRepV2::StateSaved {
+            group,
+            lane,
+            ok,
+            bytes,
+        } => KernelRepV1::StateSaved {
+            group,
+            lane,
+            ok,
+            bytes,
+        },
+        KernelRepV2::StateLoaded { group, lane, ok } => {
+            KernelRepV1::StateLoaded { group, lane, ok }
+        }
+        KernelRepV2::CheatsSet {
+            group,
+            active,
+            rejected,
+        } => KernelRepV1::CheatsSet {
+            group,
+            active,
+            rejected,
+        },
+        KernelRepV2::LaneReset { group, lane, ok } => KernelRepV1::LaneReset { group, lane, ok },
+        KernelRepV2::Forked {
+            group,
+            frames,
+            lanes,
+        } => KernelRepV1::Forked {
+            group,
+            frames,
+            lanes,
+        },
+        KernelRepV2::ForkRejected { group, reason } => KernelRepV1::ForkRejected { group, reason },
+        KernelRepV2::SaveRamDirtyPooled { .. }
+        | KernelRepV2::StateSavedPooled { .. }
+        | KernelRepV2::ForkedPooled { .. } => {
+            return Err(FabricError::Unsupported(
+                "schema v1 cannot reference pooled payloads",
+            ))
+        }
+    })
+}
+
+/// Decodes a pre-V2 kernel command, which carries no group for ticks and ROM loads.
+fn decode_kernel_cmd_v1(payload: &[u8]) -> FabricResult<KernelCmd> {
+    let archived = archived_root::<KernelCmdV1>(payload)?;
+    match archived {
+        ArchivedKernelCmdV1::Tick(tick) => Ok(KernelCmd::Tick {
+            group: 0,
+            purpose: decode_tick_purpose(&tick.purpose),
+            budget: tick.budget.to_native(),
+        }),
+        ArchivedKernelCmdV1::LoadRom(load) => Ok(KernelCmd::LoadRom {
+            group: 0,
+            bytes: load.bytes.as_slice().into(),
+            save_ram: None,
+        }),
+        ArchivedKernelCmdV1::LoadRomWithSave(load) => {
+            let rom_len = load.rom_len.to_native() as usize;
+            if rom_len > load.bytes.len() {
+                return Err(FabricError::codec("load rom prefix exceeds payload"));
+            }
+            let (rom, save_ram) = load.bytes.as_slice().split_at(rom_len);
+            Ok(KernelCmd::LoadRom {
+                group: 0,
+                bytes: rom.into(),
+                save_ram: Some(save_ram.into()),
+            })
+        }
+        ArchivedKernelCmdV1::SetInputs(inputs) => Ok(KernelCmd::SetInputs {
+            group: inputs.group.to_native(),
+            lanes_mask: inputs.lanes_mask.to_native(),
+            joypad: inputs.joypad,
+        }),
+        ArchivedKernelCmdV1::Terminate(term) => Ok(KernelCmd::Terminate {
+            group: term.group.to_native(),
+        }),
+        ArchivedKernelCmdV1::Debug(debug) => Ok(KernelCmd::Debug(decode_debug_cmd(debug))),
+        ArchivedKernelCmdV1::SaveState(save) => Ok(KernelCmd::SaveState {
+            group: save.group.to_native(),
+            lane: save.lane.to_native(),
+        }),
+        ArchivedKernelCmdV1::LoadState(load) => Ok(KernelCmd::LoadState {
+            group: load.group.to_native(),
+            lane: load.lane.to_native(),
+            bytes: load.bytes.as_slice().into(),
+        }),
+        ArchivedKernelCmdV1::SetCheats(cheats) => Ok(KernelCmd::SetCheats {
+            group: cheats.group.to_native(),
+            codes: cheats.codes.iter().map(|code| code.to_string()).collect(),
+        }),
+        ArchivedKernelCmdV1::ResetLane(reset) => Ok(KernelCmd::ResetLane {
+            group: reset.group.to_native(),
+            lane: reset.lane.to_native(),
+            rom: reset.rom.as_ref().map(|rom| rom.as_slice().into()),
+            save_ram: reset.save_ram.as_ref().map(|ram| ram.as_slice().into()),
+        }),
+        ArchivedKernelCmdV1::Fork(fork) => Ok(KernelCmd::Fork {
+            group: fork.group.to_native(),
+            source_lane: fork.source_lane.to_native(),
+            target_group: fork.target_group.to_native(),
+            input_scripts: fork
+                .input_scripts
+                .iter()
+                .map(|script| script.as_slice().into())
+                .collect(),
+        }),
+    }
+}
+
+/// Decodes a pre-V2 kernel report; ticks, frames, ROM loads and debug reports
+/// fall back to group 0.
+fn decode_kernel_rep_v1(payload: &[u8]) -> FabricResult<KernelRep> {
+    let archived = archived_root::<KernelRepV1>(payload)?;
+    let rep = match archived {
+        ArchivedKernelRepV1::TickDone { .. } => KernelRep::TickDone {
+            group: 0,
+            lanes_mask: 0,
+            cycles_done: 0,
+        },
+        ArchivedKernelRepV1::LaneFrame {
+            lane,
+            frame_id,
+            span,
+            pixels,
+        } => KernelRep::LaneFrame {
+            group: 0,
+            lane: lane.to_native(),
+            span: frame_span_from_parts(span, pixels),
+            frame_id: frame_id.to_native(),
+        },
+        ArchivedKernelRepV1::RomLoaded { bytes_len } => KernelRep::RomLoaded {
+            group: 0,
+            bytes_len: bytes_len.to_native() as usize,
+        },
+        ArchivedKernelRepV1::AudioReady { group, span } => KernelRep::AudioReady {
+            group: group.to_native(),
+            span: audio_span_from_slot(span),
+        },
+        ArchivedKernelRepV1::Debug(rep) => KernelRep::Debug {
+            group: 0,
+            rep: decode_debug_rep(rep),
+        },
+        ArchivedKernelRepV1::SaveRamDirty { group, bytes } => KernelRep::SaveRamDirty {
+            group: group.to_native(),
+            lane: 0,
+            bytes: bytes.as_slice().into(),
+        },
+        ArchivedKernelRepV1::StateSaved {
+            group,
+            lane,
+            ok,
+            bytes,
+        } => KernelRep::StateSaved {
+            group: group.to_native(),
+            lane: lane.to_native(),
+            bytes: ok.then(|| bytes.as_slice().into()),
+        },
+        ArchivedKernelRepV1::StateLoaded { group, lane, ok } => KernelRep::StateLoaded {
+            group: group.to_native(),
+            lane: lane.to_native(),
+            ok: *ok,
+        },
+        ArchivedKernelRepV1::CheatsSet {
+            group,
+            active,
+            rejected,
+        } => KernelRep::CheatsSet {
+            group: group.to_native(),
+            active: active.to_native(),
+            rejected: rejected.iter().map(|code| code.to_string()).collect(),
+        },
+        ArchivedKernelRepV1::LaneReset { group, lane, ok } => KernelRep::LaneReset {
+            group: group.to_native(),
+            lane: lane.to_native(),
+            ok: *ok,
+        },
+        ArchivedKernelRepV1::Forked {
+            group,
+            frames,
+            lanes,
+        } => KernelRep::Forked {
+            group: group.to_native(),
+            frames: frames.to_native(),
+            lanes: lanes.iter().map(decode_forked_lane).collect(),
+        },
+        ArchivedKernelRepV1::ForkRejected { group, reason } => KernelRep::ForkRejected {
+            group: group.to_native(),
+            reason: decode_fork_rejection(reason),
+        },
+    };
+    Ok(rep)
+}
+
+fn decode_tick_purpose(purpose: &ArchivedTickPurposeV1) -> TickPurpose {
+    match purpose {
+        ArchivedTickPurposeV1::Display => TickPurpose::Display,
+        ArchivedTickPurposeV1::Exploration => TickPurpose::Exploration,
+    }
+}
+
 /// Codec for filesystem service commands and reports.
 #[derive(Clone, Default)]
 pub struct FsCodec;
@@ -205,11 +977,14 @@ impl Codec for FsCodec {
                 key: path.display().to_string(),
                 payload: bytes.iter().copied().collect(),
             }),
+            FsCmd::Load { path } => FsCmdV1::Load(FsLoadCmdV1 {
+                key: path.display().to_string(),
+            }),
         };
         let payload = serialize(&schema)?;
         Ok(Encoded::new(
             class,
-            Envelope::new(TAG_FS_CMD, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_FS_CMD, SCHEMA_VERSION),
             payload,
         ))
     }
@@ -222,6 +997,9 @@ impl Codec for FsCodec {
                 path: persist.key.as_str().into(),
                 bytes: persist.payload.as_slice().into(),
             }),
+            ArchivedFsCmdV1::Load(load) => Ok(FsCmd::Load {
+                path: load.key.as_str().into(),
+            }),
         }
     }
 
@@ -231,11 +1009,15 @@ impl Codec for FsCodec {
                 key: path.display().to_string(),
                 ok: *ok,
             },
+            FsRep::Loaded { path, bytes } => FsRepV1::Loaded(Box::new(FsLoadedRepV1 {
+                key: path.display().to_string(),
+                payload: bytes.as_ref().map(|bytes| bytes.to_vec()),
+            })),
         };
         let payload = serialize(&schema)?;
         Ok(Encoded::new(
             PortClass::Lossless,
-            Envelope::new(TAG_FS_REP, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_FS_REP, SCHEMA_VERSION),
             payload,
         ))
     }
@@ -248,8 +1030,27 @@ impl Codec for FsCodec {
                 path: key.as_str().into(),
                 ok: *ok,
             }),
+            ArchivedFsRepV1::Loaded(loaded) => Ok(FsRep::Loaded {
+                path: loaded.key.as_str().into(),
+                bytes: loaded
+                    .payload
+                    .as_ref()
+                    .map(|payload| payload.as_slice().into()),
+            }),
         }
     }
+
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::CURRENT
+    }
+
+    fn encode_cmd_as(&self, cmd: &Self::Cmd, ver: u8) -> FabricResult<Encoded> {
+        restamp(self.encode_cmd(cmd)?, ver)
+    }
+
+    fn encode_rep_as(&self, rep: &Self::Rep, ver: u8) -> FabricResult<Encoded> {
+        restamp(self.encode_rep(rep)?, ver)
+    }
 }
 
 /// Codec for GPU service commands and reports.
@@ -270,7 +1071,7 @@ impl Codec for GpuCodec {
         let payload = serialize(&schema)?;
         Ok(Encoded::new(
             PortClass::Lossless,
-            Envelope::new(TAG_GPU_CMD, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_GPU_CMD, SCHEMA_VERSION),
             payload,
         ))
     }
@@ -296,7 +1097,7 @@ impl Codec for GpuCodec {
         let payload = serialize(&schema)?;
         Ok(Encoded::new(
             PortClass::Lossless,
-            Envelope::new(TAG_GPU_REP, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_GPU_REP, SCHEMA_VERSION),
             payload,
         ))
     }
@@ -311,37 +1112,89 @@ impl Codec for GpuCodec {
             }),
         }
     }
+
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::CURRENT
+    }
+
+    fn encode_cmd_as(&self, cmd: &Self::Cmd, ver: u8) -> FabricResult<Encoded> {
+        restamp(self.encode_cmd(cmd)?, ver)
+    }
+
+    fn encode_rep_as(&self, rep: &Self::Rep, ver: u8) -> FabricResult<Encoded> {
+        restamp(self.encode_rep(rep)?, ver)
+    }
 }
 
 /// Codec for audio service commands and reports.
+///
+/// Spans in the audio slot pool are passed by reference; the worker side reads
+/// the samples out of the attached pool when it decodes them.
 #[derive(Clone, Default)]
-pub struct AudioCodec;
+pub struct AudioCodec {
+    pool: Option<Arc<SlotPoolHandle>>,
+}
 
 impl Codec for AudioCodec {
     type Cmd = AudioCmd;
     type Rep = AudioRep;
 
+    fn attach_slot_pools(&mut self, pools: &[Arc<SlotPoolHandle>]) {
+        self.pool = pools.get(AUDIO_POOL).cloned();
+    }
+
     fn encode_cmd(&self, cmd: &Self::Cmd) -> FabricResult<Encoded> {
+        self.encode_cmd_as(cmd, SCHEMA_VERSION)
+    }
+
+    fn versions(&self) -> SchemaVersions {
+        SchemaVersions::CURRENT
+    }
+
+    fn encode_cmd_as(&self, cmd: &Self::Cmd, ver: u8) -> FabricResult<Encoded> {
         let schema = match cmd {
-            AudioCmd::Submit { span } => AudioCmdV1::SubmitSamples {
-                frames: audio_frames(span) as u32,
+            AudioCmd::Submit { span } => match encode_audio_slots(span) {
+                Some(audio) => AudioCmdV2::SubmitPooled { audio },
+                None => AudioCmdV2::SubmitSamples {
+                    frames: span.frames() as u32,
+                },
             },
         };
-        let payload = serialize(&schema)?;
+        let payload = match ver {
+            SCHEMA_VERSION_V1 => serialize(&audio_cmd_v1(schema))?,
+            SCHEMA_VERSION_V2 => serialize(&schema)?,
+            _ => return Err(unsupported_version(ver)),
+        };
         Ok(Encoded::new(
             PortClass::Lossless,
-            Envelope::new(TAG_AUDIO_CMD, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_AUDIO_CMD, ver),
             payload,
         ))
     }
 
     fn decode_cmd(&self, envelope: Envelope, payload: &[u8]) -> FabricResult<Self::Cmd> {
         ensure_tag(envelope, TAG_AUDIO_CMD)?;
-        let archived = archived_root::<AudioCmdV1>(payload)?;
+        if envelope.ver == SCHEMA_VERSION_V1 {
+            let archived = archived_root::<AudioCmdV1>(payload)?;
+            return match archived {
+                ArchivedAudioCmdV1::SubmitSamples { frames } => Ok(AudioCmd::Submit {
+                    span: default_audio_span(frames.to_native() as usize),
+                }),
+            };
+        }
+        let archived = archived_root::<AudioCmdV2>(payload)?;
         match archived {
-            ArchivedAudioCmdV1::SubmitSamples { frames } => Ok(AudioCmd::Submit {
+            ArchivedAudioCmdV2::SubmitSamples { frames } => Ok(AudioCmd::Submit {
                 span: default_audio_span(frames.to_native() as usize),
             }),
+            ArchivedAudioCmdV2::SubmitPooled { audio } => {
+                let pool = self.pool.as_deref().ok_or_else(|| {
+                    FabricError::codec("pooled audio without an attached audio pool")
+                })?;
+                Ok(AudioCmd::Submit {
+                    span: take_audio_slots(pool, audio)?,
+                })
+            }
         }
     }
 
@@ -355,7 +1208,7 @@ impl Codec for AudioCodec {
         let payload = serialize(&schema)?;
         Ok(Encoded::new(
             PortClass::Lossless,
-            Envelope::new(TAG_AUDIO_REP, SCHEMA_VERSION_V1),
+            Envelope::new(TAG_AUDIO_REP, SCHEMA_VERSION),
             payload,
         ))
     }
@@ -370,27 +1223,119 @@ impl Codec for AudioCodec {
             ArchivedAudioRepV1::Underrun => Ok(AudioRep::Underrun),
         }
     }
+
+    fn encode_rep_as(&self, rep: &Self::Rep, ver: u8) -> FabricResult<Encoded> {
+        restamp(self.encode_rep(rep)?, ver)
+    }
+}
+
+/// Lowers an audio command for a V1 peer, which only learns the frame count
+/// of pooled submissions.
+fn audio_cmd_v1(cmd: AudioCmdV2) -> AudioCmdV1 {
+    match cmd {
+        AudioCmdV2::SubmitSamples { frames } => AudioCmdV1::SubmitSamples { frames },
+        AudioCmdV2::SubmitPooled { audio } => AudioCmdV1::SubmitSamples {
+            frames: audio.frames,
+        },
+    }
 }
 
 fn encode_debug_cmd(cmd: &DebugCmd) -> KernelDebugCmdV1 {
     match cmd {
         DebugCmd::Snapshot { group } => KernelDebugCmdV1::Snapshot { group: *group },
+        // Only reached for lane 0; other lanes travel as `KernelCmdV2::DebugMemWindow`.
         DebugCmd::MemWindow {
             group,
             space,
             base,
             len,
-        } => KernelDebugCmdV1::MemWindow {
-            group: *group,
-            space: encode_mem_space(*space),
-            base: *base,
-            len: *len,
-        },
+            ..
+        } => {
+            let (space, bank) = encode_mem_space(*space);
+            KernelDebugCmdV1::MemWindow {
+                group: *group,
+                space,
+                base: *base,
+                len: *len,
+                bank,
+            }
+        }
         DebugCmd::StepInstruction { group, count } => KernelDebugCmdV1::StepInstruction {
             group: *group,
             count: *count,
         },
         DebugCmd::StepFrame { group } => KernelDebugCmdV1::StepFrame { group: *group },
+        DebugCmd::SetBreakpoint { group, pc } => KernelDebugCmdV1::SetBreakpoint {
+            group: *group,
+            pc: *pc,
+        },
+        DebugCmd::ClearBreakpoint { group, pc } => KernelDebugCmdV1::ClearBreakpoint {
+            group: *group,
+            pc: *pc,
+        },
+        DebugCmd::SetWatchpoint {
+            group,
+            addr_range,
+            access,
+        } => KernelDebugCmdV1::SetWatchpoint {
+            group: *group,
+            start: *addr_range.start(),
+            end: *addr_range.end(),
+            access: encode_watch_access(*access),
+        },
+        DebugCmd::ClearWatchpoint {
+            group,
+            addr_range,
+            access,
+        } => KernelDebugCmdV1::ClearWatchpoint {
+            group: *group,
+            start: *addr_range.start(),
+            end: *addr_range.end(),
+            access: encode_watch_access(*access),
+        },
+        DebugCmd::Continue { group } => KernelDebugCmdV1::Continue { group: *group },
+        DebugCmd::Disassemble { group, base, count } => KernelDebugCmdV1::Disassemble {
+            group: *group,
+            base: *base,
+            count: *count,
+        },
+        DebugCmd::SetTracing {
+            group,
+            enabled,
+            capacity,
+        } => KernelDebugCmdV1::SetTracing {
+            group: *group,
+            enabled: *enabled,
+            capacity: *capacity,
+        },
+        DebugCmd::ReadTrace { group, max } => KernelDebugCmdV1::ReadTrace {
+            group: *group,
+            max: *max,
+        },
+        DebugCmd::WriteMem {
+            group,
+            lane,
+            space,
+            base,
+            bytes,
+        } => {
+            let (space, bank) = encode_mem_space(*space);
+            KernelDebugCmdV1::WriteMem(Box::new(KernelDebugWriteMemV1 {
+                group: *group,
+                lane: *lane,
+                space,
+                base: *base,
+                bytes: bytes.as_ref().to_vec(),
+                bank,
+            }))
+        }
+        DebugCmd::SetRegisters { group, lane, regs } => {
+            KernelDebugCmdV1::SetRegisters(Box::new(KernelDebugSetRegistersV1 {
+                group: *group,
+                lane: *lane,
+                regs: encode_registers_patch(regs),
+            }))
+        }
     }
 }
 
@@ -404,9 +1349,11 @@ fn decode_debug_cmd(cmd: &ArchivedKernelDebugCmdV1) -> DebugCmd {
             space,
             base,
             len,
+            bank,
         } => DebugCmd::MemWindow {
             group: group.to_native(),
-            space: decode_mem_space(space),
+            lane: 0,
+            space: decode_mem_space(space, bank.to_native()),
             base: base.to_native(),
             len: len.to_native(),
         },
@@ -417,17 +1364,82 @@ fn decode_debug_cmd(cmd: &ArchivedKernelDebugCmdV1) -> DebugCmd {
         ArchivedKernelDebugCmdV1::StepFrame { group } => DebugCmd::StepFrame {
             group: group.to_native(),
         },
+        ArchivedKernelDebugCmdV1::SetBreakpoint { group, pc } => DebugCmd::SetBreakpoint {
+            group: group.to_native(),
+            pc: pc.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::ClearBreakpoint { group, pc } => DebugCmd::ClearBreakpoint {
+            group: group.to_native(),
+            pc: pc.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::SetWatchpoint {
+            group,
+            start,
+            end,
+            access,
+        } => DebugCmd::SetWatchpoint {
+            group: group.to_native(),
+            addr_range: start.to_native()..=end.to_native(),
+            access: decode_watch_access(access),
+        },
+        ArchivedKernelDebugCmdV1::ClearWatchpoint {
+            group,
+            start,
+            end,
+            access,
+        } => DebugCmd::ClearWatchpoint {
+            group: group.to_native(),
+            addr_range: start.to_native()..=end.to_native(),
+            access: decode_watch_access(access),
+        },
+        ArchivedKernelDebugCmdV1::Continue { group } => DebugCmd::Continue {
+            group: group.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::Disassemble { group, base, count } => DebugCmd::Disassemble {
+            group: group.to_native(),
+            base: base.to_native(),
+            count: count.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::SetTracing {
+            group,
+            enabled,
+            capacity,
+        } => DebugCmd::SetTracing {
+            group: group.to_native(),
+            enabled: *enabled,
+            capacity: capacity.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::ReadTrace { group, max } => DebugCmd::ReadTrace {
+            group: group.to_native(),
+            max: max.to_native(),
+        },
+        ArchivedKernelDebugCmdV1::WriteMem(write) => DebugCmd::WriteMem {
+            group: write.group.to_native(),
+            lane: write.lane.to_native(),
+            space: decode_mem_space(&write.space, write.bank.to_native()),
+            base: write.base.to_native(),
+            bytes: Arc::<[u8]>::from(write.bytes.as_slice()),
+        },
+        ArchivedKernelDebugCmdV1::SetRegisters(set) => DebugCmd::SetRegisters {
+            group: set.group.to_native(),
+            lane: set.lane.to_native(),
+            regs: decode_registers_patch(&set.regs),
+        },
     }
 }
 
 fn encode_debug_rep(rep: &DebugRep) -> KernelDebugRepV1 {
     match rep {
         DebugRep::Snapshot(snapshot) => KernelDebugRepV1::Snapshot(encode_snapshot(snapshot)),
-        DebugRep::MemWindow { space, base, bytes } => KernelDebugRepV1::MemWindow {
-            space: encode_mem_space(*space),
-            base: *base,
-            bytes: bytes.as_ref().to_vec(),
-        },
+        DebugRep::MemWindow { space, base, bytes } => {
+            let (space, bank) = encode_mem_space(*space);
+            KernelDebugRepV1::MemWindow {
+                space,
+                base: *base,
+                bytes: bytes.as_ref().to_vec(),
+                bank,
+            }
+        }
         DebugRep::Stepped {
             kind,
             cycles,
@@ -439,6 +1451,32 @@ fn encode_debug_rep(rep: &DebugRep) -> KernelDebugRepV1 {
             pc: *pc,
             disasm: disasm.clone(),
         },
+        DebugRep::BreakpointHit { group, pc, reason } => KernelDebugRepV1::BreakpointHit {
+            group: *group,
+            pc: *pc,
+            reason: encode_break_reason(*reason),
+        },
+        DebugRep::Disassembly { base, lines } => KernelDebugRepV1::Disassembly {
+            base: *base,
+            lines: lines
+                .iter()
+                .map(|line| KernelDebugDisasmLineV1 {
+                    addr: line.addr,
+                    bytes: line.bytes.clone(),
+                    text: line.text.clone(),
+                })
+                .collect(),
+        },
+        DebugRep::Trace {
+            entries,
+            remaining,
+            dropped,
+        } => KernelDebugRepV1::Trace(Box::new(KernelDebugTraceChunkV1 {
+            entries: entries.iter().map(encode_trace_entry).collect(),
+            remaining: *remaining,
+            dropped: *dropped,
+        })),
+        DebugRep::Lanes(lanes) => KernelDebugRepV1::Lanes(Box::new(encode_lanes(lanes))),
     }
 }
 
@@ -447,8 +1485,13 @@ fn decode_debug_rep(rep: &ArchivedKernelDebugRepV1) -> DebugRep {
         ArchivedKernelDebugRepV1::Snapshot(snapshot) => {
             DebugRep::Snapshot(decode_snapshot(snapshot))
         }
-        ArchivedKernelDebugRepV1::MemWindow { space, base, bytes } => DebugRep::MemWindow {
-            space: decode_mem_space(space),
+        ArchivedKernelDebugRepV1::MemWindow {
+            space,
+            base,
+            bytes,
+            bank,
+        } => DebugRep::MemWindow {
+            space: decode_mem_space(space, bank.to_native()),
             base: base.to_native(),
             bytes: Arc::<[u8]>::from(bytes.as_slice()),
         },
@@ -463,6 +1506,28 @@ fn decode_debug_rep(rep: &ArchivedKernelDebugRepV1) -> DebugRep {
             pc: pc.to_native(),
             disasm: disasm.as_ref().map(|arch| arch.as_str().to_string()),
         },
+        ArchivedKernelDebugRepV1::BreakpointHit { group, pc, reason } => DebugRep::BreakpointHit {
+            group: group.to_native(),
+            pc: pc.to_native(),
+            reason: decode_break_reason(reason),
+        },
+        ArchivedKernelDebugRepV1::Disassembly { base, lines } => DebugRep::Disassembly {
+            base: base.to_native(),
+            lines: lines
+                .iter()
+                .map(|line| DisasmLineVM {
+                    addr: line.addr.to_native(),
+                    bytes: line.bytes.as_slice().to_vec(),
+                    text: line.text.as_str().to_string(),
+                })
+                .collect(),
+        },
+        ArchivedKernelDebugRepV1::Trace(chunk) => DebugRep::Trace {
+            entries: chunk.entries.iter().map(decode_trace_entry).collect(),
+            remaining: chunk.remaining.to_native(),
+            dropped: chunk.dropped.to_native(),
+        },
+        ArchivedKernelDebugRepV1::Lanes(lanes) => DebugRep::Lanes(decode_lanes(lanes)),
     }
 }
 
@@ -566,21 +1631,93 @@ fn decode_timers(timers: &ArchivedKernelDebugTimersVmV1) -> TimersVM {
     }
 }
 
-fn encode_mem_space(space: MemSpace) -> KernelDebugMemSpaceV1 {
+fn encode_forked_lane(lane: &ForkedLane) -> KernelForkedLaneV1 {
+    KernelForkedLaneV1 {
+        lane: lane.lane,
+        state: lane.state.to_vec(),
+        thumb_width: lane.thumbnail.width,
+        thumb_height: lane.thumbnail.height,
+        thumbnail: lane.thumbnail.pixels.to_vec(),
+    }
+}
+
+fn decode_forked_lane(lane: &ArchivedKernelForkedLaneV1) -> ForkedLane {
+    ForkedLane {
+        lane: lane.lane.to_native(),
+        state: lane.state.as_slice().into(),
+        thumbnail: FrameSpan {
+            width: lane.thumb_width.to_native(),
+            height: lane.thumb_height.to_native(),
+            pixels: lane.thumbnail.as_slice().into(),
+            slot_span: None,
+        },
+    }
+}
+
+fn encode_fork_rejection(reason: ForkRejection) -> KernelForkRejectionV1 {
+    match reason {
+        ForkRejection::SourceMissing => KernelForkRejectionV1::SourceMissing,
+        ForkRejection::TargetIsSource => KernelForkRejectionV1::TargetIsSource,
+        ForkRejection::TargetInUse => KernelForkRejectionV1::TargetInUse,
+        ForkRejection::ScriptCount => KernelForkRejectionV1::ScriptCount,
+        ForkRejection::ScriptTooLong => KernelForkRejectionV1::ScriptTooLong,
+        ForkRejection::StateRejected => KernelForkRejectionV1::StateRejected,
+    }
+}
+
+fn decode_fork_rejection(reason: &ArchivedKernelForkRejectionV1) -> ForkRejection {
+    match reason {
+        ArchivedKernelForkRejectionV1::SourceMissing => ForkRejection::SourceMissing,
+        ArchivedKernelForkRejectionV1::TargetIsSource => ForkRejection::TargetIsSource,
+        ArchivedKernelForkRejectionV1::TargetInUse => ForkRejection::TargetInUse,
+        ArchivedKernelForkRejectionV1::ScriptCount => ForkRejection::ScriptCount,
+        ArchivedKernelForkRejectionV1::ScriptTooLong => ForkRejection::ScriptTooLong,
+        ArchivedKernelForkRejectionV1::StateRejected => ForkRejection::StateRejected,
+    }
+}
+
+fn encode_lanes(lanes: &LanesVM) -> KernelDebugLanesVmV1 {
+    KernelDebugLanesVmV1 {
+        split_mask: lanes.split_mask,
+        splits: lanes.splits,
+        merges: lanes.merges,
+        split_cycles: lanes.split_cycles,
+    }
+}
+
+fn decode_lanes(lanes: &ArchivedKernelDebugLanesVmV1) -> LanesVM {
+    LanesVM {
+        split_mask: lanes.split_mask.to_native(),
+        splits: lanes.splits.to_native(),
+        merges: lanes.merges.to_native(),
+        split_cycles: lanes.split_cycles.to_native(),
+    }
+}
+
+/// Splits a memory space into its wire tag and bank (zero for unbanked spaces).
+fn encode_mem_space(space: MemSpace) -> (KernelDebugMemSpaceV1, u16) {
     match space {
-        MemSpace::Vram => KernelDebugMemSpaceV1::Vram,
-        MemSpace::Wram => KernelDebugMemSpaceV1::Wram,
-        MemSpace::Oam => KernelDebugMemSpaceV1::Oam,
-        MemSpace::Io => KernelDebugMemSpaceV1::Io,
+        MemSpace::Vram => (KernelDebugMemSpaceV1::Vram, 0),
+        MemSpace::Wram => (KernelDebugMemSpaceV1::Wram, 0),
+        MemSpace::Oam => (KernelDebugMemSpaceV1::Oam, 0),
+        MemSpace::Io => (KernelDebugMemSpaceV1::Io, 0),
+        MemSpace::Hram => (KernelDebugMemSpaceV1::Hram, 0),
+        MemSpace::Rom { bank } => (KernelDebugMemSpaceV1::Rom, bank),
+        MemSpace::CartRam { bank } => (KernelDebugMemSpaceV1::CartRam, u16::from(bank)),
+        MemSpace::Bus => (KernelDebugMemSpaceV1::Bus, 0),
     }
 }
 
-fn decode_mem_space(space: &ArchivedKernelDebugMemSpaceV1) -> MemSpace {
+fn decode_mem_space(space: &ArchivedKernelDebugMemSpaceV1, bank: u16) -> MemSpace {
     match space {
         ArchivedKernelDebugMemSpaceV1::Vram => MemSpace::Vram,
         ArchivedKernelDebugMemSpaceV1::Wram => MemSpace::Wram,
         ArchivedKernelDebugMemSpaceV1::Oam => MemSpace::Oam,
         ArchivedKernelDebugMemSpaceV1::Io => MemSpace::Io,
+        ArchivedKernelDebugMemSpaceV1::Hram => MemSpace::Hram,
+        ArchivedKernelDebugMemSpaceV1::Rom => MemSpace::Rom { bank },
+        ArchivedKernelDebugMemSpaceV1::CartRam => MemSpace::CartRam { bank: bank as u8 },
+        ArchivedKernelDebugMemSpaceV1::Bus => MemSpace::Bus,
     }
 }
 
@@ -598,6 +1735,124 @@ fn decode_step_kind(kind: &ArchivedKernelDebugStepKindV1) -> StepKind {
     }
 }
 
+fn encode_watch_access(access: WatchAccess) -> KernelDebugWatchAccessV1 {
+    match access {
+        WatchAccess::Read => KernelDebugWatchAccessV1::Read,
+        WatchAccess::Write => KernelDebugWatchAccessV1::Write,
+    }
+}
+
+fn decode_watch_access(access: &ArchivedKernelDebugWatchAccessV1) -> WatchAccess {
+    match access {
+        ArchivedKernelDebugWatchAccessV1::Read => WatchAccess::Read,
+        ArchivedKernelDebugWatchAccessV1::Write => WatchAccess::Write,
+    }
+}
+
+fn encode_break_reason(reason: BreakReason) -> KernelDebugBreakReasonV1 {
+    match reason {
+        BreakReason::Breakpoint => KernelDebugBreakReasonV1::Breakpoint,
+        BreakReason::Watchpoint { addr, access } => KernelDebugBreakReasonV1::Watchpoint {
+            addr,
+            access: encode_watch_access(access),
+        },
+    }
+}
+
+fn decode_break_reason(reason: &ArchivedKernelDebugBreakReasonV1) -> BreakReason {
+    match reason {
+        ArchivedKernelDebugBreakReasonV1::Breakpoint => BreakReason::Breakpoint,
+        ArchivedKernelDebugBreakReasonV1::Watchpoint { addr, access } => BreakReason::Watchpoint {
+            addr: addr.to_native(),
+            access: decode_watch_access(access),
+        },
+    }
+}
+
+fn encode_registers_patch(regs: &RegistersPatch) -> KernelDebugRegistersPatchV1 {
+    KernelDebugRegistersPatchV1 {
+        a: regs.a,
+        f: regs.f,
+        b: regs.b,
+        c: regs.c,
+        d: regs.d,
+        e: regs.e,
+        h: regs.h,
+        l: regs.l,
+        sp: regs.sp,
+        pc: regs.pc,
+        ime: regs.ime,
+    }
+}
+
+fn decode_registers_patch(regs: &ArchivedKernelDebugRegistersPatchV1) -> RegistersPatch {
+    RegistersPatch {
+        a: regs.a.as_ref().copied(),
+        f: regs.f.as_ref().copied(),
+        b: regs.b.as_ref().copied(),
+        c: regs.c.as_ref().copied(),
+        d: regs.d.as_ref().copied(),
+        e: regs.e.as_ref().copied(),
+        h: regs.h.as_ref().copied(),
+        l: regs.l.as_ref().copied(),
+        sp: regs.sp.as_ref().map(|sp| sp.to_native()),
+        pc: regs.pc.as_ref().map(|pc| pc.to_native()),
+        ime: regs.ime.as_ref().copied(),
+    }
+}
+
+fn encode_trace_entry(entry: &TraceEntryVM) -> KernelDebugTraceEntryV1 {
+    KernelDebugTraceEntryV1 {
+        cycle: entry.cycle,
+        pc: entry.pc,
+        pcmem: entry.pcmem,
+        a: entry.a,
+        f: entry.f,
+        b: entry.b,
+        c: entry.c,
+        d: entry.d,
+        e: entry.e,
+        h: entry.h,
+        l: entry.l,
+        sp: entry.sp,
+        accesses: entry
+            .accesses
+            .iter()
+            .map(|access| KernelDebugMemAccessV1 {
+                addr: access.addr,
+                value: access.value,
+                access: encode_watch_access(access.access),
+            })
+            .collect(),
+    }
+}
+
+fn decode_trace_entry(entry: &ArchivedKernelDebugTraceEntryV1) -> TraceEntryVM {
+    TraceEntryVM {
+        cycle: entry.cycle.to_native(),
+        pc: entry.pc.to_native(),
+        pcmem: entry.pcmem,
+        a: entry.a,
+        f: entry.f,
+        b: entry.b,
+        c: entry.c,
+        d: entry.d,
+        e: entry.e,
+        h: entry.h,
+        l: entry.l,
+        sp: entry.sp.to_native(),
+        accesses: entry
+            .accesses
+            .iter()
+            .map(|access| MemAccessVM {
+                addr: access.addr.to_native(),
+                value: access.value,
+                access: decode_watch_access(&access.access),
+            })
+            .collect(),
+    }
+}
+
 fn serialize<T>(value: &T) -> FabricResult<Vec<u8>>
 where
     T: Archive,
@@ -617,6 +1872,10 @@ fn default_kernel_policy(cmd: &KernelCmd) -> PortClass {
         KernelCmd::LoadRom { .. } => PortClass::Lossless,
         KernelCmd::SetInputs { .. } => PortClass::Lossless,
         KernelCmd::Terminate { .. } => PortClass::Lossless,
+        KernelCmd::SaveState { .. } | KernelCmd::LoadState { .. } => PortClass::Lossless,
+        KernelCmd::ResetLane { .. } => PortClass::Lossless,
+        KernelCmd::SetCheats { .. } => PortClass::Lossless,
+        KernelCmd::Fork { .. } => PortClass::BestEffort,
         KernelCmd::Debug(cmd) => class_from_policy(cmd.submit_policy()),
     }
 }
@@ -636,15 +1895,30 @@ fn ensure_tag(envelope: Envelope, expected: u8) -> FabricResult<()> {
             envelope.tag, expected
         )));
     }
-    if envelope.ver != SCHEMA_VERSION_V1 {
-        return Err(FabricError::codec(format!(
-            "schema version mismatch: {} vs {}",
-            envelope.ver, SCHEMA_VERSION_V1
-        )));
+    if !SchemaVersions::CURRENT.contains(envelope.ver) {
+        return Err(unsupported_version(envelope.ver));
     }
     Ok(())
 }
 
+fn unsupported_version(ver: u8) -> FabricError {
+    let supported = SchemaVersions::CURRENT;
+    FabricError::codec(format!(
+        "unsupported schema version {ver} (expected {}..={})",
+        supported.min, supported.max
+    ))
+}
+
+/// Restamps an encoding for an older peer. Filesystem, GPU and audio report
+/// payloads are unchanged since V1, so only the envelope version differs.
+fn restamp(mut encoded: Encoded, ver: u8) -> FabricResult<Encoded> {
+    if !SchemaVersions::CURRENT.contains(ver) {
+        return Err(unsupported_version(ver));
+    }
+    encoded.envelope.ver = ver;
+    Ok(encoded)
+}
+
 fn archived_root<T>(payload: &[u8]) -> FabricResult<&rkyv::Archived<T>>
 where
     T: Archive,
@@ -728,7 +2002,53 @@ fn default_audio_span(_frames: usize) -> AudioSpan {
     AudioSpan::empty()
 }
 
-fn audio_frames(span: &AudioSpan) -> usize {
-    let channels = span.channels.max(1) as usize;
-    span.samples.len() / channels
+/// Describes a span whose samples live in the audio slot pool.
+fn encode_audio_slots(span: &AudioSpan) -> Option<AudioSlotSpanV2> {
+    let slot = span.slot_span.as_ref()?;
+    Some(AudioSlotSpanV2 {
+        span: SlotSpanV1 {
+            start_idx: slot.start_idx,
+            count: slot.count,
+        },
+        frames: span.slot_frames,
+        channels: span.channels,
+        sample_rate_hz: span.sample_rate_hz,
+    })
+}
+
+fn audio_span_from_slots(audio: &ArchivedAudioSlotSpanV2) -> AudioSpan {
+    AudioSpan {
+        samples: Arc::from([]),
+        channels: audio.channels,
+        sample_rate_hz: audio.sample_rate_hz.to_native(),
+        slot_span: Some(SlotSpan {
+            start_idx: audio.span.start_idx.to_native(),
+            count: audio.span.count.to_native(),
+        }),
+        slot_frames: audio.frames.to_native(),
+    }
+}
+
+/// Reads the interleaved `f32` samples of `audio` out of `pool` and frees
+/// their slots.
+fn take_audio_slots(
+    pool: &SlotPoolHandle,
+    audio: &ArchivedAudioSlotSpanV2,
+) -> FabricResult<AudioSpan> {
+    let samples = audio.frames.to_native() as usize * usize::from(audio.channels);
+    let bytes = bulk::take_span(pool, &audio.span, samples * size_of::<f32>())?;
+    let samples: Vec<i16> = bytes
+        .chunks_exact(size_of::<f32>())
+        .map(|chunk| {
+            let sample = f32::from_le_bytes(chunk.try_into().expect("four-byte chunk"));
+            (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16
+        })
+        .collect();
+    Ok(AudioSpan {
+        samples: Arc::from(samples),
+        channels: audio.channels,
+        sample_rate_hz: audio.sample_rate_hz.to_native(),
+        slot_span: None,
+        slot_frames: 0,
+    })
 }
@@ -0,0 +1,155 @@
+//! Kernel audio passed to the audio service through the shared audio slot pool.
+
+use std::sync::Arc;
+
+use service_abi::{AudioCmd, AudioSpan, KernelRep, SlotSpan};
+use transport::schema::SCHEMA_VERSION_V1;
+use transport::{SlotPool, SlotPoolConfig, SlotPoolHandle, SlotPush};
+use transport_codecs::{AudioCodec, KernelCodec, AUDIO_POOL, KERNEL_AUDIO_POOL};
+use transport_fabric::Codec;
+
+const SLOT_SIZE: usize = 1024;
+
+fn audio_pool() -> Arc<SlotPoolHandle> {
+    Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: SLOT_SIZE,
+        })
+        .expect("slot pool"),
+    ))
+}
+
+fn audio_codec(pool: &Arc<SlotPoolHandle>) -> AudioCodec {
+    let mut codec = AudioCodec::default();
+    codec.attach_slot_pools(&vec![Arc::clone(pool); AUDIO_POOL + 1]);
+    codec
+}
+
+/// Writes `samples` as interleaved `f32` into consecutive slots, as the kernel does.
+fn write_samples(pool: &SlotPoolHandle, samples: &[i16], frames: u32) -> AudioSpan {
+    let slot_span = pool.with_mut(|pool| {
+        let chunks = samples.chunks(SLOT_SIZE / 4);
+        let count = chunks.len() as u32;
+        let mut start_idx = None;
+        for chunk in chunks {
+            let idx = pool.try_acquire_free().expect("free slot");
+            start_idx.get_or_insert(idx);
+            for (out, &sample) in pool.slot_mut(idx).chunks_exact_mut(4).zip(chunk) {
+                out.copy_from_slice(&(f32::from(sample) / 32768.0).to_le_bytes());
+            }
+            assert_eq!(pool.push_ready(idx), SlotPush::Ok);
+        }
+        SlotSpan {
+            start_idx: start_idx.expect("at least one slot"),
+            count,
+        }
+    });
+    AudioSpan {
+        slot_span: Some(slot_span),
+        slot_frames: frames,
+        ..AudioSpan::empty()
+    }
+}
+
+fn samples(len: usize) -> Vec<i16> {
+    (0..len)
+        .map(|i| (i as i16).wrapping_mul(97).wrapping_sub(12_000))
+        .collect()
+}
+
+/// Counts free slots by acquiring and releasing all of them.
+fn free_len(pool: &SlotPoolHandle) -> u32 {
+    pool.with_mut(|pool| {
+        let held: Vec<u32> = std::iter::from_fn(|| pool.try_acquire_free()).collect();
+        for &idx in &held {
+            pool.release_free(idx);
+        }
+        held.len() as u32
+    })
+}
+
+#[test]
+fn kernel_audio_span_survives_report_roundtrip() {
+    let pool = audio_pool();
+    let mut kernel = KernelCodec::default();
+    kernel.attach_slot_pools(&vec![Arc::clone(&pool); KERNEL_AUDIO_POOL + 1]);
+    let rep = KernelRep::AudioReady {
+        group: 4,
+        span: write_samples(&pool, &samples(600), 300),
+    };
+
+    let encoded = kernel.encode_rep(&rep).expect("encode");
+    assert!(encoded.payload.len() < 600, "samples stay in the pool");
+    let decoded = kernel
+        .decode_rep(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, rep);
+    assert_eq!(free_len(&pool), 1, "the report does not consume the slots");
+}
+
+#[test]
+fn audio_submit_reads_samples_out_of_pool() {
+    let pool = audio_pool();
+    let codec = audio_codec(&pool);
+    let samples = samples(600);
+    let cmd = AudioCmd::Submit {
+        span: write_samples(&pool, &samples, 300),
+    };
+
+    let encoded = codec.encode_cmd(&cmd).expect("encode");
+    let AudioCmd::Submit { span } = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(span.samples.as_ref(), samples.as_slice());
+    assert_eq!(span.slot_span, None);
+    assert_eq!(span.frames(), 300);
+    assert_eq!(free_len(&pool), 4);
+}
+
+#[test]
+fn dropped_audio_is_released_by_next_submit() {
+    let pool = audio_pool();
+    let codec = audio_codec(&pool);
+    write_samples(&pool, &samples(256), 128);
+
+    let cmd = AudioCmd::Submit {
+        span: write_samples(&pool, &samples(8), 4),
+    };
+    let encoded = codec.encode_cmd(&cmd).expect("encode");
+    let AudioCmd::Submit { span } = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(span.frames(), 4);
+    assert_eq!(free_len(&pool), 4);
+}
+
+#[test]
+fn v1_peers_receive_frame_counts_only() {
+    let pool = audio_pool();
+    let codec = audio_codec(&pool);
+    let cmd = AudioCmd::Submit {
+        span: write_samples(&pool, &samples(64), 32),
+    };
+
+    let encoded = codec
+        .encode_cmd_as(&cmd, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    assert_eq!(encoded.envelope.ver, SCHEMA_VERSION_V1);
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode v1");
+    assert!(matches!(decoded, AudioCmd::Submit { span } if span.slot_span.is_none()));
+}
+
+#[test]
+fn pooled_audio_without_pool_is_rejected() {
+    let pool = audio_pool();
+    let cmd = AudioCmd::Submit {
+        span: write_samples(&pool, &samples(64), 32),
+    };
+    let encoded = audio_codec(&pool).encode_cmd(&cmd).expect("encode");
+    assert!(AudioCodec::default()
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .is_err());
+}
@@ -0,0 +1,291 @@
+//! Large kernel payloads staged through the bulk slot pools.
+
+use std::sync::Arc;
+
+use service_abi::{ForkedLane, FrameSpan, KernelCmd, KernelRep};
+use transport::schema::{SchemaVersions, SCHEMA_VERSION_V1, TAG_KERNEL_CMD, TAG_KERNEL_REP};
+use transport::{SlotPool, SlotPoolConfig, SlotPoolHandle};
+use transport_codecs::{KernelCodec, BULK_INLINE_MAX, KERNEL_CMD_BULK_POOL, KERNEL_REP_BULK_POOL};
+use transport_fabric::{
+    build_service, Codec, FabricError, PortClass, RingSpec, ServiceSpec, SlotPoolSpec,
+    SubmitOutcome,
+};
+
+const SLOT_SIZE: usize = 16 * 1024;
+
+fn pool(slot_count: u32) -> Arc<SlotPoolHandle> {
+    Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count,
+            slot_size: SLOT_SIZE,
+        })
+        .expect("slot pool"),
+    ))
+}
+
+/// Returns a codec with bulk pools attached at the kernel endpoint indices.
+fn pooled_codec(slot_count: u32) -> (KernelCodec, Arc<SlotPoolHandle>, Arc<SlotPoolHandle>) {
+    let (cmd_pool, rep_pool) = (pool(slot_count), pool(slot_count));
+    let mut pools = vec![pool(1); KERNEL_REP_BULK_POOL + 1];
+    pools[KERNEL_CMD_BULK_POOL] = Arc::clone(&cmd_pool);
+    pools[KERNEL_REP_BULK_POOL] = Arc::clone(&rep_pool);
+    let mut codec = KernelCodec::default();
+    codec.attach_slot_pools(&pools);
+    (codec, cmd_pool, rep_pool)
+}
+
+fn bytes(len: usize, seed: u8) -> Arc<[u8]> {
+    (0..len)
+        .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+        .collect()
+}
+
+/// Counts free slots by acquiring and releasing all of them.
+fn free_len(pool: &SlotPoolHandle) -> u32 {
+    pool.with_mut(|pool| {
+        let held: Vec<u32> = std::iter::from_fn(|| pool.try_acquire_free()).collect();
+        for &idx in &held {
+            pool.release_free(idx);
+        }
+        held.len() as u32
+    })
+}
+
+#[test]
+fn large_rom_travels_through_pool_in_chunks() {
+    let (codec, cmd_pool, _) = pooled_codec(8);
+    let cmd = KernelCmd::LoadRom {
+        group: 2,
+        bytes: bytes(3 * SLOT_SIZE + 100, 1),
+        save_ram: Some(bytes(SLOT_SIZE + 1, 2)),
+    };
+    let encoded = codec.encode_cmd(&cmd).expect("encode");
+    assert!(encoded.payload.len() < BULK_INLINE_MAX);
+    assert_eq!(free_len(&cmd_pool), 2, "rom and save ram occupy six slots");
+
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, cmd);
+    assert_eq!(free_len(&cmd_pool), 8);
+}
+
+#[test]
+fn save_state_and_battery_ram_reports_use_report_pool() {
+    let (codec, cmd_pool, rep_pool) = pooled_codec(4);
+    let load = KernelCmd::LoadState {
+        group: 1,
+        lane: 3,
+        bytes: bytes(SLOT_SIZE * 2, 3),
+    };
+    let saved = KernelRep::StateSaved {
+        group: 1,
+        lane: 3,
+        bytes: Some(bytes(SLOT_SIZE * 2, 4)),
+    };
+    let dirty = KernelRep::SaveRamDirty {
+        group: 1,
+        lane: 2,
+        bytes: bytes(SLOT_SIZE, 5),
+    };
+
+    let encoded = codec.encode_cmd(&load).expect("encode");
+    assert!(encoded.payload.len() < BULK_INLINE_MAX);
+    assert_eq!(free_len(&cmd_pool), 2);
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, load);
+
+    for rep in [saved, dirty] {
+        let encoded = codec.encode_rep(&rep).expect("encode");
+        assert!(encoded.payload.len() < BULK_INLINE_MAX);
+        let decoded = codec
+            .decode_rep(encoded.envelope, &encoded.payload)
+            .expect("decode");
+        assert_eq!(decoded, rep);
+    }
+    assert_eq!(free_len(&rep_pool), 4);
+}
+
+#[test]
+fn forked_lane_states_use_report_pool() {
+    let (codec, _, rep_pool) = pooled_codec(4);
+    let lane = |lane: u16| ForkedLane {
+        lane,
+        state: bytes(SLOT_SIZE + 1, lane as u8),
+        thumbnail: FrameSpan {
+            width: 2,
+            height: 1,
+            pixels: bytes(8, 9),
+            slot_span: None,
+        },
+    };
+    let forked = KernelRep::Forked {
+        group: 4,
+        frames: 60,
+        lanes: vec![lane(0), lane(1)],
+    };
+
+    let encoded = codec.encode_rep(&forked).expect("encode");
+    assert!(encoded.payload.len() < BULK_INLINE_MAX);
+    assert_eq!(free_len(&rep_pool), 0, "each lane state occupies two slots");
+    let decoded = codec
+        .decode_rep(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, forked);
+    assert_eq!(free_len(&rep_pool), 4);
+
+    let v1 = codec
+        .encode_rep_as(&forked, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    assert!(
+        v1.payload.len() > 2 * SLOT_SIZE,
+        "v1 peers get inline states"
+    );
+    assert_eq!(free_len(&rep_pool), 4);
+}
+
+#[test]
+fn payloads_fall_back_inline_without_room_in_pool() {
+    let (codec, cmd_pool, _) = pooled_codec(2);
+    let rom = KernelCmd::LoadRom {
+        group: 0,
+        bytes: bytes(3 * SLOT_SIZE, 6),
+        save_ram: None,
+    };
+    let encoded = codec.encode_cmd(&rom).expect("encode");
+    assert!(encoded.payload.len() > 3 * SLOT_SIZE);
+    assert_eq!(free_len(&cmd_pool), 2, "partial acquisitions are released");
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, rom);
+
+    let small = KernelCmd::LoadRom {
+        group: 0,
+        bytes: bytes(BULK_INLINE_MAX, 7),
+        save_ram: None,
+    };
+    codec.encode_cmd(&small).expect("encode");
+    assert_eq!(free_len(&cmd_pool), 2, "small payloads stay inline");
+
+    let v1 = codec
+        .encode_cmd_as(&rom, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    assert!(
+        v1.payload.len() > 3 * SLOT_SIZE,
+        "v1 peers get inline bytes"
+    );
+}
+
+#[test]
+fn undelivered_staging_is_skipped_by_next_decode() {
+    let (codec, cmd_pool, _) = pooled_codec(4);
+    let dropped = KernelCmd::LoadState {
+        group: 0,
+        lane: 0,
+        bytes: bytes(SLOT_SIZE + 1, 8),
+    };
+    codec.encode_cmd(&dropped).expect("encode");
+
+    let delivered = KernelCmd::LoadState {
+        group: 0,
+        lane: 1,
+        bytes: bytes(SLOT_SIZE, 9),
+    };
+    let encoded = codec.encode_cmd(&delivered).expect("encode");
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, delivered);
+    assert_eq!(free_len(&cmd_pool), 4);
+}
+
+#[test]
+fn pooled_payload_without_pool_is_rejected() {
+    let (codec, _, _) = pooled_codec(4);
+    let cmd = KernelCmd::LoadState {
+        group: 0,
+        lane: 0,
+        bytes: bytes(SLOT_SIZE, 10),
+    };
+    let encoded = codec.encode_cmd(&cmd).expect("encode");
+    assert!(KernelCodec::default()
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .is_err());
+}
+
+#[test]
+fn full_pool_blocks_payloads_larger_than_the_ring() {
+    const BIG_SLOT: usize = 256 * 1024;
+    let pool_spec = |slot_count, slot_size| SlotPoolSpec {
+        config: SlotPoolConfig {
+            slot_count,
+            slot_size,
+        },
+        shared: None,
+    };
+    let mut slot_pools: Vec<_> = (0..KERNEL_REP_BULK_POOL)
+        .map(|_| pool_spec(1, SLOT_SIZE))
+        .collect();
+    slot_pools[KERNEL_CMD_BULK_POOL] = pool_spec(32, BIG_SLOT);
+    slot_pools.push(pool_spec(8, SLOT_SIZE));
+    let (endpoint, worker, _) = build_service(ServiceSpec {
+        codec: KernelCodec::default(),
+        lossless: Some(RingSpec {
+            capacity_bytes: 128 * 1024,
+            envelope_tag: TAG_KERNEL_CMD,
+        }),
+        besteffort: None,
+        coalesce: None,
+        replies: RingSpec {
+            capacity_bytes: 512 * 1024,
+            envelope_tag: TAG_KERNEL_REP,
+        },
+        reply_policy: PortClass::Lossless,
+        slot_pools,
+        peer_versions: SchemaVersions::CURRENT,
+    })
+    .expect("build kernel endpoint");
+    let load_rom = |len, seed| KernelCmd::LoadRom {
+        group: 0,
+        bytes: bytes(len, seed),
+        save_ram: None,
+    };
+
+    let first = load_rom(6 * 1024 * 1024, 11);
+    assert!(matches!(
+        endpoint.submit(&first),
+        Ok(SubmitOutcome::Accepted)
+    ));
+    let second = load_rom(4 * 1024 * 1024, 12);
+    assert!(
+        matches!(endpoint.submit(&second), Ok(SubmitOutcome::WouldBlock)),
+        "a multi-MB ROM waits for the pool instead of flooding the ring"
+    );
+    let small = load_rom(64 * 1024, 13);
+    assert!(
+        matches!(endpoint.submit(&small), Ok(SubmitOutcome::Accepted)),
+        "payloads that fit the ring still go inline"
+    );
+    assert!(matches!(
+        endpoint.submit(&load_rom(9 * 1024 * 1024, 14)),
+        Err(FabricError::Codec(_))
+    ));
+
+    let mut drained = Vec::new();
+    worker
+        .drain_commands(8, |cmd| drained.push(cmd.clone()))
+        .expect("drain");
+    assert_eq!(drained, vec![first, small]);
+    assert!(matches!(
+        endpoint.submit(&second),
+        Ok(SubmitOutcome::Accepted)
+    ));
+    drained.clear();
+    worker
+        .drain_commands(8, |cmd| drained.push(cmd.clone()))
+        .expect("drain");
+    assert_eq!(drained, vec![second]);
+}
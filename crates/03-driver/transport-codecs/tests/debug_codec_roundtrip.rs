@@ -2,15 +2,19 @@
 
 use std::sync::Arc;
 
+use rkyv::{api::high::to_bytes, rancor::Error, util::AlignedVec};
 use service_abi::{
-    CpuVM, DebugCmd, DebugRep, InspectorVMMinimal, KernelCmd, KernelRep, MemSpace, PpuVM, StepKind,
-    TimersVM,
+    BreakReason, CpuVM, DebugCmd, DebugRep, DisasmLineVM, ForkRejection, ForkedLane, FrameSpan,
+    InspectorVMMinimal, KernelCmd, KernelRep, LanesVM, MemAccessVM, MemSpace, PpuVM,
+    RegistersPatch, SlotSpan, StepKind, TimersVM, TraceEntryVM, WatchAccess,
 };
+use transport::schema::*;
+use transport::Envelope;
 use transport_codecs::KernelCodec;
 use transport_fabric::{Codec, PortClass};
 
 fn roundtrip_cmd(cmd: KernelCmd) {
-    let codec = KernelCodec;
+    let codec = KernelCodec::default();
     let encoded = codec.encode_cmd(&cmd).expect("encode");
     assert_eq!(port_class_for(&cmd), encoded.class, "port class mismatch");
     let decoded = codec
@@ -20,7 +24,7 @@ fn roundtrip_cmd(cmd: KernelCmd) {
 }
 
 fn roundtrip_rep(rep: KernelRep) {
-    let codec = KernelCodec;
+    let codec = KernelCodec::default();
     let encoded = codec.encode_rep(&rep).expect("encode");
     let decoded = codec
         .decode_rep(encoded.envelope, &encoded.payload)
@@ -34,6 +38,10 @@ fn port_class_for(cmd: &KernelCmd) -> PortClass {
         KernelCmd::LoadRom { .. } => PortClass::Lossless,
         KernelCmd::SetInputs { .. } => PortClass::Lossless,
         KernelCmd::Terminate { .. } => PortClass::Lossless,
+        KernelCmd::SaveState { .. } | KernelCmd::LoadState { .. } => PortClass::Lossless,
+        KernelCmd::ResetLane { .. } => PortClass::Lossless,
+        KernelCmd::SetCheats { .. } => PortClass::Lossless,
+        KernelCmd::Fork { .. } => PortClass::BestEffort,
         KernelCmd::Debug(DebugCmd::Snapshot { .. }) => PortClass::Coalesce,
         KernelCmd::Debug(_) => PortClass::Lossless,
     }
@@ -44,6 +52,7 @@ fn debug_cmds_roundtrip() {
     roundtrip_cmd(KernelCmd::Debug(DebugCmd::Snapshot { group: 7 }));
     roundtrip_cmd(KernelCmd::Debug(DebugCmd::MemWindow {
         group: 3,
+        lane: 2,
         space: MemSpace::Vram,
         base: 0x8123,
         len: 0x20,
@@ -53,6 +62,11 @@ fn debug_cmds_roundtrip() {
         count: 17,
     }));
     roundtrip_cmd(KernelCmd::Debug(DebugCmd::StepFrame { group: 9 }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::Disassemble {
+        group: 2,
+        base: 0x0150,
+        count: 12,
+    }));
 }
 
 #[test]
@@ -106,9 +120,184 @@ fn debug_reps_roundtrip() {
         disasm: Some("NOP".to_string()),
     };
 
-    roundtrip_rep(KernelRep::Debug(snapshot));
-    roundtrip_rep(KernelRep::Debug(mem_window));
-    roundtrip_rep(KernelRep::Debug(stepped));
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: snapshot,
+    });
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: mem_window,
+    });
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: stepped,
+    });
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Disassembly {
+            base: 0x0150,
+            lines: vec![
+                DisasmLineVM {
+                    addr: 0x0150,
+                    bytes: vec![0x3E, 0x42],
+                    text: "LD A,$42".to_string(),
+                },
+                DisasmLineVM {
+                    addr: 0x0152,
+                    bytes: vec![0x18, 0xFC],
+                    text: "JR $0150".to_string(),
+                },
+            ],
+        },
+    });
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Lanes(LanesVM {
+            split_mask: 0b0110,
+            splits: 5,
+            merges: 3,
+            split_cycles: 123_456,
+        }),
+    });
+}
+
+#[test]
+fn breakpoint_cmds_and_hits_roundtrip() {
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetBreakpoint {
+        group: 1,
+        pc: 0x0150,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::ClearBreakpoint {
+        group: 1,
+        pc: 0x0150,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetWatchpoint {
+        group: 2,
+        addr_range: 0xC000..=0xC0FF,
+        access: WatchAccess::Write,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::ClearWatchpoint {
+        group: 2,
+        addr_range: 0xFF40..=0xFF40,
+        access: WatchAccess::Read,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::Continue { group: 3 }));
+
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::BreakpointHit {
+            group: 1,
+            pc: 0x0150,
+            reason: BreakReason::Breakpoint,
+        },
+    });
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::BreakpointHit {
+            group: 2,
+            pc: 0x4012,
+            reason: BreakReason::Watchpoint {
+                addr: 0xC010,
+                access: WatchAccess::Write,
+            },
+        },
+    });
+}
+
+#[test]
+fn extended_mem_spaces_roundtrip() {
+    for space in [
+        MemSpace::Hram,
+        MemSpace::Rom { bank: 0x1FF },
+        MemSpace::CartRam { bank: 3 },
+        MemSpace::Bus,
+    ] {
+        roundtrip_cmd(KernelCmd::Debug(DebugCmd::MemWindow {
+            group: 1,
+            lane: 0,
+            space,
+            base: 0x4000,
+            len: 0x10,
+        }));
+        roundtrip_rep(KernelRep::Debug {
+            group: 0,
+            rep: DebugRep::MemWindow {
+                space,
+                base: 0x4000,
+                bytes: Arc::from([0xC3, 0x50, 0x01].as_slice()),
+            },
+        });
+    }
+}
+
+#[test]
+fn write_cmds_roundtrip() {
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::WriteMem {
+        group: 1,
+        lane: 3,
+        space: MemSpace::CartRam { bank: 2 },
+        base: 0xA010,
+        bytes: Arc::from([0x01, 0x02, 0x03].as_slice()),
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetRegisters {
+        group: 1,
+        lane: 0,
+        regs: RegistersPatch {
+            a: Some(0x12),
+            f: Some(0xB0),
+            sp: Some(0xDFFF),
+            pc: Some(0x0150),
+            ime: Some(false),
+            ..RegistersPatch::default()
+        },
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetRegisters {
+        group: 2,
+        lane: 1,
+        regs: RegistersPatch::default(),
+    }));
+}
+
+#[test]
+fn trace_cmds_and_chunks_roundtrip() {
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetTracing {
+        group: 1,
+        enabled: true,
+        capacity: 4096,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::SetTracing {
+        group: 1,
+        enabled: false,
+        capacity: 0,
+    }));
+    roundtrip_cmd(KernelCmd::Debug(DebugCmd::ReadTrace { group: 1, max: 256 }));
+
+    roundtrip_rep(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Trace {
+            entries: vec![TraceEntryVM {
+                cycle: 1_234_567,
+                pc: 0x0150,
+                pcmem: [0xEA, 0x10, 0xC0, 0x00],
+                a: 0x42,
+                f: 0xB0,
+                b: 0x01,
+                c: 0x02,
+                d: 0x03,
+                e: 0x04,
+                h: 0x05,
+                l: 0x06,
+                sp: 0xFFFE,
+                accesses: vec![MemAccessVM {
+                    addr: 0xC010,
+                    value: 0x42,
+                    access: WatchAccess::Write,
+                }],
+            }],
+            remaining: 17,
+            dropped: 3,
+        },
+    });
 }
 
 #[test]
@@ -118,7 +307,7 @@ fn legacy_non_debug_roundtrip() {
         purpose: service_abi::TickPurpose::Display,
         budget: 10,
     };
-    let codec = KernelCodec;
+    let codec = KernelCodec::default();
     let encoded = codec.encode_cmd(&cmd).expect("encode");
     let decoded = codec
         .decode_cmd(encoded.envelope, &encoded.payload)
@@ -139,13 +328,131 @@ fn legacy_non_debug_roundtrip() {
     assert!(matches!(decoded, KernelRep::TickDone { .. }));
 }
 
+#[test]
+fn save_ram_roundtrip() {
+    roundtrip_cmd(KernelCmd::LoadRom {
+        group: 0,
+        bytes: Arc::from([0x00, 0xC3, 0x50, 0x01]),
+        save_ram: None,
+    });
+    roundtrip_cmd(KernelCmd::LoadRom {
+        group: 0,
+        bytes: Arc::from([0x00, 0xC3, 0x50, 0x01]),
+        save_ram: Some(Arc::from([0xDE, 0xAD])),
+    });
+    roundtrip_rep(KernelRep::SaveRamDirty {
+        group: 5,
+        lane: 3,
+        bytes: Arc::from([0x01, 0x02, 0x03]),
+    });
+}
+
+#[test]
+fn save_state_roundtrip() {
+    roundtrip_cmd(KernelCmd::SaveState { group: 2, lane: 3 });
+    roundtrip_cmd(KernelCmd::LoadState {
+        group: 2,
+        lane: 3,
+        bytes: Arc::from(*b"GBXS\x01\x00"),
+    });
+    roundtrip_rep(KernelRep::StateSaved {
+        group: 2,
+        lane: 3,
+        bytes: Some(Arc::from(*b"GBXS\x01\x00")),
+    });
+    roundtrip_rep(KernelRep::StateSaved {
+        group: 2,
+        lane: 7,
+        bytes: None,
+    });
+    roundtrip_rep(KernelRep::StateLoaded {
+        group: 2,
+        lane: 3,
+        ok: false,
+    });
+}
+
+#[test]
+fn cheats_roundtrip() {
+    roundtrip_cmd(KernelCmd::SetCheats {
+        group: 1,
+        codes: vec!["3EA-5FB-EEA".to_string(), "0163D1C0".to_string()],
+    });
+    roundtrip_cmd(KernelCmd::SetCheats {
+        group: 1,
+        codes: Vec::new(),
+    });
+    roundtrip_rep(KernelRep::CheatsSet {
+        group: 1,
+        active: 1,
+        rejected: vec!["bogus".to_string()],
+    });
+}
+
+#[test]
+fn reset_lane_roundtrip() {
+    roundtrip_cmd(KernelCmd::ResetLane {
+        group: 2,
+        lane: 3,
+        rom: Some(Arc::from([0x00, 0xC3, 0x50, 0x01].as_slice())),
+        save_ram: Some(Arc::from([0xAA; 8].as_slice())),
+    });
+    roundtrip_cmd(KernelCmd::ResetLane {
+        group: 2,
+        lane: 0,
+        rom: None,
+        save_ram: None,
+    });
+    roundtrip_rep(KernelRep::LaneReset {
+        group: 2,
+        lane: 3,
+        ok: true,
+    });
+}
+
+#[test]
+fn fork_roundtrip() {
+    roundtrip_cmd(KernelCmd::Fork {
+        group: 0,
+        source_lane: 1,
+        target_group: 4,
+        input_scripts: vec![
+            Arc::from([0xFF, 0xEF, 0xEF].as_slice()),
+            Arc::from(Vec::new()),
+        ],
+    });
+    roundtrip_rep(KernelRep::Forked {
+        group: 4,
+        frames: 3,
+        lanes: vec![ForkedLane {
+            lane: 0,
+            state: Arc::from([0x47, 0x42, 0x58, 0x53].as_slice()),
+            thumbnail: FrameSpan {
+                width: 2,
+                height: 1,
+                pixels: Arc::from([0x10; 8].as_slice()),
+                slot_span: None,
+            },
+        }],
+    });
+    roundtrip_rep(KernelRep::Forked {
+        group: 4,
+        frames: 0,
+        lanes: Vec::new(),
+    });
+    roundtrip_rep(KernelRep::ForkRejected {
+        group: 4,
+        reason: ForkRejection::TargetInUse,
+    });
+}
+
 #[test]
 fn envelope_metadata_is_preserved() {
     let cmd = KernelCmd::Debug(DebugCmd::StepFrame { group: 2 });
-    let codec = KernelCodec;
+    let codec = KernelCodec::default();
     let encoded = codec.encode_cmd(&cmd).expect("encode");
     assert_eq!(transport::schema::TAG_KERNEL_CMD, encoded.envelope.tag);
-    assert_eq!(transport::schema::SCHEMA_VERSION_V1, encoded.envelope.ver);
+    assert_eq!(transport::schema::SCHEMA_VERSION, encoded.envelope.ver);
 
     let decoded = codec
         .decode_cmd(encoded.envelope, &encoded.payload)
@@ -155,16 +462,219 @@ fn envelope_metadata_is_preserved() {
 
 #[test]
 fn debug_rep_contains_trace_defaults() {
-    let rep = KernelRep::Debug(DebugRep::Stepped {
-        kind: StepKind::Frame,
-        cycles: 70_224,
-        pc: 0x2000,
-        disasm: None,
-    });
-    let codec = KernelCodec;
+    let rep = KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Stepped {
+            kind: StepKind::Frame,
+            cycles: 70_224,
+            pc: 0x2000,
+            disasm: None,
+        },
+    };
+    let codec = KernelCodec::default();
     let encoded = codec.encode_rep(&rep).expect("encode");
     let decoded = codec
         .decode_rep(encoded.envelope, &encoded.payload)
         .expect("decode");
     assert_eq!(rep, decoded);
 }
+
+#[test]
+fn kernel_group_survives_roundtrip() {
+    roundtrip_cmd(KernelCmd::Tick {
+        group: 3,
+        purpose: service_abi::TickPurpose::Display,
+        budget: 4_096,
+    });
+    roundtrip_cmd(KernelCmd::LoadRom {
+        group: 3,
+        bytes: Arc::from([0x00, 0xC3, 0x50, 0x01]),
+        save_ram: None,
+    });
+    roundtrip_cmd(KernelCmd::LoadRom {
+        group: 3,
+        bytes: Arc::from([0x00, 0xC3, 0x50, 0x01]),
+        save_ram: Some(Arc::from([0xDE, 0xAD])),
+    });
+    roundtrip_rep(KernelRep::TickDone {
+        group: 3,
+        lanes_mask: 0b1010,
+        cycles_done: 70_224,
+    });
+    roundtrip_rep(KernelRep::LaneFrame {
+        group: 3,
+        lane: 1,
+        span: FrameSpan {
+            width: 160,
+            height: 144,
+            pixels: Arc::from([0x10; 8].as_slice()),
+            slot_span: Some(SlotSpan {
+                start_idx: 4,
+                count: 1,
+            }),
+        },
+        frame_id: 42,
+    });
+    roundtrip_rep(KernelRep::RomLoaded {
+        group: 3,
+        bytes_len: 32_768,
+    });
+    roundtrip_rep(KernelRep::DroppedThumb { group: 3, count: 2 });
+    roundtrip_rep(KernelRep::Debug {
+        group: 3,
+        rep: DebugRep::Stepped {
+            kind: StepKind::Instruction,
+            cycles: 4,
+            pc: 0x0101,
+            disasm: None,
+        },
+    });
+}
+
+#[test]
+fn v1_payloads_decode_with_group_zero() {
+    let codec = KernelCodec::default();
+    let v1_cmd = |tag, payload: AlignedVec| {
+        codec
+            .decode_cmd(Envelope::new(tag, SCHEMA_VERSION_V1), &payload)
+            .expect("decode v1 cmd")
+    };
+    let tick = to_bytes::<Error>(&KernelCmdV1::Tick(KernelTickCmdV1 {
+        purpose: TickPurposeV1::Display,
+        budget: 10,
+    }))
+    .expect("serialize");
+    assert_eq!(
+        v1_cmd(TAG_KERNEL_CMD, tick),
+        KernelCmd::Tick {
+            group: 0,
+            purpose: service_abi::TickPurpose::Display,
+            budget: 10,
+        }
+    );
+    let load = to_bytes::<Error>(&KernelCmdV1::LoadRomWithSave(KernelLoadRomWithSaveCmdV1 {
+        rom_len: 2,
+        bytes: vec![0x00, 0xC3, 0xDE],
+    }))
+    .expect("serialize");
+    assert_eq!(
+        v1_cmd(TAG_KERNEL_CMD, load),
+        KernelCmd::LoadRom {
+            group: 0,
+            bytes: Arc::from([0x00, 0xC3]),
+            save_ram: Some(Arc::from([0xDE])),
+        }
+    );
+
+    let rep = to_bytes::<Error>(&KernelRepV1::RomLoaded { bytes_len: 64 }).expect("serialize");
+    let decoded = codec
+        .decode_rep(Envelope::new(TAG_KERNEL_REP, SCHEMA_VERSION_V1), &rep)
+        .expect("decode v1 rep");
+    assert_eq!(
+        decoded,
+        KernelRep::RomLoaded {
+            group: 0,
+            bytes_len: 64,
+        }
+    );
+}
+
+#[test]
+fn unknown_schema_version_is_rejected() {
+    let codec = KernelCodec::default();
+    let encoded = codec
+        .encode_cmd(&KernelCmd::Terminate { group: 1 })
+        .expect("encode");
+    let envelope = Envelope::new(TAG_KERNEL_CMD, SCHEMA_VERSION + 1);
+    assert!(codec.decode_cmd(envelope, &encoded.payload).is_err());
+}
+
+#[test]
+fn v1_peers_receive_v1_encodings() {
+    let codec = KernelCodec::default();
+    let tick = KernelCmd::Tick {
+        group: 3,
+        purpose: service_abi::TickPurpose::Display,
+        budget: 10,
+    };
+    let encoded = codec
+        .encode_cmd_as(&tick, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    assert_eq!(encoded.envelope.ver, SCHEMA_VERSION_V1);
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert!(matches!(decoded, KernelCmd::Tick { group: 0, .. }));
+
+    let load = KernelCmd::LoadRom {
+        group: 0,
+        bytes: Arc::from([0x00, 0xC3]),
+        save_ram: Some(Arc::from([0xDE, 0xAD])),
+    };
+    let encoded = codec
+        .encode_cmd_as(&load, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, load);
+
+    let debug = KernelRep::Debug {
+        group: 2,
+        rep: DebugRep::Stepped {
+            kind: StepKind::Instruction,
+            cycles: 4,
+            pc: 0x0101,
+            disasm: None,
+        },
+    };
+    let encoded = codec
+        .encode_rep_as(&debug, SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    let decoded = codec
+        .decode_rep(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert!(matches!(decoded, KernelRep::Debug { group: 0, .. }));
+
+    let window = |lane| {
+        KernelCmd::Debug(DebugCmd::MemWindow {
+            group: 0,
+            lane,
+            space: MemSpace::Wram,
+            base: 0xC000,
+            len: 4,
+        })
+    };
+    let encoded = codec
+        .encode_cmd_as(&window(0), SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    let decoded = codec
+        .decode_cmd(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, window(0));
+    assert!(
+        codec.encode_cmd_as(&window(1), SCHEMA_VERSION_V1).is_err(),
+        "v1 cannot address other lanes"
+    );
+
+    let dirty = |lane| KernelRep::SaveRamDirty {
+        group: 0,
+        lane,
+        bytes: Arc::from([0x01, 0x02]),
+    };
+    let encoded = codec
+        .encode_rep_as(&dirty(0), SCHEMA_VERSION_V1)
+        .expect("encode v1");
+    let decoded = codec
+        .decode_rep(encoded.envelope, &encoded.payload)
+        .expect("decode");
+    assert_eq!(decoded, dirty(0));
+    assert!(
+        codec.encode_rep_as(&dirty(2), SCHEMA_VERSION_V1).is_err(),
+        "v1 only carries lane 0 battery RAM"
+    );
+
+    let thumb = KernelRep::DroppedThumb { group: 1, count: 1 };
+    assert!(codec.encode_rep_as(&thumb, SCHEMA_VERSION_V1).is_err());
+    assert!(codec.encode_cmd_as(&tick, SCHEMA_VERSION + 1).is_err());
+}
@@ -7,39 +7,49 @@ use service_abi::{
     CpuVM, DebugCmd, DebugRep, InspectorVMMinimal, KernelCmd, KernelRep, MemSpace, PpuVM, StepKind,
     TimersVM,
 };
+use transport::schema::SCHEMA_VERSION_V1;
 use transport_codecs::KernelCodec;
 use transport_fabric::Codec;
 
 fn codec() -> KernelCodec {
-    KernelCodec
+    KernelCodec::default()
 }
 
 #[test]
 fn debug_rep_snapshot_matches_golden() {
-    let rep = KernelRep::Debug(DebugRep::Snapshot(sample_snapshot()));
+    let rep = KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Snapshot(sample_snapshot()),
+    };
     let expected = include_bytes!("golden/debug_rep_0.bin");
     assert_encoded_matches(&rep, expected);
 }
 
 #[test]
 fn debug_rep_mem_matches_golden() {
-    let rep = KernelRep::Debug(DebugRep::MemWindow {
-        space: MemSpace::Wram,
-        base: 0xC100,
-        bytes: Arc::from([0xDE, 0xAD, 0xBE, 0xEF].as_slice()),
-    });
+    let rep = KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::MemWindow {
+            space: MemSpace::Wram,
+            base: 0xC100,
+            bytes: Arc::from([0xDE, 0xAD, 0xBE, 0xEF].as_slice()),
+        },
+    };
     let expected = include_bytes!("golden/debug_rep_1.bin");
     assert_encoded_matches(&rep, expected);
 }
 
 #[test]
 fn debug_rep_step_matches_golden() {
-    let rep = KernelRep::Debug(DebugRep::Stepped {
-        kind: StepKind::Instruction,
-        cycles: 8,
-        pc: 0x0200,
-        disasm: Some("LD A, (HL)".into()),
-    });
+    let rep = KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::Stepped {
+            kind: StepKind::Instruction,
+            cycles: 8,
+            pc: 0x0200,
+            disasm: Some("LD A, (HL)".into()),
+        },
+    };
     let expected = include_bytes!("golden/debug_rep_2.bin");
     assert_encoded_matches(&rep, expected);
 }
@@ -55,6 +65,7 @@ fn debug_cmd_snapshot_matches_golden() {
 fn debug_cmd_mem_matches_golden() {
     let cmd = KernelCmd::Debug(DebugCmd::MemWindow {
         group: 3,
+        lane: 0,
         space: MemSpace::Oam,
         base: 0xFE00,
         len: 0x10,
@@ -79,7 +90,7 @@ fn debug_cmd_step_frame_matches_golden() {
 
 fn assert_encoded_matches(rep: &KernelRep, expected: &[u8]) {
     let codec = codec();
-    let encoded = codec.encode_rep(rep).expect("encode");
+    let encoded = codec.encode_rep_as(rep, SCHEMA_VERSION_V1).expect("encode");
     assert_eq!(expected, encoded.payload.as_slice(), "payload mismatch");
 
     let aligned = aligned_from_slice(expected);
@@ -91,7 +102,7 @@ fn assert_encoded_matches(rep: &KernelRep, expected: &[u8]) {
 
 fn assert_encoded_matches_cmd(cmd: &KernelCmd, expected: &[u8]) {
     let codec = codec();
-    let encoded = codec.encode_cmd(cmd).expect("encode");
+    let encoded = codec.encode_cmd_as(cmd, SCHEMA_VERSION_V1).expect("encode");
     assert_eq!(expected, encoded.payload.as_slice(), "payload mismatch");
 
     let aligned = aligned_from_slice(expected);
@@ -1,18 +1,22 @@
 #![cfg(test)]
 //! Helper to regenerate debug codec golden binaries.
+//!
+//! The goldens pin the V1 wire format, so payloads are encoded as V1 even
+//! though the codec speaks V2 by default.
 
 use std::sync::Arc;
 
 use service_abi::{
     CpuVM, DebugCmd, DebugRep, InspectorVMMinimal, MemSpace, PpuVM, StepKind, TimersVM,
 };
+use transport::schema::SCHEMA_VERSION_V1;
 use transport_codecs::KernelCodec;
 use transport_fabric::Codec;
 
 #[test]
 #[ignore]
 fn dump_debug_goldens() {
-    let codec = KernelCodec;
+    let codec = KernelCodec::default();
 
     let snapshot = DebugRep::Snapshot(InspectorVMMinimal {
         cpu: CpuVM {
@@ -67,7 +71,10 @@ fn dump_debug_goldens() {
 
     for rep in rep_payloads {
         let encoded = codec
-            .encode_rep(&service_abi::KernelRep::Debug(rep))
+            .encode_rep_as(
+                &service_abi::KernelRep::Debug { group: 0, rep },
+                SCHEMA_VERSION_V1,
+            )
             .unwrap();
         print_bytes("rep", &encoded.payload);
     }
@@ -76,6 +83,7 @@ fn dump_debug_goldens() {
         DebugCmd::Snapshot { group: 2 },
         DebugCmd::MemWindow {
             group: 3,
+            lane: 0,
             space: MemSpace::Oam,
             base: 0xFE00,
             len: 0x10,
@@ -86,7 +94,7 @@ fn dump_debug_goldens() {
 
     for cmd in cmds {
         let encoded = codec
-            .encode_cmd(&service_abi::KernelCmd::Debug(cmd))
+            .encode_cmd_as(&service_abi::KernelCmd::Debug(cmd), SCHEMA_VERSION_V1)
             .unwrap();
         print_bytes("cmd", &encoded.payload);
     }
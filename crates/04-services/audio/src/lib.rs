@@ -40,13 +40,10 @@ impl Service for AudioService {
         try_submit_queue::<AudioRep, _>(&self.reports, self.capacity, SubmitPolicy::Must, 1, || {
             match cmd {
                 AudioCmd::Submit { span } => {
-                    let frames = if span.channels == 0 {
-                        0
-                    } else {
-                        span.samples.len() / usize::from(span.channels)
-                    };
                     let mut reps = SmallVec::new();
-                    reps.push(AudioRep::Played { frames });
+                    reps.push(AudioRep::Played {
+                        frames: span.frames(),
+                    });
                     reps
                 }
             }
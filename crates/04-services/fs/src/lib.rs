@@ -3,14 +3,19 @@
 use service_abi::{FsCmd, FsRep, FsServiceHandle, Service, SubmitOutcome, SubmitPolicy};
 use services_common::{drain_queue, try_submit_queue, LocalQueue};
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 const DEFAULT_CAPACITY: usize = 32;
 
 /// Mock filesystem service for testing and prototyping.
+///
+/// Persisted payloads are kept in memory so `Load` can read them back.
 pub struct FsService {
     reports: LocalQueue<FsRep>,
     capacity: usize,
+    files: Mutex<BTreeMap<PathBuf, Arc<[u8]>>>,
 }
 
 impl FsService {
@@ -19,6 +24,7 @@ impl FsService {
         Arc::new(Self {
             reports: LocalQueue::with_capacity(capacity),
             capacity,
+            files: Mutex::new(BTreeMap::new()),
         })
     }
 }
@@ -28,6 +34,7 @@ impl Default for FsService {
         Self {
             reports: LocalQueue::with_capacity(DEFAULT_CAPACITY),
             capacity: DEFAULT_CAPACITY,
+            files: Mutex::new(BTreeMap::new()),
         }
     }
 }
@@ -45,10 +52,15 @@ impl Service for FsService {
                     SubmitPolicy::Coalesce
                 }
             }
+            FsCmd::Load { .. } => SubmitPolicy::Lossless,
         };
 
         try_submit_queue::<FsRep, _>(&self.reports, self.capacity, policy, 1, || match cmd {
-            FsCmd::Persist { path, .. } => {
+            FsCmd::Persist { path, bytes } => {
+                self.files
+                    .lock()
+                    .expect("fs mock poisoned")
+                    .insert(path.clone(), Arc::clone(bytes));
                 let mut reps = SmallVec::new();
                 reps.push(FsRep::Saved {
                     path: path.clone(),
@@ -56,6 +68,20 @@ impl Service for FsService {
                 });
                 reps
             }
+            FsCmd::Load { path } => {
+                let bytes = self
+                    .files
+                    .lock()
+                    .expect("fs mock poisoned")
+                    .get(path)
+                    .cloned();
+                let mut reps = SmallVec::new();
+                reps.push(FsRep::Loaded {
+                    path: path.clone(),
+                    bytes,
+                });
+                reps
+            }
         })
     }
 
@@ -0,0 +1,1023 @@
+use std::collections::VecDeque;
+
+use crate::state::{SectionReader, SectionWriter, StateError};
+
+/// Default host sample rate used when the core configuration does not override it.
+pub const DEFAULT_SAMPLE_RATE_HZ: u32 = 48_000;
+/// Interleaved output channels produced by the APU (left, right).
+pub const OUTPUT_CHANNELS: u8 = 2;
+
+const CPU_HZ: u64 = 4_194_304;
+/// CPU cycles between frame sequencer steps (512 Hz).
+const FRAME_SEQUENCER_PERIOD: u32 = 8192;
+/// Upper bound on buffered output before the oldest samples are dropped (~1 s at 48 kHz).
+const MAX_BUFFERED_SAMPLES: usize = 48_000 * OUTPUT_CHANNELS as usize;
+/// Scales the mixed -480..=480 range into the i16 sample range.
+const OUTPUT_GAIN: i32 = 64;
+
+/// First APU register index relative to `0xFF00`.
+pub const NR10: usize = 0x10;
+const NR11: usize = 0x11;
+const NR12: usize = 0x12;
+const NR13: usize = 0x13;
+const NR14: usize = 0x14;
+const NR21: usize = 0x16;
+const NR22: usize = 0x17;
+const NR23: usize = 0x18;
+const NR24: usize = 0x19;
+const NR30: usize = 0x1A;
+const NR31: usize = 0x1B;
+const NR32: usize = 0x1C;
+const NR33: usize = 0x1D;
+const NR34: usize = 0x1E;
+const NR41: usize = 0x20;
+const NR42: usize = 0x21;
+const NR43: usize = 0x22;
+const NR44: usize = 0x23;
+const NR50: usize = 0x24;
+const NR51: usize = 0x25;
+const NR52: usize = 0x26;
+const WAVE_RAM: usize = 0x30;
+/// Last APU register index (inclusive) relative to `0xFF00`.
+pub const APU_REG_END: usize = 0x3F;
+
+/// Bits that always read back as 1 for `NR10..=0xFF2F`.
+const READ_MASKS: [u8; 0x20] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10-NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // unused, NR21-NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30-NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // unused, NR41-NR44
+    0x00, 0x00, 0x70, // NR50-NR52
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // unused
+];
+
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 0, 0, 0, 0, 0, 0, 1],
+    [1, 0, 0, 0, 0, 0, 0, 1],
+    [1, 0, 0, 0, 0, 1, 1, 1],
+    [0, 1, 1, 1, 1, 1, 1, 0],
+];
+
+/// Length counter shared by all four channels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Length {
+    counter: u16,
+    enabled: bool,
+}
+
+impl Length {
+    /// Clocks the counter and returns `true` when it expires.
+    fn clock(&mut self) -> bool {
+        if self.enabled && self.counter > 0 {
+            self.counter -= 1;
+            return self.counter == 0;
+        }
+        false
+    }
+}
+
+/// Volume envelope used by the square and noise channels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Envelope {
+    volume: u8,
+    timer: u8,
+}
+
+impl Envelope {
+    fn trigger(&mut self, nrx2: u8) {
+        self.volume = nrx2 >> 4;
+        self.timer = nrx2 & 0x07;
+    }
+
+    fn clock(&mut self, nrx2: u8) {
+        let period = nrx2 & 0x07;
+        if period == 0 {
+            return;
+        }
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer > 0 {
+            return;
+        }
+        self.timer = period;
+        if nrx2 & 0x08 != 0 {
+            if self.volume < 15 {
+                self.volume += 1;
+            }
+        } else if self.volume > 0 {
+            self.volume -= 1;
+        }
+    }
+}
+
+/// Frequency sweep unit of channel 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Sweep {
+    shadow: u16,
+    timer: u8,
+    enabled: bool,
+    negate_used: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Square {
+    enabled: bool,
+    duty_pos: u8,
+    timer: u32,
+    length: Length,
+    envelope: Envelope,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Wave {
+    enabled: bool,
+    pos: u8,
+    timer: u32,
+    sample: u8,
+    length: Length,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Noise {
+    enabled: bool,
+    lfsr: u16,
+    timer: u32,
+    length: Length,
+    envelope: Envelope,
+}
+
+/// DMG audio processing unit: two square channels, wave, noise and the mixer.
+///
+/// Register writes arrive through [`Apu::write`]; [`Apu::step`] advances the
+/// channel timers and frame sequencer and resamples the stereo mix to the
+/// configured host rate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Apu {
+    regs: [u8; 0x30],
+    powered: bool,
+    frame_step: u8,
+    frame_timer: u32,
+    ch1: Square,
+    sweep: Sweep,
+    ch2: Square,
+    ch3: Wave,
+    ch4: Noise,
+    sample_rate_hz: u32,
+    resample_phase: u64,
+    acc_left: i64,
+    acc_right: i64,
+    acc_cycles: u64,
+    capture: bool,
+    samples: VecDeque<i16>,
+}
+
+impl Default for Apu {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_RATE_HZ)
+    }
+}
+
+impl Apu {
+    /// Creates a powered-off APU resampling to `sample_rate_hz`.
+    pub fn new(sample_rate_hz: u32) -> Self {
+        Self {
+            regs: [0; 0x30],
+            powered: false,
+            frame_step: 0,
+            frame_timer: FRAME_SEQUENCER_PERIOD,
+            ch1: Square::default(),
+            sweep: Sweep::default(),
+            ch2: Square::default(),
+            ch3: Wave::default(),
+            ch4: Noise::default(),
+            sample_rate_hz: sample_rate_hz.max(1),
+            resample_phase: 0,
+            acc_left: 0,
+            acc_right: 0,
+            acc_cycles: 0,
+            capture: true,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Restores the power-on state while keeping the configured sample rate.
+    pub fn reset(&mut self) {
+        let capture = self.capture;
+        *self = Self::new(self.sample_rate_hz);
+        self.capture = capture;
+    }
+
+    /// Applies the register state left behind by the DMG boot ROM.
+    pub fn initialize_post_boot(&mut self) {
+        self.reset();
+        self.write(NR52, 0x80);
+        for (idx, value) in [
+            (NR10, 0x80),
+            (NR11, 0xBF),
+            (NR12, 0xF3),
+            (NR21, 0x3F),
+            (NR30, 0x7F),
+            (NR31, 0xFF),
+            (NR32, 0x9F),
+            (NR41, 0xFF),
+            (NR50, 0x77),
+            (NR51, 0xF3),
+        ] {
+            self.write(idx, value);
+        }
+        // The boot chime leaves channel 1 running with its envelope fully decayed.
+        self.ch1.enabled = true;
+        self.ch1.envelope.volume = 0;
+    }
+
+    /// Returns the host sample rate the APU resamples to.
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz
+    }
+
+    /// Changes the host sample rate; buffered samples are kept.
+    pub fn set_sample_rate(&mut self, sample_rate_hz: u32) {
+        self.sample_rate_hz = sample_rate_hz.max(1);
+        self.resample_phase = 0;
+    }
+
+    /// Returns whether host output is being resampled and buffered.
+    pub fn capture(&self) -> bool {
+        self.capture
+    }
+
+    /// Turns host output on or off; headless runners that never drain it skip
+    /// the mixer and resampler entirely. Disabling drops buffered samples.
+    pub fn set_capture(&mut self, capture: bool) {
+        self.capture = capture;
+        self.samples.clear();
+        self.resample_phase = 0;
+        self.acc_left = 0;
+        self.acc_right = 0;
+        self.acc_cycles = 0;
+    }
+
+    /// Drains the interleaved stereo samples produced since the last call.
+    pub fn take_samples(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.samples).into()
+    }
+
+    /// Returns whether the register index (relative to `0xFF00`) belongs to the APU.
+    #[inline]
+    pub fn owns(idx: usize) -> bool {
+        (NR10..=APU_REG_END).contains(&idx)
+    }
+
+    /// Reads an APU register (index relative to `0xFF00`).
+    pub fn read(&self, idx: usize) -> u8 {
+        let off = idx - NR10;
+        if idx >= WAVE_RAM {
+            return self.regs[off];
+        }
+        if idx == NR52 {
+            let mut value = READ_MASKS[off];
+            if self.powered {
+                value |= 0x80;
+            }
+            value |= u8::from(self.ch1.enabled)
+                | u8::from(self.ch2.enabled) << 1
+                | u8::from(self.ch3.enabled) << 2
+                | u8::from(self.ch4.enabled) << 3;
+            return value;
+        }
+        self.regs[off] | READ_MASKS[off]
+    }
+
+    /// Writes an APU register (index relative to `0xFF00`).
+    pub fn write(&mut self, idx: usize, value: u8) {
+        let off = idx - NR10;
+        if idx >= WAVE_RAM {
+            self.regs[off] = value;
+            return;
+        }
+        if idx == NR52 {
+            self.set_power(value & 0x80 != 0);
+            return;
+        }
+        if !self.powered {
+            // DMG keeps length counters writable while powered off.
+            match idx {
+                NR11 => self.ch1.length.counter = 64 - u16::from(value & 0x3F),
+                NR21 => self.ch2.length.counter = 64 - u16::from(value & 0x3F),
+                NR31 => self.ch3.length.counter = 256 - u16::from(value),
+                NR41 => self.ch4.length.counter = 64 - u16::from(value & 0x3F),
+                _ => {}
+            }
+            return;
+        }
+
+        let prev = self.regs[off];
+        self.regs[off] = value;
+        match idx {
+            NR10 => {
+                // Leaving negate mode after a negate calculation kills the channel.
+                if prev & 0x08 != 0 && value & 0x08 == 0 && self.sweep.negate_used {
+                    self.ch1.enabled = false;
+                }
+            }
+            NR11 => self.ch1.length.counter = 64 - u16::from(value & 0x3F),
+            NR21 => self.ch2.length.counter = 64 - u16::from(value & 0x3F),
+            NR31 => self.ch3.length.counter = 256 - u16::from(value),
+            NR41 => self.ch4.length.counter = 64 - u16::from(value & 0x3F),
+            NR12 if value & 0xF8 == 0 => self.ch1.enabled = false,
+            NR22 if value & 0xF8 == 0 => self.ch2.enabled = false,
+            NR30 if value & 0x80 == 0 => self.ch3.enabled = false,
+            NR42 if value & 0xF8 == 0 => self.ch4.enabled = false,
+            NR14 => self.write_nrx4(1, value),
+            NR24 => self.write_nrx4(2, value),
+            NR34 => self.write_nrx4(3, value),
+            NR44 => self.write_nrx4(4, value),
+            _ => {}
+        }
+    }
+
+    /// Advances channel timers and the frame sequencer by `cycles` CPU ticks.
+    pub fn step(&mut self, cycles: u32) {
+        if cycles == 0 {
+            return;
+        }
+
+        if self.powered {
+            let mut remaining = cycles;
+            while remaining > 0 {
+                let chunk = remaining.min(self.frame_timer);
+                self.step_channels(chunk);
+                self.frame_timer -= chunk;
+                remaining -= chunk;
+                if self.frame_timer == 0 {
+                    self.frame_timer = FRAME_SEQUENCER_PERIOD;
+                    self.clock_frame_sequencer();
+                }
+            }
+        }
+
+        self.resample(cycles);
+    }
+
+    /// Copies the emulated state without the buffered host output.
+    pub(crate) fn snapshot(&self) -> Self {
+        Self {
+            regs: self.regs,
+            powered: self.powered,
+            frame_step: self.frame_step,
+            frame_timer: self.frame_timer,
+            ch1: self.ch1,
+            sweep: self.sweep,
+            ch2: self.ch2,
+            ch3: self.ch3,
+            ch4: self.ch4,
+            sample_rate_hz: self.sample_rate_hz,
+            resample_phase: self.resample_phase,
+            acc_left: self.acc_left,
+            acc_right: self.acc_right,
+            acc_cycles: self.acc_cycles,
+            capture: self.capture,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Adopts the emulated state of `state`, keeping this APU's host sample rate
+    /// and capture setting.
+    pub(crate) fn restore(&mut self, state: &Apu) {
+        let sample_rate_hz = self.sample_rate_hz;
+        let capture = self.capture;
+        *self = state.snapshot();
+        self.capture = capture;
+        if sample_rate_hz != self.sample_rate_hz {
+            self.sample_rate_hz = sample_rate_hz;
+            self.resample_phase = 0;
+        }
+    }
+
+    /// Appends the channel, sequencer and resampler state to a save-state section.
+    pub(crate) fn write_state(&self, w: &mut SectionWriter) {
+        w.bytes(&self.regs);
+        w.bool(self.powered);
+        w.u8(self.frame_step);
+        w.u32(self.frame_timer);
+        for ch in [&self.ch1, &self.ch2] {
+            w.bool(ch.enabled);
+            w.u8(ch.duty_pos);
+            w.u32(ch.timer);
+            write_length(w, ch.length);
+            write_envelope(w, ch.envelope);
+        }
+        w.u16(self.sweep.shadow);
+        w.u8(self.sweep.timer);
+        w.bool(self.sweep.enabled);
+        w.bool(self.sweep.negate_used);
+        w.bool(self.ch3.enabled);
+        w.u8(self.ch3.pos);
+        w.u32(self.ch3.timer);
+        w.u8(self.ch3.sample);
+        write_length(w, self.ch3.length);
+        w.bool(self.ch4.enabled);
+        w.u16(self.ch4.lfsr);
+        w.u32(self.ch4.timer);
+        write_length(w, self.ch4.length);
+        write_envelope(w, self.ch4.envelope);
+        w.u32(self.sample_rate_hz);
+        w.u64(self.resample_phase);
+        w.i64(self.acc_left);
+        w.i64(self.acc_right);
+        w.u64(self.acc_cycles);
+    }
+
+    /// Decodes state written by [`Apu::write_state`].
+    pub(crate) fn read_state(r: &mut SectionReader<'_>) -> Result<Self, StateError> {
+        let regs = r.array()?;
+        let powered = r.bool()?;
+        let frame_step = r.u8()? & 0x07;
+        let frame_timer = r.u32()?.clamp(1, FRAME_SEQUENCER_PERIOD);
+        let ch1 = read_square(r)?;
+        let ch2 = read_square(r)?;
+        let sweep = Sweep {
+            shadow: r.u16()?,
+            timer: r.u8()?,
+            enabled: r.bool()?,
+            negate_used: r.bool()?,
+        };
+        let ch3 = Wave {
+            enabled: r.bool()?,
+            pos: r.u8()? & 0x1F,
+            timer: r.u32()?,
+            sample: r.u8()? & 0x0F,
+            length: read_length(r)?,
+        };
+        let ch4 = Noise {
+            enabled: r.bool()?,
+            lfsr: r.u16()?,
+            timer: r.u32()?,
+            length: read_length(r)?,
+            envelope: read_envelope(r)?,
+        };
+        Ok(Self {
+            regs,
+            powered,
+            frame_step,
+            frame_timer,
+            ch1,
+            sweep,
+            ch2,
+            ch3,
+            ch4,
+            sample_rate_hz: r.u32()?.max(1),
+            resample_phase: r.u64()?,
+            acc_left: r.i64()?,
+            acc_right: r.i64()?,
+            acc_cycles: r.u64()?,
+            capture: true,
+            samples: VecDeque::new(),
+        })
+    }
+
+    fn set_power(&mut self, on: bool) {
+        if on == self.powered {
+            return;
+        }
+        if on {
+            self.powered = true;
+            self.frame_step = 0;
+            self.frame_timer = FRAME_SEQUENCER_PERIOD;
+            self.ch1.duty_pos = 0;
+            self.ch2.duty_pos = 0;
+            self.ch3.sample = 0;
+            return;
+        }
+
+        let lengths = [
+            self.ch1.length.counter,
+            self.ch2.length.counter,
+            self.ch3.length.counter,
+            self.ch4.length.counter,
+        ];
+        // Every register except wave RAM clears while the APU is off.
+        self.regs[..WAVE_RAM - NR10].fill(0);
+        self.powered = false;
+        self.ch1 = Square::default();
+        self.sweep = Sweep::default();
+        self.ch2 = Square::default();
+        self.ch3 = Wave::default();
+        self.ch4 = Noise::default();
+        self.ch1.length.counter = lengths[0];
+        self.ch2.length.counter = lengths[1];
+        self.ch3.length.counter = lengths[2];
+        self.ch4.length.counter = lengths[3];
+    }
+
+    fn reg(&self, idx: usize) -> u8 {
+        self.regs[idx - NR10]
+    }
+
+    fn square_freq(&self, channel: u8) -> u16 {
+        let (lo, hi) = if channel == 1 {
+            (NR13, NR14)
+        } else {
+            (NR23, NR24)
+        };
+        u16::from(self.reg(lo)) | (u16::from(self.reg(hi) & 0x07) << 8)
+    }
+
+    fn set_ch1_freq(&mut self, freq: u16) {
+        self.regs[NR13 - NR10] = freq as u8;
+        self.regs[NR14 - NR10] = (self.regs[NR14 - NR10] & !0x07) | ((freq >> 8) as u8 & 0x07);
+    }
+
+    fn write_nrx4(&mut self, channel: u8, value: u8) {
+        let length_enable = value & 0x40 != 0;
+        let trigger = value & 0x80 != 0;
+        // Steps 0/2/4/6 clock length next; an odd step means the next one will not.
+        let extra_clock = !self.frame_step.is_multiple_of(2);
+
+        let length = match channel {
+            1 => &mut self.ch1.length,
+            2 => &mut self.ch2.length,
+            3 => &mut self.ch3.length,
+            _ => &mut self.ch4.length,
+        };
+        let was_enabled = length.enabled;
+        length.enabled = length_enable;
+        let mut expired = false;
+        if extra_clock && !was_enabled && length_enable && length.counter > 0 {
+            length.counter -= 1;
+            expired = length.counter == 0 && !trigger;
+        }
+        if trigger && length.counter == 0 {
+            let max = if channel == 3 { 256 } else { 64 };
+            length.counter = if extra_clock && length_enable {
+                max - 1
+            } else {
+                max
+            };
+        }
+        if expired {
+            self.set_channel_enabled(channel, false);
+        }
+
+        if trigger {
+            self.trigger(channel);
+        }
+    }
+
+    fn set_channel_enabled(&mut self, channel: u8, enabled: bool) {
+        match channel {
+            1 => self.ch1.enabled = enabled,
+            2 => self.ch2.enabled = enabled,
+            3 => self.ch3.enabled = enabled,
+            _ => self.ch4.enabled = enabled,
+        }
+    }
+
+    fn trigger(&mut self, channel: u8) {
+        match channel {
+            1 => {
+                let nr12 = self.reg(NR12);
+                self.ch1.enabled = nr12 & 0xF8 != 0;
+                self.ch1.timer = square_period(self.square_freq(1));
+                self.ch1.envelope.trigger(nr12);
+
+                let nr10 = self.reg(NR10);
+                let period = (nr10 >> 4) & 0x07;
+                let shift = nr10 & 0x07;
+                self.sweep.shadow = self.square_freq(1);
+                self.sweep.timer = if period == 0 { 8 } else { period };
+                self.sweep.enabled = period != 0 || shift != 0;
+                self.sweep.negate_used = false;
+                if shift != 0 {
+                    self.sweep_calculate();
+                }
+            }
+            2 => {
+                let nr22 = self.reg(NR22);
+                self.ch2.enabled = nr22 & 0xF8 != 0;
+                self.ch2.timer = square_period(self.square_freq(2));
+                self.ch2.envelope.trigger(nr22);
+            }
+            3 => {
+                self.ch3.enabled = self.reg(NR30) & 0x80 != 0;
+                self.ch3.timer = wave_period(self.wave_freq()) + 6;
+                self.ch3.pos = 0;
+            }
+            _ => {
+                let nr42 = self.reg(NR42);
+                self.ch4.enabled = nr42 & 0xF8 != 0;
+                self.ch4.timer = noise_period(self.reg(NR43));
+                self.ch4.envelope.trigger(nr42);
+                self.ch4.lfsr = 0x7FFF;
+            }
+        }
+    }
+
+    fn wave_freq(&self) -> u16 {
+        u16::from(self.reg(NR33)) | (u16::from(self.reg(NR34) & 0x07) << 8)
+    }
+
+    /// Computes the next sweep frequency, disabling channel 1 on overflow.
+    fn sweep_calculate(&mut self) -> u16 {
+        let nr10 = self.reg(NR10);
+        let delta = self.sweep.shadow >> (nr10 & 0x07);
+        let next = if nr10 & 0x08 != 0 {
+            self.sweep.negate_used = true;
+            self.sweep.shadow.wrapping_sub(delta)
+        } else {
+            self.sweep.shadow + delta
+        };
+        if next > 0x7FF {
+            self.ch1.enabled = false;
+        }
+        next
+    }
+
+    fn clock_sweep(&mut self) {
+        self.sweep.timer = self.sweep.timer.saturating_sub(1);
+        if self.sweep.timer > 0 {
+            return;
+        }
+        let nr10 = self.reg(NR10);
+        let period = (nr10 >> 4) & 0x07;
+        self.sweep.timer = if period == 0 { 8 } else { period };
+        if !self.sweep.enabled || period == 0 {
+            return;
+        }
+        let next = self.sweep_calculate();
+        if next <= 0x7FF && nr10 & 0x07 != 0 {
+            self.sweep.shadow = next;
+            self.set_ch1_freq(next);
+            self.sweep_calculate();
+        }
+    }
+
+    fn clock_frame_sequencer(&mut self) {
+        let step = self.frame_step;
+        self.frame_step = (self.frame_step + 1) & 0x07;
+
+        if step.is_multiple_of(2) {
+            if self.ch1.length.clock() {
+                self.ch1.enabled = false;
+            }
+            if self.ch2.length.clock() {
+                self.ch2.enabled = false;
+            }
+            if self.ch3.length.clock() {
+                self.ch3.enabled = false;
+            }
+            if self.ch4.length.clock() {
+                self.ch4.enabled = false;
+            }
+        }
+        if step == 2 || step == 6 {
+            self.clock_sweep();
+        }
+        if step == 7 {
+            let (nr12, nr22, nr42) = (self.reg(NR12), self.reg(NR22), self.reg(NR42));
+            if self.ch1.enabled {
+                self.ch1.envelope.clock(nr12);
+            }
+            if self.ch2.enabled {
+                self.ch2.envelope.clock(nr22);
+            }
+            if self.ch4.enabled {
+                self.ch4.envelope.clock(nr42);
+            }
+        }
+    }
+
+    fn step_channels(&mut self, cycles: u32) {
+        let ch1_period = square_period(self.square_freq(1));
+        let ticks = timer_ticks(&mut self.ch1.timer, cycles, ch1_period);
+        self.ch1.duty_pos = ((u32::from(self.ch1.duty_pos) + ticks) % 8) as u8;
+
+        let ch2_period = square_period(self.square_freq(2));
+        let ticks = timer_ticks(&mut self.ch2.timer, cycles, ch2_period);
+        self.ch2.duty_pos = ((u32::from(self.ch2.duty_pos) + ticks) % 8) as u8;
+
+        let ch3_period = wave_period(self.wave_freq());
+        let ticks = timer_ticks(&mut self.ch3.timer, cycles, ch3_period);
+        if ticks > 0 && self.ch3.enabled {
+            self.ch3.pos = ((u32::from(self.ch3.pos) + ticks) % 32) as u8;
+            let byte = self.regs[WAVE_RAM - NR10 + usize::from(self.ch3.pos / 2)];
+            self.ch3.sample = if self.ch3.pos.is_multiple_of(2) {
+                byte >> 4
+            } else {
+                byte & 0x0F
+            };
+        }
+
+        let nr43 = self.reg(NR43);
+        let ticks = timer_ticks(&mut self.ch4.timer, cycles, noise_period(nr43));
+        for _ in 0..ticks {
+            let lfsr = self.ch4.lfsr;
+            let xor = (lfsr & 0x01) ^ ((lfsr >> 1) & 0x01);
+            let mut next = (lfsr >> 1) | (xor << 14);
+            if nr43 & 0x08 != 0 {
+                next = (next & !0x40) | (xor << 6);
+            }
+            self.ch4.lfsr = next;
+        }
+    }
+
+    /// Returns the digital 0..=15 output of each channel, or `None` when its DAC is off.
+    fn channel_outputs(&self) -> [Option<u8>; 4] {
+        let duty = |nrx1: u8, pos: u8| DUTY_TABLE[usize::from(nrx1 >> 6)][usize::from(pos)];
+        let ch1 = if self.ch1.enabled {
+            duty(self.reg(NR11), self.ch1.duty_pos) * self.ch1.envelope.volume
+        } else {
+            0
+        };
+        let ch2 = if self.ch2.enabled {
+            duty(self.reg(NR21), self.ch2.duty_pos) * self.ch2.envelope.volume
+        } else {
+            0
+        };
+        let ch3 = match (self.reg(NR32) >> 5) & 0x03 {
+            _ if !self.ch3.enabled => 0,
+            0 => 0,
+            code => self.ch3.sample >> (code - 1),
+        };
+        let ch4 = if self.ch4.enabled && self.ch4.lfsr & 0x01 == 0 {
+            self.ch4.envelope.volume
+        } else {
+            0
+        };
+        [
+            (self.reg(NR12) & 0xF8 != 0).then_some(ch1),
+            (self.reg(NR22) & 0xF8 != 0).then_some(ch2),
+            (self.reg(NR30) & 0x80 != 0).then_some(ch3),
+            (self.reg(NR42) & 0xF8 != 0).then_some(ch4),
+        ]
+    }
+
+    /// Mixes the four channels through NR51 panning and NR50 master volume.
+    fn mix(&self) -> (i32, i32) {
+        if !self.powered {
+            return (0, 0);
+        }
+        let nr50 = self.reg(NR50);
+        let nr51 = self.reg(NR51);
+        let mut left = 0i32;
+        let mut right = 0i32;
+        for (channel, output) in self.channel_outputs().into_iter().enumerate() {
+            let Some(digital) = output else {
+                continue;
+            };
+            let analog = i32::from(digital) * 2 - 15;
+            if nr51 & (0x10 << channel) != 0 {
+                left += analog;
+            }
+            if nr51 & (0x01 << channel) != 0 {
+                right += analog;
+            }
+        }
+        let left_vol = i32::from((nr50 >> 4) & 0x07) + 1;
+        let right_vol = i32::from(nr50 & 0x07) + 1;
+        (left * left_vol, right * right_vol)
+    }
+
+    /// Box-filters the mix over `cycles` and emits samples at the host rate.
+    fn resample(&mut self, cycles: u32) {
+        if !self.capture {
+            return;
+        }
+        let (left, right) = self.mix();
+        self.acc_left += i64::from(left) * i64::from(cycles);
+        self.acc_right += i64::from(right) * i64::from(cycles);
+        self.acc_cycles += u64::from(cycles);
+        self.resample_phase += u64::from(cycles) * u64::from(self.sample_rate_hz);
+
+        while self.resample_phase >= CPU_HZ {
+            self.resample_phase -= CPU_HZ;
+            let span = self.acc_cycles.max(1) as i64;
+            let left = (self.acc_left / span) as i32 * OUTPUT_GAIN;
+            let right = (self.acc_right / span) as i32 * OUTPUT_GAIN;
+            self.acc_left = 0;
+            self.acc_right = 0;
+            self.acc_cycles = 0;
+            if self.samples.len() >= MAX_BUFFERED_SAMPLES {
+                self.samples.pop_front();
+                self.samples.pop_front();
+            }
+            self.samples.push_back(clamp_i16(left));
+            self.samples.push_back(clamp_i16(right));
+        }
+    }
+}
+
+fn square_period(freq: u16) -> u32 {
+    (2048 - u32::from(freq)) * 4
+}
+
+fn wave_period(freq: u16) -> u32 {
+    (2048 - u32::from(freq)) * 2
+}
+
+fn noise_period(nr43: u8) -> u32 {
+    let divisor = match nr43 & 0x07 {
+        0 => 8,
+        code => u32::from(code) * 16,
+    };
+    divisor << (nr43 >> 4)
+}
+
+/// Runs a down-counting frequency timer and returns how many times it reloaded.
+fn timer_ticks(timer: &mut u32, cycles: u32, period: u32) -> u32 {
+    if *timer > cycles {
+        *timer -= cycles;
+        return 0;
+    }
+    let overshoot = cycles - *timer;
+    let ticks = 1 + overshoot / period;
+    *timer = period - overshoot % period;
+    ticks
+}
+
+fn read_square(r: &mut SectionReader<'_>) -> Result<Square, StateError> {
+    Ok(Square {
+        enabled: r.bool()?,
+        duty_pos: r.u8()? & 0x07,
+        timer: r.u32()?,
+        length: read_length(r)?,
+        envelope: read_envelope(r)?,
+    })
+}
+
+fn write_length(w: &mut SectionWriter, length: Length) {
+    w.u16(length.counter);
+    w.bool(length.enabled);
+}
+
+fn read_length(r: &mut SectionReader<'_>) -> Result<Length, StateError> {
+    Ok(Length {
+        counter: r.u16()?,
+        enabled: r.bool()?,
+    })
+}
+
+fn write_envelope(w: &mut SectionWriter, envelope: Envelope) {
+    w.u8(envelope.volume);
+    w.u8(envelope.timer);
+}
+
+fn read_envelope(r: &mut SectionReader<'_>) -> Result<Envelope, StateError> {
+    Ok(Envelope {
+        volume: r.u8()? & 0x0F,
+        timer: r.u8()?,
+    })
+}
+
+fn clamp_i16(value: i32) -> i16 {
+    value.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_CYCLES: u32 = 70_224;
+
+    fn powered() -> Apu {
+        let mut apu = Apu::new(DEFAULT_SAMPLE_RATE_HZ);
+        apu.write(NR52, 0x80);
+        apu.write(NR50, 0x77);
+        apu.write(NR51, 0xFF);
+        apu
+    }
+
+    fn run(apu: &mut Apu, cycles: u32) {
+        for _ in 0..cycles / 4 {
+            apu.step(4);
+        }
+    }
+
+    #[test]
+    fn power_off_clears_registers_and_ignores_writes() {
+        let mut apu = powered();
+        apu.write(NR12, 0xF0);
+        apu.write(WAVE_RAM, 0x5A);
+        apu.write(NR52, 0x00);
+
+        assert_eq!(apu.read(NR52), 0x70);
+        assert_eq!(apu.read(NR12), 0x00);
+        assert_eq!(apu.read(NR50), 0x00);
+        assert_eq!(apu.read(WAVE_RAM), 0x5A, "wave RAM survives power off");
+
+        apu.write(NR50, 0x77);
+        assert_eq!(apu.read(NR50), 0x00, "writes ignored while powered off");
+        assert_eq!(apu.read(NR10), 0x80, "unused bits read as 1");
+    }
+
+    #[test]
+    fn trigger_sets_status_and_dac_off_disables() {
+        let mut apu = powered();
+        apu.write(NR22, 0xF0);
+        apu.write(NR24, 0x80);
+        assert_eq!(apu.read(NR52) & 0x0F, 0x02);
+
+        apu.write(NR22, 0x00);
+        assert_eq!(apu.read(NR52) & 0x0F, 0x00, "DAC off stops the channel");
+    }
+
+    #[test]
+    fn length_counter_expires_channel() {
+        let mut apu = powered();
+        apu.write(NR21, 0x3E); // two length ticks remaining
+        apu.write(NR22, 0xF0);
+        apu.write(NR24, 0xC0);
+        assert!(apu.ch2.enabled);
+
+        run(&mut apu, FRAME_SEQUENCER_PERIOD * 4);
+        assert!(!apu.ch2.enabled, "length of 2 expires within 4 steps");
+    }
+
+    #[test]
+    fn sweep_overflow_on_trigger_disables_channel_one() {
+        let mut apu = powered();
+        apu.write(NR10, 0x11); // period 1, increase, shift 1
+        apu.write(NR12, 0xF0);
+        apu.write(NR13, 0xFF);
+        apu.write(NR14, 0x87); // freq 0x7FF, trigger
+        assert!(!apu.ch1.enabled, "0x7FF + 0x3FF overflows immediately");
+    }
+
+    #[test]
+    fn envelope_decays_volume() {
+        let mut apu = powered();
+        apu.write(NR12, 0xF1); // volume 15, decrease, period 1
+        apu.write(NR14, 0x80);
+        run(&mut apu, FRAME_SEQUENCER_PERIOD * 8);
+        assert_eq!(apu.ch1.envelope.volume, 14);
+    }
+
+    #[test]
+    fn noise_lfsr_shifts_with_short_mode() {
+        let mut apu = powered();
+        apu.write(NR42, 0xF0);
+        apu.write(NR43, 0x08); // 7-bit mode, divisor 8
+        apu.write(NR44, 0x80);
+        run(&mut apu, 8 * 4);
+        assert_ne!(apu.ch4.lfsr, 0x7FFF);
+        assert_eq!(apu.ch4.lfsr & 0x40, (apu.ch4.lfsr >> 8) & 0x40);
+    }
+
+    #[test]
+    fn square_wave_resamples_to_host_rate() {
+        let mut apu = powered();
+        apu.write(NR12, 0xF0);
+        apu.write(NR11, 0x80); // 50% duty
+        apu.write(NR13, 0x00);
+        apu.write(NR14, 0x87); // ~ 1 kHz tone
+        run(&mut apu, FRAME_CYCLES);
+
+        let samples = apu.take_samples();
+        let frames = samples.len() / usize::from(OUTPUT_CHANNELS);
+        let expected =
+            (u64::from(FRAME_CYCLES) * u64::from(DEFAULT_SAMPLE_RATE_HZ) / CPU_HZ) as usize;
+        assert!(frames.abs_diff(expected) <= 1, "{frames} vs {expected}");
+        assert!(samples.iter().any(|&s| s > 0) && samples.iter().any(|&s| s < 0));
+        assert!(apu.take_samples().is_empty(), "samples drain once");
+    }
+
+    #[test]
+    fn panning_routes_channels_per_side() {
+        let mut apu = powered();
+        apu.write(NR51, 0x02); // channel 2 right only
+        apu.write(NR22, 0xF0);
+        apu.write(NR24, 0x80);
+        run(&mut apu, 4096);
+        let samples = apu.take_samples();
+        assert!(samples.chunks_exact(2).all(|frame| frame[0] == 0));
+        assert!(samples.chunks_exact(2).any(|frame| frame[1] != 0));
+    }
+
+    #[test]
+    fn full_buffer_keeps_the_newest_second() {
+        let mut apu = powered();
+        run(&mut apu, 2 * CPU_HZ as u32);
+        let samples = apu.take_samples();
+        assert_eq!(samples.len(), MAX_BUFFERED_SAMPLES);
+    }
+
+    #[test]
+    fn capture_off_buffers_nothing() {
+        let mut apu = powered();
+        apu.write(NR22, 0xF0);
+        apu.write(NR24, 0x80);
+        run(&mut apu, 4096);
+        apu.set_capture(false);
+        assert!(
+            apu.take_samples().is_empty(),
+            "disabling drops buffered output"
+        );
+        run(&mut apu, FRAME_CYCLES);
+        assert!(apu.take_samples().is_empty());
+
+        apu.reset();
+        assert!(!apu.capture(), "reset keeps the capture setting");
+        apu.set_capture(true);
+        run(&mut apu, FRAME_CYCLES);
+        assert!(!apu.take_samples().is_empty());
+    }
+}
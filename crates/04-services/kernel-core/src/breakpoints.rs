@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+/// Kind of CPU memory access a watchpoint traps on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WatchAccess {
+    /// The CPU reads from the watched range.
+    Read,
+    /// The CPU writes to the watched range.
+    Write,
+}
+
+/// Address range trapped on a particular kind of access.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Watchpoint {
+    /// Inclusive address range being watched.
+    pub range: RangeInclusive<u16>,
+    /// Access kind that triggers the watchpoint.
+    pub access: WatchAccess,
+}
+
+/// Why [`crate::Core::step_cycles`] stopped short of its budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakReason {
+    /// The PC reached a breakpoint; the instruction there has not run yet.
+    Breakpoint,
+    /// The previous instruction touched a watched address.
+    Watchpoint {
+        /// Address that was accessed.
+        addr: u16,
+        /// Kind of access that tripped the watchpoint.
+        access: WatchAccess,
+    },
+}
+
+/// Breakpoints and watchpoints consulted while the core runs.
+///
+/// Only [`crate::Core::step_cycles`] honours them: single-stepping always
+/// executes the next instruction, which is also how a paused core steps off a
+/// breakpoint. Addresses are checked against lane 0 on SIMD cores.
+#[derive(Clone, Debug, Default)]
+pub struct Breakpoints {
+    pcs: BTreeSet<u16>,
+    watchpoints: Vec<Watchpoint>,
+    hit: Option<BreakReason>,
+    resume_pc: Option<u16>,
+}
+
+impl Breakpoints {
+    /// Stops execution before the instruction at `pc`.
+    pub fn set_breakpoint(&mut self, pc: u16) {
+        self.pcs.insert(pc);
+    }
+
+    /// Removes the breakpoint at `pc`, returning whether one was set.
+    pub fn clear_breakpoint(&mut self, pc: u16) -> bool {
+        self.pcs.remove(&pc)
+    }
+
+    /// Stops execution after any instruction that accesses `range` as `access`.
+    pub fn set_watchpoint(&mut self, range: RangeInclusive<u16>, access: WatchAccess) {
+        let watchpoint = Watchpoint { range, access };
+        if !self.watchpoints.contains(&watchpoint) {
+            self.watchpoints.push(watchpoint);
+        }
+    }
+
+    /// Removes the watchpoint matching `range` and `access`, returning whether one was set.
+    pub fn clear_watchpoint(&mut self, range: RangeInclusive<u16>, access: WatchAccess) -> bool {
+        let before = self.watchpoints.len();
+        self.watchpoints
+            .retain(|wp| wp.range != range || wp.access != access);
+        self.watchpoints.len() != before
+    }
+
+    /// Returns the active breakpoint addresses in ascending order.
+    pub fn breakpoints(&self) -> impl Iterator<Item = u16> + '_ {
+        self.pcs.iter().copied()
+    }
+
+    /// Returns the active watchpoints in insertion order.
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Returns the pending break, if the core stopped on one.
+    #[inline]
+    pub fn hit(&self) -> Option<BreakReason> {
+        self.hit
+    }
+
+    /// Clears and returns the pending break.
+    pub fn take_hit(&mut self) -> Option<BreakReason> {
+        self.hit.take()
+    }
+
+    /// Lets the next instruction at `pc` run even if a breakpoint sits there.
+    ///
+    /// Call this before resuming a core that stopped on a breakpoint so it
+    /// does not trap on the same instruction again.
+    pub fn resume_from(&mut self, pc: u16) {
+        self.hit = None;
+        self.resume_pc = Some(pc);
+    }
+
+    /// Records a breakpoint hit if one is set at `pc`.
+    pub(crate) fn check_pc(&mut self, pc: u16) -> bool {
+        let resuming = self.resume_pc.take() == Some(pc);
+        if resuming || !self.pcs.contains(&pc) {
+            return false;
+        }
+        self.hit = Some(BreakReason::Breakpoint);
+        true
+    }
+
+    /// Records a watchpoint hit if `addr` is watched for `access`.
+    #[inline]
+    pub(crate) fn check_access(&mut self, addr: u16, access: WatchAccess) {
+        if self.watchpoints.is_empty() || self.hit.is_some() {
+            return;
+        }
+        if self
+            .watchpoints
+            .iter()
+            .any(|wp| wp.access == access && wp.range.contains(&addr))
+        {
+            self.hit = Some(BreakReason::Watchpoint { addr, access });
+        }
+    }
+}
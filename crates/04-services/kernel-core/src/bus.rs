@@ -1,5 +1,11 @@
+use crate::apu::Apu;
+use crate::cartridge::Cartridge;
+use crate::cgb::Cgb;
+use crate::cheats::Cheats;
+use crate::dma::OamDma;
 use crate::exec::{Exec, Scalar};
 use crate::mmu;
+use crate::rtc::RtcClock;
 use std::sync::Arc;
 
 /// Memory and IO bus abstraction.
@@ -22,12 +28,51 @@ pub trait SerialIo {
     fn step_serial(&mut self, cycles: u32);
 }
 
+/// Trait exposing the joypad input lines.
+pub trait JoypadIo {
+    /// Latches host inputs (active-low, buttons in the high nibble) and raises
+    /// the joypad interrupt on a high-to-low edge of a selected line; returns
+    /// whether such an edge occurred.
+    fn set_inputs(&mut self, joypad: u8) -> bool;
+    /// Returns whether any joypad line selected through JOYP is held low.
+    fn joypad_line_low(&self) -> bool;
+}
+
+/// Trait exposing cartridge hardware that advances with CPU time.
+pub trait CartridgeIo {
+    /// Advances cartridge peripherals such as the MBC3 clock by `cycles` CPU ticks.
+    fn step_cartridge(&mut self, cycles: u32);
+    /// Selects the MBC3 clock source for the current and future cartridges.
+    fn set_rtc_clock(&mut self, clock: RtcClock);
+    /// Advances a wall-clock driven MBC3 clock to the host time `unix_secs`.
+    fn sync_rtc(&mut self, unix_secs: u64);
+}
+
+/// Trait exposing the audio unit that advances with CPU time.
+pub trait ApuIo {
+    /// Advances the APU by `cycles` CPU ticks.
+    fn step_apu(&mut self, cycles: u32);
+    /// Selects the host sample rate generated audio is resampled to.
+    fn set_audio_sample_rate(&mut self, sample_rate_hz: u32);
+    /// Turns resampled host output on or off.
+    fn set_audio_capture(&mut self, capture: bool);
+    /// Drains the interleaved stereo samples produced since the last call.
+    fn take_audio_samples(&mut self) -> Vec<i16>;
+    /// Applies the APU register state left behind by the boot ROM.
+    fn initialize_apu_post_boot(&mut self);
+}
+
 /// Scalar bus implementation backed by in-memory regions.
+#[derive(Clone)]
 pub struct BusScalar {
-    /// Entire cartridge ROM contents.
-    pub rom: Arc<[u8]>,
-    /// Currently selected switchable ROM bank (bank 0 fixed).
-    pub rom_bank: usize,
+    /// Inserted cartridge: ROM, external RAM, and mapper registers.
+    pub cart: Cartridge,
+    /// Audio processing unit owning `0xFF10..=0xFF3F`.
+    pub apu: Apu,
+    /// CGB-only banks, palettes and VRAM DMA; inert in DMG mode.
+    pub cgb: Cgb,
+    /// Time source applied to MBC3 clocks of inserted cartridges.
+    rtc_clock: RtcClock,
     /// Optional bootstrap ROM overlay.
     boot_rom: Option<Arc<[u8]>>,
     /// Tracks whether the bootstrap overlay is active.
@@ -38,6 +83,8 @@ pub struct BusScalar {
     pub wram: Box<[u8; 0x2000]>,
     /// Object attribute memory backing sprites.
     pub oam: Box<[u8; 0xA0]>,
+    /// OAM DMA controller driven by writes to `0xFF46`.
+    pub oam_dma: OamDma,
     /// High RAM window at `0xFF80`.
     pub hram: [u8; 0x7F],
     /// IO register block.
@@ -74,20 +121,25 @@ pub struct BusScalar {
     pub timer_tma_write: Option<u8>,
     /// Captures the most recent TAC write (old, new) until timers acknowledge it.
     pub timer_tac_write: Option<(u8, u8)>,
+    /// Active Game Genie and GameShark codes.
+    pub cheats: Cheats,
 }
 
 impl BusScalar {
     /// Creates a bus using the supplied ROM bytes.
     pub fn new(rom: Arc<[u8]>, boot_rom: Option<Arc<[u8]>>) -> Self {
         let boot_rom_enabled = boot_rom.is_some();
-        let mut bus = Self {
-            rom,
-            rom_bank: 1,
+        Self {
+            cart: Cartridge::new(rom),
+            apu: Apu::default(),
+            cgb: Cgb::default(),
+            rtc_clock: RtcClock::default(),
             boot_rom,
             boot_rom_enabled,
             vram: Box::new([0; 0x2000]),
             wram: Box::new([0; 0x2000]),
             oam: Box::new([0; 0xA0]),
+            oam_dma: OamDma::default(),
             hram: [0; 0x7F],
             io: IoRegs::new(),
             ie: 0,
@@ -106,14 +158,14 @@ impl BusScalar {
             timer_tima_write: None,
             timer_tma_write: None,
             timer_tac_write: None,
-        };
-        bus.set_rom_bank(1);
-        bus
+            cheats: Cheats::default(),
+        }
     }
 
-    /// Replaces the ROM contents.
+    /// Replaces the ROM contents and drops the cheats meant for the previous game.
     pub fn load_rom(&mut self, rom: Arc<[u8]>) {
-        self.rom = rom;
+        self.cart = Cartridge::with_rtc_clock(rom, self.rtc_clock);
+        self.cheats = Cheats::default();
         self.boot_rom_enabled = self.boot_rom.is_some();
         self.serial_out.clear();
         self.reset_serial_state();
@@ -124,7 +176,6 @@ impl BusScalar {
         self.timer_tima_write = None;
         self.timer_tma_write = None;
         self.timer_tac_write = None;
-        self.set_rom_bank(1);
     }
 
     /// Resets VRAM, WRAM, and IO state to their power-on defaults.
@@ -132,6 +183,7 @@ impl BusScalar {
         self.vram.fill(0);
         self.wram.fill(0);
         self.oam.fill(0);
+        self.oam_dma = OamDma::default();
         self.hram = [0; 0x7F];
         self.io = IoRegs::new();
         self.ie = 0;
@@ -150,7 +202,49 @@ impl BusScalar {
         self.timer_tma_write = None;
         self.timer_tac_write = None;
         self.boot_rom_enabled = self.boot_rom.is_some();
-        self.set_rom_bank(1);
+        self.cart.reset();
+        self.apu.reset();
+        self.cgb = Cgb::default();
+    }
+
+    /// Copies the registers, OAM and high RAM a parked SIMD lane shares with
+    /// `lead`, skipping video and work RAM, cartridge RAM and the APU.
+    pub(crate) fn sync_registers_from(&mut self, lead: &BusScalar) {
+        self.cgb.sync_registers_from(&lead.cgb);
+        self.boot_rom_enabled = lead.boot_rom_enabled;
+        self.oam.copy_from_slice(&lead.oam[..]);
+        self.oam_dma = lead.oam_dma;
+        self.hram = lead.hram;
+        self.io = lead.io.clone();
+        self.ie = lead.ie;
+        self.serial_counter = lead.serial_counter;
+        self.serial_active = lead.serial_active;
+        self.serial_pending_data = lead.serial_pending_data;
+        self.serial_shift_reg = lead.serial_shift_reg;
+        self.serial_bits_remaining = lead.serial_bits_remaining;
+        self.serial_internal_clock = lead.serial_internal_clock;
+        self.lockstep_ly_override = lead.lockstep_ly_override;
+        self.joyp_select = lead.joyp_select;
+        self.joyp_buttons = lead.joyp_buttons;
+        self.joyp_dpad = lead.joyp_dpad;
+        self.timer_div_reset = lead.timer_div_reset;
+        self.timer_tima_write = lead.timer_tima_write;
+        self.timer_tma_write = lead.timer_tma_write;
+        self.timer_tac_write = lead.timer_tac_write;
+    }
+
+    /// Seeds battery-backed cartridge RAM from a persisted image.
+    pub fn load_save_ram(&mut self, bytes: &[u8]) {
+        self.cart.load_save_data(bytes);
+    }
+
+    /// Returns the battery image if battery-backed state changed since the last call.
+    pub fn take_dirty_save_ram(&mut self) -> Option<Arc<[u8]>> {
+        if self.cart.take_ram_dirty() {
+            Some(Arc::from(self.cart.save_data()))
+        } else {
+            None
+        }
     }
 
     /// Enables or disables the bootstrap overlay.
@@ -194,23 +288,14 @@ impl BusScalar {
         IoRegs::SC
     }
 
-    fn rom_bank_count(&self) -> usize {
-        self.rom.len().div_ceil(0x4000)
-    }
-
-    pub(crate) fn set_rom_bank(&mut self, bank: usize) {
-        let total = self.rom_bank_count().max(1);
-        let mut bank = bank % total;
-        if total > 1 && bank == 0 {
-            bank = 1;
-        }
-        self.rom_bank = bank;
-    }
-
     pub(crate) fn boot_rom_byte(&self, addr: u16) -> Option<u8> {
         if !self.boot_rom_enabled {
             return None;
         }
+        // CGB boot ROMs skip the cartridge header window at 0x0100..=0x01FF.
+        if (0x0100..0x0200).contains(&addr) {
+            return None;
+        }
         self.boot_rom
             .as_ref()
             .and_then(|boot| boot.get(addr as usize).copied())
@@ -313,10 +398,37 @@ impl BusScalar {
     }
 
     /// Updates the latched joypad inputs using active-low semantics per Game Boy hardware.
+    ///
+    /// Returns whether a selected line went low, which also requests the joypad interrupt.
     #[inline]
-    pub fn set_inputs(&mut self, joypad: u8) {
+    pub fn set_inputs(&mut self, joypad: u8) -> bool {
+        let before = self.joypad_lines();
         self.joyp_buttons = (joypad >> 4) & 0x0F;
         self.joyp_dpad = joypad & 0x0F;
+        self.latch_joypad_edge(before)
+    }
+
+    /// Returns the JOYP input lines (bits 0-3, active-low) for the current column select.
+    #[inline]
+    pub fn joypad_lines(&self) -> u8 {
+        let mut lines = 0x0F;
+        if self.joyp_select & 0x10 == 0 {
+            lines &= self.joyp_dpad;
+        }
+        if self.joyp_select & 0x20 == 0 {
+            lines &= self.joyp_buttons;
+        }
+        lines
+    }
+
+    /// Requests the joypad interrupt if a line that was high in `before` is now low.
+    pub(crate) fn latch_joypad_edge(&mut self, before: u8) -> bool {
+        let fell = before & !self.joypad_lines() & 0x0F != 0;
+        if fell {
+            let if_reg = self.io.if_reg() | 0x10;
+            self.io.set_if(if_reg);
+        }
+        fell
     }
 }
 
@@ -346,6 +458,61 @@ impl SerialIo for BusScalar {
     }
 }
 
+impl JoypadIo for BusScalar {
+    #[inline]
+    fn set_inputs(&mut self, joypad: u8) -> bool {
+        BusScalar::set_inputs(self, joypad)
+    }
+
+    #[inline]
+    fn joypad_line_low(&self) -> bool {
+        self.joypad_lines() != 0x0F
+    }
+}
+
+impl CartridgeIo for BusScalar {
+    #[inline]
+    fn step_cartridge(&mut self, cycles: u32) {
+        self.cart.step(cycles);
+    }
+
+    fn set_rtc_clock(&mut self, clock: RtcClock) {
+        self.rtc_clock = clock;
+        self.cart.set_rtc_clock(clock);
+    }
+
+    fn sync_rtc(&mut self, unix_secs: u64) {
+        self.cart.sync_rtc(unix_secs);
+    }
+}
+
+impl ApuIo for BusScalar {
+    #[inline]
+    fn step_apu(&mut self, cycles: u32) {
+        self.apu.step(cycles);
+    }
+
+    fn set_audio_sample_rate(&mut self, sample_rate_hz: u32) {
+        self.apu.set_sample_rate(sample_rate_hz);
+    }
+
+    fn set_audio_capture(&mut self, capture: bool) {
+        self.apu.set_capture(capture);
+    }
+
+    fn take_audio_samples(&mut self) -> Vec<i16> {
+        self.apu.take_samples()
+    }
+
+    fn initialize_apu_post_boot(&mut self) {
+        self.apu.initialize_post_boot();
+        for idx in crate::apu::NR10..=crate::apu::APU_REG_END {
+            let value = self.apu.read(idx);
+            self.io.write(idx, value);
+        }
+    }
+}
+
 /// IO register block for the scalar bus.
 #[derive(Clone)]
 pub struct IoRegs {
@@ -1,10 +1,14 @@
 //! SIMD-aware bus that multiplexes the scalar implementation across lanes.
 
-use crate::bus::{Bus, BusScalar, InterruptCtrl, SerialIo};
+use crate::bus::{ApuIo, Bus, BusScalar, CartridgeIo, InterruptCtrl, JoypadIo, SerialIo};
+use crate::cgb::CgbIo;
+use crate::cheats::{CheatIo, Cheats};
+use crate::dma::OamDmaIo;
 use crate::exec::Exec;
 use crate::exec_simd::SimdExec;
 use crate::mmu;
-use crate::ppu_stub::{PpuFrameSource, PpuIo};
+use crate::ppu::{PpuFrameSource, PpuIo};
+use crate::rtc::RtcClock;
 use crate::timers::TimerIo;
 use core::simd::{LaneCount, Simd, SupportedLaneCount};
 use std::array;
@@ -70,9 +74,25 @@ where
         }
     }
 
+    /// Seeds battery-backed cartridge RAM for every lane.
+    pub fn load_save_ram(&mut self, bytes: &[u8]) {
+        for lane in &mut self.lanes {
+            lane.load_save_ram(bytes);
+        }
+    }
+
+    /// Returns each lane whose cartridge RAM changed, with its contents, clearing those flags.
+    pub fn take_dirty_save_ram(&mut self) -> Vec<(usize, Arc<[u8]>)> {
+        self.lanes
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(lane, bus)| Some((lane, bus.take_dirty_save_ram()?)))
+            .collect()
+    }
+
     /// Returns a clone of the cartridge ROM for diagnostics.
     pub fn snapshot_rom(&self) -> Arc<[u8]> {
-        Arc::clone(&self.lanes[0].rom)
+        Arc::clone(self.lanes[0].cart.rom())
     }
 
     /// Provides immutable access to a specific lane for diagnostics and tests.
@@ -85,13 +105,6 @@ where
         &mut self.lanes[lane]
     }
 
-    /// Applies joypad inputs across lanes, keeping them in lockstep.
-    pub fn set_inputs(&mut self, joypad: u8) {
-        for lane in &mut self.lanes {
-            lane.set_inputs(joypad);
-        }
-    }
-
     fn canonical_lane(&self) -> &BusScalar {
         &self.lanes[0]
     }
@@ -152,6 +165,156 @@ where
     }
 }
 
+impl<const LANES: usize> JoypadIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    /// Applies joypad inputs across lanes, keeping them in lockstep.
+    fn set_inputs(&mut self, joypad: u8) -> bool {
+        let mut edge = false;
+        for lane in &mut self.lanes {
+            edge |= lane.set_inputs(joypad);
+        }
+        edge
+    }
+
+    fn joypad_line_low(&self) -> bool {
+        self.lanes.iter().any(|lane| lane.joypad_line_low())
+    }
+}
+
+impl<const LANES: usize> CartridgeIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    #[inline]
+    fn step_cartridge(&mut self, cycles: u32) {
+        for lane in &mut self.lanes {
+            lane.step_cartridge(cycles);
+        }
+    }
+
+    fn set_rtc_clock(&mut self, clock: RtcClock) {
+        for lane in &mut self.lanes {
+            lane.set_rtc_clock(clock);
+        }
+    }
+
+    fn sync_rtc(&mut self, unix_secs: u64) {
+        for lane in &mut self.lanes {
+            lane.sync_rtc(unix_secs);
+        }
+    }
+}
+
+impl<const LANES: usize> CgbIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    fn reset_cgb(&mut self, enabled: bool) {
+        for lane in &mut self.lanes {
+            lane.reset_cgb(enabled);
+        }
+    }
+
+    #[inline]
+    fn double_speed(&self) -> bool {
+        self.lanes[0].double_speed()
+    }
+
+    fn try_speed_switch(&mut self) -> bool {
+        let mut switched = false;
+        for (idx, lane) in self.lanes.iter_mut().enumerate() {
+            let lane_switched = lane.try_speed_switch();
+            if idx == 0 {
+                switched = lane_switched;
+            }
+        }
+        switched
+    }
+
+    fn hdma_hblank(&mut self) {
+        for lane in &mut self.lanes {
+            lane.hdma_hblank();
+        }
+    }
+
+    /// Returns the canonical lane's stall and discards the others.
+    fn take_dma_stall(&mut self) -> u32 {
+        for lane in self.lanes.iter_mut().skip(1) {
+            lane.take_dma_stall();
+        }
+        self.lanes[0].take_dma_stall()
+    }
+}
+
+impl<const LANES: usize> CheatIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    fn set_cheats(&mut self, cheats: &Cheats) {
+        for lane in &mut self.lanes {
+            lane.set_cheats(cheats);
+        }
+    }
+
+    fn apply_frame_cheats(&mut self) {
+        for lane in &mut self.lanes {
+            lane.apply_frame_cheats();
+        }
+    }
+}
+
+impl<const LANES: usize> OamDmaIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    #[inline]
+    fn step_oam_dma(&mut self, cycles: u32) {
+        for lane in &mut self.lanes {
+            lane.step_oam_dma(cycles);
+        }
+    }
+}
+
+impl<const LANES: usize> ApuIo for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    #[inline]
+    fn step_apu(&mut self, cycles: u32) {
+        for lane in &mut self.lanes {
+            lane.step_apu(cycles);
+        }
+    }
+
+    fn set_audio_sample_rate(&mut self, sample_rate_hz: u32) {
+        for lane in &mut self.lanes {
+            lane.set_audio_sample_rate(sample_rate_hz);
+        }
+    }
+
+    fn set_audio_capture(&mut self, capture: bool) {
+        for lane in &mut self.lanes {
+            lane.set_audio_capture(capture);
+        }
+    }
+
+    /// Returns the canonical lane's samples and discards the others.
+    fn take_audio_samples(&mut self) -> Vec<i16> {
+        for lane in self.lanes.iter_mut().skip(1) {
+            lane.take_audio_samples();
+        }
+        self.lanes[0].take_audio_samples()
+    }
+
+    fn initialize_apu_post_boot(&mut self) {
+        for lane in &mut self.lanes {
+            lane.initialize_apu_post_boot();
+        }
+    }
+}
+
 impl<const LANES: usize> TimerIo for BusSimd<LANES>
 where
     LaneCount<LANES>: SupportedLaneCount,
@@ -322,17 +485,27 @@ where
     LaneCount<LANES>: SupportedLaneCount,
 {
     #[inline]
-    fn ppu_io(&self) -> &crate::bus::IoRegs {
-        self.canonical_lane().ppu_io()
+    fn ppu_lanes(&self) -> usize {
+        LANES
+    }
+
+    #[inline]
+    fn ppu_io(&self, lane: usize) -> &crate::bus::IoRegs {
+        &self.lanes[lane].io
+    }
+
+    #[inline]
+    fn ppu_vram(&self, lane: usize) -> &[u8; 0x2000] {
+        &self.lanes[lane].vram
     }
 
     #[inline]
-    fn ppu_vram(&self) -> &[u8; 0x2000] {
-        self.canonical_lane().ppu_vram()
+    fn ppu_oam(&self, lane: usize) -> &[u8; 0xA0] {
+        &self.lanes[lane].oam
     }
 
     #[inline]
-    fn ppu_oam(&self) -> &[u8; 0xA0] {
-        self.canonical_lane().ppu_oam()
+    fn ppu_cgb(&self, lane: usize) -> Option<&crate::cgb::Cgb> {
+        self.lanes[lane].ppu_cgb(0)
     }
 }
@@ -0,0 +1,926 @@
+use crate::rtc::{Rtc, RtcClock, RTC_FOOTER_LEN};
+use crate::state::{MapperState, RtcState};
+use std::sync::Arc;
+
+/// Size of a switchable ROM bank.
+pub const ROM_BANK_SIZE: usize = 0x4000;
+/// Size of a switchable external RAM bank.
+pub const RAM_BANK_SIZE: usize = 0x2000;
+/// Size of the MBC2 built-in RAM (512 half-bytes).
+const MBC2_RAM_SIZE: usize = 0x200;
+
+const HEADER_LOGO: usize = 0x0104;
+const HEADER_LOGO_LEN: usize = 0x30;
+const HEADER_CGB_FLAG: usize = 0x0143;
+const HEADER_CART_TYPE: usize = 0x0147;
+const HEADER_RAM_SIZE: usize = 0x0149;
+
+/// Memory bank controller family declared by the cartridge header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapperKind {
+    /// Plain 32 KiB ROM with optional unbanked RAM.
+    RomOnly,
+    /// MBC1, optionally wired as a multicart (MBC1M).
+    Mbc1 {
+        /// Whether the upper bank bits are wired for a multicart.
+        multicart: bool,
+    },
+    /// MBC2 with built-in 512×4-bit RAM.
+    Mbc2,
+    /// MBC3, optionally with a real-time clock.
+    Mbc3,
+    /// MBC5, optionally with a rumble motor.
+    Mbc5,
+}
+
+/// Decoded subset of the cartridge header relevant to the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CartridgeHeader {
+    /// Raw cartridge type byte at `0x0147`.
+    pub cart_type: u8,
+    /// Mapper family derived from the type byte.
+    pub mapper: MapperKind,
+    /// External RAM size in bytes.
+    pub ram_size: usize,
+    /// Whether external RAM is battery backed.
+    pub has_battery: bool,
+    /// Whether the cartridge carries an MBC3 real-time clock.
+    pub has_rtc: bool,
+    /// Whether the cartridge carries an MBC5 rumble motor.
+    pub has_rumble: bool,
+    /// Whether the CGB flag at `0x0143` asks for Game Boy Color features.
+    pub cgb: bool,
+}
+
+impl CartridgeHeader {
+    /// Decodes the header from raw ROM bytes.
+    ///
+    /// Images without a valid type byte fall back to [`MapperKind::RomOnly`];
+    /// headerless images larger than 32 KiB are treated as MBC1 so synthetic
+    /// test ROMs keep their bank switching.
+    pub fn parse(rom: &[u8]) -> Self {
+        let cart_type = rom.get(HEADER_CART_TYPE).copied().unwrap_or(0x00);
+        let (mapper, has_ram, has_battery, has_rtc, has_rumble) = match cart_type {
+            0x00 if rom.len() > 2 * ROM_BANK_SIZE => (
+                MapperKind::Mbc1 { multicart: false },
+                false,
+                false,
+                false,
+                false,
+            ),
+            0x01 => (mbc1_kind(rom), false, false, false, false),
+            0x02 => (mbc1_kind(rom), true, false, false, false),
+            0x03 => (mbc1_kind(rom), true, true, false, false),
+            0x05 => (MapperKind::Mbc2, true, false, false, false),
+            0x06 => (MapperKind::Mbc2, true, true, false, false),
+            0x08 => (MapperKind::RomOnly, true, false, false, false),
+            0x09 => (MapperKind::RomOnly, true, true, false, false),
+            0x0F => (MapperKind::Mbc3, false, true, true, false),
+            0x10 => (MapperKind::Mbc3, true, true, true, false),
+            0x11 => (MapperKind::Mbc3, false, false, false, false),
+            0x12 => (MapperKind::Mbc3, true, false, false, false),
+            0x13 => (MapperKind::Mbc3, true, true, false, false),
+            0x19 => (MapperKind::Mbc5, false, false, false, false),
+            0x1A => (MapperKind::Mbc5, true, false, false, false),
+            0x1B => (MapperKind::Mbc5, true, true, false, false),
+            0x1C => (MapperKind::Mbc5, false, false, false, true),
+            0x1D => (MapperKind::Mbc5, true, false, false, true),
+            0x1E => (MapperKind::Mbc5, true, true, false, true),
+            _ => (MapperKind::RomOnly, false, false, false, false),
+        };
+
+        let ram_size = if mapper == MapperKind::Mbc2 {
+            MBC2_RAM_SIZE
+        } else if has_ram {
+            match rom.get(HEADER_RAM_SIZE).copied().unwrap_or(0) {
+                0x01 => 0x800,
+                0x02 => RAM_BANK_SIZE,
+                0x03 => 4 * RAM_BANK_SIZE,
+                0x04 => 16 * RAM_BANK_SIZE,
+                0x05 => 8 * RAM_BANK_SIZE,
+                _ => 0,
+            }
+        } else {
+            0
+        };
+
+        Self {
+            cart_type,
+            mapper,
+            ram_size,
+            has_battery,
+            has_rtc,
+            has_rumble,
+            cgb: rom
+                .get(HEADER_CGB_FLAG)
+                .is_some_and(|flag| flag & 0x80 != 0),
+        }
+    }
+}
+
+/// Detects MBC1 multicarts: 1 MiB images whose second game (bank 0x10+)
+/// repeats the boot logo from bank 0.
+fn mbc1_kind(rom: &[u8]) -> MapperKind {
+    let multicart = rom.len() == 64 * ROM_BANK_SIZE && {
+        let logo = &rom[HEADER_LOGO..HEADER_LOGO + HEADER_LOGO_LEN];
+        let blank = logo.iter().all(|&b| b == 0x00) || logo.iter().all(|&b| b == 0xFF);
+        !blank
+            && (1..4).any(|game| {
+                let base = game * 0x10 * ROM_BANK_SIZE + HEADER_LOGO;
+                &rom[base..base + HEADER_LOGO_LEN] == logo
+            })
+    };
+    MapperKind::Mbc1 { multicart }
+}
+
+/// MBC1 register file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mbc1 {
+    ram_enabled: bool,
+    bank1: u8,
+    bank2: u8,
+    mode: bool,
+    multicart: bool,
+}
+
+impl Mbc1 {
+    fn new(multicart: bool) -> Self {
+        Self {
+            bank1: 1,
+            multicart,
+            ..Self::default()
+        }
+    }
+
+    fn upper_shift(&self) -> u32 {
+        if self.multicart {
+            4
+        } else {
+            5
+        }
+    }
+
+    fn rom_bank0(&self) -> usize {
+        if self.mode {
+            usize::from(self.bank2) << self.upper_shift()
+        } else {
+            0
+        }
+    }
+
+    fn rom_bankn(&self) -> usize {
+        let bank1 = if self.bank1 == 0 { 1 } else { self.bank1 };
+        let lower_mask = if self.multicart { 0x0F } else { 0x1F };
+        (usize::from(self.bank2) << self.upper_shift()) | usize::from(bank1 & lower_mask)
+    }
+
+    fn ram_bank(&self) -> usize {
+        if self.mode {
+            usize::from(self.bank2)
+        } else {
+            0
+        }
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => self.bank1 = value & 0x1F,
+            0x4000..=0x5FFF => self.bank2 = value & 0x03,
+            _ => self.mode = value & 0x01 != 0,
+        }
+    }
+}
+
+/// MBC2 register file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mbc2 {
+    ram_enabled: bool,
+    rom_bank: u8,
+}
+
+impl Default for Mbc2 {
+    fn default() -> Self {
+        Self {
+            ram_enabled: false,
+            rom_bank: 1,
+        }
+    }
+}
+
+impl Mbc2 {
+    fn write(&mut self, addr: u16, value: u8) {
+        if addr >= 0x4000 {
+            return;
+        }
+        // Address bit 8 selects between the RAM gate and the ROM bank register.
+        if addr & 0x0100 == 0 {
+            self.ram_enabled = value & 0x0F == 0x0A;
+        } else {
+            let bank = value & 0x0F;
+            self.rom_bank = if bank == 0 { 1 } else { bank };
+        }
+    }
+}
+
+/// MBC3 register file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mbc3 {
+    ram_enabled: bool,
+    rom_bank: u8,
+    ram_select: u8,
+}
+
+impl Default for Mbc3 {
+    fn default() -> Self {
+        Self {
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_select: 0,
+        }
+    }
+}
+
+impl Mbc3 {
+    fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => {
+                let bank = value & 0x7F;
+                self.rom_bank = if bank == 0 { 1 } else { bank };
+            }
+            0x4000..=0x5FFF => self.ram_select = value & 0x0F,
+            _ => {
+                // The RTC latch lives on the cartridge so it survives mapper resets.
+            }
+        }
+    }
+
+    fn rtc_select(&self) -> Option<u8> {
+        (self.ram_enabled && (0x08..=0x0C).contains(&self.ram_select)).then_some(self.ram_select)
+    }
+}
+
+/// MBC5 register file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mbc5 {
+    ram_enabled: bool,
+    rom_bank: u16,
+    ram_bank: u8,
+    rumble: bool,
+}
+
+impl Mbc5 {
+    fn new(rumble: bool) -> Self {
+        Self {
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            rumble,
+        }
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value == 0x0A,
+            0x2000..=0x2FFF => self.rom_bank = (self.rom_bank & 0x100) | u16::from(value),
+            0x3000..=0x3FFF => {
+                self.rom_bank = (self.rom_bank & 0x0FF) | (u16::from(value & 0x01) << 8)
+            }
+            0x4000..=0x5FFF => {
+                // Rumble carts drive the motor from bit 3 instead of the bank lines.
+                let mask = if self.rumble { 0x07 } else { 0x0F };
+                self.ram_bank = value & mask;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Active mapper state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mapper {
+    /// No banking hardware.
+    RomOnly,
+    /// MBC1 registers.
+    Mbc1(Mbc1),
+    /// MBC2 registers.
+    Mbc2(Mbc2),
+    /// MBC3 registers.
+    Mbc3(Mbc3),
+    /// MBC5 registers.
+    Mbc5(Mbc5),
+}
+
+impl Mapper {
+    fn for_header(header: &CartridgeHeader) -> Self {
+        match header.mapper {
+            MapperKind::RomOnly => Mapper::RomOnly,
+            MapperKind::Mbc1 { multicart } => Mapper::Mbc1(Mbc1::new(multicart)),
+            MapperKind::Mbc2 => Mapper::Mbc2(Mbc2::default()),
+            MapperKind::Mbc3 => Mapper::Mbc3(Mbc3::default()),
+            MapperKind::Mbc5 => Mapper::Mbc5(Mbc5::new(header.has_rumble)),
+        }
+    }
+
+    /// Flattens the register file into the mapper-agnostic save-state layout.
+    pub(crate) fn state(&self) -> MapperState {
+        match self {
+            Mapper::RomOnly => MapperState::default(),
+            Mapper::Mbc1(m) => MapperState {
+                ram_enabled: m.ram_enabled,
+                rom_bank: u16::from(m.bank1),
+                ram_bank: m.bank2,
+                mode: m.mode,
+            },
+            Mapper::Mbc2(m) => MapperState {
+                ram_enabled: m.ram_enabled,
+                rom_bank: u16::from(m.rom_bank),
+                ..MapperState::default()
+            },
+            Mapper::Mbc3(m) => MapperState {
+                ram_enabled: m.ram_enabled,
+                rom_bank: u16::from(m.rom_bank),
+                ram_bank: m.ram_select,
+                mode: false,
+            },
+            Mapper::Mbc5(m) => MapperState {
+                ram_enabled: m.ram_enabled,
+                rom_bank: m.rom_bank,
+                ram_bank: m.ram_bank,
+                mode: false,
+            },
+        }
+    }
+
+    /// Restores registers captured by [`Mapper::state`], masking values to the register widths.
+    pub(crate) fn load_state(&mut self, state: &MapperState) {
+        match self {
+            Mapper::RomOnly => {}
+            Mapper::Mbc1(m) => {
+                m.ram_enabled = state.ram_enabled;
+                m.bank1 = state.rom_bank as u8 & 0x1F;
+                m.bank2 = state.ram_bank & 0x03;
+                m.mode = state.mode;
+            }
+            Mapper::Mbc2(m) => {
+                m.ram_enabled = state.ram_enabled;
+                m.rom_bank = (state.rom_bank as u8 & 0x0F).max(1);
+            }
+            Mapper::Mbc3(m) => {
+                m.ram_enabled = state.ram_enabled;
+                m.rom_bank = (state.rom_bank as u8 & 0x7F).max(1);
+                m.ram_select = state.ram_bank & 0x0F;
+            }
+            Mapper::Mbc5(m) => {
+                m.ram_enabled = state.ram_enabled;
+                m.rom_bank = state.rom_bank & 0x1FF;
+                m.ram_bank = state.ram_bank & 0x0F;
+            }
+        }
+    }
+}
+
+/// Cartridge ROM, external RAM and the mapper that connects them to the bus.
+///
+/// Owns the `0x0000–0x7FFF` ROM window, the `0xA000–0xBFFF` external RAM
+/// window, and every mapper control write.
+#[derive(Clone)]
+pub struct Cartridge {
+    rom: Arc<[u8]>,
+    ram: Vec<u8>,
+    ram_dirty: bool,
+    rtc: Option<Rtc>,
+    header: CartridgeHeader,
+    mapper: Mapper,
+}
+
+impl Cartridge {
+    /// Creates a cartridge from raw ROM bytes, decoding the header.
+    pub fn new(rom: Arc<[u8]>) -> Self {
+        Self::with_rtc_clock(rom, RtcClock::default())
+    }
+
+    /// Creates a cartridge whose MBC3 clock, if present, is driven by `clock`.
+    pub fn with_rtc_clock(rom: Arc<[u8]>, clock: RtcClock) -> Self {
+        let header = CartridgeHeader::parse(&rom);
+        Self {
+            mapper: Mapper::for_header(&header),
+            ram: vec![0; header.ram_size],
+            ram_dirty: false,
+            rtc: header.has_rtc.then(|| Rtc::new(clock)),
+            header,
+            rom,
+        }
+    }
+
+    /// Returns the full ROM image.
+    #[inline]
+    pub fn rom(&self) -> &Arc<[u8]> {
+        &self.rom
+    }
+
+    /// Returns the decoded cartridge header.
+    #[inline]
+    pub fn header(&self) -> &CartridgeHeader {
+        &self.header
+    }
+
+    /// Returns the active mapper state.
+    #[inline]
+    pub fn mapper(&self) -> &Mapper {
+        &self.mapper
+    }
+
+    /// Returns the external RAM contents.
+    #[inline]
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Returns the external RAM contents mutably.
+    #[inline]
+    pub fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    /// Returns the MBC3 real-time clock, if the cartridge has one.
+    #[inline]
+    pub fn rtc(&self) -> Option<&Rtc> {
+        self.rtc.as_ref()
+    }
+
+    /// Selects the time source for the MBC3 clock, if present.
+    pub fn set_rtc_clock(&mut self, clock: RtcClock) {
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.set_clock(clock);
+        }
+    }
+
+    /// Advances cycle-driven cartridge hardware by `cycles` CPU ticks.
+    #[inline]
+    pub fn step(&mut self, cycles: u32) {
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.step_cycles(cycles);
+        }
+    }
+
+    /// Advances a wall-clock driven MBC3 clock to the host time `unix_secs`.
+    pub fn sync_rtc(&mut self, unix_secs: u64) {
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.sync_wall_clock(unix_secs);
+        }
+    }
+
+    /// Returns whether external RAM or the clock survives power-off.
+    #[inline]
+    pub fn has_battery(&self) -> bool {
+        self.header.has_battery && (!self.ram.is_empty() || self.rtc.is_some())
+    }
+
+    /// Returns the battery image: external RAM followed by the RTC trailer, if any.
+    pub fn save_data(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.ram.len() + RTC_FOOTER_LEN);
+        out.extend_from_slice(&self.ram);
+        if let Some(rtc) = &self.rtc {
+            out.extend_from_slice(&rtc.to_footer());
+        }
+        out
+    }
+
+    /// Seeds external RAM and the clock from a previously persisted battery image.
+    ///
+    /// The RTC trailer is only applied when the image is exactly RAM plus a 44- or
+    /// 48-byte trailer; otherwise extra bytes are ignored and missing bytes keep
+    /// their current value.
+    pub fn load_save_data(&mut self, bytes: &[u8]) {
+        let len = bytes.len().min(self.ram.len());
+        self.ram[..len].copy_from_slice(&bytes[..len]);
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.load_footer(&bytes[len..]);
+        }
+        self.ram_dirty = false;
+    }
+
+    /// Returns and clears whether battery-backed state changed since the last call.
+    pub fn take_ram_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.ram_dirty)
+    }
+
+    /// Returns the mapper registers in save-state form.
+    pub(crate) fn mapper_state(&self) -> MapperState {
+        self.mapper.state()
+    }
+
+    /// Restores mapper registers from a save state.
+    pub(crate) fn load_mapper_state(&mut self, state: &MapperState) {
+        self.mapper.load_state(state);
+    }
+
+    /// Returns the clock in save-state form, if the cartridge has one.
+    pub(crate) fn rtc_state(&self) -> Option<RtcState> {
+        self.rtc.as_ref().map(Rtc::state)
+    }
+
+    /// Restores the clock from a save state; ignored when the cartridge has none.
+    pub(crate) fn load_rtc_state(&mut self, state: &RtcState) {
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.load_state(state);
+        }
+    }
+
+    /// Restores mapper registers to their power-on values, keeping RAM contents.
+    pub fn reset(&mut self) {
+        self.mapper = Mapper::for_header(&self.header);
+    }
+
+    /// Returns the number of 16 KiB ROM banks in the image.
+    #[inline]
+    pub fn rom_bank_count(&self) -> usize {
+        self.rom.len().div_ceil(ROM_BANK_SIZE).max(1)
+    }
+
+    /// Returns the bytes of ROM bank `bank`, or `None` past the end of the image.
+    pub fn rom_bank_bytes(&self, bank: usize) -> Option<&[u8]> {
+        let start = bank.checked_mul(ROM_BANK_SIZE)?;
+        let end = (start + ROM_BANK_SIZE).min(self.rom.len());
+        self.rom.get(start..end).filter(|bytes| !bytes.is_empty())
+    }
+
+    /// Returns the bytes of external RAM bank `bank`, or `None` if the cartridge has no such bank.
+    pub fn ram_bank_bytes(&self, bank: usize) -> Option<&[u8]> {
+        let start = bank.checked_mul(RAM_BANK_SIZE)?;
+        let end = (start + RAM_BANK_SIZE).min(self.ram.len());
+        self.ram.get(start..end).filter(|bytes| !bytes.is_empty())
+    }
+
+    /// Overwrites ROM bytes starting at `offset` within `bank`, returning how many were written.
+    ///
+    /// The image is copied only while other holders share it, so they keep
+    /// seeing the unpatched bytes; later writes patch the private copy in place.
+    pub fn patch_rom(&mut self, bank: usize, offset: usize, bytes: &[u8]) -> usize {
+        let Some(start) = bank
+            .checked_mul(ROM_BANK_SIZE)
+            .and_then(|base| base.checked_add(offset))
+            .filter(|&start| offset < ROM_BANK_SIZE && start < self.rom.len())
+        else {
+            return 0;
+        };
+        let end = (start + bytes.len())
+            .min((bank + 1) * ROM_BANK_SIZE)
+            .min(self.rom.len());
+        Arc::make_mut(&mut self.rom)[start..end].copy_from_slice(&bytes[..end - start]);
+        end - start
+    }
+
+    /// Returns external RAM bank `bank` for writing, marking battery RAM dirty.
+    pub fn ram_bank_bytes_mut(&mut self, bank: usize) -> Option<&mut [u8]> {
+        let start = bank.checked_mul(RAM_BANK_SIZE)?;
+        let end = (start + RAM_BANK_SIZE).min(self.ram.len());
+        if start >= end {
+            return None;
+        }
+        self.ram_dirty |= self.header.has_battery;
+        Some(&mut self.ram[start..end])
+    }
+
+    /// Returns the ROM bank currently mapped at `0x0000–0x3FFF`.
+    pub fn rom_bank0(&self) -> usize {
+        let bank = match &self.mapper {
+            Mapper::Mbc1(mbc) => mbc.rom_bank0(),
+            _ => 0,
+        };
+        bank % self.rom_bank_count()
+    }
+
+    /// Returns the ROM bank currently mapped at `0x4000–0x7FFF`.
+    pub fn rom_bank(&self) -> usize {
+        let bank = match &self.mapper {
+            Mapper::RomOnly => 1,
+            Mapper::Mbc1(mbc) => mbc.rom_bankn(),
+            Mapper::Mbc2(mbc) => usize::from(mbc.rom_bank),
+            Mapper::Mbc3(mbc) => usize::from(mbc.rom_bank),
+            Mapper::Mbc5(mbc) => usize::from(mbc.rom_bank),
+        };
+        bank % self.rom_bank_count()
+    }
+
+    /// Returns the external RAM bank currently mapped at `0xA000–0xBFFF`.
+    pub fn ram_bank(&self) -> usize {
+        match &self.mapper {
+            Mapper::RomOnly | Mapper::Mbc2(_) => 0,
+            Mapper::Mbc1(mbc) => mbc.ram_bank(),
+            Mapper::Mbc3(mbc) => usize::from(mbc.ram_select & 0x03),
+            Mapper::Mbc5(mbc) => usize::from(mbc.ram_bank),
+        }
+    }
+
+    /// Reads a byte from the `0x0000–0x7FFF` ROM window.
+    #[inline]
+    pub fn read_rom(&self, addr: u16) -> u8 {
+        let bank = if addr < 0x4000 {
+            self.rom_bank0()
+        } else {
+            self.rom_bank()
+        };
+        let index = bank * ROM_BANK_SIZE + usize::from(addr & 0x3FFF);
+        self.rom.get(index).copied().unwrap_or(0xFF)
+    }
+
+    /// Handles a write to the `0x0000–0x7FFF` mapper control window.
+    pub fn write_control(&mut self, addr: u16, value: u8) {
+        match &mut self.mapper {
+            Mapper::RomOnly => {}
+            Mapper::Mbc1(mbc) => mbc.write(addr, value),
+            Mapper::Mbc2(mbc) => mbc.write(addr, value),
+            Mapper::Mbc3(_) if addr >= 0x6000 => {
+                if let Some(rtc) = self.rtc.as_mut() {
+                    rtc.write_latch(value);
+                }
+            }
+            Mapper::Mbc3(mbc) => mbc.write(addr, value),
+            Mapper::Mbc5(mbc) => mbc.write(addr, value),
+        }
+    }
+
+    fn rtc_select(&self) -> Option<u8> {
+        match &self.mapper {
+            Mapper::Mbc3(mbc) => mbc.rtc_select(),
+            _ => None,
+        }
+    }
+
+    fn ram_accessible(&self) -> bool {
+        match &self.mapper {
+            Mapper::RomOnly => true,
+            Mapper::Mbc1(mbc) => mbc.ram_enabled,
+            Mapper::Mbc2(mbc) => mbc.ram_enabled,
+            Mapper::Mbc3(mbc) => mbc.ram_enabled && mbc.ram_select <= 0x03,
+            Mapper::Mbc5(mbc) => mbc.ram_enabled,
+        }
+    }
+
+    fn ram_index(&self, addr: u16) -> Option<usize> {
+        if self.ram.is_empty() {
+            return None;
+        }
+        let offset = usize::from(addr - 0xA000);
+        let index = match &self.mapper {
+            Mapper::Mbc2(_) => offset & (MBC2_RAM_SIZE - 1),
+            _ => self.ram_bank() * RAM_BANK_SIZE + offset,
+        };
+        Some(index % self.ram.len())
+    }
+
+    /// Reads a byte from the `0xA000–0xBFFF` external RAM window.
+    pub fn read_ram(&self, addr: u16) -> u8 {
+        if let Some(select) = self.rtc_select() {
+            return self.rtc.as_ref().map_or(0xFF, |rtc| rtc.read(select));
+        }
+        if !self.ram_accessible() {
+            return 0xFF;
+        }
+        let Some(index) = self.ram_index(addr) else {
+            return 0xFF;
+        };
+        let byte = self.ram[index];
+        if matches!(self.mapper, Mapper::Mbc2(_)) {
+            // Only the low nibble is backed; the upper lines float high.
+            byte | 0xF0
+        } else {
+            byte
+        }
+    }
+
+    /// Writes a byte to the `0xA000–0xBFFF` external RAM window.
+    pub fn write_ram(&mut self, addr: u16, value: u8) {
+        if let Some(select) = self.rtc_select() {
+            if let Some(rtc) = self.rtc.as_mut() {
+                rtc.write(select, value);
+                self.ram_dirty |= self.header.has_battery;
+            }
+            return;
+        }
+        if !self.ram_accessible() {
+            return;
+        }
+        let Some(index) = self.ram_index(addr) else {
+            return;
+        };
+        let value = if matches!(self.mapper, Mapper::Mbc2(_)) {
+            value & 0x0F
+        } else {
+            value
+        };
+        if self.ram[index] != value {
+            self.ram[index] = value;
+            self.ram_dirty |= self.header.has_battery;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a ROM where every bank starts with its own bank number.
+    fn banked_rom(banks: usize, cart_type: u8, ram_size: u8) -> Arc<[u8]> {
+        let mut rom = vec![0u8; banks * ROM_BANK_SIZE];
+        for bank in 0..banks {
+            rom[bank * ROM_BANK_SIZE] = bank as u8;
+            rom[bank * ROM_BANK_SIZE + 1] = (bank >> 8) as u8;
+        }
+        rom[HEADER_CART_TYPE] = cart_type;
+        rom[HEADER_RAM_SIZE] = ram_size;
+        Arc::from(rom)
+    }
+
+    fn switchable_bank(cart: &Cartridge) -> usize {
+        usize::from(cart.read_rom(0x4000)) | usize::from(cart.read_rom(0x4001)) << 8
+    }
+
+    #[test]
+    fn header_decodes_mapper_and_ram() {
+        let header = CartridgeHeader::parse(&banked_rom(4, 0x03, 0x03));
+        assert_eq!(header.mapper, MapperKind::Mbc1 { multicart: false });
+        assert_eq!(header.ram_size, 0x8000);
+        assert!(header.has_battery);
+
+        let header = CartridgeHeader::parse(&banked_rom(4, 0x10, 0x03));
+        assert_eq!(header.mapper, MapperKind::Mbc3);
+        assert!(header.has_rtc);
+
+        let header = CartridgeHeader::parse(&banked_rom(4, 0x06, 0x00));
+        assert_eq!(header.mapper, MapperKind::Mbc2);
+        assert_eq!(header.ram_size, 0x200);
+    }
+
+    #[test]
+    fn mbc1_bank_zero_maps_to_one_and_upper_bits_apply() {
+        let mut cart = Cartridge::new(banked_rom(128, 0x01, 0x00));
+        assert_eq!(switchable_bank(&cart), 1);
+
+        cart.write_control(0x2000, 0x00);
+        assert_eq!(switchable_bank(&cart), 1);
+
+        cart.write_control(0x2000, 0x05);
+        cart.write_control(0x4000, 0x02);
+        assert_eq!(switchable_bank(&cart), 0x45);
+        assert_eq!(cart.read_rom(0x0000), 0, "mode 0 keeps bank 0 fixed");
+
+        cart.write_control(0x6000, 0x01);
+        assert_eq!(cart.read_rom(0x0000), 0x40, "mode 1 remaps the low window");
+    }
+
+    #[test]
+    fn mbc1_ram_requires_enable_and_banks_in_mode_one() {
+        let mut cart = Cartridge::new(banked_rom(4, 0x03, 0x03));
+        cart.write_ram(0xA000, 0x12);
+        assert_eq!(cart.read_ram(0xA000), 0xFF);
+
+        cart.write_control(0x0000, 0x0A);
+        cart.write_ram(0xA000, 0x12);
+        cart.write_control(0x6000, 0x01);
+        cart.write_control(0x4000, 0x02);
+        cart.write_ram(0xA000, 0x34);
+        assert_eq!(cart.read_ram(0xA000), 0x34);
+        assert_eq!(cart.ram()[2 * RAM_BANK_SIZE], 0x34);
+
+        cart.write_control(0x6000, 0x00);
+        assert_eq!(cart.read_ram(0xA000), 0x12);
+    }
+
+    #[test]
+    fn mbc1_multicart_uses_four_bit_lower_bank() {
+        let mut rom = banked_rom(64, 0x01, 0x00).to_vec();
+        for game in 0..4 {
+            let base = game * 0x10 * ROM_BANK_SIZE + HEADER_LOGO;
+            rom[base..base + HEADER_LOGO_LEN].fill(0xCE);
+        }
+        let mut cart = Cartridge::new(Arc::from(rom));
+        assert_eq!(cart.header().mapper, MapperKind::Mbc1 { multicart: true });
+
+        cart.write_control(0x4000, 0x01);
+        cart.write_control(0x2000, 0x12);
+        assert_eq!(switchable_bank(&cart), 0x12);
+        cart.write_control(0x6000, 0x01);
+        assert_eq!(cart.read_rom(0x0000), 0x10);
+    }
+
+    #[test]
+    fn mbc2_ram_is_four_bits_and_mirrored() {
+        let mut cart = Cartridge::new(banked_rom(16, 0x06, 0x00));
+        cart.write_control(0x0000, 0x0A);
+        cart.write_ram(0xA000, 0xAB);
+        assert_eq!(cart.read_ram(0xA000), 0xFB);
+        assert_eq!(cart.read_ram(0xA200), 0xFB);
+
+        cart.write_control(0x0100, 0x07);
+        assert_eq!(switchable_bank(&cart), 7);
+        cart.write_control(0x0100, 0x00);
+        assert_eq!(switchable_bank(&cart), 1);
+    }
+
+    #[test]
+    fn mbc3_selects_seven_bit_rom_bank() {
+        let mut cart = Cartridge::new(banked_rom(128, 0x13, 0x03));
+        cart.write_control(0x2000, 0x7F);
+        assert_eq!(switchable_bank(&cart), 0x7F);
+
+        cart.write_control(0x0000, 0x0A);
+        cart.write_control(0x4000, 0x03);
+        cart.write_ram(0xA000, 0x55);
+        assert_eq!(cart.ram()[3 * RAM_BANK_SIZE], 0x55);
+    }
+
+    #[test]
+    fn battery_ram_tracks_dirty_writes_and_seeds() {
+        let mut cart = Cartridge::new(banked_rom(4, 0x03, 0x02));
+        cart.load_save_data(&[0x11, 0x22]);
+        cart.write_control(0x0000, 0x0A);
+        assert_eq!(cart.read_ram(0xA001), 0x22);
+        assert!(!cart.take_ram_dirty());
+
+        cart.write_ram(0xA001, 0x22);
+        assert!(
+            !cart.take_ram_dirty(),
+            "rewriting the same value is not a change"
+        );
+        cart.write_ram(0xA001, 0x33);
+        assert!(cart.take_ram_dirty());
+        assert!(!cart.take_ram_dirty());
+
+        let mut volatile = Cartridge::new(banked_rom(4, 0x02, 0x02));
+        volatile.write_control(0x0000, 0x0A);
+        volatile.write_ram(0xA000, 0x44);
+        assert!(!volatile.take_ram_dirty());
+    }
+
+    #[test]
+    fn mbc3_rtc_registers_latch_and_persist_in_footer() {
+        let mut cart = Cartridge::new(banked_rom(4, 0x10, 0x03));
+        cart.write_control(0x0000, 0x0A);
+        cart.write_control(0x4000, 0x09);
+        cart.write_ram(0xA000, 30);
+        assert!(cart.take_ram_dirty(), "RTC writes are battery state");
+
+        cart.step(crate::rtc::RTC_CYCLES_PER_SECOND * 90);
+        cart.write_control(0x6000, 0x00);
+        cart.write_control(0x6000, 0x01);
+        assert_eq!(cart.read_ram(0xA000), 31);
+        cart.write_control(0x4000, 0x08);
+        assert_eq!(cart.read_ram(0xA000), 30);
+        assert!(
+            !cart.take_ram_dirty(),
+            "ticking alone does not dirty the save"
+        );
+
+        let image = cart.save_data();
+        assert_eq!(image.len(), 0x8000 + RTC_FOOTER_LEN);
+
+        let mut restored = Cartridge::new(banked_rom(4, 0x10, 0x03));
+        restored.load_save_data(&image);
+        assert_eq!(restored.rtc(), cart.rtc());
+    }
+
+    #[test]
+    fn mbc5_supports_nine_bit_rom_bank_and_bank_zero() {
+        let mut cart = Cartridge::new(banked_rom(512, 0x19, 0x00));
+        cart.write_control(0x2000, 0x00);
+        assert_eq!(switchable_bank(&cart), 0);
+
+        cart.write_control(0x2000, 0x23);
+        cart.write_control(0x3000, 0x01);
+        assert_eq!(switchable_bank(&cart), 0x123);
+    }
+
+    #[test]
+    fn bank_bytes_ignore_the_mapped_bank() {
+        let cart = Cartridge::new(banked_rom(4, 0x03, 0x03));
+        assert_eq!(cart.rom_bank_bytes(2).map(|bank| bank[0]), Some(2));
+        assert_eq!(cart.rom_bank_bytes(3).map(<[u8]>::len), Some(ROM_BANK_SIZE));
+        assert!(cart.rom_bank_bytes(4).is_none());
+        assert_eq!(cart.ram_bank_bytes(3).map(<[u8]>::len), Some(RAM_BANK_SIZE));
+        assert!(cart.ram_bank_bytes(4).is_none());
+    }
+
+    #[test]
+    fn patch_rom_copies_the_image_and_stays_in_bank() {
+        let original = banked_rom(4, 0x03, 0x03);
+        let mut cart = Cartridge::new(Arc::clone(&original));
+        assert_eq!(cart.patch_rom(1, ROM_BANK_SIZE - 1, &[0xAA, 0xBB]), 1);
+        assert_eq!(cart.rom_bank_bytes(1).unwrap()[ROM_BANK_SIZE - 1], 0xAA);
+        assert_eq!(cart.rom_bank_bytes(2).unwrap()[0], 2, "next bank untouched");
+        assert_eq!(original[2 * ROM_BANK_SIZE - 1], 0, "shared image untouched");
+        assert_eq!(cart.patch_rom(4, 0, &[0xAA]), 0);
+
+        let patched = Arc::as_ptr(cart.rom());
+        assert_eq!(cart.patch_rom(2, 0, &[0xCC]), 1);
+        assert_eq!(
+            Arc::as_ptr(cart.rom()),
+            patched,
+            "private copy patched in place"
+        );
+        assert_eq!(cart.rom_bank_bytes(2).unwrap()[0], 0xCC);
+    }
+}
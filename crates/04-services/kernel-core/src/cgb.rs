@@ -0,0 +1,339 @@
+use crate::bus::{BusScalar, IoRegs};
+use crate::exec::{Exec, Scalar};
+use crate::mmu;
+
+const KEY1: usize = 0x4D;
+const VBK: usize = 0x4F;
+const HDMA1: usize = 0x51;
+const HDMA2: usize = 0x52;
+const HDMA3: usize = 0x53;
+const HDMA4: usize = 0x54;
+const HDMA5: usize = 0x55;
+const BCPS: usize = 0x68;
+const BCPD: usize = 0x69;
+const OCPS: usize = 0x6A;
+const OCPD: usize = 0x6B;
+const SVBK: usize = 0x70;
+
+/// Bytes moved per HDMA/GDMA block.
+const HDMA_BLOCK_LEN: u16 = 0x10;
+/// CPU cycles a block stalls the CPU for at single speed (8 µs).
+const HDMA_BLOCK_CYCLES: u32 = 32;
+/// Bytes of palette RAM per bank (8 palettes × 4 RGB555 colours).
+pub const PALETTE_RAM_LEN: usize = 64;
+/// RGB555 white, little endian.
+const WHITE_RGB555: [u8; 2] = [0xFF, 0x7F];
+
+/// Trait exposing CGB-only hardware that the core drives.
+pub trait CgbIo {
+    /// Resets CGB-only state and selects whether CGB features are active.
+    fn reset_cgb(&mut self, enabled: bool);
+    /// Returns whether the CPU currently runs in double-speed mode.
+    fn double_speed(&self) -> bool;
+    /// Performs an armed KEY1 speed switch on STOP; returns `true` if the speed changed.
+    fn try_speed_switch(&mut self) -> bool;
+    /// Moves the next HBlank DMA block, if one is pending.
+    fn hdma_hblank(&mut self);
+    /// Returns and clears the CPU cycles owed to completed DMA blocks.
+    fn take_dma_stall(&mut self) -> u32;
+}
+
+/// CGB-only memory banks, colour palettes, speed switch and VRAM DMA state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cgb {
+    /// Whether CGB features are active; when `false` every register reads as on the DMG.
+    pub enabled: bool,
+    /// VRAM bank 1 holding BG attributes and extra tile data.
+    pub vram1: Box<[u8; 0x2000]>,
+    /// WRAM banks 2–7 (bank 1 lives in the upper half of the bus' `wram`).
+    pub wram_banks: Box<[u8; 0x6000]>,
+    /// VBK: selected VRAM bank.
+    pub vbk: u8,
+    /// SVBK: selected WRAM bank for `0xD000..=0xDFFF` (0 selects bank 1).
+    pub svbk: u8,
+    /// KEY1 bit 0: a speed switch is armed for the next STOP.
+    pub speed_prepare: bool,
+    /// KEY1 bit 7: CPU runs at double speed.
+    pub double_speed: bool,
+    /// Background palette RAM (8 palettes × 4 RGB555 colours).
+    pub bg_palette: [u8; PALETTE_RAM_LEN],
+    /// Object palette RAM (8 palettes × 4 RGB555 colours).
+    pub obj_palette: [u8; PALETTE_RAM_LEN],
+    /// BCPS: background palette index and auto-increment flag.
+    pub bcps: u8,
+    /// OCPS: object palette index and auto-increment flag.
+    pub ocps: u8,
+    /// HDMA source address.
+    pub hdma_src: u16,
+    /// HDMA destination offset within VRAM.
+    pub hdma_dst: u16,
+    /// Remaining HBlank DMA blocks minus one, as reported through HDMA5.
+    pub hdma_remaining: u8,
+    /// Whether an HBlank DMA transfer is in progress.
+    pub hdma_active: bool,
+    /// CPU cycles owed to DMA blocks that have not been accounted yet.
+    pub dma_stall: u32,
+}
+
+impl Default for Cgb {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Cgb {
+    /// Creates CGB state with white palettes and bank 0/1 selected.
+    pub fn new(enabled: bool) -> Self {
+        let mut palette = [0u8; PALETTE_RAM_LEN];
+        for color in palette.chunks_exact_mut(2) {
+            color.copy_from_slice(&WHITE_RGB555);
+        }
+        Self {
+            enabled,
+            vram1: Box::new([0; 0x2000]),
+            wram_banks: Box::new([0; 0x6000]),
+            vbk: 0,
+            svbk: 0,
+            speed_prepare: false,
+            double_speed: false,
+            bg_palette: palette,
+            obj_palette: palette,
+            bcps: 0,
+            ocps: 0,
+            hdma_src: 0,
+            hdma_dst: 0,
+            hdma_remaining: 0x7F,
+            hdma_active: false,
+            dma_stall: 0,
+        }
+    }
+
+    /// Copies every register and palette from `lead`, leaving the VRAM and WRAM
+    /// banks untouched.
+    pub(crate) fn sync_registers_from(&mut self, lead: &Cgb) {
+        self.enabled = lead.enabled;
+        self.vbk = lead.vbk;
+        self.svbk = lead.svbk;
+        self.speed_prepare = lead.speed_prepare;
+        self.double_speed = lead.double_speed;
+        self.bg_palette = lead.bg_palette;
+        self.obj_palette = lead.obj_palette;
+        self.bcps = lead.bcps;
+        self.ocps = lead.ocps;
+        self.hdma_src = lead.hdma_src;
+        self.hdma_dst = lead.hdma_dst;
+        self.hdma_remaining = lead.hdma_remaining;
+        self.hdma_active = lead.hdma_active;
+        self.dma_stall = lead.dma_stall;
+    }
+
+    /// Returns the WRAM bank mapped at `0xD000..=0xDFFF`.
+    #[inline]
+    pub fn wram_bank(&self) -> usize {
+        if !self.enabled {
+            return 1;
+        }
+        usize::from(self.svbk & 0x07).max(1)
+    }
+
+    /// Returns whether CPU accesses to `0x8000..=0x9FFF` target VRAM bank 1.
+    #[inline]
+    pub fn vram1_selected(&self) -> bool {
+        self.enabled && self.vbk & 0x01 != 0
+    }
+
+    /// Returns a background colour as `0xRRGGBB`.
+    #[inline]
+    pub fn bg_rgb(&self, palette: u8, color: u8) -> u32 {
+        palette_rgb(&self.bg_palette, palette, color)
+    }
+
+    /// Returns an object colour as `0xRRGGBB`.
+    #[inline]
+    pub fn obj_rgb(&self, palette: u8, color: u8) -> u32 {
+        palette_rgb(&self.obj_palette, palette, color)
+    }
+
+    /// Reads a CGB register, or returns `None` if `idx` is not one.
+    pub fn read_io(&self, idx: usize) -> Option<u8> {
+        if !self.enabled {
+            return None;
+        }
+        let value = match idx {
+            KEY1 => 0x7E | u8::from(self.double_speed) << 7 | u8::from(self.speed_prepare),
+            VBK => 0xFE | self.vbk,
+            HDMA1..=HDMA4 => 0xFF,
+            HDMA5 => {
+                if self.hdma_active {
+                    self.hdma_remaining & 0x7F
+                } else {
+                    0x80 | self.hdma_remaining
+                }
+            }
+            BCPS => self.bcps | 0x40,
+            BCPD => self.bg_palette[usize::from(self.bcps & 0x3F)],
+            OCPS => self.ocps | 0x40,
+            OCPD => self.obj_palette[usize::from(self.ocps & 0x3F)],
+            SVBK => 0xF8 | self.svbk,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn write_palette(index: &mut u8, ram: &mut [u8; PALETTE_RAM_LEN], value: u8) {
+        ram[usize::from(*index & 0x3F)] = value;
+        if *index & 0x80 != 0 {
+            *index = 0x80 | (index.wrapping_add(1) & 0x3F);
+        }
+    }
+}
+
+impl BusScalar {
+    /// Handles a write to a CGB register; returns `false` if `idx` is not one.
+    pub(crate) fn cgb_write_io(&mut self, idx: usize, value: u8) -> bool {
+        if !self.cgb.enabled {
+            return false;
+        }
+        if idx == HDMA5 {
+            self.write_hdma5(value);
+            return true;
+        }
+        let cgb = &mut self.cgb;
+        match idx {
+            KEY1 => cgb.speed_prepare = value & 0x01 != 0,
+            VBK => cgb.vbk = value & 0x01,
+            HDMA1 => cgb.hdma_src = (cgb.hdma_src & 0x00FF) | (u16::from(value) << 8),
+            HDMA2 => cgb.hdma_src = (cgb.hdma_src & 0xFF00) | u16::from(value & 0xF0),
+            HDMA3 => cgb.hdma_dst = (cgb.hdma_dst & 0x00FF) | (u16::from(value & 0x1F) << 8),
+            HDMA4 => cgb.hdma_dst = (cgb.hdma_dst & 0xFF00) | u16::from(value & 0xF0),
+            BCPS => cgb.bcps = value & 0xBF,
+            BCPD => Cgb::write_palette(&mut cgb.bcps, &mut cgb.bg_palette, value),
+            OCPS => cgb.ocps = value & 0xBF,
+            OCPD => Cgb::write_palette(&mut cgb.ocps, &mut cgb.obj_palette, value),
+            SVBK => cgb.svbk = value & 0x07,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Reads `0xD000 + offset` from the selected WRAM bank.
+    pub(crate) fn banked_wram(&self, offset: usize) -> u8 {
+        match self.cgb.wram_bank() {
+            1 => self.wram[0x1000 + offset],
+            bank => self.cgb.wram_banks[(bank - 2) * 0x1000 + offset],
+        }
+    }
+
+    /// Returns the byte backing `0xD000 + offset` in the selected WRAM bank.
+    pub(crate) fn banked_wram_mut(&mut self, offset: usize) -> &mut u8 {
+        match self.cgb.wram_bank() {
+            1 => &mut self.wram[0x1000 + offset],
+            bank => &mut self.cgb.wram_banks[(bank - 2) * 0x1000 + offset],
+        }
+    }
+
+    /// Returns the VRAM bank selected for CPU accesses.
+    pub(crate) fn cpu_vram(&self) -> &[u8; 0x2000] {
+        if self.cgb.vram1_selected() {
+            &self.cgb.vram1
+        } else {
+            &self.vram
+        }
+    }
+
+    /// Returns the VRAM bank selected for CPU accesses, mutably.
+    pub(crate) fn cpu_vram_mut(&mut self) -> &mut [u8; 0x2000] {
+        if self.cgb.vram1_selected() {
+            &mut self.cgb.vram1
+        } else {
+            &mut self.vram
+        }
+    }
+
+    fn write_hdma5(&mut self, value: u8) {
+        if self.cgb.hdma_active && value & 0x80 == 0 {
+            // Clearing bit 7 while an HBlank transfer runs cancels it.
+            self.cgb.hdma_active = false;
+            return;
+        }
+
+        self.cgb.hdma_remaining = value & 0x7F;
+        if value & 0x80 != 0 {
+            self.cgb.hdma_active = true;
+            if self.io.read(IoRegs::LCDC) & 0x80 == 0 {
+                // With the LCD off no HBlank arrives, so the first block moves at once.
+                self.hdma_hblank_block();
+            }
+            return;
+        }
+
+        for _ in 0..=value & 0x7F {
+            self.hdma_transfer_block();
+        }
+        self.cgb.hdma_remaining = 0x7F;
+    }
+
+    fn hdma_hblank_block(&mut self) {
+        self.hdma_transfer_block();
+        if self.cgb.hdma_remaining == 0 {
+            self.cgb.hdma_active = false;
+            self.cgb.hdma_remaining = 0x7F;
+        } else {
+            self.cgb.hdma_remaining -= 1;
+        }
+    }
+
+    fn hdma_transfer_block(&mut self) {
+        for offset in 0..HDMA_BLOCK_LEN {
+            let src = Scalar::from_u16(self.cgb.hdma_src.wrapping_add(offset));
+            let byte = Scalar::to_u8(mmu::read8_scalar(self, src));
+            let dst = usize::from((self.cgb.hdma_dst + offset) & 0x1FFF);
+            self.cpu_vram_mut()[dst] = byte;
+        }
+        self.cgb.hdma_src = self.cgb.hdma_src.wrapping_add(HDMA_BLOCK_LEN);
+        self.cgb.hdma_dst = (self.cgb.hdma_dst + HDMA_BLOCK_LEN) & 0x1FF0;
+        let speed = if self.cgb.double_speed { 2 } else { 1 };
+        self.cgb.dma_stall += HDMA_BLOCK_CYCLES * speed;
+    }
+}
+
+impl CgbIo for BusScalar {
+    fn reset_cgb(&mut self, enabled: bool) {
+        self.cgb = Cgb::new(enabled);
+    }
+
+    #[inline]
+    fn double_speed(&self) -> bool {
+        self.cgb.double_speed
+    }
+
+    fn try_speed_switch(&mut self) -> bool {
+        if !self.cgb.enabled || !self.cgb.speed_prepare {
+            return false;
+        }
+        self.cgb.speed_prepare = false;
+        self.cgb.double_speed = !self.cgb.double_speed;
+        true
+    }
+
+    fn hdma_hblank(&mut self) {
+        if self.cgb.hdma_active {
+            self.hdma_hblank_block();
+        }
+    }
+
+    #[inline]
+    fn take_dma_stall(&mut self) -> u32 {
+        std::mem::take(&mut self.cgb.dma_stall)
+    }
+}
+
+fn palette_rgb(ram: &[u8; PALETTE_RAM_LEN], palette: u8, color: u8) -> u32 {
+    let idx = (usize::from(palette & 0x07) * 4 + usize::from(color & 0x03)) * 2;
+    let rgb555 = u16::from_le_bytes([ram[idx], ram[idx + 1]]);
+    let expand = |c: u16| {
+        let c = u32::from(c & 0x1F);
+        (c << 3) | (c >> 2)
+    };
+    expand(rgb555) << 16 | expand(rgb555 >> 5) << 8 | expand(rgb555 >> 10)
+}
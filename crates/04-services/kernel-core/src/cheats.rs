@@ -0,0 +1,353 @@
+use crate::bus::BusScalar;
+use crate::exec::{Exec, Scalar};
+use crate::mmu;
+use std::fmt;
+use std::str::FromStr;
+
+/// Trait exposing the cheat engine wired into the bus.
+pub trait CheatIo {
+    /// Replaces the active cheat set.
+    fn set_cheats(&mut self, cheats: &Cheats);
+    /// Applies the per-frame RAM writes of the active GameShark codes.
+    fn apply_frame_cheats(&mut self);
+}
+
+/// A decoded cheat code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cheat {
+    /// Game Genie code substituting a byte whenever the CPU reads ROM at `addr`.
+    GameGenie {
+        /// ROM address in `0x0000..=0x7FFF`.
+        addr: u16,
+        /// Byte returned instead of the ROM contents.
+        value: u8,
+        /// Original byte the ROM must hold for the substitution to apply; it
+        /// tells apart the banks mapped at the same address.
+        compare: Option<u8>,
+    },
+    /// GameShark code writing a byte to RAM once per frame.
+    GameShark {
+        /// Code type: `0x00`/`0x01` write through the bus, `0x8n` targets
+        /// cartridge RAM bank `n` and `0x9n` CGB work RAM bank `n`.
+        kind: u8,
+        /// Target address.
+        addr: u16,
+        /// Byte to store.
+        value: u8,
+    },
+}
+
+/// Reasons a cheat code fails to decode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheatError {
+    /// The code is neither a 6/9-digit Game Genie nor an 8-digit GameShark code.
+    InvalidFormat(String),
+    /// A Game Genie code patches an address outside the cartridge ROM.
+    NotRomAddress(u16),
+    /// A GameShark code uses a type byte the engine does not implement.
+    UnsupportedType(u8),
+}
+
+impl fmt::Display for CheatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheatError::InvalidFormat(code) => write!(f, "'{code}' is not a cheat code"),
+            CheatError::NotRomAddress(addr) => {
+                write!(f, "Game Genie address {addr:04X} is outside ROM")
+            }
+            CheatError::UnsupportedType(kind) => {
+                write!(f, "unsupported GameShark code type {kind:02X}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheatError {}
+
+impl FromStr for Cheat {
+    type Err = CheatError;
+
+    /// Decodes `ABC-DEF-GHI` / `ABC-DEF` Game Genie or `TTVVLLHH` GameShark codes.
+    ///
+    /// Dashes and surrounding whitespace are ignored and digits are case-insensitive.
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let invalid = || CheatError::InvalidFormat(code.to_string());
+        let digits = code
+            .trim()
+            .chars()
+            .filter(|&c| c != '-')
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        match digits.len() {
+            6 | 9 => {
+                let value = (digits[0] << 4) | digits[1];
+                let addr = (u16::from(digits[5] ^ 0xF) << 12)
+                    | (u16::from(digits[2]) << 8)
+                    | (u16::from(digits[3]) << 4)
+                    | u16::from(digits[4]);
+                if addr >= 0x8000 {
+                    return Err(CheatError::NotRomAddress(addr));
+                }
+                // The compare byte is stored rotated left by two and XORed with
+                // 0xBA; the middle digit of the last group is not used.
+                let compare = (digits.len() == 9)
+                    .then(|| ((digits[6] << 4) | digits[8]).rotate_right(2) ^ 0xBA);
+                Ok(Cheat::GameGenie {
+                    addr,
+                    value,
+                    compare,
+                })
+            }
+            8 => {
+                let byte = |i: usize| (digits[i] << 4) | digits[i + 1];
+                let kind = byte(0);
+                if !matches!(kind, 0x00 | 0x01 | 0x80..=0x8F | 0x90..=0x97) {
+                    return Err(CheatError::UnsupportedType(kind));
+                }
+                Ok(Cheat::GameShark {
+                    kind,
+                    addr: u16::from_le_bytes([byte(4), byte(6)]),
+                    value: byte(2),
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Active cheat codes of one bus.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cheats {
+    codes: Vec<Cheat>,
+    rom_patches: bool,
+}
+
+impl Cheats {
+    /// Builds a cheat set from decoded codes.
+    pub fn new(codes: impl IntoIterator<Item = Cheat>) -> Self {
+        let codes: Vec<Cheat> = codes.into_iter().collect();
+        let rom_patches = codes
+            .iter()
+            .any(|cheat| matches!(cheat, Cheat::GameGenie { .. }));
+        Self { codes, rom_patches }
+    }
+
+    /// Returns the active codes in the order they were supplied.
+    pub fn codes(&self) -> &[Cheat] {
+        &self.codes
+    }
+
+    /// Returns whether no code is active.
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Applies Game Genie substitutions to a byte the CPU read from ROM.
+    #[inline]
+    pub(crate) fn patch_rom_read(&self, addr: u16, byte: u8) -> u8 {
+        if !self.rom_patches {
+            return byte;
+        }
+        self.codes
+            .iter()
+            .find_map(|cheat| match *cheat {
+                Cheat::GameGenie {
+                    addr: target,
+                    value,
+                    compare,
+                } if target == addr && compare.is_none_or(|old| old == byte) => Some(value),
+                _ => None,
+            })
+            .unwrap_or(byte)
+    }
+
+    fn apply_ram_writes(&self, bus: &mut BusScalar) {
+        for cheat in &self.codes {
+            let Cheat::GameShark { kind, addr, value } = *cheat else {
+                continue;
+            };
+            match (kind, addr) {
+                // Cartridge RAM is only written when the byte differs; every write
+                // marks battery RAM dirty, which would persist it every frame.
+                (0x80..=0x8F, 0xA000..=0xBFFF) => {
+                    let bank = usize::from(kind & 0x0F);
+                    let offset = usize::from(addr - 0xA000);
+                    let current = bus
+                        .cart
+                        .ram_bank_bytes(bank)
+                        .and_then(|ram| ram.get(offset).copied());
+                    if current.is_some_and(|byte| byte != value) {
+                        if let Some(ram) = bus.cart.ram_bank_bytes_mut(bank) {
+                            ram[offset] = value;
+                        }
+                    }
+                }
+                (_, 0xA000..=0xBFFF) if mmu::peek8_scalar(bus, addr) == value => {}
+                (0x90..=0x97, 0xD000..=0xDFFF) => {
+                    let offset = usize::from(addr - 0xD000);
+                    match usize::from(kind & 0x07) {
+                        0 | 1 => bus.wram[0x1000 + offset] = value,
+                        bank => bus.cgb.wram_banks[(bank - 2) * 0x1000 + offset] = value,
+                    }
+                }
+                _ => mmu::write8_scalar(
+                    bus,
+                    <Scalar as Exec>::from_u16(addr),
+                    <Scalar as Exec>::from_u8(value),
+                ),
+            }
+        }
+    }
+}
+
+impl CheatIo for BusScalar {
+    fn set_cheats(&mut self, cheats: &Cheats) {
+        self.cheats = cheats.clone();
+    }
+
+    fn apply_frame_cheats(&mut self) {
+        if self.cheats.is_empty() {
+            return;
+        }
+        let cheats = std::mem::take(&mut self.cheats);
+        cheats.apply_ram_writes(self);
+        self.cheats = cheats;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+    use std::sync::Arc;
+
+    fn make_bus() -> BusScalar {
+        let mut rom = vec![0u8; 0x8000];
+        rom[0x0147] = 0x03; // MBC1 + RAM + battery
+        rom[0x0149] = 0x03; // 32 KiB RAM
+        rom[0x4A5F] = 0x42;
+        BusScalar::new(Arc::from(rom.into_boxed_slice()), None)
+    }
+
+    #[test]
+    fn decodes_game_genie_codes() {
+        assert_eq!(
+            "3EA-5FB-EEA".parse(),
+            Ok(Cheat::GameGenie {
+                addr: 0x4A5F,
+                value: 0x3E,
+                compare: Some(0x00),
+            })
+        );
+        assert_eq!(
+            "3ea5fb".parse(),
+            Ok(Cheat::GameGenie {
+                addr: 0x4A5F,
+                value: 0x3E,
+                compare: None,
+            })
+        );
+        assert_eq!(
+            "000-000".parse::<Cheat>(),
+            Err(CheatError::NotRomAddress(0xF000))
+        );
+    }
+
+    #[test]
+    fn decodes_game_shark_codes() {
+        assert_eq!(
+            "0163D1C0".parse(),
+            Ok(Cheat::GameShark {
+                kind: 0x01,
+                addr: 0xC0D1,
+                value: 0x63,
+            })
+        );
+        assert_eq!(
+            "4163D1C0".parse::<Cheat>(),
+            Err(CheatError::UnsupportedType(0x41))
+        );
+        assert!(matches!(
+            "nope".parse::<Cheat>(),
+            Err(CheatError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn game_genie_substitutes_rom_reads_matching_compare() {
+        let mut bus = make_bus();
+        bus.set_cheats(&Cheats::new([
+            "3EA-5FB-EEA".parse().unwrap(),
+            Cheat::GameGenie {
+                addr: 0x0100,
+                value: 0x99,
+                compare: None,
+            },
+        ]));
+        // The compare byte 0x00 does not match the 0x42 in ROM.
+        assert_eq!(bus.read8(0x4A5F), 0x42);
+        assert_eq!(bus.read8(0x0100), 0x99);
+        assert_eq!(mmu::peek8_scalar(&bus, 0x0100), 0x00);
+
+        bus.set_cheats(&Cheats::new([Cheat::GameGenie {
+            addr: 0x4A5F,
+            value: 0x3E,
+            compare: Some(0x42),
+        }]));
+        assert_eq!(bus.read8(0x4A5F), 0x3E);
+    }
+
+    #[test]
+    fn game_shark_writes_ram_each_frame() {
+        let mut bus = make_bus();
+        bus.set_cheats(&Cheats::new([
+            "0163D1C0".parse().unwrap(),
+            Cheat::GameShark {
+                kind: 0x82,
+                addr: 0xA010,
+                value: 0x77,
+            },
+        ]));
+        bus.apply_frame_cheats();
+        assert_eq!(bus.read8(0xC0D1), 0x63);
+        assert_eq!(bus.cart.ram_bank_bytes(2).unwrap()[0x10], 0x77);
+
+        bus.write8(0xC0D1, 0x00);
+        bus.apply_frame_cheats();
+        assert_eq!(bus.read8(0xC0D1), 0x63);
+    }
+
+    #[test]
+    fn game_shark_marks_cart_ram_dirty_only_on_change() {
+        let mut bus = make_bus();
+        bus.write8(0x0000, 0x0A); // Enable cartridge RAM for MMU writes.
+        bus.set_cheats(&Cheats::new([
+            Cheat::GameShark {
+                kind: 0x82,
+                addr: 0xA010,
+                value: 0x77,
+            },
+            Cheat::GameShark {
+                kind: 0x01,
+                addr: 0xA020,
+                value: 0x55,
+            },
+        ]));
+        bus.apply_frame_cheats();
+        assert_eq!(bus.read8(0xA020), 0x55);
+        assert!(bus.cart.take_ram_dirty(), "first frame writes the codes");
+
+        bus.apply_frame_cheats();
+        assert!(
+            !bus.cart.take_ram_dirty(),
+            "unchanged bytes are not rewritten"
+        );
+
+        bus.cart.ram_bank_bytes_mut(2).unwrap()[0x10] = 0x00;
+        bus.cart.take_ram_dirty();
+        bus.apply_frame_cheats();
+        assert_eq!(bus.cart.ram_bank_bytes(2).unwrap()[0x10], 0x77);
+        assert!(bus.cart.take_ram_dirty());
+    }
+}
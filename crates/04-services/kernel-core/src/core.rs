@@ -1,15 +1,56 @@
-use crate::bus::{Bus, BusScalar, InterruptCtrl, SerialIo};
+use crate::apu;
+use crate::breakpoints::{Breakpoints, WatchAccess};
+use crate::bus::{ApuIo, Bus, BusScalar, CartridgeIo, InterruptCtrl, JoypadIo, SerialIo};
 use crate::bus_simd::BusSimd;
-use crate::cpu::Cpu;
+use crate::cartridge::CartridgeHeader;
+use crate::cgb::CgbIo;
+use crate::cheats::{CheatIo, Cheats};
+use crate::cpu::{Cpu, Register};
+use crate::divergence::{DivergenceStats, LaneSplit, SplitLanes};
+use crate::dma::OamDmaIo;
 use crate::exec::{Exec, Scalar};
 use crate::exec_simd::SimdExec;
 use crate::instr::{self, AluOp};
-use crate::ppu_stub::{PpuFrameSource, PpuIo, PpuStub};
+use crate::opcodes::{self, OPCODES};
+use crate::ppu::{Ppu, PpuFrameSource, PpuIo};
+use crate::rtc::RtcClock;
+use crate::state::CoreState;
 use crate::timers::{TimerIo, Timers};
+use crate::trace::{Trace, TraceEntry};
 
 /// Bundles the bus traits required by the scalar core for timing-sensitive peripherals.
-pub trait CoreBus<E: Exec>: Bus<E> + TimerIo + InterruptCtrl + PpuIo + SerialIo {}
-impl<E: Exec, B> CoreBus<E> for B where B: Bus<E> + TimerIo + InterruptCtrl + PpuIo + SerialIo {}
+pub trait CoreBus<E: Exec>:
+    Bus<E>
+    + TimerIo
+    + InterruptCtrl
+    + PpuIo
+    + PpuFrameSource
+    + SerialIo
+    + CartridgeIo
+    + ApuIo
+    + CgbIo
+    + OamDmaIo
+    + JoypadIo
+    + CheatIo
+    + LaneSplit<E>
+{
+}
+impl<E: Exec, B> CoreBus<E> for B where
+    B: Bus<E>
+        + TimerIo
+        + InterruptCtrl
+        + PpuIo
+        + PpuFrameSource
+        + SerialIo
+        + CartridgeIo
+        + ApuIo
+        + CgbIo
+        + OamDmaIo
+        + JoypadIo
+        + CheatIo
+        + LaneSplit<E>
+{
+}
 use core::simd::{LaneCount, SupportedLaneCount};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
@@ -19,6 +60,19 @@ use std::sync::Arc;
 pub enum Model {
     /// Original DMG model.
     Dmg,
+    /// Game Boy Color running a CGB-enhanced cartridge.
+    Cgb,
+}
+
+impl Model {
+    /// Picks the model a ROM asks for through the CGB flag in its header.
+    pub fn for_rom(rom: &[u8]) -> Self {
+        if CartridgeHeader::parse(rom).cgb {
+            Model::Cgb
+        } else {
+            Model::Dmg
+        }
+    }
 }
 
 /// Core configuration shared across backends.
@@ -30,6 +84,12 @@ pub struct CoreConfig {
     pub frame_height: u16,
     /// Number of SIMD lanes the core should drive.
     pub lanes: NonZeroUsize,
+    /// Time source for MBC3 cartridge clocks.
+    pub rtc_clock: RtcClock,
+    /// Host sample rate the APU resamples its stereo output to.
+    pub audio_sample_rate_hz: u32,
+    /// Whether the APU buffers host audio; headless runners turn this off.
+    pub audio_capture: bool,
 }
 
 impl Default for CoreConfig {
@@ -38,6 +98,9 @@ impl Default for CoreConfig {
             frame_width: 160,
             frame_height: 144,
             lanes: NonZeroUsize::new(1).expect("non-zero"),
+            rtc_clock: RtcClock::Cycles,
+            audio_sample_rate_hz: apu::DEFAULT_SAMPLE_RATE_HZ,
+            audio_capture: true,
         }
     }
 }
@@ -50,10 +113,15 @@ pub struct Core<E: Exec, B: CoreBus<E>> {
     pub bus: B,
     /// Timer block shared with the scheduler.
     pub timers: Timers,
-    /// Stub PPU used for frame pacing.
-    pub ppu: PpuStub,
+    /// Dot-based PPU driving LCD timing and per-scanline rendering.
+    pub ppu: Ppu,
     /// Cycle budget accumulated within the current frame.
     pub cycles_this_frame: u32,
+    /// Breakpoints and watchpoints honoured by [`Core::step_cycles`].
+    pub breakpoints: Breakpoints,
+    /// Execution trace ring, recorded while enabled.
+    pub trace: Trace,
+    pub(crate) split: SplitLanes,
     inline_cycle_credit: u32,
     ppu_enabled: bool,
     config: CoreConfig,
@@ -62,13 +130,19 @@ pub struct Core<E: Exec, B: CoreBus<E>> {
 
 impl<E: Exec, B: CoreBus<E>> Core<E, B> {
     /// Creates a core with the supplied bus and configuration.
-    pub fn new(bus: B, config: CoreConfig, model: Model) -> Self {
+    pub fn new(mut bus: B, config: CoreConfig, model: Model) -> Self {
+        bus.set_rtc_clock(config.rtc_clock);
+        bus.set_audio_sample_rate(config.audio_sample_rate_hz);
+        bus.set_audio_capture(config.audio_capture);
         Self {
             cpu: Cpu::new(),
             bus,
             timers: Timers::new(),
-            ppu: PpuStub::new(),
+            ppu: Ppu::new(),
             cycles_this_frame: 0,
+            breakpoints: Breakpoints::default(),
+            trace: Trace::default(),
+            split: SplitLanes::default(),
             inline_cycle_credit: 0,
             ppu_enabled: true,
             config,
@@ -83,6 +157,7 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
     {
         self.model = model;
         self.cpu = Cpu::new();
+        self.split.clear();
         self.timers.reset();
         self.ppu.reset();
         self.cycles_this_frame = 0;
@@ -90,6 +165,7 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         self.ppu_enabled = true;
         self.cpu.halted = false;
         self.cpu.enable_ime_pending = false;
+        self.bus.reset_cgb(model == Model::Cgb);
         match model {
             Model::Dmg => {
                 // Classic DMG register defaults per Pan Docs reference.
@@ -104,18 +180,33 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
                 self.cpu.sp = E::from_u16(0xFFFE);
                 self.cpu.pc = E::from_u16(0x0100);
                 self.cpu.ime = true;
-
-                // PPU post-boot defaults so the LCD begins in the enabled state.
-                self.bus.write8(E::from_u16(0xFF40), E::from_u8(0x91)); // LCDC
-                self.bus.write8(E::from_u16(0xFF41), E::from_u8(0x85)); // STAT
-                self.bus.write8(E::from_u16(0xFF42), E::from_u8(0x00)); // SCY
-                self.bus.write8(E::from_u16(0xFF43), E::from_u8(0x00)); // SCX
-                self.bus.write8(E::from_u16(0xFF44), E::from_u8(0x00)); // LY
-                self.bus.write8(E::from_u16(0xFF47), E::from_u8(0xFC)); // BGP
-                self.bus.write8(E::from_u16(0xFF50), E::from_u8(0x01)); // disable boot ROM overlay
-                self.timers.initialize_post_boot(&mut self.bus);
+            }
+            Model::Cgb => {
+                // CGB register defaults for a colour cartridge per Pan Docs reference.
+                self.cpu.a = E::from_u8(0x11);
+                self.cpu.f.from_byte(0x80);
+                self.cpu.b = E::from_u8(0x00);
+                self.cpu.c = E::from_u8(0x00);
+                self.cpu.d = E::from_u8(0xFF);
+                self.cpu.e = E::from_u8(0x56);
+                self.cpu.h = E::from_u8(0x00);
+                self.cpu.l = E::from_u8(0x0D);
+                self.cpu.sp = E::from_u16(0xFFFE);
+                self.cpu.pc = E::from_u16(0x0100);
+                self.cpu.ime = true;
             }
         }
+
+        // PPU post-boot defaults so the LCD begins in the enabled state.
+        self.bus.write8(E::from_u16(0xFF40), E::from_u8(0x91)); // LCDC
+        self.bus.write8(E::from_u16(0xFF41), E::from_u8(0x85)); // STAT
+        self.bus.write8(E::from_u16(0xFF42), E::from_u8(0x00)); // SCY
+        self.bus.write8(E::from_u16(0xFF43), E::from_u8(0x00)); // SCX
+        self.bus.write8(E::from_u16(0xFF44), E::from_u8(0x00)); // LY
+        self.bus.write8(E::from_u16(0xFF47), E::from_u8(0xFC)); // BGP
+        self.bus.write8(E::from_u16(0xFF50), E::from_u8(0x01)); // disable boot ROM overlay
+        self.timers.initialize_post_boot(&mut self.bus);
+        self.bus.initialize_apu_post_boot();
     }
 
     fn consume_cycles(&mut self, cycles: u32)
@@ -125,12 +216,78 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         if cycles == 0 {
             return;
         }
+        // Timers, serial and OAM DMA follow the CPU clock; everything else runs at the
+        // fixed dot rate, so double speed halves their share of each cycle.
+        let dots = if self.bus.double_speed() {
+            cycles / 2
+        } else {
+            cycles
+        };
         self.timers.step(cycles, &mut self.bus);
+        self.bus.step_oam_dma(cycles);
         if self.ppu_enabled {
-            self.ppu.step(cycles, &mut self.bus);
+            let was_hblank = self.ppu.mode == 0;
+            let was_ready = self.ppu.frame_ready();
+            self.ppu.step(dots, &mut self.bus);
+            if !was_hblank && self.ppu.mode == 0 && self.ppu.lcd_was_on {
+                self.bus.hdma_hblank();
+            }
+            if !was_ready && self.ppu.frame_ready() {
+                self.bus.apply_frame_cheats();
+            }
         }
         self.bus.step_serial(cycles);
+        self.bus.step_cartridge(dots);
+        self.bus.step_apu(dots);
+        self.trace.advance(cycles);
+        self.cycles_this_frame = self.cycles_this_frame.wrapping_add(dots);
+    }
+
+    /// Enters STOP mode: DIV resets and the LCD blanks until a joypad line goes low.
+    pub(crate) fn enter_stop(&mut self) {
+        self.cpu.stopped = true;
+        self.bus.write8(E::from_u16(0xFF04), E::from_u8(0));
+        self.ppu.blank_lanes();
+    }
+
+    /// Idles a stopped CPU for `cycles`, returning `false` once it is running.
+    ///
+    /// The system clock is halted in STOP, so only the cartridge clock and frame
+    /// pacing advance; timers, PPU, APU and serial stay frozen.
+    fn idle_while_stopped(&mut self, cycles: u32) -> bool {
+        if !self.cpu.stopped {
+            return false;
+        }
+        if self.bus.joypad_line_low() {
+            self.cpu.stopped = false;
+            return false;
+        }
+        self.bus.step_cartridge(cycles);
+        self.trace.advance(cycles);
         self.cycles_this_frame = self.cycles_this_frame.wrapping_add(cycles);
+        true
+    }
+
+    /// Applies host joypad inputs (active-low, buttons in the high nibble).
+    ///
+    /// A newly pressed selected button requests the joypad interrupt and wakes
+    /// the CPU from STOP straight away, so inputs applied mid-frame take effect
+    /// on the next step. Returns whether such an edge occurred.
+    pub fn set_inputs(&mut self, joypad: u8) -> bool {
+        let edge = self.bus.set_inputs(joypad);
+        if edge {
+            self.cpu.stopped = false;
+        }
+        edge
+    }
+
+    /// Burns the CPU cycles owed to VRAM DMA blocks moved since the last check.
+    fn consume_dma_stall(&mut self)
+    where
+        B: TimerIo + InterruptCtrl + PpuIo + SerialIo,
+    {
+        let stall = self.bus.take_dma_stall();
+        self.consume_cycles(stall);
     }
 
     pub(crate) fn fetch_imm8(&mut self) -> E::U8
@@ -157,6 +314,7 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         B: TimerIo + InterruptCtrl + PpuIo + SerialIo,
     {
         let byte = self.bus.read8(addr);
+        self.observe_access(E::to_u16(addr), E::to_u8(byte), WatchAccess::Read);
         self.consume_cycles(4);
         self.inline_cycle_credit = self.inline_cycle_credit.wrapping_add(4);
         byte
@@ -166,30 +324,141 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
     where
         B: TimerIo + InterruptCtrl + PpuIo + SerialIo,
     {
+        self.observe_access(E::to_u16(addr), E::to_u8(value), WatchAccess::Write);
         self.bus.write8(addr, value);
         self.consume_cycles(4);
         self.inline_cycle_credit = self.inline_cycle_credit.wrapping_add(4);
     }
 
+    pub(crate) fn push16(&mut self, value: E::U16) {
+        if self.observing_accesses() {
+            let sp = E::to_u16(self.cpu.sp);
+            let (hi, lo) = E::split_u16(value);
+            self.observe_access(sp.wrapping_sub(1), E::to_u8(hi), WatchAccess::Write);
+            self.observe_access(sp.wrapping_sub(2), E::to_u8(lo), WatchAccess::Write);
+        }
+        self.cpu.push16(&mut self.bus, value);
+    }
+
+    pub(crate) fn pop16(&mut self) -> E::U16 {
+        let sp = E::to_u16(self.cpu.sp);
+        let value = self.cpu.pop16(&mut self.bus);
+        if self.observing_accesses() {
+            let (hi, lo) = E::split_u16(value);
+            self.observe_access(sp, E::to_u8(lo), WatchAccess::Read);
+            self.observe_access(sp.wrapping_add(1), E::to_u8(hi), WatchAccess::Read);
+        }
+        value
+    }
+
+    /// Returns whether data accesses feed watchpoints or the trace.
+    #[inline]
+    fn observing_accesses(&self) -> bool {
+        !self.breakpoints.watchpoints().is_empty() || self.trace.is_enabled()
+    }
+
+    /// Reports a CPU data access to the watchpoints and the trace recorder.
+    #[inline]
+    fn observe_access(&mut self, addr: u16, value: u8, kind: WatchAccess) {
+        self.breakpoints.check_access(addr, kind);
+        self.trace.note_access(addr, value, kind);
+    }
+
+    /// Appends the state before the next instruction to the trace ring.
+    fn record_trace_entry(&mut self) {
+        let pc = E::to_u16(self.cpu.pc);
+        let mut pcmem = [0u8; 4];
+        for (offset, byte) in pcmem.iter_mut().enumerate() {
+            let addr = pc.wrapping_add(offset as u16);
+            *byte = E::to_u8(self.bus.read8(E::from_u16(addr)));
+        }
+        self.trace.begin(TraceEntry {
+            cycle: 0,
+            pc,
+            pcmem,
+            a: E::to_u8(self.cpu.a),
+            f: self.cpu.f.to_byte(),
+            b: E::to_u8(self.cpu.b),
+            c: E::to_u8(self.cpu.c),
+            d: E::to_u8(self.cpu.d),
+            e: E::to_u8(self.cpu.e),
+            h: E::to_u8(self.cpu.h),
+            l: E::to_u8(self.cpu.l),
+            sp: E::to_u16(self.cpu.sp),
+            accesses: Vec::new(),
+        });
+    }
+
+    /// Advances a wall-clock driven MBC3 clock to the host time `unix_secs`.
+    pub fn sync_rtc(&mut self, unix_secs: u64) {
+        self.bus.sync_rtc(unix_secs);
+    }
+
+    /// Replaces the active cheat codes on every lane.
+    ///
+    /// Game Genie codes patch ROM reads immediately; GameShark codes are written
+    /// to RAM at each frame boundary.
+    pub fn set_cheats(&mut self, cheats: &Cheats) {
+        self.bus.set_cheats(cheats);
+    }
+
     /// Returns whether the PPU reported a frame boundary.
     #[inline]
     pub fn frame_ready(&self) -> bool {
         self.ppu.frame_ready()
     }
 
+    /// Acknowledges the completed frame of every lane without copying it out.
+    pub fn skip_frame(&mut self) {
+        for split in self.split.cores_mut() {
+            split.skip_frame();
+        }
+        self.ppu.clear_frame_ready();
+        self.cycles_this_frame = 0;
+    }
+
     /// Enables or disables PPU stepping.
     #[inline]
     pub fn set_ppu_enabled(&mut self, enabled: bool) {
         self.ppu_enabled = enabled;
     }
 
-    /// Renders the current frame into `out_rgba`.
-    ///
-    /// The stub implementation fills a simple gradient to surface determinism.
-    pub fn take_frame(&mut self, out_rgba: &mut [u8])
-    where
-        B: PpuFrameSource,
-    {
+    /// Returns the hardware model selected by the last reset.
+    #[inline]
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// Returns how often SIMD lanes split off and merged back; scalar cores report zeros.
+    pub fn divergence(&self) -> DivergenceStats {
+        self.split.stats()
+    }
+
+    /// Overrides the model when restoring a save state.
+    #[inline]
+    pub(crate) fn set_model(&mut self, model: Model) {
+        self.model = model;
+    }
+
+    /// Returns the host sample rate of the audio produced by [`Core::take_audio`].
+    #[inline]
+    pub fn audio_sample_rate_hz(&self) -> u32 {
+        self.config.audio_sample_rate_hz
+    }
+
+    /// Turns audio capture on or off; see [`CoreConfig::audio_capture`].
+    pub fn set_audio_capture(&mut self, capture: bool) {
+        self.config.audio_capture = capture;
+        self.bus.set_audio_capture(capture);
+    }
+
+    /// Drains the interleaved stereo samples generated since the last call.
+    pub fn take_audio(&mut self) -> Vec<i16> {
+        self.bus.take_audio_samples()
+    }
+
+    /// Copies the last completed frame into `out_rgba`.
+    pub fn take_frame(&mut self, out_rgba: &mut [u8]) {
         let width = usize::from(self.config.frame_width);
         let height = usize::from(self.config.frame_height);
         let expected_len = width
@@ -203,10 +472,8 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
             expected_len
         );
 
-        self.ppu.render_frame(
-            self.bus.ppu_io(),
-            self.bus.ppu_vram(),
-            self.bus.ppu_oam(),
+        self.ppu.write_frame_rgba(
+            0,
             out_rgba,
             self.config.frame_width,
             self.config.frame_height,
@@ -216,7 +483,8 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         self.cycles_this_frame = 0;
     }
 
-    /// Executes instructions until the cycle budget is exhausted or a frame boundary is hit.
+    /// Executes instructions until the cycle budget is exhausted, a frame boundary is
+    /// hit or a breakpoint trips (see [`Breakpoints::hit`]).
     pub fn step_cycles(&mut self, mut budget: u32) -> u32
     where
         B: TimerIo + InterruptCtrl + PpuIo + SerialIo,
@@ -225,15 +493,18 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
             return 0;
         }
 
+        B::enter_lanes(self);
         let mut consumed = 0u32;
         while budget > 0 {
             let frame_before = self.cycles_this_frame;
 
-            if self.service_interrupts() == 0 {
+            B::guard_lanes(self);
+            self.consume_dma_stall();
+            if !self.idle_while_stopped(budget.min(4)) && self.service_interrupts() == 0 {
                 if self.cpu.halted {
                     let step = budget.min(4);
                     self.consume_cycles(step);
-                } else {
+                } else if !self.breakpoints.check_pc(E::to_u16(self.cpu.pc)) {
                     self.execute_opcode();
                 }
             }
@@ -246,10 +517,11 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
             consumed = consumed.wrapping_add(delta);
             budget = budget.saturating_sub(delta);
 
-            if self.ppu.frame_ready() {
+            if self.ppu.frame_ready() || self.breakpoints.hit().is_some() {
                 break;
             }
         }
+        B::follow_lanes(self, consumed);
 
         consumed
     }
@@ -262,15 +534,21 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         let prev_pc = E::to_u16(self.cpu.pc);
         let frame_before = self.cycles_this_frame;
 
-        if self.service_interrupts() == 0 {
+        B::enter_lanes(self);
+        B::guard_lanes(self);
+        self.consume_dma_stall();
+        if !self.idle_while_stopped(4) && self.service_interrupts() == 0 {
             if self.cpu.halted {
                 self.consume_cycles(4);
             } else {
                 self.execute_opcode();
             }
         }
+        // Single steps always run; drop any watchpoint the instruction tripped.
+        self.breakpoints.take_hit();
 
         let cycles = self.cycles_this_frame.wrapping_sub(frame_before);
+        B::follow_lanes(self, cycles);
         let pc = if cycles == 0 {
             prev_pc
         } else {
@@ -284,12 +562,16 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
         B: TimerIo + InterruptCtrl,
     {
         self.inline_cycle_credit = 0;
+        if self.trace.is_enabled() {
+            self.record_trace_entry();
+        }
         let pending_before = self.cpu.enable_ime_pending;
         let opcode = self.cpu.fetch8(&mut self.bus);
         let opcode_u8 = E::to_u8(opcode);
         self.consume_cycles(4);
         self.inline_cycle_credit = self.inline_cycle_credit.wrapping_add(4);
         let was_ei = opcode_u8 == 0xFB;
+        let mut cb_sub = None;
         let cycles = match opcode_u8 {
             0x00 => instr::op_nop(),
             0x01 => instr::op_ld_rr_d16(self, 0),
@@ -359,56 +641,52 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
                     instr::op_ld_r_r(self, opcode_u8)
                 }
             }
-            0x80..=0x87 => instr::op_alu_a_r(self, opcode_u8, AluOp::Add),
-            0x88..=0x8F => instr::op_alu_a_r(self, opcode_u8, AluOp::Adc),
-            0x90..=0x97 => instr::op_alu_a_r(self, opcode_u8, AluOp::Sub),
-            0x98..=0x9F => instr::op_alu_a_r(self, opcode_u8, AluOp::Sbc),
-            0xA0..=0xA7 => instr::op_alu_a_r(self, opcode_u8, AluOp::And),
-            0xA8..=0xAF => instr::op_alu_a_r(self, opcode_u8, AluOp::Xor),
-            0xB0..=0xB7 => instr::op_alu_a_r(self, opcode_u8, AluOp::Or),
-            0xB8..=0xBF => instr::op_alu_a_r(self, opcode_u8, AluOp::Cp),
+            0x80..=0xBF => instr::op_alu_a_r(self, opcode_u8, AluOp::from_opcode(opcode_u8)),
             0xC0 | 0xC8 | 0xD0 | 0xD8 => instr::op_ret_cc(self, opcode_u8),
             0xC1 => instr::op_pop_rr(self, 0),
             0xC2 | 0xCA | 0xD2 | 0xDA => instr::op_jp_cc_a16(self, opcode_u8),
             0xC3 => instr::op_jp_a16(self),
             0xC4 | 0xCC | 0xD4 | 0xDC => instr::op_call_cc_a16(self, opcode_u8),
             0xC5 => instr::op_push_rr(self, 0),
-            0xC6 => instr::op_alu_a_d8(self, AluOp::Add),
             0xC9 => instr::op_ret(self),
             0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | 0xFF => instr::op_rst(self, opcode_u8),
+            0xC6 | 0xCE | 0xD6 | 0xDE | 0xE6 | 0xEE | 0xF6 | 0xFE => {
+                instr::op_alu_a_d8(self, AluOp::from_opcode(opcode_u8))
+            }
             0xCB => {
-                let sub = self.fetch_imm8();
-                instr::op_cb(self, E::to_u8(sub))
+                let sub = E::to_u8(self.fetch_imm8());
+                cb_sub = Some(sub);
+                instr::op_cb(self, sub)
             }
             0xCD => instr::op_call_a16(self),
-            0xCE => instr::op_alu_a_d8(self, AluOp::Adc),
             0xD1 => instr::op_pop_rr(self, 1),
             0xD9 => instr::op_reti(self),
             0xD5 => instr::op_push_rr(self, 1),
-            0xD6 => instr::op_alu_a_d8(self, AluOp::Sub),
-            0xDE => instr::op_alu_a_d8(self, AluOp::Sbc),
             0xE0 => instr::op_ldh_a8_a(self),
             0xE1 => instr::op_pop_rr(self, 2),
             0xE2 => instr::op_ldh_c_a(self),
             0xE5 => instr::op_push_rr(self, 2),
-            0xE6 => instr::op_alu_a_d8(self, AluOp::And),
             0xE8 => instr::op_add_sp_e8(self),
             0xE9 => instr::op_jp_hl(self),
             0xEA => instr::op_ld_a16_a(self),
-            0xEE => instr::op_alu_a_d8(self, AluOp::Xor),
             0xF0 => instr::op_ldh_a_a8(self),
             0xF1 => instr::op_pop_rr(self, 3),
             0xF2 => instr::op_ldh_a_c(self),
             0xF3 => instr::op_di(self),
             0xF5 => instr::op_push_rr(self, 3),
-            0xF6 => instr::op_alu_a_d8(self, AluOp::Or),
             0xF8 => instr::op_ld_hl_sp_plus_e8(self),
             0xF9 => instr::op_ld_sp_hl(self),
             0xFA => instr::op_ld_a_a16(self),
             0xFB => instr::op_ei(self),
-            0xFE => instr::op_alu_a_d8(self, AluOp::Cp),
             _ => instr::op_unimplemented(),
         };
+        debug_assert!(
+            match cb_sub {
+                Some(sub) => cycles == opcodes::cb_cycles(sub),
+                None => OPCODES[usize::from(opcode_u8)].allows_cycles(cycles),
+            },
+            "opcode {opcode_u8:02X} took {cycles} cycles"
+        );
         if pending_before {
             self.cpu.ime = true;
             self.cpu.enable_ime_pending = was_ei;
@@ -418,6 +696,7 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
             self.consume_cycles(cycles - accounted);
         }
         self.inline_cycle_credit = 0;
+        self.trace.end();
         cycles
     }
 
@@ -507,7 +786,7 @@ impl<E: Exec, B: CoreBus<E>> Core<E, B> {
             if_reg &= !mask;
             TimerIo::write_if(&mut self.bus, if_reg);
             let pc = self.cpu.pc;
-            self.cpu.push16(&mut self.bus, pc);
+            self.push16(pc);
             const VECTORS: [u16; 5] = [0x40, 0x48, 0x50, 0x58, 0x60];
             self.cpu.pc = E::from_u16(VECTORS[bit]);
             self.consume_cycles(20);
@@ -525,7 +804,7 @@ impl<const LANES: usize> Core<SimdExec<LANES>, BusSimd<LANES>>
 where
     LaneCount<LANES>: SupportedLaneCount,
 {
-    /// Renders the current frame for a specific SIMD lane into `out_rgba`.
+    /// Copies the last completed frame of a specific SIMD lane into `out_rgba`.
     pub fn take_frame_lane(&mut self, lane: usize, out_rgba: &mut [u8]) {
         assert!(
             lane < LANES,
@@ -547,15 +826,15 @@ where
             expected_len
         );
 
-        let lane_bus = self.bus.lane(lane);
-        self.ppu.render_frame(
-            lane_bus.ppu_io(),
-            lane_bus.ppu_vram(),
-            lane_bus.ppu_oam(),
-            out_rgba,
-            self.config.frame_width,
-            self.config.frame_height,
-        );
+        match self.split.get_mut(lane) {
+            Some(split) => split.take_frame(out_rgba),
+            None => self.ppu.write_frame_rgba(
+                lane,
+                out_rgba,
+                self.config.frame_width,
+                self.config.frame_height,
+            ),
+        }
 
         self.ppu.clear_frame_ready();
         self.cycles_this_frame = 0;
@@ -601,6 +880,7 @@ impl Core<Scalar, BusScalar> {
         self.cpu.halted = false;
         self.cpu.enable_ime_pending = false;
         self.bus.reset_memory();
+        self.bus.reset_cgb(model == Model::Cgb);
         self.bus.set_boot_rom_enabled(self.bus.has_boot_rom());
         self.cpu.a = <Scalar as Exec>::from_u8(0x00);
         self.cpu.f.from_byte(0x00);
@@ -619,7 +899,69 @@ impl Core<Scalar, BusScalar> {
 
     /// Returns a clone of the currently loaded cartridge ROM.
     pub fn snapshot_rom(&self) -> Arc<[u8]> {
-        Arc::clone(&self.bus.rom)
+        Arc::clone(self.bus.cart.rom())
+    }
+
+    /// Builds a scalar core that continues `lane` of a SIMD group on `bus`.
+    ///
+    /// The lane's registers are taken from the group; the flags, timers and LCD
+    /// position it shares with the group are copied.
+    pub(crate) fn split_from<const LANES: usize>(
+        group: &Core<SimdExec<LANES>, BusSimd<LANES>>,
+        lane: usize,
+        bus: BusScalar,
+    ) -> Self
+    where
+        LaneCount<LANES>: SupportedLaneCount,
+    {
+        let mut cpu = Cpu::new();
+        for reg in Register::ALL {
+            cpu.set_lane_register(0, reg, group.cpu.lane_register(lane, reg));
+        }
+        cpu.ime = group.cpu.ime;
+        cpu.halted = group.cpu.halted;
+        cpu.stopped = group.cpu.stopped;
+        cpu.enable_ime_pending = group.cpu.enable_ime_pending;
+        cpu.halt_bug = group.cpu.halt_bug;
+
+        let shared = &group.ppu;
+        let ppu = Ppu {
+            dot_in_line: shared.dot_in_line,
+            ly: shared.ly,
+            mode: shared.mode,
+            lyc_equal: shared.lyc_equal,
+            frame_ready: shared.frame_ready,
+            lcd_was_on: shared.lcd_was_on,
+            lanes: shared.lanes.get(lane).cloned().into_iter().collect(),
+        };
+
+        Self {
+            cpu,
+            bus,
+            timers: group.timers.clone(),
+            ppu,
+            cycles_this_frame: group.cycles_this_frame,
+            breakpoints: Breakpoints::default(),
+            trace: Trace::default(),
+            split: SplitLanes::default(),
+            inline_cycle_credit: 0,
+            ppu_enabled: group.ppu_enabled,
+            config: CoreConfig {
+                lanes: NonZeroUsize::MIN,
+                ..group.config
+            },
+            model: group.model,
+        }
+    }
+
+    /// Seeds battery-backed cartridge RAM from a persisted image.
+    pub fn load_save_ram(&mut self, bytes: &[u8]) {
+        self.bus.load_save_ram(bytes);
+    }
+
+    /// Returns cartridge RAM contents if battery-backed RAM changed since the last call.
+    pub fn take_dirty_save_ram(&mut self) -> Option<Arc<[u8]>> {
+        self.bus.take_dirty_save_ram()
     }
 }
 
@@ -639,6 +981,7 @@ where
 
     /// Replaces the cartridge ROM for every SIMD lane.
     pub fn load_rom(&mut self, rom: Arc<[u8]>) {
+        self.split.clear();
         self.bus.load_rom(rom);
         self.cpu.pc = <SimdExec<LANES> as Exec>::from_u16(0x0100);
     }
@@ -662,6 +1005,7 @@ where
     pub fn reset_power_on(&mut self, model: Model) {
         self.model = model;
         self.cpu = Cpu::new();
+        self.split.clear();
         self.timers.reset();
         self.ppu.reset();
         self.cycles_this_frame = 0;
@@ -669,6 +1013,7 @@ where
         self.cpu.halted = false;
         self.cpu.enable_ime_pending = false;
         self.bus.reset_memory();
+        self.bus.reset_cgb(model == Model::Cgb);
         self.bus.set_boot_rom_enabled(self.bus.has_boot_rom());
         self.cpu.a = SimdExec::<LANES>::from_u8(0x00);
         self.cpu.f.from_byte(0x00);
@@ -687,6 +1032,61 @@ where
 
     /// Returns a clone of the currently loaded cartridge ROM.
     pub fn snapshot_rom(&self) -> Arc<[u8]> {
-        Arc::clone(&self.bus.lane(0).rom)
+        Arc::clone(self.bus.lane(0).cart.rom())
+    }
+
+    /// Seeds battery-backed cartridge RAM from a persisted image.
+    pub fn load_save_ram(&mut self, bytes: &[u8]) {
+        self.bus.load_save_ram(bytes);
+    }
+
+    /// Returns each lane whose battery-backed RAM changed since the last call, with its contents.
+    pub fn take_dirty_save_ram(&mut self) -> Vec<(usize, Arc<[u8]>)> {
+        self.bus.take_dirty_save_ram()
+    }
+
+    /// Applies host joypad inputs to a single lane.
+    ///
+    /// A new press wakes the lane from STOP: its own scalar core when the lane is
+    /// split off, otherwise the whole group. Returns whether the press requested
+    /// the joypad interrupt.
+    pub fn set_lane_inputs(&mut self, lane: usize, joypad: u8) -> bool {
+        let edge = self.bus.lane_mut(lane).set_inputs(joypad);
+        if edge {
+            match self.split.get_mut(lane) {
+                Some(split) => split.cpu.stopped = false,
+                None => self.cpu.stopped = false,
+            }
+        }
+        edge
+    }
+
+    /// Inserts `rom` into a single lane and resets that lane.
+    pub fn load_lane_rom(&mut self, lane: usize, rom: Arc<[u8]>) {
+        self.bus.lane_mut(lane).load_rom(rom);
+        self.reset_lane(lane);
+    }
+
+    /// Returns one lane to the post-boot state of its cartridge.
+    ///
+    /// Battery RAM and the cartridge clock survive, as on a power cycle. The lane
+    /// rejoins the group's shared timers and PPU position rather than restarting
+    /// them, and runs as the group's hardware model.
+    pub fn reset_lane(&mut self, lane: usize) {
+        let bus = self.bus.lane(lane);
+        let config = CoreConfig {
+            lanes: NonZeroUsize::MIN,
+            ..self.config
+        };
+        let mut fresh = Core::new(
+            BusScalar::new(Arc::clone(bus.cart.rom()), None),
+            config,
+            self.model,
+        );
+        fresh.reset_post_boot(self.model);
+        let mut state = CoreState::from(&fresh);
+        state.cart.ram = bus.cart.ram().to_vec();
+        state.cart.rtc = bus.cart.rtc_state();
+        self.rejoin_lane_state(lane, &state);
     }
 }
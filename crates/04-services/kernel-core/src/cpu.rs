@@ -28,6 +28,8 @@ pub struct Cpu<E: Exec> {
     pub ime: bool,
     /// HALT state of the CPU.
     pub halted: bool,
+    /// STOP state: the system clock is halted until a selected joypad line goes low.
+    pub stopped: bool,
     /// Pending IME enable following an `EI`.
     pub enable_ime_pending: bool,
     /// Indicates the HALT bug should adjust the next opcode fetch.
@@ -60,6 +62,7 @@ impl<E: Exec> Cpu<E> {
             pc: E::from_u16(0x0100),
             ime: false,
             halted: false,
+            stopped: false,
             enable_ime_pending: false,
             halt_bug: false,
         }
@@ -162,4 +165,80 @@ impl<E: Exec> Cpu<E> {
         self.sp = E::from_u16(sp);
         E::combine_u16(hi, lo)
     }
+    /// Reads one register of a single lane; scalar backends ignore `lane`.
+    pub fn lane_register(&self, lane: usize, reg: Register) -> u16 {
+        match reg {
+            Register::A => u16::from(E::lane_u8(self.a, lane)),
+            Register::F => u16::from(self.f.lane_byte(lane)),
+            Register::B => u16::from(E::lane_u8(self.b, lane)),
+            Register::C => u16::from(E::lane_u8(self.c, lane)),
+            Register::D => u16::from(E::lane_u8(self.d, lane)),
+            Register::E => u16::from(E::lane_u8(self.e, lane)),
+            Register::H => u16::from(E::lane_u8(self.h, lane)),
+            Register::L => u16::from(E::lane_u8(self.l, lane)),
+            Register::Sp => E::lane_u16(self.sp, lane),
+            Register::Pc => E::lane_u16(self.pc, lane),
+        }
+    }
+
+    /// Overwrites one register of a single lane; scalar backends ignore `lane`.
+    ///
+    /// 8-bit registers take the low byte of `value`, and the low nibble of `F`
+    /// is dropped as on hardware.
+    pub fn set_lane_register(&mut self, lane: usize, reg: Register, value: u16) {
+        let byte = value as u8;
+        match reg {
+            Register::A => self.a = E::with_lane_u8(self.a, lane, byte),
+            Register::F => self.f.set_lane_byte(lane, byte & 0xF0),
+            Register::B => self.b = E::with_lane_u8(self.b, lane, byte),
+            Register::C => self.c = E::with_lane_u8(self.c, lane, byte),
+            Register::D => self.d = E::with_lane_u8(self.d, lane, byte),
+            Register::E => self.e = E::with_lane_u8(self.e, lane, byte),
+            Register::H => self.h = E::with_lane_u8(self.h, lane, byte),
+            Register::L => self.l = E::with_lane_u8(self.l, lane, byte),
+            Register::Sp => self.sp = E::with_lane_u16(self.sp, lane, value),
+            Register::Pc => self.pc = E::with_lane_u16(self.pc, lane, value),
+        }
+    }
+}
+
+/// CPU register addressed by [`Cpu::lane_register`] and [`Cpu::set_lane_register`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    /// Accumulator.
+    A,
+    /// Flags register.
+    F,
+    /// `B` register.
+    B,
+    /// `C` register.
+    C,
+    /// `D` register.
+    D,
+    /// `E` register.
+    E,
+    /// `H` register.
+    H,
+    /// `L` register.
+    L,
+    /// Stack pointer.
+    Sp,
+    /// Program counter.
+    Pc,
+}
+
+impl Register {
+    /// Every register, in the order save states and traces list them.
+    pub const ALL: [Register; 10] = [
+        Register::A,
+        Register::F,
+        Register::B,
+        Register::C,
+        Register::D,
+        Register::E,
+        Register::H,
+        Register::L,
+        Register::Sp,
+        Register::Pc,
+    ];
 }
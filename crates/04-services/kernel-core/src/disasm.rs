@@ -0,0 +1,161 @@
+use crate::core::{Core, CoreBus};
+use crate::exec::Exec;
+use crate::opcodes::{CB_SHIFT_NAMES, OPCODES, R8_NAMES};
+
+/// One decoded instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisasmLine {
+    /// Address of the opcode byte.
+    pub addr: u16,
+    /// Encoded bytes, opcode first.
+    pub bytes: Vec<u8>,
+    /// Rendered mnemonic with operands resolved, e.g. `JR NZ,$0150`.
+    pub text: String,
+}
+
+impl DisasmLine {
+    /// Returns the address of the following instruction.
+    #[inline]
+    pub fn next_addr(&self) -> u16 {
+        self.addr.wrapping_add(self.bytes.len() as u16)
+    }
+}
+
+/// Decodes the instruction at `addr`, fetching bytes through `read`.
+///
+/// Immediates are printed in hex, `LDH` operands as full `$FFxx` addresses and
+/// relative jumps as their absolute target. Unused opcodes render as `DB $xx`.
+pub fn decode(addr: u16, mut read: impl FnMut(u16) -> u8) -> DisasmLine {
+    let opcode = read(addr);
+    let info = &OPCODES[usize::from(opcode)];
+    let bytes: Vec<u8> = (0..u16::from(info.len))
+        .map(|offset| {
+            if offset == 0 {
+                opcode
+            } else {
+                read(addr.wrapping_add(offset))
+            }
+        })
+        .collect();
+
+    let text = if info.is_illegal() {
+        format!("DB ${opcode:02X}")
+    } else if opcode == 0xCB {
+        cb_mnemonic(bytes[1])
+    } else {
+        render_operands(info.mnemonic, addr, &bytes)
+    };
+
+    DisasmLine { addr, bytes, text }
+}
+
+fn cb_mnemonic(sub: u8) -> String {
+    let y = (sub >> 3) & 0x07;
+    let reg = R8_NAMES[usize::from(sub & 0x07)];
+    match sub >> 6 {
+        0 => format!("{} {reg}", CB_SHIFT_NAMES[usize::from(y)]),
+        1 => format!("BIT {y},{reg}"),
+        2 => format!("RES {y},{reg}"),
+        _ => format!("SET {y},{reg}"),
+    }
+}
+
+fn render_operands(template: &str, addr: u16, bytes: &[u8]) -> String {
+    let imm8 = bytes.get(1).copied().unwrap_or(0);
+    let imm16 = u16::from_le_bytes([imm8, bytes.get(2).copied().unwrap_or(0)]);
+    let offset = imm8 as i8;
+
+    if template.contains("d16") {
+        template.replace("d16", &format!("${imm16:04X}"))
+    } else if template.contains("a16") {
+        template.replace("a16", &format!("${imm16:04X}"))
+    } else if template.contains("d8") {
+        template.replace("d8", &format!("${imm8:02X}"))
+    } else if template.contains("a8") {
+        template.replace("a8", &format!("$FF{imm8:02X}"))
+    } else if template.contains("r8") {
+        let target = addr.wrapping_add(2).wrapping_add(offset as i16 as u16);
+        template.replace("r8", &format!("${target:04X}"))
+    } else if template.contains("+e8") {
+        template.replace("+e8", &format!("{offset:+}"))
+    } else if template.contains("e8") {
+        template.replace("e8", &offset.to_string())
+    } else {
+        template.to_string()
+    }
+}
+
+impl<E: Exec, B: CoreBus<E>> Core<E, B> {
+    /// Disassembles `count` consecutive instructions starting at `base`.
+    ///
+    /// Bytes are read through the bus as the CPU would see them, so banked ROM
+    /// and the boot ROM overlay are honoured. SIMD cores decode lane 0.
+    pub fn disassemble(&mut self, base: u16, count: usize) -> Vec<DisasmLine> {
+        let mut lines = Vec::with_capacity(count);
+        let mut addr = base;
+        for _ in 0..count {
+            let line = decode(addr, |at| E::to_u8(self.bus.read8(E::from_u16(at))));
+            addr = line.next_addr();
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_bytes(addr: u16, program: &[u8]) -> DisasmLine {
+        decode(addr, |at| {
+            program
+                .get(usize::from(at.wrapping_sub(addr)))
+                .copied()
+                .unwrap_or(0)
+        })
+    }
+
+    fn text(program: &[u8]) -> String {
+        decode_bytes(0x0150, program).text
+    }
+
+    #[test]
+    fn immediates_and_addresses_are_rendered() {
+        assert_eq!(text(&[0x00]), "NOP");
+        assert_eq!(text(&[0x3E, 0x42]), "LD A,$42");
+        assert_eq!(text(&[0x21, 0x34, 0x12]), "LD HL,$1234");
+        assert_eq!(text(&[0xEA, 0x10, 0xC0]), "LD ($C010),A");
+        assert_eq!(text(&[0xE0, 0x40]), "LDH ($FF40),A");
+        assert_eq!(text(&[0x08, 0x00, 0xD0]), "LD ($D000),SP");
+        assert_eq!(text(&[0xCD, 0x00, 0x40]), "CALL $4000");
+        assert_eq!(text(&[0xFE, 0x90]), "CP $90");
+        assert_eq!(text(&[0x8E]), "ADC A,(HL)");
+        assert_eq!(text(&[0x36, 0x07]), "LD (HL),$07");
+    }
+
+    #[test]
+    fn relative_and_signed_operands_are_resolved() {
+        assert_eq!(text(&[0x18, 0xFE]), "JR $0150");
+        assert_eq!(text(&[0x20, 0x05]), "JR NZ,$0157");
+        assert_eq!(text(&[0xE8, 0xFE]), "ADD SP,-2");
+        assert_eq!(text(&[0xF8, 0x03]), "LD HL,SP+3");
+    }
+
+    #[test]
+    fn cb_and_illegal_opcodes_decode() {
+        assert_eq!(text(&[0xCB, 0x37]), "SWAP A");
+        assert_eq!(text(&[0xCB, 0x7E]), "BIT 7,(HL)");
+        assert_eq!(text(&[0xCB, 0x80]), "RES 0,B");
+        assert_eq!(text(&[0xCB, 0xFF]), "SET 7,A");
+        let illegal = decode_bytes(0x0150, &[0xD3, 0x00]);
+        assert_eq!(illegal.text, "DB $D3");
+        assert_eq!(illegal.next_addr(), 0x0151);
+    }
+
+    #[test]
+    fn line_length_matches_encoding() {
+        let line = decode_bytes(0xFFFE, &[0xC3, 0x50, 0x01]);
+        assert_eq!(line.bytes, vec![0xC3, 0x50, 0x01]);
+        assert_eq!(line.next_addr(), 0x0001);
+    }
+}
@@ -0,0 +1,372 @@
+//! Keeps SIMD lanes correct when their control flow or data dependencies diverge.
+//!
+//! A SIMD group executes lane 0's instruction stream for every lane. Before each
+//! instruction the group checks whether another lane would behave differently:
+//! different registers, interrupt state, instruction bytes or operand memory.
+//! Such a lane is split off onto its own scalar core, which takes over the lane's
+//! bus and runs in step with the group, while the group parks a copy of lane 0's
+//! bus in the vacated slot. The copy is taken once on split; before every step
+//! only its registers are resynced with lane 0 so the shared peripherals stay
+//! uniform without copying RAM or audio buffers. Once the split core's CPU,
+//! timers and LCD position match lane 0 again it merges back.
+//!
+//! Outside of [`Core::step_cycles`] and [`Core::step_instruction`] every lane's
+//! own bus lives in the group's [`BusSimd`], so lane reads, writes and group-wide
+//! operations such as cheats or save RAM need no routing.
+
+use crate::bus::{ApuIo, BusScalar, JoypadIo};
+use crate::bus_simd::BusSimd;
+use crate::cgb::CgbIo;
+use crate::core::{Core, CoreBus};
+use crate::cpu::Register;
+use crate::exec::{Exec, Scalar};
+use crate::exec_simd::SimdExec;
+use crate::mmu::peek8_scalar;
+use crate::opcodes::OPCODES;
+use crate::state::TimersState;
+use core::simd::{LaneCount, SupportedLaneCount};
+
+/// Counters describing how often SIMD lanes left and rejoined the vector path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DivergenceStats {
+    /// Lanes currently running on their own scalar core, one bit per lane.
+    pub split_mask: u32,
+    /// Times a lane was split off because it would diverge from lane 0.
+    pub splits: u64,
+    /// Times a split lane reconverged with lane 0 and rejoined the group.
+    pub merges: u64,
+    /// CPU cycles executed by split lanes on their scalar cores.
+    pub split_cycles: u64,
+}
+
+/// Bus hooks the core calls around execution to split and merge diverging lanes.
+///
+/// Scalar buses have a single lane and implement every hook as a no-op.
+pub trait LaneSplit<E: Exec>: Sized {
+    /// Hands split lanes their own bus and parks their copy of lane 0's, with
+    /// its registers resynced, in the group.
+    fn enter_lanes(core: &mut Core<E, Self>)
+    where
+        Self: CoreBus<E>;
+
+    /// Splits off every lane that would diverge from lane 0 on the next instruction.
+    fn guard_lanes(core: &mut Core<E, Self>)
+    where
+        Self: CoreBus<E>;
+
+    /// Advances split lanes by the `cycles` the group just ran, returns their
+    /// buses to the group and merges the lanes that reconverged.
+    fn follow_lanes(core: &mut Core<E, Self>, cycles: u32)
+    where
+        Self: CoreBus<E>;
+}
+
+impl LaneSplit<Scalar> for BusScalar {
+    #[inline]
+    fn enter_lanes(_core: &mut Core<Scalar, Self>) {}
+
+    #[inline]
+    fn guard_lanes(_core: &mut Core<Scalar, Self>) {}
+
+    #[inline]
+    fn follow_lanes(_core: &mut Core<Scalar, Self>, _cycles: u32) {}
+}
+
+/// Lanes of a SIMD group currently running on their own scalar cores.
+#[derive(Default)]
+pub(crate) struct SplitLanes {
+    lanes: Vec<SplitLane>,
+    /// Group frame cycle count when the current step began.
+    entered_at: u32,
+    splits: u64,
+    merges: u64,
+    split_cycles: u64,
+}
+
+struct SplitLane {
+    lane: usize,
+    core: Box<Core<Scalar, BusScalar>>,
+    /// Cycles the scalar core ran past the group, settled on the next advance.
+    ahead: u32,
+}
+
+impl SplitLanes {
+    pub(crate) fn stats(&self) -> DivergenceStats {
+        DivergenceStats {
+            split_mask: self
+                .lanes
+                .iter()
+                .fold(0, |mask, split| mask | 1u32 << split.lane),
+            splits: self.splits,
+            merges: self.merges,
+            split_cycles: self.split_cycles,
+        }
+    }
+
+    /// Drops every split core; their buses must already be back in the group.
+    pub(crate) fn clear(&mut self) {
+        self.lanes.clear();
+    }
+
+    /// Drops the split core running `lane`, if any, leaving the lane to be restored
+    /// by the caller.
+    pub(crate) fn remove(&mut self, lane: usize) {
+        self.lanes.retain(|split| split.lane != lane);
+    }
+
+    pub(crate) fn get(&self, lane: usize) -> Option<&Core<Scalar, BusScalar>> {
+        self.lanes
+            .iter()
+            .find(|split| split.lane == lane)
+            .map(|split| &*split.core)
+    }
+
+    pub(crate) fn get_mut(&mut self, lane: usize) -> Option<&mut Core<Scalar, BusScalar>> {
+        self.lanes
+            .iter_mut()
+            .find(|split| split.lane == lane)
+            .map(|split| &mut *split.core)
+    }
+
+    pub(crate) fn cores_mut(&mut self) -> impl Iterator<Item = &mut Core<Scalar, BusScalar>> {
+        self.lanes.iter_mut().map(|split| &mut *split.core)
+    }
+
+    fn contains(&self, lane: usize) -> bool {
+        self.lanes.iter().any(|split| split.lane == lane)
+    }
+}
+
+impl SplitLane {
+    /// Runs the scalar core until it has caught up with `cycles` of group time and
+    /// returns the cycles it executed.
+    fn advance(&mut self, cycles: u32) -> u32 {
+        if self.ahead >= cycles {
+            self.ahead -= cycles;
+            return 0;
+        }
+        let mut remaining = cycles - self.ahead;
+        let mut executed = 0;
+        self.ahead = 0;
+        while remaining > 0 {
+            let ran = self.core.step_cycles(remaining);
+            if ran == 0 {
+                break;
+            }
+            executed += ran;
+            if ran >= remaining {
+                self.ahead = ran - remaining;
+                remaining = 0;
+            } else {
+                remaining -= ran;
+            }
+        }
+        executed
+    }
+}
+
+impl<const LANES: usize> LaneSplit<SimdExec<LANES>> for BusSimd<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    fn enter_lanes(core: &mut Core<SimdExec<LANES>, Self>) {
+        core.split.entered_at = core.cycles_this_frame;
+        let lead = core.bus.lane(0);
+        for split in &mut core.split.lanes {
+            split.core.bus.sync_registers_from(lead);
+        }
+        for split in &mut core.split.lanes {
+            std::mem::swap(&mut split.core.bus, core.bus.lane_mut(split.lane));
+        }
+    }
+
+    fn guard_lanes(core: &mut Core<SimdExec<LANES>, Self>) {
+        for lane in 1..LANES {
+            if !core.split.contains(lane) && lane_diverges(core, lane) {
+                let mut lead = core.bus.lane(0).clone();
+                lead.set_audio_capture(false);
+                let bus = std::mem::replace(core.bus.lane_mut(lane), lead);
+                let split = Core::split_from(core, lane, bus);
+                // The lane already ran this step's earlier cycles with the group.
+                let ahead = core.cycles_this_frame.wrapping_sub(core.split.entered_at);
+                core.split.lanes.push(SplitLane {
+                    lane,
+                    core: Box::new(split),
+                    ahead,
+                });
+                core.split.splits += 1;
+            }
+        }
+    }
+
+    fn follow_lanes(core: &mut Core<SimdExec<LANES>, Self>, cycles: u32) {
+        let mut index = 0;
+        while index < core.split.lanes.len() {
+            let split = &mut core.split.lanes[index];
+            let executed = split.advance(cycles);
+            std::mem::swap(&mut split.core.bus, core.bus.lane_mut(split.lane));
+            core.split.split_cycles += u64::from(executed);
+            if lane_reconverged(core, index) {
+                let split = core.split.lanes.swap_remove(index);
+                merge_lane(core, split);
+                core.split.merges += 1;
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Returns whether `lane` would execute the next instruction differently from lane 0.
+fn lane_diverges<const LANES: usize>(
+    core: &Core<SimdExec<LANES>, BusSimd<LANES>>,
+    lane: usize,
+) -> bool
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let cpu = &core.cpu;
+    if Register::ALL
+        .iter()
+        .any(|&reg| cpu.lane_register(lane, reg) != cpu.lane_register(0, reg))
+    {
+        return true;
+    }
+
+    let lead = core.bus.lane(0);
+    let bus = core.bus.lane(lane);
+    if bus.ie != lead.ie || bus.io.regs() != lead.io.regs() {
+        return true;
+    }
+    if cpu.stopped {
+        return bus.joypad_line_low() != lead.joypad_line_low();
+    }
+    if cpu.halted {
+        return false;
+    }
+
+    let pc = cpu.lane_register(0, Register::Pc);
+    let opcode = peek8_scalar(lead, pc);
+    let len = if opcode == 0xCB {
+        2
+    } else {
+        u16::from(OPCODES[usize::from(opcode)].len)
+    };
+    let same = |addr: u16| peek8_scalar(bus, addr) == peek8_scalar(lead, addr);
+    if !(0..len).all(|offset| same(pc.wrapping_add(offset))) {
+        return true;
+    }
+
+    let operand = |offset: u16| peek8_scalar(lead, pc.wrapping_add(offset));
+    let pair = |hi, lo| cpu.lane_register(0, hi) << 8 | cpu.lane_register(0, lo);
+    let hl = pair(Register::H, Register::L);
+    let sp = cpu.lane_register(0, Register::Sp);
+    let reads = match opcode {
+        0x0A => [Some(pair(Register::B, Register::C)), None],
+        0x1A => [Some(pair(Register::D, Register::E)), None],
+        0x2A | 0x3A | 0x34 | 0x35 => [Some(hl), None],
+        0x40..=0xBF if opcode & 0x07 == 0x06 && opcode != 0x76 => [Some(hl), None],
+        0xCB if operand(1) & 0x07 == 0x06 => [Some(hl), None],
+        0xF0 => [Some(0xFF00 | u16::from(operand(1))), None],
+        0xF2 => [Some(0xFF00 | cpu.lane_register(0, Register::C)), None],
+        0xFA => [Some(u16::from_le_bytes([operand(1), operand(2)])), None],
+        0xC0 | 0xC1 | 0xC8 | 0xC9 | 0xD0 | 0xD1 | 0xD8 | 0xD9 | 0xE1 | 0xF1 => {
+            [Some(sp), Some(sp.wrapping_add(1))]
+        }
+        _ => [None, None],
+    };
+    !reads.into_iter().flatten().all(same)
+}
+
+/// Returns whether the split lane at `index` again matches lane 0 cycle for cycle.
+fn lane_reconverged<const LANES: usize>(
+    core: &Core<SimdExec<LANES>, BusSimd<LANES>>,
+    index: usize,
+) -> bool
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let split = &core.split.lanes[index];
+    if split.ahead != 0 {
+        return false;
+    }
+    let (cpu, lead_cpu) = (&split.core.cpu, &core.cpu);
+    let (ppu, lead_ppu) = (&split.core.ppu, &core.ppu);
+    let (bus, lead) = (core.bus.lane(split.lane), core.bus.lane(0));
+    Register::ALL
+        .iter()
+        .all(|&reg| cpu.lane_register(0, reg) == lead_cpu.lane_register(0, reg))
+        && cpu.ime == lead_cpu.ime
+        && cpu.halted == lead_cpu.halted
+        && cpu.stopped == lead_cpu.stopped
+        && cpu.enable_ime_pending == lead_cpu.enable_ime_pending
+        && cpu.halt_bug == lead_cpu.halt_bug
+        && TimersState::from(&split.core.timers) == TimersState::from(&core.timers)
+        && ppu.dot_in_line == lead_ppu.dot_in_line
+        && ppu.ly == lead_ppu.ly
+        && ppu.mode == lead_ppu.mode
+        && ppu.lyc_equal == lead_ppu.lyc_equal
+        && ppu.lcd_was_on == lead_ppu.lcd_was_on
+        && bus.ie == lead.ie
+        && bus.io.regs() == lead.io.regs()
+        && bus.double_speed() == lead.double_speed()
+}
+
+/// Folds a reconverged split core back into its lane; its bus is already in place.
+fn merge_lane<const LANES: usize>(
+    core: &mut Core<SimdExec<LANES>, BusSimd<LANES>>,
+    split: SplitLane,
+) where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let lane = split.lane;
+    for reg in Register::ALL {
+        let value = split.core.cpu.lane_register(0, reg);
+        core.cpu.set_lane_register(lane, reg, value);
+    }
+    let Core { ppu, .. } = *split.core;
+    if let (Some(slot), Some(pipeline)) =
+        (core.ppu.lanes.get_mut(lane), ppu.lanes.into_iter().next())
+    {
+        *slot = pipeline;
+    }
+}
+
+impl<const LANES: usize> Core<SimdExec<LANES>, BusSimd<LANES>>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    /// Splits `lane` off onto its own scalar core between steps and returns it.
+    ///
+    /// The lane's bus stays in the group as usual; the scalar core parks a copy
+    /// of lane 0's until the next step hands it over.
+    pub(crate) fn split_lane(&mut self, lane: usize) -> &mut Core<Scalar, BusScalar> {
+        let mut parked = self.bus.lane(0).clone();
+        parked.set_audio_capture(false);
+        let core = Box::new(Core::split_from(self, lane, parked));
+        self.split.lanes.push(SplitLane {
+            lane,
+            core,
+            ahead: 0,
+        });
+        self.split.splits += 1;
+        let split = self.split.lanes.last_mut().expect("lane was just split");
+        &mut split.core
+    }
+
+    /// Reads a register of one lane, following the lane onto its scalar core while split.
+    pub fn lane_register(&self, lane: usize, reg: Register) -> u16 {
+        match self.split.get(lane) {
+            Some(split) => split.cpu.lane_register(0, reg),
+            None => self.cpu.lane_register(lane, reg),
+        }
+    }
+
+    /// Writes a register of one lane, following the lane onto its scalar core while split.
+    pub fn set_lane_register(&mut self, lane: usize, reg: Register, value: u16) {
+        match self.split.get_mut(lane) {
+            Some(split) => split.cpu.set_lane_register(0, reg, value),
+            None => self.cpu.set_lane_register(lane, reg, value),
+        }
+    }
+}
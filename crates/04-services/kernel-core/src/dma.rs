@@ -0,0 +1,237 @@
+use crate::bus::BusScalar;
+use crate::exec::{Exec, Scalar};
+use crate::mmu;
+
+/// Bytes copied into OAM by one transfer.
+pub const OAM_DMA_LEN: u8 = 0xA0;
+/// CPU cycles per copied byte (one M-cycle).
+const CYCLES_PER_BYTE: u32 = 4;
+/// CPU cycles from the `0xFF46` write until the first byte lands: the rest of
+/// the write's M-cycle, one startup M-cycle and the first copy M-cycle.
+const FIRST_BYTE_CYCLES: u32 = 3 * CYCLES_PER_BYTE;
+
+/// Trait exposing the OAM DMA controller that advances with CPU time.
+pub trait OamDmaIo {
+    /// Advances an in-flight OAM DMA transfer by `cycles` CPU ticks.
+    fn step_oam_dma(&mut self, cycles: u32);
+}
+
+/// OAM DMA controller started by writes to `0xFF46`.
+///
+/// The controller copies one byte per M-cycle after a one M-cycle startup
+/// delay. While it owns the bus the CPU reads `0xFF` from OAM and sees the byte
+/// being transferred on whichever bus the source lives on, so only HRAM, IO
+/// registers and the other bus stay usable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OamDma {
+    /// Source address of the first byte (`page << 8`).
+    pub source: u16,
+    /// Bytes already copied by the current transfer.
+    pub index: u8,
+    /// Whether a transfer is starting up or in progress.
+    pub active: bool,
+    /// CPU cycles until the next byte is copied.
+    pub countdown: u32,
+    /// Whether the CPU is locked out of OAM and the source bus.
+    pub blocking: bool,
+}
+
+/// Memory buses that can conflict with an OAM DMA transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DmaBus {
+    /// Cartridge ROM/RAM, plus WRAM on the DMG.
+    External,
+    /// Video RAM.
+    Video,
+    /// Work RAM, which sits on its own bus on the CGB.
+    Work,
+    /// OAM itself.
+    Oam,
+    /// IO registers, HRAM and IE, which the CPU can always reach.
+    Internal,
+}
+
+impl OamDma {
+    /// Schedules a transfer from `page << 8`.
+    ///
+    /// Restarting a running transfer keeps the bus locked through the new
+    /// startup delay.
+    pub fn start(&mut self, page: u8) {
+        // Pages above WRAM read through the echo mirror.
+        let page = if page >= 0xE0 { page - 0x20 } else { page };
+        self.source = u16::from(page) << 8;
+        self.index = 0;
+        self.countdown = FIRST_BYTE_CYCLES;
+        self.blocking = self.active && self.blocking;
+        self.active = true;
+    }
+
+    /// Returns the address the controller reads during the current M-cycle.
+    #[inline]
+    pub fn current_source(&self) -> u16 {
+        self.source.wrapping_add(u16::from(self.index))
+    }
+}
+
+fn dma_bus(addr: u16, cgb: bool) -> DmaBus {
+    match addr {
+        0x8000..=0x9FFF => DmaBus::Video,
+        0xC000..=0xFDFF if cgb => DmaBus::Work,
+        0xFE00..=0xFEFF => DmaBus::Oam,
+        0xFF00..=0xFFFF => DmaBus::Internal,
+        _ => DmaBus::External,
+    }
+}
+
+impl BusScalar {
+    /// Returns the bus `addr` shares with a running transfer, if any.
+    fn oam_dma_conflict(&self, addr: u16) -> Option<DmaBus> {
+        if !self.oam_dma.blocking {
+            return None;
+        }
+        let cgb = self.cgb.enabled;
+        match dma_bus(addr, cgb) {
+            DmaBus::Internal => None,
+            DmaBus::Oam => Some(DmaBus::Oam),
+            bus if bus == dma_bus(self.oam_dma.current_source(), cgb) => Some(bus),
+            _ => None,
+        }
+    }
+
+    /// Returns what a CPU read of `addr` sees while OAM DMA owns the bus.
+    pub(crate) fn oam_dma_read(&mut self, addr: u16) -> Option<u8> {
+        match self.oam_dma_conflict(addr)? {
+            DmaBus::Oam => Some(0xFF),
+            _ => {
+                let src = self.oam_dma.current_source();
+                Some(Scalar::to_u8(mmu::read8_direct(
+                    self,
+                    Scalar::from_u16(src),
+                )))
+            }
+        }
+    }
+
+    /// Returns whether a CPU write to `addr` is lost to a running transfer.
+    pub(crate) fn oam_dma_blocks_write(&self, addr: u16) -> bool {
+        self.oam_dma_conflict(addr).is_some()
+    }
+
+    pub(crate) fn advance_oam_dma(&mut self, mut cycles: u32) {
+        while self.oam_dma.active && cycles > 0 {
+            let step = cycles.min(self.oam_dma.countdown);
+            self.oam_dma.countdown -= step;
+            cycles -= step;
+            if self.oam_dma.countdown <= CYCLES_PER_BYTE {
+                self.oam_dma.blocking = true;
+            }
+            if self.oam_dma.countdown > 0 {
+                continue;
+            }
+
+            let src = Scalar::from_u16(self.oam_dma.current_source());
+            let byte = Scalar::to_u8(mmu::read8_direct(self, src));
+            self.oam[usize::from(self.oam_dma.index)] = byte;
+            self.oam_dma.index += 1;
+            if self.oam_dma.index == OAM_DMA_LEN {
+                self.oam_dma.active = false;
+                self.oam_dma.blocking = false;
+            } else {
+                self.oam_dma.countdown = CYCLES_PER_BYTE;
+            }
+        }
+    }
+}
+
+impl OamDmaIo for BusScalar {
+    #[inline]
+    fn step_oam_dma(&mut self, cycles: u32) {
+        self.advance_oam_dma(cycles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+    use std::sync::Arc;
+
+    fn make_bus() -> BusScalar {
+        let rom = Arc::<[u8]>::from(vec![0u8; 0x8000]);
+        BusScalar::new(rom, None)
+    }
+
+    fn read(bus: &mut BusScalar, addr: u16) -> u8 {
+        bus.read8(addr)
+    }
+
+    fn start_from_wram(bus: &mut BusScalar) {
+        for i in 0..usize::from(OAM_DMA_LEN) {
+            bus.wram[i] = i as u8;
+        }
+        bus.write8(0xFF46, 0xC0);
+        // The write's own M-cycle.
+        bus.step_oam_dma(4);
+    }
+
+    #[test]
+    fn transfer_takes_160_m_cycles_after_startup() {
+        let mut bus = make_bus();
+        start_from_wram(&mut bus);
+        assert!(!bus.oam_dma.blocking, "startup M-cycle leaves the bus free");
+        assert_eq!(read(&mut bus, 0xFE00), 0);
+
+        bus.step_oam_dma(4);
+        assert!(bus.oam_dma.blocking);
+        assert_eq!(read(&mut bus, 0xFE00), 0xFF);
+
+        bus.step_oam_dma(4 * 159);
+        assert!(bus.oam_dma.active);
+        assert_eq!(bus.oam[158], 158);
+        assert_eq!(bus.oam[159], 0);
+
+        bus.step_oam_dma(4);
+        assert!(!bus.oam_dma.active);
+        assert!(!bus.oam_dma.blocking);
+        assert_eq!(read(&mut bus, 0xFE9F), 159);
+    }
+
+    #[test]
+    fn cpu_sees_conflicts_on_the_source_bus_only() {
+        let mut bus = make_bus();
+        bus.vram[0x10] = 0x77;
+        bus.hram[0] = 0x42;
+        start_from_wram(&mut bus);
+        bus.step_oam_dma(4 * 6);
+
+        // WRAM shares the external bus with the source: reads see the DMA byte.
+        assert_eq!(read(&mut bus, 0xC123), 5);
+        assert_eq!(read(&mut bus, 0x0000), 5);
+        bus.write8(0xC123, 0x99);
+        assert_eq!(bus.wram[0x123], 0);
+
+        // VRAM, HRAM and IO stay reachable.
+        assert_eq!(read(&mut bus, 0x8010), 0x77);
+        assert_eq!(read(&mut bus, 0xFF80), 0x42);
+        bus.write8(0xFF81, 0x24);
+        assert_eq!(bus.hram[1], 0x24);
+
+        // OAM writes are dropped while the controller owns it.
+        bus.write8(0xFE50, 0xAB);
+        assert_eq!(bus.oam[0x50], 0);
+    }
+
+    #[test]
+    fn restart_keeps_the_bus_locked() {
+        let mut bus = make_bus();
+        start_from_wram(&mut bus);
+        bus.step_oam_dma(4 * 10);
+        assert!(bus.oam_dma.blocking);
+
+        bus.write8(0xFF46, 0xC0);
+        bus.step_oam_dma(4);
+        assert!(bus.oam_dma.blocking);
+        assert_eq!(bus.oam_dma.index, 0);
+        assert_eq!(read(&mut bus, 0xFE00), 0xFF);
+    }
+}
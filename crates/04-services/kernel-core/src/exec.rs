@@ -10,6 +10,10 @@ pub trait MaskValue: Copy + PartialEq {
     fn to_bool(self) -> bool;
     /// Produces a mask from a boolean.
     fn from_bool(value: bool) -> Self;
+    /// Returns a copy with only `lane` changed; backends without lanes ignore the index.
+    fn with_lane(self, lane: usize, value: bool) -> Self;
+    /// Returns the value of a single lane; backends without lanes ignore the index.
+    fn test_lane(self, lane: usize) -> bool;
 }
 
 impl MaskValue for bool {
@@ -32,6 +36,16 @@ impl MaskValue for bool {
     fn from_bool(value: bool) -> Self {
         value
     }
+
+    #[inline]
+    fn with_lane(self, _lane: usize, value: bool) -> Self {
+        value
+    }
+
+    #[inline]
+    fn test_lane(self, _lane: usize) -> bool {
+        self
+    }
 }
 
 /// Flag register representation parametrised by a mask type.
@@ -126,6 +140,22 @@ impl<M: MaskValue> Flags<M> {
         self.set_h(value & 0x20 != 0);
         self.set_c(value & 0x10 != 0);
     }
+
+    /// Encodes the flags of a single lane into an `F` register value.
+    pub fn lane_byte(&self, lane: usize) -> u8 {
+        (u8::from(self.z.test_lane(lane)) << 7)
+            | (u8::from(self.n.test_lane(lane)) << 6)
+            | (u8::from(self.h.test_lane(lane)) << 5)
+            | (u8::from(self.c.test_lane(lane)) << 4)
+    }
+
+    /// Restores the flags of a single lane from an `F` register value.
+    pub fn set_lane_byte(&mut self, lane: usize, value: u8) {
+        self.z = self.z.with_lane(lane, value & 0x80 != 0);
+        self.n = self.n.with_lane(lane, value & 0x40 != 0);
+        self.h = self.h.with_lane(lane, value & 0x20 != 0);
+        self.c = self.c.with_lane(lane, value & 0x10 != 0);
+    }
 }
 
 impl<M: MaskValue + fmt::Debug> fmt::Debug for Flags<M> {
@@ -160,6 +190,14 @@ pub trait Exec: 'static + Copy {
     fn combine_u16(high: Self::U8, low: Self::U8) -> Self::U16;
     /// Splits a 16-bit lane into its `(high, low)` components.
     fn split_u16(value: Self::U16) -> (Self::U8, Self::U8);
+    /// Reads a single lane of an 8-bit element; scalar backends ignore the index.
+    fn lane_u8(value: Self::U8, lane: usize) -> u8;
+    /// Reads a single lane of a 16-bit element; scalar backends ignore the index.
+    fn lane_u16(value: Self::U16, lane: usize) -> u16;
+    /// Replaces a single lane of an 8-bit element; scalar backends ignore the index.
+    fn with_lane_u8(value: Self::U8, lane: usize, byte: u8) -> Self::U8;
+    /// Replaces a single lane of a 16-bit element; scalar backends ignore the index.
+    fn with_lane_u16(value: Self::U16, lane: usize, word: u16) -> Self::U16;
 
     /// Bitwise AND.
     fn and(a: Self::U8, b: Self::U8) -> Self::U8;
@@ -218,6 +256,26 @@ impl Exec for Scalar {
         ((value >> 8) as u8, (value & 0x00FF) as u8)
     }
 
+    #[inline]
+    fn lane_u8(value: Self::U8, _lane: usize) -> u8 {
+        value
+    }
+
+    #[inline]
+    fn lane_u16(value: Self::U16, _lane: usize) -> u16 {
+        value
+    }
+
+    #[inline]
+    fn with_lane_u8(_value: Self::U8, _lane: usize, byte: u8) -> Self::U8 {
+        byte
+    }
+
+    #[inline]
+    fn with_lane_u16(_value: Self::U16, _lane: usize, word: u16) -> Self::U16 {
+        word
+    }
+
     #[inline]
     fn and(a: Self::U8, b: Self::U8) -> Self::U8 {
         a & b
@@ -66,6 +66,17 @@ where
             inner: Mask::splat(value),
         }
     }
+
+    #[inline]
+    fn with_lane(mut self, lane: usize, value: bool) -> Self {
+        self.inner.set(lane, value);
+        self
+    }
+
+    #[inline]
+    fn test_lane(self, lane: usize) -> bool {
+        self.inner.test(lane)
+    }
 }
 
 /// SIMD execution backend using `LANES` parallel Game Boy instances.
@@ -115,6 +126,28 @@ where
         (hi, lo)
     }
 
+    #[inline]
+    fn lane_u8(value: Self::U8, lane: usize) -> u8 {
+        value[lane]
+    }
+
+    #[inline]
+    fn lane_u16(value: Self::U16, lane: usize) -> u16 {
+        value[lane]
+    }
+
+    #[inline]
+    fn with_lane_u8(mut value: Self::U8, lane: usize, byte: u8) -> Self::U8 {
+        value[lane] = byte;
+        value
+    }
+
+    #[inline]
+    fn with_lane_u16(mut value: Self::U16, lane: usize, word: u16) -> Self::U16 {
+        value[lane] = word;
+        value
+    }
+
     #[inline]
     fn and(a: Self::U8, b: Self::U8) -> Self::U8 {
         a & b
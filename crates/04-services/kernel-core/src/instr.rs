@@ -55,6 +55,23 @@ pub enum AluOp {
     Cp,
 }
 
+impl AluOp {
+    /// Decodes the operation selected by bits 3-5 of an ALU opcode.
+    #[inline(always)]
+    pub fn from_opcode(opcode: u8) -> Self {
+        match (opcode >> 3) & 0x07 {
+            0 => AluOp::Add,
+            1 => AluOp::Adc,
+            2 => AluOp::Sub,
+            3 => AluOp::Sbc,
+            4 => AluOp::And,
+            5 => AluOp::Xor,
+            6 => AluOp::Or,
+            _ => AluOp::Cp,
+        }
+    }
+}
+
 /// Implements the `NOP` instruction.
 #[inline(always)]
 pub fn op_nop() -> u32 {
@@ -748,10 +765,16 @@ where
     CycleCost::Clocks4.as_u32()
 }
 
-/// Enters the STOP low-power state (treated as HALT).
+/// Enters the STOP low-power state, or performs an armed CGB speed switch.
 #[inline(always)]
 pub fn op_stop<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
-    core.cpu.halted = true;
+    // STOP is encoded as `10 00`; the padding byte is skipped.
+    let pc = E::to_u16(core.cpu.pc);
+    core.cpu.pc = E::from_u16(pc.wrapping_add(1));
+    // An armed speed switch and a held button both keep the CPU running.
+    if !core.bus.try_speed_switch() && !core.bus.joypad_line_low() {
+        core.enter_stop();
+    }
     CycleCost::Clocks4.as_u32()
 }
 
@@ -773,14 +796,14 @@ pub fn op_push_rr<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, rp: u8) -> u32
         3 => core.cpu.af(),
         _ => unreachable!(),
     };
-    core.cpu.push16(&mut core.bus, value);
+    core.push16(value);
     CycleCost::Clocks16.as_u32()
 }
 
 /// Pops a register pair from the stack.
 #[inline(always)]
 pub fn op_pop_rr<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, rp: u8) -> u32 {
-    let value = core.cpu.pop16(&mut core.bus);
+    let value = core.pop16();
     match rp & 0x03 {
         0 => core.cpu.set_bc(value),
         1 => core.cpu.set_de(value),
@@ -937,7 +960,7 @@ pub fn op_cb<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, sub: u8) -> u32 {
 /// Returns from an interrupt handler and re-enables IME.
 #[inline(always)]
 pub fn op_reti<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
-    let addr = core.cpu.pop16(&mut core.bus);
+    let addr = core.pop16();
     core.cpu.pc = addr;
     core.cpu.ime = true;
     CycleCost::Clocks16.as_u32()
@@ -948,7 +971,7 @@ pub fn op_reti<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
 pub fn op_rst<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, opcode: u8) -> u32 {
     let target = (opcode & 0x38) as u16;
     let ret = core.cpu.pc;
-    core.cpu.push16(&mut core.bus, ret);
+    core.push16(ret);
     core.cpu.pc = E::from_u16(target);
     CycleCost::Clocks16.as_u32()
 }
@@ -958,7 +981,7 @@ pub fn op_rst<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, opcode: u8) -> u32
 pub fn op_ret_cc<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, opcode: u8) -> u32 {
     let cc = (opcode >> 3) & 0x03;
     if cond::<E>(&core.cpu.f, cc) {
-        let addr = core.cpu.pop16(&mut core.bus);
+        let addr = core.pop16();
         core.cpu.pc = addr;
         CycleCost::Clocks20.as_u32()
     } else {
@@ -986,7 +1009,7 @@ pub fn op_call_cc_a16<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>, opcode: u8)
     let cc = (opcode >> 3) & 0x03;
     if cond::<E>(&core.cpu.f, cc) {
         let ret = core.cpu.pc;
-        core.cpu.push16(&mut core.bus, ret);
+        core.push16(ret);
         core.cpu.pc = addr;
         CycleCost::Clocks24.as_u32()
     } else {
@@ -1005,7 +1028,7 @@ pub fn op_jp_a16<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
 /// Returns from a subroutine.
 #[inline(always)]
 pub fn op_ret<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
-    let addr = core.cpu.pop16(&mut core.bus);
+    let addr = core.pop16();
     core.cpu.pc = addr;
     CycleCost::Clocks16.as_u32()
 }
@@ -1015,7 +1038,7 @@ pub fn op_ret<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
 pub fn op_call_a16<E: Exec, B: CoreBus<E>>(core: &mut Core<E, B>) -> u32 {
     let addr = core.fetch_imm16();
     let ret = core.cpu.pc;
-    core.cpu.push16(&mut core.bus, ret);
+    core.push16(ret);
     core.cpu.pc = addr;
     CycleCost::Clocks24.as_u32()
 }
@@ -7,14 +7,30 @@
 //! The crate exposes scalar-first CPU, bus, and scheduler primitives that can
 //! later be specialised for SIMD execution without touching instruction logic.
 
+/// DMG audio processing unit.
+pub mod apu;
+/// Breakpoints and watchpoints checked while the core runs.
+pub mod breakpoints;
 /// Bus traits and scalar IO implementations.
 pub mod bus;
 /// SIMD bus wrapper combining multiple scalar lanes.
 pub mod bus_simd;
+/// Cartridge header decoding and memory bank controllers.
+pub mod cartridge;
+/// Game Boy Color banks, palettes, speed switch and VRAM DMA.
+pub mod cgb;
+/// Game Genie and GameShark cheat codes.
+pub mod cheats;
 /// Central CPU + scheduler core implementation.
 pub mod core;
 /// CPU register file representation.
 pub mod cpu;
+/// SM83 disassembler built on the opcode metadata table.
+pub mod disasm;
+/// Splitting and merging of SIMD lanes whose execution diverges.
+pub mod divergence;
+/// OAM DMA controller and its bus-conflict rules.
+pub mod dma;
 /// Execution backend abstractions.
 pub mod exec;
 /// SIMD execution backend built on `std::simd`.
@@ -23,23 +39,53 @@ pub mod exec_simd;
 pub mod instr;
 /// Memory management unit helpers.
 pub mod mmu;
-/// Placeholder PPU implementation used for tests.
-pub mod ppu_stub;
+/// Opcode lengths, timings and mnemonics shared by the executor and disassembler.
+pub mod opcodes;
+/// Dot-based pixel FIFO PPU.
+pub mod ppu;
+/// MBC3 real-time clock.
+pub mod rtc;
 /// Serialization helpers for saving and restoring core state.
 pub mod state;
 /// Timer block abstraction shared across services.
 pub mod timers;
+/// Ring buffer recording executed instructions for debugging.
+pub mod trace;
 
+/// Re-export of the APU.
+pub use apu::Apu;
+/// Re-export of the breakpoint and watchpoint types.
+pub use breakpoints::{BreakReason, Breakpoints, WatchAccess, Watchpoint};
 /// Re-export of bus traits and IO structures.
-pub use bus::{Bus, BusScalar, IoRegs};
+pub use bus::{ApuIo, Bus, BusScalar, CartridgeIo, IoRegs, JoypadIo};
 /// Re-export of the SIMD bus implementation.
 pub use bus_simd::BusSimd;
+/// Re-export of the cartridge and mapper types.
+pub use cartridge::{Cartridge, CartridgeHeader, MapperKind};
+/// Re-export of the CGB hardware state and its bus trait.
+pub use cgb::{Cgb, CgbIo};
+/// Re-export of the cheat engine types and its bus trait.
+pub use cheats::{Cheat, CheatError, CheatIo, Cheats};
 /// Re-export of the high-level core types.
 pub use core::{Core, CoreConfig, Model};
+/// Re-export of the register selector used by debug writes.
+pub use cpu::Register;
+/// Re-export of the decoded disassembly line.
+pub use disasm::DisasmLine;
+/// Re-export of the lane divergence counters and bus hooks.
+pub use divergence::{DivergenceStats, LaneSplit};
+/// Re-export of the OAM DMA controller and its bus trait.
+pub use dma::{OamDma, OamDmaIo};
 /// Re-export of execution backend traits and scalar implementation.
 pub use exec::{Exec, Flags, MaskValue, Scalar};
 /// Re-export of the SIMD execution backend.
 pub use exec_simd::{LaneMask, SimdExec};
+/// Re-export of the PPU.
+pub use ppu::Ppu;
+/// Re-export of the MBC3 clock source selector.
+pub use rtc::RtcClock;
+/// Re-export of the execution trace types.
+pub use trace::{MemAccess, Trace, TraceEntry};
 
 /// Convenience alias for a SIMD-configured core.
 pub type SimdCore<const LANES: usize> = Core<SimdExec<LANES>, bus_simd::BusSimd<LANES>>;
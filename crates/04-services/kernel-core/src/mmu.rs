@@ -1,44 +1,60 @@
+use crate::apu::Apu;
 use crate::bus::{BusScalar, IoRegs};
 use crate::exec::{Exec, Scalar};
 
-/// Reads a byte from the scalar bus.
+/// Reads a byte from the scalar bus as the CPU sees it.
+///
+/// While OAM DMA owns the bus the read may return `0xFF` or the byte being
+/// transferred instead of the addressed memory. ROM reads are patched by the
+/// active Game Genie codes.
 pub fn read8_scalar(bus: &mut BusScalar, addr: <Scalar as Exec>::U16) -> <Scalar as Exec>::U8 {
+    let raw = <Scalar as Exec>::to_u16(addr);
+    if let Some(byte) = bus.oam_dma_read(raw) {
+        return byte;
+    }
+    let byte = read8_direct(bus, addr);
+    if raw < 0x8000 {
+        bus.cheats.patch_rom_read(raw, byte)
+    } else {
+        byte
+    }
+}
+
+/// Reads a byte without side effects, for debuggers and inspectors.
+///
+/// The address is decoded exactly as the CPU would decode it, including the
+/// boot ROM overlay and the currently mapped banks, but OAM DMA bus conflicts
+/// are ignored and neither watchpoints nor the trace observe the access.
+pub fn peek8_scalar(bus: &BusScalar, addr: u16) -> u8 {
+    read8_direct(bus, <Scalar as Exec>::from_u16(addr))
+}
+
+/// Reads a byte from the scalar bus, bypassing OAM DMA bus conflicts.
+pub(crate) fn read8_direct(bus: &BusScalar, addr: <Scalar as Exec>::U16) -> <Scalar as Exec>::U8 {
     let addr = <Scalar as Exec>::to_u16(addr);
     match addr {
         0x0000..=0x3FFF => {
             if let Some(byte) = bus.boot_rom_byte(addr) {
                 byte
             } else {
-                bus.rom.get(addr as usize).copied().unwrap_or(0xFF)
+                bus.cart.read_rom(addr)
             }
         }
-        0x4000..=0x7FFF => {
-            let bank = bus.rom_bank;
-            let offset = (addr - 0x4000) as usize;
-            let index = bank.saturating_mul(0x4000).saturating_add(offset);
-            bus.rom.get(index).copied().unwrap_or(0xFF)
-        }
-        0x8000..=0x9FFF => bus.vram[(addr - 0x8000) as usize],
-        0xA000..=0xBFFF => 0xFF, // No MBC for M1 scope.
-        0xC000..=0xDFFF => bus.wram[(addr - 0xC000) as usize],
-        0xE000..=0xFDFF => bus.wram[(addr - 0xE000) as usize],
+        0x4000..=0x7FFF => bus.cart.read_rom(addr),
+        0x8000..=0x9FFF => bus.cpu_vram()[(addr - 0x8000) as usize],
+        0xA000..=0xBFFF => bus.cart.read_ram(addr),
+        0xC000..=0xCFFF => bus.wram[(addr - 0xC000) as usize],
+        0xD000..=0xDFFF => bus.banked_wram((addr - 0xD000) as usize),
+        0xE000..=0xEFFF => bus.wram[(addr - 0xE000) as usize],
+        0xF000..=0xFDFF => bus.banked_wram((addr - 0xF000) as usize),
         0xFE00..=0xFE9F => bus.oam[(addr - 0xFE00) as usize],
         0xFEA0..=0xFEFF => 0xFF,
         0xFF00..=0xFF7F => {
             let idx = (addr - 0xFF00) as usize;
             match idx {
-                IoRegs::JOYP => {
-                    let sel = bus.joyp_select;
-                    let mut lo = 0x0F;
-                    if sel & 0x10 == 0 {
-                        lo &= bus.joyp_dpad;
-                    }
-                    if sel & 0x20 == 0 {
-                        lo &= bus.joyp_buttons;
-                    }
-                    0xC0 | sel | (!lo & 0x0F)
-                }
+                IoRegs::JOYP => 0xC0 | bus.joyp_select | bus.joypad_lines(),
                 IoRegs::IF => bus.io.if_reg(),
+                idx if Apu::owns(idx) => bus.apu.read(idx),
                 IoRegs::LY => bus.lockstep_ly_override.unwrap_or_else(|| bus.io.read(idx)),
                 0x50 => {
                     if bus.boot_rom_enabled() {
@@ -47,7 +63,7 @@ pub fn read8_scalar(bus: &mut BusScalar, addr: <Scalar as Exec>::U16) -> <Scalar
                         0x01
                     }
                 }
-                _ => bus.io.read(idx),
+                _ => bus.cgb.read_io(idx).unwrap_or_else(|| bus.io.read(idx)),
             }
         }
         0xFF80..=0xFFFE => bus.hram[(addr - 0xFF80) as usize],
@@ -62,33 +78,31 @@ pub fn write8_scalar(
     value: <Scalar as Exec>::U8,
 ) {
     let addr = <Scalar as Exec>::to_u16(addr);
+    if bus.oam_dma_blocks_write(addr) {
+        return;
+    }
     match addr {
-        0x0000..=0x1FFF => {
-            // Cartridge RAM enable (unsupported)
-        }
-        0x2000..=0x3FFF => {
-            let value = <Scalar as Exec>::to_u8(value);
-            let bank = (value & 0x1F) as usize;
-            bus.set_rom_bank(bank);
-        }
-        0x4000..=0x5FFF => {
-            // High ROM bank bits / RAM bank (ignored for current cartridges)
-        }
-        0x6000..=0x7FFF => {
-            // Banking mode select (ignored)
+        0x0000..=0x7FFF => {
+            bus.cart.write_control(addr, value);
         }
         0x8000..=0x9FFF => {
-            bus.vram[(addr - 0x8000) as usize] = value;
+            bus.cpu_vram_mut()[(addr - 0x8000) as usize] = value;
         }
         0xA000..=0xBFFF => {
-            // No cartridge RAM in scope.
+            bus.cart.write_ram(addr, value);
         }
-        0xC000..=0xDFFF => {
+        0xC000..=0xCFFF => {
             bus.wram[(addr - 0xC000) as usize] = value;
         }
-        0xE000..=0xFDFF => {
+        0xD000..=0xDFFF => {
+            *bus.banked_wram_mut((addr - 0xD000) as usize) = value;
+        }
+        0xE000..=0xEFFF => {
             bus.wram[(addr - 0xE000) as usize] = value;
         }
+        0xF000..=0xFDFF => {
+            *bus.banked_wram_mut((addr - 0xF000) as usize) = value;
+        }
         0xFE00..=0xFE9F => {
             bus.oam[(addr - 0xFE00) as usize] = value;
         }
@@ -96,12 +110,8 @@ pub fn write8_scalar(
         0xFF00..=0xFF7F => {
             let idx = (addr - 0xFF00) as usize;
             if idx == 0x46 {
-                let base = u16::from(value) << 8;
-                for offset in 0..=0x9F {
-                    let src = Scalar::from_u16(base.wrapping_add(offset as u16));
-                    let byte = read8_scalar(bus, src);
-                    bus.oam[offset as usize] = Scalar::to_u8(byte);
-                }
+                bus.io.write(idx, value);
+                bus.oam_dma.start(value);
                 return;
             }
             if idx == 0x50 {
@@ -110,10 +120,25 @@ pub fn write8_scalar(
                 }
                 return;
             }
+            if Apu::owns(idx) {
+                bus.apu.write(idx, value);
+                // Mirror the readback so raw IO snapshots reflect the APU registers.
+                let readback = bus.apu.read(idx);
+                bus.io.write(idx, readback);
+                return;
+            }
+            if bus.cgb_write_io(idx, value) {
+                let readback = bus.cgb.read_io(idx).unwrap_or(value);
+                bus.io.write(idx, readback);
+                return;
+            }
             if idx == IoRegs::JOYP {
+                let before = bus.joypad_lines();
                 bus.joyp_select = value & 0x30;
                 let current = bus.io.read(idx);
                 bus.io.write(idx, (current & !0x30) | bus.joyp_select);
+                // Selecting a column with a held button pulls its line low.
+                bus.latch_joypad_edge(before);
                 return;
             }
             if idx == BusScalar::io_div_index() {
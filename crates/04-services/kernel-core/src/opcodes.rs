@@ -0,0 +1,319 @@
+/// Static metadata for one base (non-CB) opcode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpcodeInfo {
+    /// Mnemonic template; `d8`/`d16`, `a8`/`a16`, `r8` and `e8` mark operands.
+    pub mnemonic: &'static str,
+    /// Encoded length in bytes, including the opcode.
+    pub len: u8,
+    /// CPU cycles when no branch is taken.
+    pub cycles: u8,
+    /// CPU cycles when a conditional branch is taken.
+    pub cycles_taken: u8,
+}
+
+impl OpcodeInfo {
+    /// Returns whether the opcode is unused on the SM83.
+    #[inline]
+    pub fn is_illegal(&self) -> bool {
+        self.mnemonic.is_empty()
+    }
+
+    /// Returns whether `cycles` is a valid duration for this opcode.
+    #[inline]
+    pub fn allows_cycles(&self, cycles: u32) -> bool {
+        cycles == u32::from(self.cycles) || cycles == u32::from(self.cycles_taken)
+    }
+}
+
+const fn op(mnemonic: &'static str, len: u8, cycles: u8) -> OpcodeInfo {
+    branch(mnemonic, len, cycles, cycles)
+}
+
+const fn branch(mnemonic: &'static str, len: u8, cycles: u8, cycles_taken: u8) -> OpcodeInfo {
+    OpcodeInfo {
+        mnemonic,
+        len,
+        cycles,
+        cycles_taken,
+    }
+}
+
+const ILLEGAL: OpcodeInfo = op("", 1, 4);
+
+/// Operand names indexed by the 3-bit register selector used by `read_r8`/`write_r8`.
+pub const R8_NAMES: [&str; 8] = ["B", "C", "D", "E", "H", "L", "(HL)", "A"];
+
+/// Rotate/shift mnemonics for CB opcodes `0x00-0x3F`, indexed by bits 3-5.
+pub const CB_SHIFT_NAMES: [&str; 8] = ["RLC", "RRC", "RL", "RR", "SLA", "SRA", "SWAP", "SRL"];
+
+/// Returns the CPU cycles taken by the CB-prefixed opcode `sub`, prefix included.
+pub const fn cb_cycles(sub: u8) -> u32 {
+    match (sub >> 6, sub & 0x07) {
+        (1, 6) => 12,
+        (_, 6) => 16,
+        _ => 8,
+    }
+}
+
+/// Metadata for every base opcode, indexed by opcode byte.
+///
+/// `Core::execute_opcode` checks its cycle counts against this table in debug
+/// builds and the disassembler renders listings from it, so the two cannot drift.
+pub static OPCODES: [OpcodeInfo; 256] = [
+    op("NOP", 1, 4),                  // 00
+    op("LD BC,d16", 3, 12),           // 01
+    op("LD (BC),A", 1, 8),            // 02
+    op("INC BC", 1, 8),               // 03
+    op("INC B", 1, 4),                // 04
+    op("DEC B", 1, 4),                // 05
+    op("LD B,d8", 2, 8),              // 06
+    op("RLCA", 1, 4),                 // 07
+    op("LD (a16),SP", 3, 20),         // 08
+    op("ADD HL,BC", 1, 8),            // 09
+    op("LD A,(BC)", 1, 8),            // 0A
+    op("DEC BC", 1, 8),               // 0B
+    op("INC C", 1, 4),                // 0C
+    op("DEC C", 1, 4),                // 0D
+    op("LD C,d8", 2, 8),              // 0E
+    op("RRCA", 1, 4),                 // 0F
+    op("STOP", 2, 4),                 // 10
+    op("LD DE,d16", 3, 12),           // 11
+    op("LD (DE),A", 1, 8),            // 12
+    op("INC DE", 1, 8),               // 13
+    op("INC D", 1, 4),                // 14
+    op("DEC D", 1, 4),                // 15
+    op("LD D,d8", 2, 8),              // 16
+    op("RLA", 1, 4),                  // 17
+    op("JR r8", 2, 12),               // 18
+    op("ADD HL,DE", 1, 8),            // 19
+    op("LD A,(DE)", 1, 8),            // 1A
+    op("DEC DE", 1, 8),               // 1B
+    op("INC E", 1, 4),                // 1C
+    op("DEC E", 1, 4),                // 1D
+    op("LD E,d8", 2, 8),              // 1E
+    op("RRA", 1, 4),                  // 1F
+    branch("JR NZ,r8", 2, 8, 12),     // 20
+    op("LD HL,d16", 3, 12),           // 21
+    op("LD (HL+),A", 1, 8),           // 22
+    op("INC HL", 1, 8),               // 23
+    op("INC H", 1, 4),                // 24
+    op("DEC H", 1, 4),                // 25
+    op("LD H,d8", 2, 8),              // 26
+    op("DAA", 1, 4),                  // 27
+    branch("JR Z,r8", 2, 8, 12),      // 28
+    op("ADD HL,HL", 1, 8),            // 29
+    op("LD A,(HL+)", 1, 8),           // 2A
+    op("DEC HL", 1, 8),               // 2B
+    op("INC L", 1, 4),                // 2C
+    op("DEC L", 1, 4),                // 2D
+    op("LD L,d8", 2, 8),              // 2E
+    op("CPL", 1, 4),                  // 2F
+    branch("JR NC,r8", 2, 8, 12),     // 30
+    op("LD SP,d16", 3, 12),           // 31
+    op("LD (HL-),A", 1, 8),           // 32
+    op("INC SP", 1, 8),               // 33
+    op("INC (HL)", 1, 12),            // 34
+    op("DEC (HL)", 1, 12),            // 35
+    op("LD (HL),d8", 2, 12),          // 36
+    op("SCF", 1, 4),                  // 37
+    branch("JR C,r8", 2, 8, 12),      // 38
+    op("ADD HL,SP", 1, 8),            // 39
+    op("LD A,(HL-)", 1, 8),           // 3A
+    op("DEC SP", 1, 8),               // 3B
+    op("INC A", 1, 4),                // 3C
+    op("DEC A", 1, 4),                // 3D
+    op("LD A,d8", 2, 8),              // 3E
+    op("CCF", 1, 4),                  // 3F
+    op("LD B,B", 1, 4),               // 40
+    op("LD B,C", 1, 4),               // 41
+    op("LD B,D", 1, 4),               // 42
+    op("LD B,E", 1, 4),               // 43
+    op("LD B,H", 1, 4),               // 44
+    op("LD B,L", 1, 4),               // 45
+    op("LD B,(HL)", 1, 8),            // 46
+    op("LD B,A", 1, 4),               // 47
+    op("LD C,B", 1, 4),               // 48
+    op("LD C,C", 1, 4),               // 49
+    op("LD C,D", 1, 4),               // 4A
+    op("LD C,E", 1, 4),               // 4B
+    op("LD C,H", 1, 4),               // 4C
+    op("LD C,L", 1, 4),               // 4D
+    op("LD C,(HL)", 1, 8),            // 4E
+    op("LD C,A", 1, 4),               // 4F
+    op("LD D,B", 1, 4),               // 50
+    op("LD D,C", 1, 4),               // 51
+    op("LD D,D", 1, 4),               // 52
+    op("LD D,E", 1, 4),               // 53
+    op("LD D,H", 1, 4),               // 54
+    op("LD D,L", 1, 4),               // 55
+    op("LD D,(HL)", 1, 8),            // 56
+    op("LD D,A", 1, 4),               // 57
+    op("LD E,B", 1, 4),               // 58
+    op("LD E,C", 1, 4),               // 59
+    op("LD E,D", 1, 4),               // 5A
+    op("LD E,E", 1, 4),               // 5B
+    op("LD E,H", 1, 4),               // 5C
+    op("LD E,L", 1, 4),               // 5D
+    op("LD E,(HL)", 1, 8),            // 5E
+    op("LD E,A", 1, 4),               // 5F
+    op("LD H,B", 1, 4),               // 60
+    op("LD H,C", 1, 4),               // 61
+    op("LD H,D", 1, 4),               // 62
+    op("LD H,E", 1, 4),               // 63
+    op("LD H,H", 1, 4),               // 64
+    op("LD H,L", 1, 4),               // 65
+    op("LD H,(HL)", 1, 8),            // 66
+    op("LD H,A", 1, 4),               // 67
+    op("LD L,B", 1, 4),               // 68
+    op("LD L,C", 1, 4),               // 69
+    op("LD L,D", 1, 4),               // 6A
+    op("LD L,E", 1, 4),               // 6B
+    op("LD L,H", 1, 4),               // 6C
+    op("LD L,L", 1, 4),               // 6D
+    op("LD L,(HL)", 1, 8),            // 6E
+    op("LD L,A", 1, 4),               // 6F
+    op("LD (HL),B", 1, 8),            // 70
+    op("LD (HL),C", 1, 8),            // 71
+    op("LD (HL),D", 1, 8),            // 72
+    op("LD (HL),E", 1, 8),            // 73
+    op("LD (HL),H", 1, 8),            // 74
+    op("LD (HL),L", 1, 8),            // 75
+    op("HALT", 1, 4),                 // 76
+    op("LD (HL),A", 1, 8),            // 77
+    op("LD A,B", 1, 4),               // 78
+    op("LD A,C", 1, 4),               // 79
+    op("LD A,D", 1, 4),               // 7A
+    op("LD A,E", 1, 4),               // 7B
+    op("LD A,H", 1, 4),               // 7C
+    op("LD A,L", 1, 4),               // 7D
+    op("LD A,(HL)", 1, 8),            // 7E
+    op("LD A,A", 1, 4),               // 7F
+    op("ADD A,B", 1, 4),              // 80
+    op("ADD A,C", 1, 4),              // 81
+    op("ADD A,D", 1, 4),              // 82
+    op("ADD A,E", 1, 4),              // 83
+    op("ADD A,H", 1, 4),              // 84
+    op("ADD A,L", 1, 4),              // 85
+    op("ADD A,(HL)", 1, 8),           // 86
+    op("ADD A,A", 1, 4),              // 87
+    op("ADC A,B", 1, 4),              // 88
+    op("ADC A,C", 1, 4),              // 89
+    op("ADC A,D", 1, 4),              // 8A
+    op("ADC A,E", 1, 4),              // 8B
+    op("ADC A,H", 1, 4),              // 8C
+    op("ADC A,L", 1, 4),              // 8D
+    op("ADC A,(HL)", 1, 8),           // 8E
+    op("ADC A,A", 1, 4),              // 8F
+    op("SUB B", 1, 4),                // 90
+    op("SUB C", 1, 4),                // 91
+    op("SUB D", 1, 4),                // 92
+    op("SUB E", 1, 4),                // 93
+    op("SUB H", 1, 4),                // 94
+    op("SUB L", 1, 4),                // 95
+    op("SUB (HL)", 1, 8),             // 96
+    op("SUB A", 1, 4),                // 97
+    op("SBC A,B", 1, 4),              // 98
+    op("SBC A,C", 1, 4),              // 99
+    op("SBC A,D", 1, 4),              // 9A
+    op("SBC A,E", 1, 4),              // 9B
+    op("SBC A,H", 1, 4),              // 9C
+    op("SBC A,L", 1, 4),              // 9D
+    op("SBC A,(HL)", 1, 8),           // 9E
+    op("SBC A,A", 1, 4),              // 9F
+    op("AND B", 1, 4),                // A0
+    op("AND C", 1, 4),                // A1
+    op("AND D", 1, 4),                // A2
+    op("AND E", 1, 4),                // A3
+    op("AND H", 1, 4),                // A4
+    op("AND L", 1, 4),                // A5
+    op("AND (HL)", 1, 8),             // A6
+    op("AND A", 1, 4),                // A7
+    op("XOR B", 1, 4),                // A8
+    op("XOR C", 1, 4),                // A9
+    op("XOR D", 1, 4),                // AA
+    op("XOR E", 1, 4),                // AB
+    op("XOR H", 1, 4),                // AC
+    op("XOR L", 1, 4),                // AD
+    op("XOR (HL)", 1, 8),             // AE
+    op("XOR A", 1, 4),                // AF
+    op("OR B", 1, 4),                 // B0
+    op("OR C", 1, 4),                 // B1
+    op("OR D", 1, 4),                 // B2
+    op("OR E", 1, 4),                 // B3
+    op("OR H", 1, 4),                 // B4
+    op("OR L", 1, 4),                 // B5
+    op("OR (HL)", 1, 8),              // B6
+    op("OR A", 1, 4),                 // B7
+    op("CP B", 1, 4),                 // B8
+    op("CP C", 1, 4),                 // B9
+    op("CP D", 1, 4),                 // BA
+    op("CP E", 1, 4),                 // BB
+    op("CP H", 1, 4),                 // BC
+    op("CP L", 1, 4),                 // BD
+    op("CP (HL)", 1, 8),              // BE
+    op("CP A", 1, 4),                 // BF
+    branch("RET NZ", 1, 8, 20),       // C0
+    op("POP BC", 1, 12),              // C1
+    branch("JP NZ,a16", 3, 12, 16),   // C2
+    op("JP a16", 3, 16),              // C3
+    branch("CALL NZ,a16", 3, 12, 24), // C4
+    op("PUSH BC", 1, 16),             // C5
+    op("ADD A,d8", 2, 8),             // C6
+    op("RST $00", 1, 16),             // C7
+    branch("RET Z", 1, 8, 20),        // C8
+    op("RET", 1, 16),                 // C9
+    branch("JP Z,a16", 3, 12, 16),    // CA
+    op("PREFIX CB", 2, 4),            // CB
+    branch("CALL Z,a16", 3, 12, 24),  // CC
+    op("CALL a16", 3, 24),            // CD
+    op("ADC A,d8", 2, 8),             // CE
+    op("RST $08", 1, 16),             // CF
+    branch("RET NC", 1, 8, 20),       // D0
+    op("POP DE", 1, 12),              // D1
+    branch("JP NC,a16", 3, 12, 16),   // D2
+    ILLEGAL,                          // D3
+    branch("CALL NC,a16", 3, 12, 24), // D4
+    op("PUSH DE", 1, 16),             // D5
+    op("SUB d8", 2, 8),               // D6
+    op("RST $10", 1, 16),             // D7
+    branch("RET C", 1, 8, 20),        // D8
+    op("RETI", 1, 16),                // D9
+    branch("JP C,a16", 3, 12, 16),    // DA
+    ILLEGAL,                          // DB
+    branch("CALL C,a16", 3, 12, 24),  // DC
+    ILLEGAL,                          // DD
+    op("SBC A,d8", 2, 8),             // DE
+    op("RST $18", 1, 16),             // DF
+    op("LDH (a8),A", 2, 12),          // E0
+    op("POP HL", 1, 12),              // E1
+    op("LD (C),A", 1, 8),             // E2
+    ILLEGAL,                          // E3
+    ILLEGAL,                          // E4
+    op("PUSH HL", 1, 16),             // E5
+    op("AND d8", 2, 8),               // E6
+    op("RST $20", 1, 16),             // E7
+    op("ADD SP,e8", 2, 16),           // E8
+    op("JP HL", 1, 4),                // E9
+    op("LD (a16),A", 3, 16),          // EA
+    ILLEGAL,                          // EB
+    ILLEGAL,                          // EC
+    ILLEGAL,                          // ED
+    op("XOR d8", 2, 8),               // EE
+    op("RST $28", 1, 16),             // EF
+    op("LDH A,(a8)", 2, 12),          // F0
+    op("POP AF", 1, 12),              // F1
+    op("LD A,(C)", 1, 8),             // F2
+    op("DI", 1, 4),                   // F3
+    ILLEGAL,                          // F4
+    op("PUSH AF", 1, 16),             // F5
+    op("OR d8", 2, 8),                // F6
+    op("RST $30", 1, 16),             // F7
+    op("LD HL,SP+e8", 2, 12),         // F8
+    op("LD SP,HL", 1, 8),             // F9
+    op("LD A,(a16)", 3, 16),          // FA
+    op("EI", 1, 4),                   // FB
+    ILLEGAL,                          // FC
+    ILLEGAL,                          // FD
+    op("CP d8", 2, 8),                // FE
+    op("RST $38", 1, 16),             // FF
+];
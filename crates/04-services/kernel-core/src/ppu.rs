@@ -0,0 +1,946 @@
+use crate::bus::{BusScalar, IoRegs};
+use crate::cgb::Cgb;
+
+/// Total CPU cycles per frame on the DMG.
+pub const CYCLES_PER_FRAME: u32 = 70_224;
+/// Visible screen width in pixels.
+pub const SCREEN_WIDTH: usize = 160;
+/// Visible screen height in pixels.
+pub const SCREEN_HEIGHT: usize = 144;
+
+const DOTS_PER_LINE: u32 = 456;
+const MODE2_CYCLES: u32 = 80;
+const VBLANK_START_LINE: u8 = 144;
+const TOTAL_LINES: u8 = 154;
+const DMG_SHADES: [u8; 4] = [0xFF, 0xAA, 0x55, 0x00];
+/// Blank-screen colour as `0xRRGGBB`.
+const WHITE_RGB: u32 = 0xFF_FFFF;
+
+/// Dots spent on the discarded tile fetch at the start of mode 3.
+const MODE3_STARTUP_DOTS: u8 = 6;
+/// Dots spent fetching sprite tile data once the background fetcher is idle.
+const SPRITE_FETCH_DOTS: u8 = 6;
+/// Upper bound on mode 3 length used when flushing non-canonical lanes.
+const MODE3_MAX_DOTS: u32 = DOTS_PER_LINE - MODE2_CYCLES;
+const MAX_SPRITES_PER_LINE: usize = 10;
+
+const LCDC_BG_ENABLE: u8 = 0x01;
+const LCDC_OBJ_ENABLE: u8 = 0x02;
+const LCDC_OBJ_TALL: u8 = 0x04;
+const LCDC_BG_MAP: u8 = 0x08;
+const LCDC_TILE_DATA: u8 = 0x10;
+const LCDC_WINDOW_ENABLE: u8 = 0x20;
+const LCDC_WINDOW_MAP: u8 = 0x40;
+const LCDC_ENABLE: u8 = 0x80;
+const STAT_MODE_MASK: u8 = 0x03;
+const STAT_COINCIDENCE_BIT: u8 = 0x04;
+const STAT_MODE0_INT: u8 = 0x08;
+const STAT_MODE1_INT: u8 = 0x10;
+const STAT_MODE2_INT: u8 = 0x20;
+const STAT_LYC_INT: u8 = 0x40;
+const IF_VBLANK: u8 = 0x01;
+const IF_LCD_STAT: u8 = 0x02;
+
+const OBJ_ATTR_PALETTE: u8 = 0x10;
+const OBJ_ATTR_FLIP_X: u8 = 0x20;
+const OBJ_ATTR_FLIP_Y: u8 = 0x40;
+const OBJ_ATTR_BEHIND_BG: u8 = 0x80;
+/// CGB attribute bits shared by BG map entries and objects.
+const ATTR_CGB_PALETTE: u8 = 0x07;
+const ATTR_VRAM_BANK: u8 = 0x08;
+const BG_ATTR_PRIORITY: u8 = 0x80;
+
+/// Minimal IO surface required to drive PPU timing.
+pub trait PpuIo {
+    /// Reads an IO register by index.
+    fn read_io(&self, idx: usize) -> u8;
+    /// Writes an IO register by index.
+    fn write_io(&mut self, idx: usize, value: u8);
+    /// Reads the interrupt flag register.
+    fn read_if(&self) -> u8;
+    /// Writes the interrupt flag register.
+    fn write_if(&mut self, value: u8);
+}
+
+impl PpuIo for BusScalar {
+    #[inline]
+    fn read_io(&self, idx: usize) -> u8 {
+        self.io.read(idx)
+    }
+
+    #[inline]
+    fn write_io(&mut self, idx: usize, value: u8) {
+        self.io.write(idx, value);
+    }
+
+    #[inline]
+    fn read_if(&self) -> u8 {
+        self.io.if_reg()
+    }
+
+    #[inline]
+    fn write_if(&mut self, value: u8) {
+        self.io.set_if(value);
+    }
+}
+
+/// Read-only per-lane view over the memory the pixel pipeline fetches from.
+pub trait PpuFrameSource {
+    /// Returns the number of lanes with independent video memory.
+    fn ppu_lanes(&self) -> usize {
+        1
+    }
+    /// Returns a shared reference to the IO register block of `lane`.
+    fn ppu_io(&self, lane: usize) -> &IoRegs;
+    /// Returns a shared reference to VRAM of `lane`.
+    fn ppu_vram(&self, lane: usize) -> &[u8; 0x2000];
+    /// Returns a shared reference to OAM of `lane`.
+    fn ppu_oam(&self, lane: usize) -> &[u8; 0xA0];
+    /// Returns the CGB banks and palettes of `lane` when CGB features are active.
+    fn ppu_cgb(&self, lane: usize) -> Option<&Cgb>;
+}
+
+impl PpuFrameSource for BusScalar {
+    #[inline]
+    fn ppu_io(&self, lane: usize) -> &IoRegs {
+        debug_assert_eq!(lane, 0, "scalar bus only exposes lane 0");
+        &self.io
+    }
+
+    #[inline]
+    fn ppu_vram(&self, lane: usize) -> &[u8; 0x2000] {
+        debug_assert_eq!(lane, 0, "scalar bus only exposes lane 0");
+        &self.vram
+    }
+
+    #[inline]
+    fn ppu_oam(&self, lane: usize) -> &[u8; 0xA0] {
+        debug_assert_eq!(lane, 0, "scalar bus only exposes lane 0");
+        &self.oam
+    }
+
+    #[inline]
+    fn ppu_cgb(&self, lane: usize) -> Option<&Cgb> {
+        debug_assert_eq!(lane, 0, "scalar bus only exposes lane 0");
+        self.cgb.enabled.then_some(&self.cgb)
+    }
+}
+
+/// Memory a lane's pixel pipeline fetches from.
+#[derive(Clone, Copy)]
+struct LaneMem<'a> {
+    io: &'a IoRegs,
+    vram: &'a [u8; 0x2000],
+    cgb: Option<&'a Cgb>,
+}
+
+impl<'a> LaneMem<'a> {
+    fn of<I: PpuFrameSource>(bus: &'a I, lane: usize) -> Self {
+        Self {
+            io: bus.ppu_io(lane),
+            vram: bus.ppu_vram(lane),
+            cgb: bus.ppu_cgb(lane),
+        }
+    }
+
+    /// Reads VRAM at `addr` from bank 1 when `attrs` selects it in CGB mode.
+    fn read_vram(&self, attrs: u8, addr: u16) -> u8 {
+        match self.cgb {
+            Some(cgb) if attrs & ATTR_VRAM_BANK != 0 => read_vram(&cgb.vram1, addr),
+            _ => read_vram(self.vram, addr),
+        }
+    }
+}
+
+/// Dot-based PPU with a background/window fetcher and pixel FIFOs.
+///
+/// Mode and LY timing is shared across lanes and driven through [`PpuIo`]; each
+/// lane owns its own pixel pipeline and frame buffer so diverging VRAM renders
+/// independently. Mode 3 ends when the canonical lane (lane 0) has shifted out
+/// all 160 pixels of the line.
+#[derive(Clone, Default)]
+pub struct Ppu {
+    pub(crate) dot_in_line: u32,
+    pub(crate) ly: u8,
+    pub(crate) mode: u8,
+    pub(crate) lyc_equal: bool,
+    pub(crate) frame_ready: bool,
+    pub(crate) lcd_was_on: bool,
+    pub(crate) lanes: Vec<LanePipeline>,
+}
+
+impl Ppu {
+    /// Creates a new PPU instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the PPU by the provided number of CPU cycles.
+    pub fn step<I: PpuIo + PpuFrameSource>(&mut self, mut cycles: u32, bus: &mut I) {
+        if cycles == 0 {
+            return;
+        }
+
+        self.ensure_lanes(bus.ppu_lanes());
+        if !self.ensure_lcd_on(bus) {
+            return;
+        }
+
+        while cycles > 0 {
+            if bus.read_io(IoRegs::LCDC) & LCDC_ENABLE == 0 {
+                self.force_lcd_off(bus);
+                return;
+            }
+
+            self.refresh_coincidence(bus);
+
+            if self.mode == 3 {
+                self.dot_in_line += 1;
+                cycles -= 1;
+                self.tick_pixel_pipelines(bus);
+                continue;
+            }
+
+            let remaining = self.cycles_until_next_event();
+            if remaining == 0 {
+                self.handle_event(bus);
+                continue;
+            }
+
+            let step = remaining.min(cycles);
+            self.dot_in_line = self.dot_in_line.wrapping_add(step);
+            cycles -= step;
+
+            if step == remaining {
+                self.handle_event(bus);
+            }
+        }
+    }
+
+    /// Returns whether a new frame is available.
+    pub fn frame_ready(&self) -> bool {
+        self.frame_ready
+    }
+
+    /// Clears the ready flag after the frame has been consumed.
+    pub fn clear_frame_ready(&mut self) {
+        self.frame_ready = false;
+    }
+
+    /// Resets the PPU counters and clears every lane's frame buffers.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Copies the last completed frame of `lane` into `out_rgba`.
+    ///
+    /// Lanes that have not produced a frame yet read back as a blank screen.
+    pub fn write_frame_rgba(&self, lane: usize, out_rgba: &mut [u8], width: u16, height: u16) {
+        let width_px = usize::from(width);
+        let height_px = usize::from(height);
+        let Some(pipeline) = self.lanes.get(lane) else {
+            fill_solid(out_rgba, WHITE_RGB);
+            return;
+        };
+
+        for y in 0..height_px {
+            for x in 0..width_px {
+                let rgb = if x < SCREEN_WIDTH && y < SCREEN_HEIGHT {
+                    pipeline.front[y * SCREEN_WIDTH + x]
+                } else {
+                    WHITE_RGB
+                };
+                let dst_idx = (y * width_px + x) * 4;
+                write_rgba(&mut out_rgba[dst_idx..dst_idx + 4], rgb);
+            }
+        }
+    }
+
+    fn ensure_lanes(&mut self, lanes: usize) {
+        if self.lanes.len() != lanes {
+            self.lanes.resize_with(lanes, LanePipeline::new);
+        }
+    }
+
+    fn ensure_lcd_on<I: PpuIo>(&mut self, bus: &mut I) -> bool {
+        let lcdc = bus.read_io(IoRegs::LCDC);
+        if lcdc & LCDC_ENABLE == 0 {
+            self.force_lcd_off(bus);
+            return false;
+        }
+
+        if !self.lcd_was_on {
+            self.start_lcd(bus);
+        }
+
+        true
+    }
+
+    fn start_lcd<I: PpuIo>(&mut self, bus: &mut I) {
+        self.lcd_was_on = true;
+        self.dot_in_line = 0;
+        self.ly = 0;
+        self.mode = 0;
+        self.lyc_equal = false;
+        self.frame_ready = false;
+        for pipeline in &mut self.lanes {
+            pipeline.start_frame();
+        }
+
+        bus.write_io(IoRegs::LY, self.ly);
+        self.set_mode(bus, 2);
+    }
+
+    /// Clears every lane's frame buffers to white, as the LCD shows in STOP.
+    pub(crate) fn blank_lanes(&mut self) {
+        for pipeline in &mut self.lanes {
+            pipeline.blank();
+        }
+    }
+
+    fn force_lcd_off<I: PpuIo>(&mut self, bus: &mut I) {
+        if self.lcd_was_on {
+            self.blank_lanes();
+        }
+        self.lcd_was_on = false;
+        self.dot_in_line = 0;
+        self.mode = 0;
+        self.frame_ready = false;
+        self.ly = 0;
+
+        bus.write_io(IoRegs::LY, 0);
+
+        let mut stat = bus.read_io(IoRegs::STAT);
+        let lyc = bus.read_io(IoRegs::LYC);
+        self.lyc_equal = lyc == 0;
+        stat &= !0x07;
+        if self.lyc_equal {
+            stat |= STAT_COINCIDENCE_BIT;
+        }
+        bus.write_io(IoRegs::STAT, stat);
+    }
+
+    fn refresh_coincidence<I: PpuIo>(&mut self, bus: &mut I) {
+        let stat_before = bus.read_io(IoRegs::STAT);
+        let lyc = bus.read_io(IoRegs::LYC);
+        let equal = self.ly == lyc;
+        let prev_equal = self.lyc_equal;
+        self.lyc_equal = equal;
+
+        let mut stat_after = stat_before & !0x07;
+        stat_after |= self.mode & STAT_MODE_MASK;
+        if equal {
+            stat_after |= STAT_COINCIDENCE_BIT;
+        }
+        bus.write_io(IoRegs::STAT, stat_after);
+
+        if !prev_equal && equal && stat_before & STAT_LYC_INT != 0 {
+            self.raise_stat_interrupt(bus);
+        }
+    }
+
+    fn set_mode<I: PpuIo>(&mut self, bus: &mut I, new_mode: u8) {
+        if self.mode == new_mode {
+            return;
+        }
+
+        let stat_before = bus.read_io(IoRegs::STAT);
+        let mut stat_after = stat_before & !0x07;
+        stat_after |= new_mode & STAT_MODE_MASK;
+        if self.lyc_equal {
+            stat_after |= STAT_COINCIDENCE_BIT;
+        }
+
+        bus.write_io(IoRegs::STAT, stat_after);
+        self.mode = new_mode;
+
+        let enabled = match new_mode {
+            0 => stat_before & STAT_MODE0_INT != 0,
+            1 => stat_before & STAT_MODE1_INT != 0,
+            2 => stat_before & STAT_MODE2_INT != 0,
+            _ => false,
+        };
+
+        if enabled {
+            self.raise_stat_interrupt(bus);
+        }
+    }
+
+    fn cycles_until_next_event(&self) -> u32 {
+        match self.mode {
+            0 | 1 => DOTS_PER_LINE.saturating_sub(self.dot_in_line),
+            2 => MODE2_CYCLES.saturating_sub(self.dot_in_line),
+            _ => 0,
+        }
+    }
+
+    fn handle_event<I: PpuIo + PpuFrameSource>(&mut self, bus: &mut I) {
+        match self.mode {
+            2 => {
+                for (lane, pipeline) in self.lanes.iter_mut().enumerate() {
+                    pipeline.start_line(self.ly, bus.ppu_io(lane), bus.ppu_oam(lane));
+                }
+                self.set_mode(bus, 3);
+            }
+            0 | 1 => {
+                if self.dot_in_line >= DOTS_PER_LINE {
+                    self.dot_in_line -= DOTS_PER_LINE;
+                } else {
+                    self.dot_in_line = 0;
+                }
+                self.advance_line(bus);
+            }
+            _ => {}
+        }
+    }
+
+    fn tick_pixel_pipelines<I: PpuIo + PpuFrameSource>(&mut self, bus: &mut I) {
+        let ly = self.ly;
+        for (lane, pipeline) in self.lanes.iter_mut().enumerate() {
+            if !pipeline.line_done() {
+                pipeline.tick(ly, LaneMem::of(bus, lane));
+            }
+        }
+
+        let canonical_done = self.lanes.first().is_none_or(LanePipeline::line_done);
+        if canonical_done || self.dot_in_line >= MODE2_CYCLES + MODE3_MAX_DOTS {
+            for (lane, pipeline) in self.lanes.iter_mut().enumerate().skip(1) {
+                let mut guard = MODE3_MAX_DOTS;
+                while !pipeline.line_done() && guard > 0 {
+                    pipeline.tick(ly, LaneMem::of(bus, lane));
+                    guard -= 1;
+                }
+            }
+            for pipeline in &mut self.lanes {
+                pipeline.finish_line();
+            }
+            self.set_mode(bus, 0);
+        }
+    }
+
+    fn advance_line<I: PpuIo>(&mut self, bus: &mut I) {
+        self.ly = self.ly.wrapping_add(1);
+        if self.ly >= TOTAL_LINES {
+            self.ly = 0;
+        }
+
+        bus.write_io(IoRegs::LY, self.ly);
+        self.refresh_coincidence(bus);
+
+        if self.ly == 0 {
+            self.frame_ready = true;
+            for pipeline in &mut self.lanes {
+                pipeline.start_frame();
+            }
+            self.set_mode(bus, 2);
+        } else if self.ly < VBLANK_START_LINE {
+            self.set_mode(bus, 2);
+        } else if self.ly == VBLANK_START_LINE {
+            for pipeline in &mut self.lanes {
+                pipeline.present();
+            }
+            self.raise_vblank_interrupt(bus);
+            self.set_mode(bus, 1);
+        } else {
+            // Lines 145..153 remain in mode 1.
+        }
+    }
+
+    fn raise_vblank_interrupt<I: PpuIo>(&mut self, bus: &mut I) {
+        let mut if_reg = bus.read_if();
+        if_reg |= IF_VBLANK;
+        bus.write_if(if_reg);
+    }
+
+    fn raise_stat_interrupt<I: PpuIo>(&mut self, bus: &mut I) {
+        let mut if_reg = bus.read_if();
+        if_reg |= IF_LCD_STAT;
+        bus.write_if(if_reg);
+    }
+}
+
+/// Sprite selected during the OAM scan of the current line.
+#[derive(Clone, Copy, Default)]
+struct LineSprite {
+    oam_index: u8,
+    y: u8,
+    x: u8,
+    tile: u8,
+    attrs: u8,
+    fetched: bool,
+}
+
+/// Pixel queued in the background FIFO.
+#[derive(Clone, Copy, Default)]
+struct BgPixel {
+    color: u8,
+    attrs: u8,
+}
+
+/// Pixel queued in the object FIFO.
+#[derive(Clone, Copy, Default)]
+struct ObjPixel {
+    color: u8,
+    attrs: u8,
+    oam_index: u8,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum FetchStep {
+    #[default]
+    Tile,
+    DataLow,
+    DataHigh,
+    Push,
+}
+
+/// Background/window tile fetcher advancing one step every two dots.
+#[derive(Clone, Copy, Default)]
+struct Fetcher {
+    step: FetchStep,
+    half: bool,
+    tile_x: u8,
+    window: bool,
+    tile_id: u8,
+    attrs: u8,
+    lo: u8,
+    hi: u8,
+}
+
+/// Per-lane pixel pipeline: fetcher, FIFOs, sprite buffer and frame buffers.
+#[derive(Clone)]
+pub(crate) struct LanePipeline {
+    back: Box<[u32; SCREEN_WIDTH * SCREEN_HEIGHT]>,
+    front: Box<[u32; SCREEN_WIDTH * SCREEN_HEIGHT]>,
+    fetcher: Fetcher,
+    bg_fifo: [BgPixel; 8],
+    bg_len: u8,
+    obj_fifo: [ObjPixel; 8],
+    obj_len: u8,
+    sprites: [LineSprite; MAX_SPRITES_PER_LINE],
+    sprite_count: u8,
+    sprite_fetch: Option<(usize, u8)>,
+    startup: u8,
+    discard: u8,
+    lx: u8,
+    window_line: u8,
+    window_drawn: bool,
+    wy_triggered: bool,
+}
+
+impl LanePipeline {
+    pub(crate) fn new() -> Self {
+        Self {
+            back: Box::new([WHITE_RGB; SCREEN_WIDTH * SCREEN_HEIGHT]),
+            front: Box::new([WHITE_RGB; SCREEN_WIDTH * SCREEN_HEIGHT]),
+            fetcher: Fetcher::default(),
+            bg_fifo: [BgPixel::default(); 8],
+            bg_len: 0,
+            obj_fifo: [ObjPixel::default(); 8],
+            obj_len: 0,
+            sprites: [LineSprite::default(); MAX_SPRITES_PER_LINE],
+            sprite_count: 0,
+            sprite_fetch: None,
+            startup: 0,
+            discard: 0,
+            lx: SCREEN_WIDTH as u8,
+            window_line: 0,
+            window_drawn: false,
+            wy_triggered: false,
+        }
+    }
+
+    /// Returns the window line counter and WY trigger latch for persistence.
+    pub(crate) fn window_state(&self) -> (u8, bool) {
+        (self.window_line, self.wy_triggered)
+    }
+
+    /// Restores the window line counter and WY trigger latch.
+    pub(crate) fn set_window_state(&mut self, window_line: u8, wy_triggered: bool) {
+        self.window_line = window_line;
+        self.wy_triggered = wy_triggered;
+    }
+
+    fn start_frame(&mut self) {
+        self.window_line = 0;
+        self.wy_triggered = false;
+        self.lx = SCREEN_WIDTH as u8;
+    }
+
+    fn blank(&mut self) {
+        self.back.fill(WHITE_RGB);
+        self.front.fill(WHITE_RGB);
+    }
+
+    fn present(&mut self) {
+        self.front.copy_from_slice(&self.back[..]);
+    }
+
+    fn line_done(&self) -> bool {
+        usize::from(self.lx) >= SCREEN_WIDTH
+    }
+
+    /// Performs the OAM scan and resets the fetcher for a new mode 3 period.
+    fn start_line(&mut self, ly: u8, io: &IoRegs, oam: &[u8; 0xA0]) {
+        let lcdc = io.read(IoRegs::LCDC);
+        if ly == io.read(IoRegs::WY) {
+            self.wy_triggered = true;
+        }
+
+        let height = if lcdc & LCDC_OBJ_TALL != 0 { 16 } else { 8 };
+        let line = u16::from(ly) + 16;
+        self.sprite_count = 0;
+        for (oam_index, entry) in oam.chunks_exact(4).enumerate() {
+            if usize::from(self.sprite_count) == MAX_SPRITES_PER_LINE {
+                break;
+            }
+            let y = u16::from(entry[0]);
+            if line >= y && line < y + height {
+                self.sprites[usize::from(self.sprite_count)] = LineSprite {
+                    oam_index: oam_index as u8,
+                    y: entry[0],
+                    x: entry[1],
+                    tile: entry[2],
+                    attrs: entry[3],
+                    fetched: false,
+                };
+                self.sprite_count += 1;
+            }
+        }
+
+        self.fetcher = Fetcher::default();
+        self.bg_len = 0;
+        self.obj_len = 0;
+        self.sprite_fetch = None;
+        self.startup = MODE3_STARTUP_DOTS;
+        self.discard = io.read(IoRegs::SCX) & 0x07;
+        self.lx = 0;
+        self.window_drawn = false;
+    }
+
+    fn finish_line(&mut self) {
+        if self.window_drawn {
+            self.window_line = self.window_line.wrapping_add(1);
+        }
+    }
+
+    /// Advances the pipeline by a single dot.
+    fn tick(&mut self, ly: u8, mem: LaneMem<'_>) {
+        if self.startup > 0 {
+            self.startup -= 1;
+            return;
+        }
+
+        if self.sprite_fetch.is_some() {
+            if self.fetcher.step != FetchStep::Push {
+                self.tick_fetcher(ly, mem);
+            }
+            self.advance_sprite_fetch(ly, mem);
+            return;
+        }
+
+        self.tick_fetcher(ly, mem);
+        if self.bg_len == 0 {
+            return;
+        }
+
+        if self.discard > 0 {
+            self.pop_bg();
+            self.discard -= 1;
+            return;
+        }
+
+        let io = mem.io;
+        let lcdc = io.read(IoRegs::LCDC);
+        if !self.fetcher.window
+            && lcdc & LCDC_WINDOW_ENABLE != 0
+            && self.wy_triggered
+            && self.lx.saturating_add(7) >= io.read(IoRegs::WX)
+        {
+            self.fetcher = Fetcher {
+                window: true,
+                half: true,
+                ..Fetcher::default()
+            };
+            self.bg_len = 0;
+            self.window_drawn = true;
+            return;
+        }
+
+        if lcdc & LCDC_OBJ_ENABLE != 0 {
+            let pending = self.sprites[..usize::from(self.sprite_count)]
+                .iter()
+                .position(|sprite| !sprite.fetched && sprite.x <= self.lx.saturating_add(8));
+            if let Some(idx) = pending {
+                self.sprite_fetch = Some((idx, 0));
+                self.advance_sprite_fetch(ly, mem);
+                return;
+            }
+        }
+
+        self.shift_pixel(ly, mem);
+    }
+
+    fn tick_fetcher(&mut self, ly: u8, mem: LaneMem<'_>) {
+        if self.fetcher.step == FetchStep::Push {
+            if self.bg_len == 0 {
+                let attrs = self.fetcher.attrs;
+                for (i, slot) in self.bg_fifo.iter_mut().enumerate() {
+                    let bit = if attrs & OBJ_ATTR_FLIP_X != 0 {
+                        i
+                    } else {
+                        7 - i
+                    };
+                    let color =
+                        ((self.fetcher.hi >> bit) & 0x01) << 1 | ((self.fetcher.lo >> bit) & 0x01);
+                    *slot = BgPixel { color, attrs };
+                }
+                self.bg_len = 8;
+                self.fetcher.tile_x = self.fetcher.tile_x.wrapping_add(1);
+                // The next tile fetch starts in the same dot as the push.
+                self.fetcher.step = FetchStep::Tile;
+                self.fetcher.half = true;
+            }
+            return;
+        }
+
+        if !self.fetcher.half {
+            self.fetcher.half = true;
+            return;
+        }
+        self.fetcher.half = false;
+
+        let io = mem.io;
+        let lcdc = io.read(IoRegs::LCDC);
+        match self.fetcher.step {
+            FetchStep::Tile => {
+                let (map_base, row, col) = if self.fetcher.window {
+                    let base = if lcdc & LCDC_WINDOW_MAP != 0 {
+                        0x9C00
+                    } else {
+                        0x9800
+                    };
+                    (base, self.window_line, self.fetcher.tile_x)
+                } else {
+                    let base = if lcdc & LCDC_BG_MAP != 0 {
+                        0x9C00
+                    } else {
+                        0x9800
+                    };
+                    let row = ly.wrapping_add(io.read(IoRegs::SCY));
+                    let col = (io.read(IoRegs::SCX) >> 3).wrapping_add(self.fetcher.tile_x);
+                    (base, row, col)
+                };
+                let map_index = (u16::from(row >> 3) & 31) * 32 + (u16::from(col) & 31);
+                self.fetcher.tile_id = read_vram(mem.vram, map_base + map_index);
+                self.fetcher.attrs = mem
+                    .cgb
+                    .map_or(0, |cgb| read_vram(&cgb.vram1, map_base + map_index));
+                self.fetcher.step = FetchStep::DataLow;
+            }
+            FetchStep::DataLow => {
+                let addr = self.tile_row_addr(ly, io, lcdc);
+                self.fetcher.lo = mem.read_vram(self.fetcher.attrs, addr);
+                self.fetcher.step = FetchStep::DataHigh;
+            }
+            FetchStep::DataHigh => {
+                let addr = self.tile_row_addr(ly, io, lcdc) + 1;
+                self.fetcher.hi = mem.read_vram(self.fetcher.attrs, addr);
+                self.fetcher.step = FetchStep::Push;
+            }
+            FetchStep::Push => {}
+        }
+    }
+
+    /// Counts sprite fetch dots once the background fetcher has paused at its push step.
+    fn advance_sprite_fetch(&mut self, ly: u8, mem: LaneMem<'_>) {
+        let Some((idx, dots)) = self.sprite_fetch else {
+            return;
+        };
+        if self.fetcher.step != FetchStep::Push {
+            return;
+        }
+        let dots = dots + 1;
+        if dots >= SPRITE_FETCH_DOTS {
+            self.sprite_fetch = None;
+            self.merge_sprite(idx, ly, mem);
+        } else {
+            self.sprite_fetch = Some((idx, dots));
+        }
+    }
+
+    fn tile_row_addr(&self, ly: u8, io: &IoRegs, lcdc: u8) -> u16 {
+        let row = if self.fetcher.window {
+            self.window_line
+        } else {
+            ly.wrapping_add(io.read(IoRegs::SCY))
+        };
+        let row = if self.fetcher.attrs & OBJ_ATTR_FLIP_Y != 0 {
+            7 - (row & 0x07)
+        } else {
+            row
+        };
+        let tile_id = self.fetcher.tile_id;
+        let tile_base = if lcdc & LCDC_TILE_DATA != 0 {
+            0x8000u16 + u16::from(tile_id) * 16
+        } else {
+            (0x9000i32 + i32::from(tile_id as i8) * 16) as u16
+        };
+        tile_base + u16::from(row & 0x07) * 2
+    }
+
+    fn merge_sprite(&mut self, idx: usize, ly: u8, mem: LaneMem<'_>) {
+        let sprite = self.sprites[idx];
+        self.sprites[idx].fetched = true;
+
+        let lcdc = mem.io.read(IoRegs::LCDC);
+        let tall = lcdc & LCDC_OBJ_TALL != 0;
+        let height: u8 = if tall { 16 } else { 8 };
+        let mut row = ly.wrapping_add(16).wrapping_sub(sprite.y) & (height - 1);
+        if sprite.attrs & OBJ_ATTR_FLIP_Y != 0 {
+            row = height - 1 - row;
+        }
+        let tile = if tall {
+            sprite.tile & 0xFE
+        } else {
+            sprite.tile
+        };
+        let addr = 0x8000u16 + u16::from(tile) * 16 + u16::from(row) * 2;
+        let lo = mem.read_vram(sprite.attrs, addr);
+        let hi = mem.read_vram(sprite.attrs, addr + 1);
+
+        // Sprites hanging off the left edge lose their leading columns.
+        let skip = usize::from(self.lx.saturating_add(8).saturating_sub(sprite.x));
+        for col in skip..8 {
+            let bit = if sprite.attrs & OBJ_ATTR_FLIP_X != 0 {
+                col
+            } else {
+                7 - col
+            };
+            let color = ((hi >> bit) & 0x01) << 1 | ((lo >> bit) & 0x01);
+            let slot = col - skip;
+            let pixel = ObjPixel {
+                color,
+                attrs: sprite.attrs,
+                oam_index: sprite.oam_index,
+            };
+            if slot >= usize::from(self.obj_len) {
+                self.obj_fifo[slot] = pixel;
+                self.obj_len = slot as u8 + 1;
+                continue;
+            }
+            let current = self.obj_fifo[slot];
+            // Earlier fetched objects keep priority on the DMG; CGB ranks by OAM index.
+            let wins = current.color == 0
+                || (mem.cgb.is_some() && color != 0 && pixel.oam_index < current.oam_index);
+            if wins {
+                self.obj_fifo[slot] = pixel;
+            }
+        }
+    }
+
+    fn pop_bg(&mut self) -> BgPixel {
+        let pixel = self.bg_fifo[8 - usize::from(self.bg_len)];
+        self.bg_len -= 1;
+        pixel
+    }
+
+    fn pop_obj(&mut self) -> Option<ObjPixel> {
+        if self.obj_len == 0 {
+            return None;
+        }
+        let pixel = self.obj_fifo[0];
+        self.obj_fifo.copy_within(1.., 0);
+        self.obj_len -= 1;
+        Some(pixel)
+    }
+
+    fn shift_pixel(&mut self, ly: u8, mem: LaneMem<'_>) {
+        let io = mem.io;
+        let lcdc = io.read(IoRegs::LCDC);
+        let bg = self.pop_bg();
+        let obj = self.pop_obj();
+
+        let rgb = match mem.cgb {
+            Some(cgb) => Self::mix_cgb(cgb, lcdc, bg, obj),
+            None => Self::mix_dmg(io, lcdc, bg.color, obj),
+        };
+
+        let idx = usize::from(ly) * SCREEN_WIDTH + usize::from(self.lx);
+        if let Some(px) = self.back.get_mut(idx) {
+            *px = rgb;
+        }
+        self.lx += 1;
+    }
+
+    fn mix_dmg(io: &IoRegs, lcdc: u8, bg_color: u8, obj: Option<ObjPixel>) -> u32 {
+        let bg_enabled = lcdc & LCDC_BG_ENABLE != 0;
+        let bg_color = if bg_enabled { bg_color } else { 0 };
+        let mut shade = if bg_enabled {
+            palette_shade(io.read(IoRegs::BGP), bg_color)
+        } else {
+            DMG_SHADES[0]
+        };
+
+        if let Some(obj) = obj {
+            let visible = lcdc & LCDC_OBJ_ENABLE != 0
+                && obj.color != 0
+                && (obj.attrs & OBJ_ATTR_BEHIND_BG == 0 || bg_color == 0);
+            if visible {
+                let palette = if obj.attrs & OBJ_ATTR_PALETTE != 0 {
+                    io.read(IoRegs::OBP1)
+                } else {
+                    io.read(IoRegs::OBP0)
+                };
+                shade = palette_shade(palette, obj.color);
+            }
+        }
+        gray_rgb(shade)
+    }
+
+    /// On the CGB, LCDC bit 0 strips BG priority instead of blanking the background.
+    fn mix_cgb(cgb: &Cgb, lcdc: u8, bg: BgPixel, obj: Option<ObjPixel>) -> u32 {
+        let bg_rgb = cgb.bg_rgb(bg.attrs & ATTR_CGB_PALETTE, bg.color);
+        let Some(obj) = obj else {
+            return bg_rgb;
+        };
+        if lcdc & LCDC_OBJ_ENABLE == 0 || obj.color == 0 {
+            return bg_rgb;
+        }
+        let bg_priority = lcdc & LCDC_BG_ENABLE != 0
+            && bg.color != 0
+            && (bg.attrs & BG_ATTR_PRIORITY != 0 || obj.attrs & OBJ_ATTR_BEHIND_BG != 0);
+        if bg_priority {
+            bg_rgb
+        } else {
+            cgb.obj_rgb(obj.attrs & ATTR_CGB_PALETTE, obj.color)
+        }
+    }
+}
+
+fn palette_shade(palette: u8, color: u8) -> u8 {
+    DMG_SHADES[usize::from((palette >> (color * 2)) & 0x03)]
+}
+
+fn gray_rgb(shade: u8) -> u32 {
+    u32::from(shade) * 0x01_0101
+}
+
+fn write_rgba(px: &mut [u8], rgb: u32) {
+    px[0] = (rgb >> 16) as u8;
+    px[1] = (rgb >> 8) as u8;
+    px[2] = rgb as u8;
+    px[3] = 0xFF;
+}
+
+fn fill_solid(out: &mut [u8], rgb: u32) {
+    for px in out.chunks_exact_mut(4) {
+        write_rgba(px, rgb);
+    }
+}
+
+fn read_vram(vram: &[u8; 0x2000], addr: u16) -> u8 {
+    debug_assert!((0x8000..0xA000).contains(&addr));
+    let idx = usize::from(addr - 0x8000);
+    vram[idx]
+}
@@ -0,0 +1,397 @@
+use crate::state::RtcState;
+
+/// CPU cycles per RTC second at the normal-speed clock.
+pub const RTC_CYCLES_PER_SECOND: u32 = 4_194_304;
+/// Length of the RTC trailer appended to battery RAM images.
+pub const RTC_FOOTER_LEN: usize = 48;
+/// Legacy RTC trailer length using a 32-bit timestamp.
+const RTC_FOOTER_LEN_LEGACY: usize = 44;
+
+const DH_DAY_HIGH: u8 = 0x01;
+const DH_HALT: u8 = 0x40;
+const DH_CARRY: u8 = 0x80;
+
+/// Time source driving the MBC3 clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RtcClock {
+    /// Advance from emulated CPU cycles so lockstep, SIMD lanes and replays stay deterministic.
+    #[default]
+    Cycles,
+    /// Advance from host wall-clock time supplied via [`Rtc::sync_wall_clock`].
+    WallClock,
+}
+
+/// MBC3 clock register file as seen by the CPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RtcRegs {
+    /// Seconds counter (`0x08`).
+    pub seconds: u8,
+    /// Minutes counter (`0x09`).
+    pub minutes: u8,
+    /// Hours counter (`0x0A`).
+    pub hours: u8,
+    /// Low eight bits of the day counter (`0x0B`).
+    pub day_low: u8,
+    /// Day counter bit 8, halt flag and day carry (`0x0C`).
+    pub day_high: u8,
+}
+
+impl RtcRegs {
+    fn get(&self, select: u8) -> u8 {
+        match select {
+            0x08 => self.seconds & 0x3F,
+            0x09 => self.minutes & 0x3F,
+            0x0A => self.hours & 0x1F,
+            0x0B => self.day_low,
+            0x0C => self.day_high & (DH_DAY_HIGH | DH_HALT | DH_CARRY),
+            _ => 0xFF,
+        }
+    }
+
+    fn days(&self) -> u16 {
+        u16::from(self.day_low) | (u16::from(self.day_high & DH_DAY_HIGH) << 8)
+    }
+
+    fn set_days(&mut self, days: u16) {
+        self.day_low = days as u8;
+        self.day_high = (self.day_high & !DH_DAY_HIGH) | ((days >> 8) as u8 & DH_DAY_HIGH);
+    }
+
+    fn halted(&self) -> bool {
+        self.day_high & DH_HALT != 0
+    }
+
+    /// Advances one second, honouring the 6/6/5-bit register widths so
+    /// out-of-range values wrap without carrying like the hardware.
+    fn tick_second(&mut self) {
+        self.seconds = (self.seconds + 1) & 0x3F;
+        if self.seconds != 60 {
+            return;
+        }
+        self.seconds = 0;
+        self.minutes = (self.minutes + 1) & 0x3F;
+        if self.minutes != 60 {
+            return;
+        }
+        self.minutes = 0;
+        self.hours = (self.hours + 1) & 0x1F;
+        if self.hours != 24 {
+            return;
+        }
+        self.hours = 0;
+        self.advance_days(1);
+    }
+
+    fn advance_days(&mut self, days: u64) {
+        let total = u64::from(self.days()) + days;
+        if total > 0x1FF {
+            self.day_high |= DH_CARRY;
+        }
+        self.set_days((total & 0x1FF) as u16);
+    }
+
+    fn in_range(&self) -> bool {
+        self.seconds < 60 && self.minutes < 60 && self.hours < 24
+    }
+
+    fn advance_seconds(&mut self, mut secs: u64) {
+        while secs > 0 && !self.in_range() {
+            self.tick_second();
+            secs -= 1;
+        }
+        if secs == 0 {
+            return;
+        }
+        let clock = u64::from(self.seconds)
+            + u64::from(self.minutes) * 60
+            + u64::from(self.hours) * 3_600
+            + secs;
+        self.seconds = (clock % 60) as u8;
+        self.minutes = (clock / 60 % 60) as u8;
+        self.hours = (clock / 3_600 % 24) as u8;
+        self.advance_days(clock / 86_400);
+    }
+}
+
+/// MBC3 real-time clock with latch, halt and day-carry semantics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rtc {
+    clock: RtcClock,
+    live: RtcRegs,
+    latched: RtcRegs,
+    latch_armed: bool,
+    subsecond_cycles: u32,
+    wall_anchor: u64,
+}
+
+impl Rtc {
+    /// Creates a zeroed clock driven by `clock`.
+    pub fn new(clock: RtcClock) -> Self {
+        Self {
+            clock,
+            live: RtcRegs::default(),
+            latched: RtcRegs::default(),
+            latch_armed: false,
+            subsecond_cycles: 0,
+            wall_anchor: 0,
+        }
+    }
+
+    /// Returns the active time source.
+    #[inline]
+    pub fn clock(&self) -> RtcClock {
+        self.clock
+    }
+
+    /// Switches the time source; wall-clock mode re-anchors on the next sync.
+    pub fn set_clock(&mut self, clock: RtcClock) {
+        self.clock = clock;
+        self.wall_anchor = 0;
+    }
+
+    /// Returns the running registers.
+    #[inline]
+    pub fn live(&self) -> RtcRegs {
+        self.live
+    }
+
+    /// Returns the registers captured by the last latch sequence.
+    #[inline]
+    pub fn latched(&self) -> RtcRegs {
+        self.latched
+    }
+
+    /// Advances the clock by `cycles` CPU ticks when cycle-driven.
+    #[inline]
+    pub fn step_cycles(&mut self, cycles: u32) {
+        if self.clock != RtcClock::Cycles || self.live.halted() {
+            return;
+        }
+        self.subsecond_cycles += cycles;
+        while self.subsecond_cycles >= RTC_CYCLES_PER_SECOND {
+            self.subsecond_cycles -= RTC_CYCLES_PER_SECOND;
+            self.live.tick_second();
+        }
+    }
+
+    /// Advances the clock to the host time `unix_secs` when wall-clock driven.
+    ///
+    /// The first sync only anchors the clock so time spent before the cartridge
+    /// was loaded is not counted twice.
+    pub fn sync_wall_clock(&mut self, unix_secs: u64) {
+        if self.clock != RtcClock::WallClock {
+            return;
+        }
+        if self.wall_anchor != 0 && unix_secs > self.wall_anchor && !self.live.halted() {
+            self.live.advance_seconds(unix_secs - self.wall_anchor);
+        }
+        self.wall_anchor = unix_secs;
+    }
+
+    /// Handles a write to the `0x6000–0x7FFF` latch register.
+    pub fn write_latch(&mut self, value: u8) {
+        if self.latch_armed && value == 0x01 {
+            self.latched = self.live;
+        }
+        self.latch_armed = value == 0x00;
+    }
+
+    /// Reads the latched register selected by `select` (`0x08–0x0C`).
+    #[inline]
+    pub fn read(&self, select: u8) -> u8 {
+        self.latched.get(select)
+    }
+
+    /// Writes the running register selected by `select` (`0x08–0x0C`).
+    pub fn write(&mut self, select: u8, value: u8) {
+        match select {
+            0x08 => {
+                self.live.seconds = value & 0x3F;
+                self.subsecond_cycles = 0;
+            }
+            0x09 => self.live.minutes = value & 0x3F,
+            0x0A => self.live.hours = value & 0x1F,
+            0x0B => self.live.day_low = value,
+            0x0C => self.live.day_high = value & (DH_DAY_HIGH | DH_HALT | DH_CARRY),
+            _ => {}
+        }
+    }
+
+    /// Encodes the clock in the common 48-byte trailer layout.
+    ///
+    /// Ten little-endian `u32` words (live then latched S/M/H/DL/DH) followed
+    /// by a little-endian `u64` UNIX timestamp; cycle-driven clocks store `0`.
+    pub fn to_footer(&self) -> [u8; RTC_FOOTER_LEN] {
+        let mut out = [0u8; RTC_FOOTER_LEN];
+        let regs = [self.live, self.latched];
+        for (i, regs) in regs.iter().enumerate() {
+            let fields = [
+                regs.seconds,
+                regs.minutes,
+                regs.hours,
+                regs.day_low,
+                regs.day_high,
+            ];
+            for (j, field) in fields.into_iter().enumerate() {
+                let at = (i * 5 + j) * 4;
+                out[at..at + 4].copy_from_slice(&u32::from(field).to_le_bytes());
+            }
+        }
+        let stamp = match self.clock {
+            RtcClock::Cycles => 0,
+            RtcClock::WallClock => self.wall_anchor,
+        };
+        out[40..48].copy_from_slice(&stamp.to_le_bytes());
+        out
+    }
+
+    /// Restores the clock from a 48- or 44-byte trailer, returning `false` on size mismatch.
+    ///
+    /// Wall-clock mode resumes from the stored timestamp so offline time is applied on the
+    /// next sync; cycle mode ignores it to stay deterministic.
+    pub fn load_footer(&mut self, footer: &[u8]) -> bool {
+        let stamp = match footer.len() {
+            RTC_FOOTER_LEN => u64::from_le_bytes(footer[40..48].try_into().expect("8 bytes")),
+            RTC_FOOTER_LEN_LEGACY => u64::from(u32::from_le_bytes(
+                footer[40..44].try_into().expect("4 bytes"),
+            )),
+            _ => return false,
+        };
+        let word = |idx: usize| footer[idx * 4];
+        let read_regs = |base: usize| RtcRegs {
+            seconds: word(base) & 0x3F,
+            minutes: word(base + 1) & 0x3F,
+            hours: word(base + 2) & 0x1F,
+            day_low: word(base + 3),
+            day_high: word(base + 4) & (DH_DAY_HIGH | DH_HALT | DH_CARRY),
+        };
+        self.live = read_regs(0);
+        self.latched = read_regs(5);
+        self.subsecond_cycles = 0;
+        self.wall_anchor = match self.clock {
+            RtcClock::Cycles => 0,
+            RtcClock::WallClock => stamp,
+        };
+        true
+    }
+
+    /// Captures the full clock state, including the sub-second accumulator.
+    pub(crate) fn state(&self) -> RtcState {
+        RtcState {
+            live: self.live,
+            latched: self.latched,
+            latch_armed: self.latch_armed,
+            subsecond_cycles: self.subsecond_cycles,
+            wall_anchor: self.wall_anchor,
+        }
+    }
+
+    /// Restores a state captured by [`Rtc::state`], keeping the configured time source.
+    pub(crate) fn load_state(&mut self, state: &RtcState) {
+        self.live = state.live;
+        self.latched = state.latched;
+        self.latch_armed = state.latch_armed;
+        self.subsecond_cycles = state.subsecond_cycles % RTC_CYCLES_PER_SECOND;
+        self.wall_anchor = match self.clock {
+            RtcClock::Cycles => 0,
+            RtcClock::WallClock => state.wall_anchor,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latch(rtc: &mut Rtc) {
+        rtc.write_latch(0x00);
+        rtc.write_latch(0x01);
+    }
+
+    #[test]
+    fn cycles_advance_seconds_and_latch_freezes_reads() {
+        let mut rtc = Rtc::new(RtcClock::Cycles);
+        rtc.step_cycles(RTC_CYCLES_PER_SECOND * 2 - 1);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 1);
+
+        rtc.step_cycles(1);
+        assert_eq!(rtc.read(0x08), 1, "reads stay latched");
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 2);
+    }
+
+    #[test]
+    fn halt_stops_counting() {
+        let mut rtc = Rtc::new(RtcClock::Cycles);
+        rtc.write(0x0C, DH_HALT);
+        rtc.step_cycles(RTC_CYCLES_PER_SECOND * 5);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 0);
+    }
+
+    #[test]
+    fn day_overflow_sets_carry() {
+        let mut rtc = Rtc::new(RtcClock::Cycles);
+        rtc.write(0x08, 59);
+        rtc.write(0x09, 59);
+        rtc.write(0x0A, 23);
+        rtc.write(0x0B, 0xFF);
+        rtc.write(0x0C, DH_DAY_HIGH);
+        rtc.step_cycles(RTC_CYCLES_PER_SECOND);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x0B), 0);
+        assert_eq!(rtc.read(0x0C), DH_CARRY);
+    }
+
+    #[test]
+    fn out_of_range_seconds_wrap_without_carry() {
+        let mut regs = RtcRegs {
+            seconds: 63,
+            ..RtcRegs::default()
+        };
+        regs.tick_second();
+        assert_eq!((regs.seconds, regs.minutes), (0, 0));
+    }
+
+    #[test]
+    fn bulk_advance_matches_per_second_ticks() {
+        let mut bulk = RtcRegs {
+            seconds: 61,
+            minutes: 59,
+            hours: 23,
+            day_low: 0xFE,
+            day_high: DH_DAY_HIGH,
+        };
+        let mut stepped = bulk;
+        bulk.advance_seconds(200_000);
+        for _ in 0..200_000 {
+            stepped.tick_second();
+        }
+        assert_eq!(bulk, stepped);
+    }
+
+    #[test]
+    fn wall_clock_applies_elapsed_time_after_anchor() {
+        let mut rtc = Rtc::new(RtcClock::WallClock);
+        rtc.step_cycles(RTC_CYCLES_PER_SECOND * 10);
+        rtc.sync_wall_clock(1_000);
+        rtc.sync_wall_clock(1_000 + 3_661);
+        latch(&mut rtc);
+        assert_eq!((rtc.read(0x0A), rtc.read(0x09), rtc.read(0x08)), (1, 1, 1));
+    }
+
+    #[test]
+    fn footer_roundtrips() {
+        let mut rtc = Rtc::new(RtcClock::WallClock);
+        rtc.write(0x09, 42);
+        rtc.sync_wall_clock(1_700_000_000);
+        latch(&mut rtc);
+        let footer = rtc.to_footer();
+
+        let mut restored = Rtc::new(RtcClock::WallClock);
+        assert!(restored.load_footer(&footer));
+        assert_eq!(restored, rtc);
+        assert!(!restored.load_footer(&footer[..40]));
+    }
+}
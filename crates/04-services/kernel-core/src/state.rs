@@ -1,12 +1,58 @@
+use crate::apu::Apu;
 use crate::bus::BusScalar;
-use crate::core::Core;
+use crate::bus_simd::BusSimd;
+use crate::cgb::{Cgb, PALETTE_RAM_LEN};
+use crate::core::{Core, CoreBus, Model};
+use crate::cpu::{Cpu, Register};
+use crate::dma::{OamDma, OAM_DMA_LEN};
 use crate::exec::{Exec, Scalar};
-use crate::ppu_stub::PpuStub;
+use crate::exec_simd::SimdExec;
+use crate::ppu::{LanePipeline, Ppu};
+use crate::rtc::RtcRegs;
 use crate::timers::{TimaState, Timers};
+use core::simd::{LaneCount, SupportedLaneCount};
+use std::fmt;
 
-/// Snapshot of a scalar core state used for determinism tests and migration.
+/// Leading bytes of every encoded save state.
+pub const STATE_MAGIC: [u8; 4] = *b"GBXS";
+/// Current save-state format version.
+///
+/// Only bumped for incompatible layout changes; new sections and fields appended
+/// to existing sections stay readable by older decoders.
+pub const STATE_VERSION: u16 = 1;
+
+/// Magic, version, header checksum, reserved byte and global checksum.
+const HEADER_LEN: usize = 10;
+const HEADER_CHECKSUM_ADDR: usize = 0x014D;
+const GLOBAL_CHECKSUM_ADDR: usize = 0x014E;
+
+/// Four-byte identifier prefixed to every save-state section.
+pub type SectionTag = [u8; 4];
+
+const TAG_CPU: SectionTag = *b"CPU ";
+const TAG_MEM: SectionTag = *b"MEM ";
+const TAG_TIMERS: SectionTag = *b"TIMR";
+const TAG_PPU: SectionTag = *b"PPU ";
+const TAG_SYSTEM: SectionTag = *b"SYS ";
+const TAG_CART: SectionTag = *b"CART";
+const TAG_SERIAL: SectionTag = *b"SERL";
+const TAG_JOYPAD: SectionTag = *b"JOYP";
+const TAG_APU: SectionTag = *b"APU ";
+const TAG_CGB: SectionTag = *b"CGB ";
+const TAG_OAM_DMA: SectionTag = *b"ODMA";
+
+/// Snapshot of a scalar core state used for determinism tests, migration and save states.
+///
+/// [`CoreState::encode`] produces the on-disk format: a header carrying
+/// [`STATE_MAGIC`], [`STATE_VERSION`] and the ROM checksums, followed by
+/// tagged, length-prefixed sections per subsystem. Decoders skip sections they
+/// do not recognise and ignore trailing bytes inside known sections.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CoreState {
+    /// Checksums of the ROM the state was captured from.
+    pub rom_checksum: RomChecksum,
+    /// Hardware model the core was running as.
+    pub model: Model,
     /// Serialized CPU register file.
     pub regs: RegsScalar,
     /// Copy of work RAM.
@@ -35,6 +81,121 @@ pub struct CoreState {
     pub enable_ime_pending: bool,
     /// Pending HALT bug adjustment flag.
     pub halt_bug: bool,
+    /// STOP indicator.
+    pub stopped: bool,
+    /// Whether the boot ROM overlay was still mapped.
+    pub boot_rom_enabled: bool,
+    /// Cartridge mapper registers, external RAM and clock.
+    pub cart: CartState,
+    /// Serial port transfer state.
+    pub serial: SerialState,
+    /// Joypad select and input latches.
+    pub joypad: JoypadState,
+    /// Audio unit state; buffered host samples are not included.
+    pub apu: Apu,
+    /// CGB banks, palettes and VRAM DMA; left at its default on the DMG.
+    pub cgb: Cgb,
+    /// OAM DMA controller.
+    pub oam_dma: OamDma,
+}
+
+/// Cartridge header and global checksums identifying the ROM a state belongs to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RomChecksum {
+    /// Header checksum at `0x014D`.
+    pub header: u8,
+    /// Big-endian global checksum at `0x014E..=0x014F`.
+    pub global: u16,
+}
+
+impl RomChecksum {
+    /// Reads the checksums from the cartridge header; missing bytes read as zero.
+    pub fn of(rom: &[u8]) -> Self {
+        let byte = |addr: usize| rom.get(addr).copied().unwrap_or(0);
+        Self {
+            header: byte(HEADER_CHECKSUM_ADDR),
+            global: u16::from_be_bytes([
+                byte(GLOBAL_CHECKSUM_ADDR),
+                byte(GLOBAL_CHECKSUM_ADDR + 1),
+            ]),
+        }
+    }
+}
+
+impl fmt::Display for RomChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02X}/{:04X}", self.header, self.global)
+    }
+}
+
+/// Cartridge state persisted alongside the core.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CartState {
+    /// Mapper bank registers.
+    pub mapper: MapperState,
+    /// External RAM contents.
+    pub ram: Vec<u8>,
+    /// MBC3 clock, when the cartridge has one.
+    pub rtc: Option<RtcState>,
+}
+
+/// Mapper registers in a layout shared by every MBC.
+///
+/// MBC1 stores its BANK1 register in `rom_bank` and BANK2 in `ram_bank`;
+/// MBC3 stores its RAM/RTC select in `ram_bank`. Unused fields stay zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MapperState {
+    /// RAM gate register.
+    pub ram_enabled: bool,
+    /// Switchable ROM bank register.
+    pub rom_bank: u16,
+    /// RAM bank (or MBC1 upper bank) register.
+    pub ram_bank: u8,
+    /// MBC1 banking mode.
+    pub mode: bool,
+}
+
+/// MBC3 clock state including the sub-second accumulator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RtcState {
+    /// Running registers.
+    pub live: RtcRegs,
+    /// Registers captured by the last latch sequence.
+    pub latched: RtcRegs,
+    /// Whether a `0x00` latch write is waiting for its `0x01`.
+    pub latch_armed: bool,
+    /// Cycles accumulated towards the next second.
+    pub subsecond_cycles: u32,
+    /// Host timestamp of the last wall-clock sync.
+    pub wall_anchor: u64,
+}
+
+/// Serial port transfer state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SerialState {
+    /// Cycles remaining before the current bit shifts.
+    pub counter: u32,
+    /// Whether a transfer is in progress.
+    pub active: bool,
+    /// Byte captured when the transfer completes.
+    pub pending_data: u8,
+    /// Shift register contents.
+    pub shift_reg: u8,
+    /// Bits left in the active transfer.
+    pub bits_remaining: u8,
+    /// Whether the transfer uses the internal clock.
+    pub internal_clock: bool,
+}
+
+/// Joypad select and input latches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JoypadState {
+    /// JOYP column select bits (P14/P15).
+    pub select: u8,
+    /// Button inputs, active-low.
+    pub buttons: u8,
+    /// D-pad inputs, active-low.
+    pub dpad: u8,
 }
 
 /// Scalar register snapshot.
@@ -77,7 +238,7 @@ pub struct TimersState {
     pub reload_delay: u8,
 }
 
-/// PPU stub persisted state.
+/// PPU persisted state.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PpuState {
     /// Dot position within the active scanline.
@@ -92,6 +253,10 @@ pub struct PpuState {
     pub frame_ready: bool,
     /// Whether the LCD controller was previously enabled.
     pub lcd_was_on: bool,
+    /// Internal window line counter.
+    pub window_line: u8,
+    /// Whether LY matched WY earlier in the current frame.
+    pub wy_triggered: bool,
 }
 
 impl From<&Timers> for TimersState {
@@ -125,8 +290,20 @@ impl Timers {
     }
 }
 
-impl From<&PpuStub> for PpuState {
-    fn from(ppu: &PpuStub) -> Self {
+impl From<&Ppu> for PpuState {
+    fn from(ppu: &Ppu) -> Self {
+        Self::of_lane(ppu, 0)
+    }
+}
+
+impl PpuState {
+    /// Captures the shared PPU counters together with the window state of `lane`.
+    fn of_lane(ppu: &Ppu, lane: usize) -> Self {
+        let (window_line, wy_triggered) = ppu
+            .lanes
+            .get(lane)
+            .map(|lane| lane.window_state())
+            .unwrap_or_default();
         Self {
             dot_in_line: ppu.dot_in_line,
             ly: ppu.ly,
@@ -134,12 +311,17 @@ impl From<&PpuStub> for PpuState {
             lyc_equal: ppu.lyc_equal,
             frame_ready: ppu.frame_ready,
             lcd_was_on: ppu.lcd_was_on,
+            window_line,
+            wy_triggered,
         }
     }
 }
 
-impl PpuStub {
+impl Ppu {
     /// Restores counters from persisted state.
+    ///
+    /// In-flight FIFO contents are not persisted: a state captured during mode 3
+    /// leaves mode 3 on the next dot and resumes rendering from the next line.
     pub fn load_state(&mut self, state: &PpuState) {
         self.dot_in_line = state.dot_in_line;
         self.ly = state.ly;
@@ -147,70 +329,1038 @@ impl PpuStub {
         self.lyc_equal = state.lyc_equal;
         self.frame_ready = state.frame_ready;
         self.lcd_was_on = state.lcd_was_on;
+        if self.lanes.is_empty() {
+            self.lanes.push(LanePipeline::new());
+        }
+        self.lanes[0].set_window_state(state.window_line, state.wy_triggered);
     }
 }
 
 impl From<&Core<Scalar, BusScalar>> for CoreState {
     fn from(core: &Core<Scalar, BusScalar>) -> Self {
+        Self::capture(core, 0, &core.bus)
+    }
+}
+
+impl CoreState {
+    /// Captures `lane` of `core`, whose memory and peripherals live on `bus`.
+    ///
+    /// Timers, PPU counters and the CPU's interrupt/HALT/STOP state are shared
+    /// by every lane of a core and are captured as they are.
+    fn capture<E: Exec, B: CoreBus<E>>(core: &Core<E, B>, lane: usize, bus: &BusScalar) -> Self {
         let cpu = &core.cpu;
+        let byte = |reg| cpu.lane_register(lane, reg) as u8;
         Self {
+            rom_checksum: RomChecksum::of(bus.cart.rom()),
+            model: core.model(),
             regs: RegsScalar {
-                a: <Scalar as Exec>::to_u8(cpu.a),
-                f: cpu.f.to_byte(),
-                b: <Scalar as Exec>::to_u8(cpu.b),
-                c: <Scalar as Exec>::to_u8(cpu.c),
-                d: <Scalar as Exec>::to_u8(cpu.d),
-                e: <Scalar as Exec>::to_u8(cpu.e),
-                h: <Scalar as Exec>::to_u8(cpu.h),
-                l: <Scalar as Exec>::to_u8(cpu.l),
-                sp: <Scalar as Exec>::to_u16(cpu.sp),
-                pc: <Scalar as Exec>::to_u16(cpu.pc),
+                a: byte(Register::A),
+                f: byte(Register::F),
+                b: byte(Register::B),
+                c: byte(Register::C),
+                d: byte(Register::D),
+                e: byte(Register::E),
+                h: byte(Register::H),
+                l: byte(Register::L),
+                sp: cpu.lane_register(lane, Register::Sp),
+                pc: cpu.lane_register(lane, Register::Pc),
             },
-            wram: core.bus.wram.as_ref().to_vec(),
-            vram: core.bus.vram.as_ref().to_vec(),
-            oam: core.bus.oam.as_ref().to_vec(),
-            hram: core.bus.hram.to_vec(),
-            io: core.bus.io.regs().to_vec(),
-            ie: core.bus.ie,
+            wram: bus.wram.as_ref().to_vec(),
+            vram: bus.vram.as_ref().to_vec(),
+            oam: bus.oam.as_ref().to_vec(),
+            hram: bus.hram.to_vec(),
+            io: bus.io.regs().to_vec(),
+            ie: bus.ie,
             timers: TimersState::from(&core.timers),
-            ppu: PpuState::from(&core.ppu),
+            ppu: PpuState::of_lane(&core.ppu, lane),
             cycles_this_frame: core.cycles_this_frame,
             ime: cpu.ime,
             halted: cpu.halted,
             enable_ime_pending: cpu.enable_ime_pending,
             halt_bug: cpu.halt_bug,
+            stopped: cpu.stopped,
+            boot_rom_enabled: bus.boot_rom_enabled(),
+            cart: CartState {
+                mapper: bus.cart.mapper_state(),
+                ram: bus.cart.ram().to_vec(),
+                rtc: bus.cart.rtc_state(),
+            },
+            serial: SerialState {
+                counter: bus.serial_counter,
+                active: bus.serial_active,
+                pending_data: bus.serial_pending_data,
+                shift_reg: bus.serial_shift_reg,
+                bits_remaining: bus.serial_bits_remaining,
+                internal_clock: bus.serial_internal_clock,
+            },
+            joypad: JoypadState {
+                select: bus.joyp_select,
+                buttons: bus.joyp_buttons,
+                dpad: bus.joyp_dpad,
+            },
+            apu: bus.apu.snapshot(),
+            cgb: bus.cgb.clone(),
+            oam_dma: bus.oam_dma,
         }
     }
+
+    /// Restores the registers of `lane` and everything held by its bus.
+    fn restore_lane<E: Exec>(&self, cpu: &mut Cpu<E>, lane: usize, bus: &mut BusScalar) {
+        let regs = &self.regs;
+        for (reg, value) in [
+            (Register::A, u16::from(regs.a)),
+            (Register::F, u16::from(regs.f)),
+            (Register::B, u16::from(regs.b)),
+            (Register::C, u16::from(regs.c)),
+            (Register::D, u16::from(regs.d)),
+            (Register::E, u16::from(regs.e)),
+            (Register::H, u16::from(regs.h)),
+            (Register::L, u16::from(regs.l)),
+            (Register::Sp, regs.sp),
+            (Register::Pc, regs.pc),
+        ] {
+            cpu.set_lane_register(lane, reg, value);
+        }
+
+        bus.wram.copy_from_slice(&self.wram);
+        bus.vram.copy_from_slice(&self.vram);
+        bus.oam.copy_from_slice(&self.oam);
+        bus.hram.copy_from_slice(&self.hram);
+        bus.io.regs_mut().copy_from_slice(&self.io);
+        bus.ie = self.ie;
+        bus.set_boot_rom_enabled(self.boot_rom_enabled);
+
+        let cart = &mut bus.cart;
+        cart.load_mapper_state(&self.cart.mapper);
+        let len = self.cart.ram.len().min(cart.ram().len());
+        cart.ram_mut()[..len].copy_from_slice(&self.cart.ram[..len]);
+        if let Some(rtc) = &self.cart.rtc {
+            cart.load_rtc_state(rtc);
+        }
+
+        bus.serial_counter = self.serial.counter;
+        bus.serial_active = self.serial.active;
+        bus.serial_pending_data = self.serial.pending_data;
+        bus.serial_shift_reg = self.serial.shift_reg;
+        bus.serial_bits_remaining = self.serial.bits_remaining;
+        bus.serial_internal_clock = self.serial.internal_clock;
+
+        bus.joyp_select = self.joypad.select & 0x30;
+        bus.joyp_buttons = self.joypad.buttons & 0x0F;
+        bus.joyp_dpad = self.joypad.dpad & 0x0F;
+
+        bus.apu.restore(&self.apu);
+        bus.cgb = self.cgb.clone();
+        bus.oam_dma = self.oam_dma;
+    }
+
+    /// Restores the timing every lane of a core shares: the interrupt/HALT/STOP
+    /// flags, timers, LCD position and the cycles run in the current frame.
+    fn restore_shared<E: Exec, B: CoreBus<E>>(&self, core: &mut Core<E, B>) {
+        core.cpu.ime = self.ime;
+        core.cpu.halted = self.halted;
+        core.cpu.enable_ime_pending = self.enable_ime_pending;
+        core.cpu.halt_bug = self.halt_bug;
+        core.cpu.stopped = self.stopped;
+
+        core.timers.load_state(&self.timers);
+        core.ppu.load_state(&self.ppu);
+        core.cycles_this_frame = self.cycles_this_frame;
+    }
+
+    /// Returns whether `core` already runs with the shared timing of this state.
+    fn shares_timing_with<E: Exec, B: CoreBus<E>>(&self, core: &Core<E, B>) -> bool {
+        let (cpu, ppu) = (&core.cpu, &core.ppu);
+        self.ime == cpu.ime
+            && self.halted == cpu.halted
+            && self.enable_ime_pending == cpu.enable_ime_pending
+            && self.halt_bug == cpu.halt_bug
+            && self.stopped == cpu.stopped
+            && self.timers == TimersState::from(&core.timers)
+            && self.ppu.dot_in_line == ppu.dot_in_line
+            && self.ppu.ly == ppu.ly
+            && self.ppu.mode == ppu.mode
+            && self.ppu.lyc_equal == ppu.lyc_equal
+            && self.ppu.frame_ready == ppu.frame_ready
+            && self.ppu.lcd_was_on == ppu.lcd_was_on
+            && self.cycles_this_frame == core.cycles_this_frame
+    }
+
+    /// Decodes `bytes` and checks that they belong to the cartridge in `bus`.
+    fn decode_for(bytes: &[u8], bus: &BusScalar) -> Result<Self, StateError> {
+        let state = CoreState::decode(bytes)?;
+        let expected = RomChecksum::of(bus.cart.rom());
+        if state.rom_checksum != expected || state.cart.ram.len() != bus.cart.ram().len() {
+            return Err(StateError::RomMismatch {
+                expected,
+                found: state.rom_checksum,
+            });
+        }
+        Ok(state)
+    }
 }
 
 impl Core<Scalar, BusScalar> {
     /// Loads a previously captured state into the core.
+    ///
+    /// The state must come from the inserted ROM; [`Core::load_state_bytes`]
+    /// checks this before loading. External RAM is copied up to the shorter of
+    /// the two sizes.
     pub fn load_state(&mut self, state: &CoreState) {
-        self.cpu.a = <Scalar as Exec>::from_u8(state.regs.a);
-        self.cpu.f.from_byte(state.regs.f);
-        self.cpu.b = <Scalar as Exec>::from_u8(state.regs.b);
-        self.cpu.c = <Scalar as Exec>::from_u8(state.regs.c);
-        self.cpu.d = <Scalar as Exec>::from_u8(state.regs.d);
-        self.cpu.e = <Scalar as Exec>::from_u8(state.regs.e);
-        self.cpu.h = <Scalar as Exec>::from_u8(state.regs.h);
-        self.cpu.l = <Scalar as Exec>::from_u8(state.regs.l);
-        self.cpu.sp = <Scalar as Exec>::from_u16(state.regs.sp);
-        self.cpu.pc = <Scalar as Exec>::from_u16(state.regs.pc);
-        self.cpu.ime = state.ime;
-        self.cpu.halted = state.halted;
-        self.cpu.enable_ime_pending = state.enable_ime_pending;
-        self.cpu.halt_bug = state.halt_bug;
-
-        self.bus.wram.copy_from_slice(&state.wram);
-        self.bus.vram.copy_from_slice(&state.vram);
-        self.bus.oam.copy_from_slice(&state.oam);
-        self.bus.hram.copy_from_slice(&state.hram);
-        self.bus.io.regs_mut().copy_from_slice(&state.io);
-        self.bus.ie = state.ie;
-        self.bus.joyp_select = self.bus.io.joyp() & 0x30;
-
-        self.timers.load_state(&state.timers);
-        self.ppu.load_state(&state.ppu);
-        self.cycles_this_frame = state.cycles_this_frame;
+        self.set_model(state.model);
+        state.restore_lane(&mut self.cpu, 0, &mut self.bus);
+        state.restore_shared(self);
+    }
+
+    /// Captures the core in the versioned binary save-state format.
+    pub fn save_state_bytes(&self) -> Vec<u8> {
+        CoreState::from(self).encode()
+    }
+
+    /// Decodes a save state and loads it, rejecting states taken from another ROM.
+    pub fn load_state_bytes(&mut self, bytes: &[u8]) -> Result<(), StateError> {
+        let state = CoreState::decode_for(bytes, &self.bus)?;
+        self.load_state(&state);
+        Ok(())
+    }
+}
+
+impl<const LANES: usize> Core<SimdExec<LANES>, BusSimd<LANES>>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    /// Captures a single lane as a standalone core state.
+    ///
+    /// Timers, PPU counters and the interrupt/HALT/STOP flags are shared by the
+    /// lanes running in the group; a split-off lane captures its own.
+    pub fn lane_state(&self, lane: usize) -> CoreState {
+        match self.split.get(lane) {
+            Some(split) => CoreState::capture(split, 0, self.bus.lane(lane)),
+            None => CoreState::capture(self, lane, self.bus.lane(lane)),
+        }
+    }
+
+    /// Loads a state into a single lane, leaving the other lanes untouched.
+    ///
+    /// When the state's timers, PPU position or interrupt/HALT/STOP flags differ
+    /// from the group's, the lane continues on its own scalar core with the
+    /// state's timing and merges back once it reconverges. Loading lane 0, which
+    /// drives the group's timing, first splits off the other lanes so they keep
+    /// theirs.
+    pub fn load_lane_state(&mut self, lane: usize, state: &CoreState) {
+        self.rejoin_lane_state(lane, state);
+        if state.shares_timing_with(self) {
+            return;
+        }
+        if lane == 0 {
+            for other in 1..LANES {
+                if self.split.get(other).is_none() {
+                    self.split_lane(other);
+                }
+            }
+            state.restore_shared(self);
+        } else {
+            state.restore_shared(self.split_lane(lane));
+        }
+    }
+
+    /// Restores the lane-owned part of a state: registers, memory, cartridge and
+    /// bus peripherals.
+    ///
+    /// A split-off lane rejoins the group first, so the lane picks up the group's
+    /// shared timers, PPU position and interrupt/HALT/STOP flags.
+    pub(crate) fn rejoin_lane_state(&mut self, lane: usize, state: &CoreState) {
+        self.split.remove(lane);
+        state.restore_lane(&mut self.cpu, lane, self.bus.lane_mut(lane));
+        if let Some(pipeline) = self.ppu.lanes.get_mut(lane) {
+            pipeline.set_window_state(state.ppu.window_line, state.ppu.wy_triggered);
+        }
+    }
+
+    /// Captures a lane in the versioned binary save-state format.
+    pub fn save_lane_state_bytes(&self, lane: usize) -> Vec<u8> {
+        self.lane_state(lane).encode()
+    }
+
+    /// Decodes a save state into a lane, rejecting states taken from another ROM
+    /// or captured on a different hardware model than the group runs.
+    pub fn load_lane_state_bytes(&mut self, lane: usize, bytes: &[u8]) -> Result<(), StateError> {
+        let state = self.decode_lane_state(lane, bytes)?;
+        self.load_lane_state(lane, &state);
+        Ok(())
+    }
+
+    /// Decodes a save state into every lane, together with the timing they share.
+    ///
+    /// Split-off lanes rejoin the group, which then runs as one copy of the state.
+    pub fn load_group_state_bytes(&mut self, bytes: &[u8]) -> Result<(), StateError> {
+        let state = self.decode_lane_state(0, bytes)?;
+        for lane in 0..LANES {
+            self.rejoin_lane_state(lane, &state);
+        }
+        state.restore_shared(self);
+        Ok(())
+    }
+
+    fn decode_lane_state(&self, lane: usize, bytes: &[u8]) -> Result<CoreState, StateError> {
+        let state = CoreState::decode_for(bytes, self.bus.lane(lane))?;
+        if state.model != self.model() {
+            return Err(StateError::ModelMismatch {
+                expected: self.model(),
+                found: state.model,
+            });
+        }
+        Ok(state)
+    }
+}
+
+/// Errors raised while decoding a save state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The buffer does not start with [`STATE_MAGIC`].
+    BadMagic,
+    /// The state uses a format version this build cannot read.
+    UnsupportedVersion(u16),
+    /// The state was captured from a different ROM.
+    RomMismatch {
+        /// Checksums of the inserted ROM.
+        expected: RomChecksum,
+        /// Checksums recorded in the state.
+        found: RomChecksum,
+    },
+    /// The buffer ends inside the header or a section header.
+    Truncated,
+    /// A required section is absent.
+    MissingSection(SectionTag),
+    /// A section is shorter than its fields or holds an invalid value.
+    InvalidSection(SectionTag),
+    /// The state was captured on another hardware model than the lane runs.
+    ModelMismatch {
+        /// Model of the receiving core.
+        expected: Model,
+        /// Model recorded in the state.
+        found: Model,
+    },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::BadMagic => write!(f, "not a GBX save state"),
+            StateError::UnsupportedVersion(version) => {
+                write!(f, "unsupported save-state version {version}")
+            }
+            StateError::RomMismatch { expected, found } => write!(
+                f,
+                "save state belongs to ROM {found}, inserted ROM is {expected}"
+            ),
+            StateError::Truncated => write!(f, "save state is truncated"),
+            StateError::MissingSection(tag) => {
+                write!(f, "save state lacks section {:?}", tag_name(tag))
+            }
+            StateError::InvalidSection(tag) => {
+                write!(f, "save state section {:?} is malformed", tag_name(tag))
+            }
+            StateError::ModelMismatch { expected, found } => write!(
+                f,
+                "save state was captured on {found:?} hardware, core runs {expected:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+fn tag_name(tag: &SectionTag) -> String {
+    String::from_utf8_lossy(tag).trim_end().to_string()
+}
+
+impl CoreState {
+    /// Encodes the state in the versioned binary save-state format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(0x4000 + self.cart.ram.len());
+        out.extend_from_slice(&STATE_MAGIC);
+        out.extend_from_slice(&STATE_VERSION.to_le_bytes());
+        out.push(self.rom_checksum.header);
+        out.push(0);
+        out.extend_from_slice(&self.rom_checksum.global.to_le_bytes());
+
+        write_section(&mut out, TAG_SYSTEM, |w| {
+            w.u8(match self.model {
+                Model::Dmg => 0,
+                Model::Cgb => 1,
+            });
+            w.bool(self.boot_rom_enabled);
+            w.u32(self.cycles_this_frame);
+        });
+        write_section(&mut out, TAG_CPU, |w| {
+            let r = &self.regs;
+            for reg in [r.a, r.f, r.b, r.c, r.d, r.e, r.h, r.l] {
+                w.u8(reg);
+            }
+            w.u16(r.sp);
+            w.u16(r.pc);
+            w.bool(self.ime);
+            w.bool(self.halted);
+            w.bool(self.enable_ime_pending);
+            w.bool(self.halt_bug);
+            w.bool(self.stopped);
+        });
+        write_section(&mut out, TAG_MEM, |w| {
+            w.bytes(&self.wram);
+            w.bytes(&self.vram);
+            w.bytes(&self.oam);
+            w.bytes(&self.hram);
+            w.bytes(&self.io);
+            w.u8(self.ie);
+        });
+        write_section(&mut out, TAG_TIMERS, |w| {
+            let t = &self.timers;
+            w.u16(t.div_counter);
+            w.bool(t.timer_input);
+            w.u8(t.tima_state);
+            w.u8(t.pending_reload_value);
+            w.u8(t.reload_delay);
+        });
+        write_section(&mut out, TAG_PPU, |w| {
+            let p = &self.ppu;
+            w.u32(p.dot_in_line);
+            w.u8(p.ly);
+            w.u8(p.mode);
+            w.bool(p.lyc_equal);
+            w.bool(p.frame_ready);
+            w.bool(p.lcd_was_on);
+            w.u8(p.window_line);
+            w.bool(p.wy_triggered);
+        });
+        write_section(&mut out, TAG_CART, |w| {
+            let m = &self.cart.mapper;
+            w.bool(m.ram_enabled);
+            w.u16(m.rom_bank);
+            w.u8(m.ram_bank);
+            w.bool(m.mode);
+            w.bytes(&self.cart.ram);
+            w.bool(self.cart.rtc.is_some());
+            if let Some(rtc) = &self.cart.rtc {
+                write_rtc_regs(w, &rtc.live);
+                write_rtc_regs(w, &rtc.latched);
+                w.bool(rtc.latch_armed);
+                w.u32(rtc.subsecond_cycles);
+                w.u64(rtc.wall_anchor);
+            }
+        });
+        write_section(&mut out, TAG_SERIAL, |w| {
+            let s = &self.serial;
+            w.u32(s.counter);
+            w.bool(s.active);
+            w.u8(s.pending_data);
+            w.u8(s.shift_reg);
+            w.u8(s.bits_remaining);
+            w.bool(s.internal_clock);
+        });
+        write_section(&mut out, TAG_JOYPAD, |w| {
+            w.u8(self.joypad.select);
+            w.u8(self.joypad.buttons);
+            w.u8(self.joypad.dpad);
+        });
+        write_section(&mut out, TAG_APU, |w| self.apu.write_state(w));
+        write_section(&mut out, TAG_OAM_DMA, |w| {
+            let d = &self.oam_dma;
+            w.u16(d.source);
+            w.u8(d.index);
+            w.bool(d.active);
+            w.u32(d.countdown);
+            w.bool(d.blocking);
+        });
+        if self.model == Model::Cgb {
+            write_section(&mut out, TAG_CGB, |w| {
+                let c = &self.cgb;
+                w.bool(c.enabled);
+                w.bytes(c.vram1.as_ref());
+                w.bytes(c.wram_banks.as_ref());
+                w.u8(c.vbk);
+                w.u8(c.svbk);
+                w.bool(c.speed_prepare);
+                w.bool(c.double_speed);
+                w.bytes(&c.bg_palette);
+                w.bytes(&c.obj_palette);
+                w.u8(c.bcps);
+                w.u8(c.ocps);
+                w.u16(c.hdma_src);
+                w.u16(c.hdma_dst);
+                w.u8(c.hdma_remaining);
+                w.bool(c.hdma_active);
+                w.u32(c.dma_stall);
+            });
+        }
+        out
+    }
+
+    /// Decodes a state produced by [`CoreState::encode`].
+    ///
+    /// Unknown sections are skipped so states written by newer builds load as
+    /// long as the format version matches. The CGB section is optional and
+    /// defaults to a disabled [`Cgb`]; a missing OAM DMA section leaves the
+    /// controller idle.
+    pub fn decode(bytes: &[u8]) -> Result<Self, StateError> {
+        if bytes.get(..STATE_MAGIC.len()) != Some(&STATE_MAGIC[..]) {
+            return Err(StateError::BadMagic);
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(StateError::Truncated);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version == 0 || version > STATE_VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+        let rom_checksum = RomChecksum {
+            header: bytes[6],
+            global: u16::from_le_bytes([bytes[8], bytes[9]]),
+        };
+
+        let mut sections: Vec<(SectionTag, &[u8])> = Vec::new();
+        let mut rest = &bytes[HEADER_LEN..];
+        while !rest.is_empty() {
+            if rest.len() < 8 {
+                return Err(StateError::Truncated);
+            }
+            let tag: SectionTag = rest[..4].try_into().expect("4 bytes");
+            let len = u32::from_le_bytes(rest[4..8].try_into().expect("4 bytes")) as usize;
+            let payload = rest[8..].get(..len).ok_or(StateError::Truncated)?;
+            if !sections.iter().any(|(seen, _)| *seen == tag) {
+                sections.push((tag, payload));
+            }
+            rest = &rest[8 + len..];
+        }
+        let find = |tag: SectionTag| {
+            sections
+                .iter()
+                .find(|(seen, _)| *seen == tag)
+                .map(|(_, payload)| SectionReader::new(tag, payload))
+        };
+        let section = |tag: SectionTag| find(tag).ok_or(StateError::MissingSection(tag));
+
+        let mut r = section(TAG_SYSTEM)?;
+        let model = match r.u8()? {
+            0 => Model::Dmg,
+            1 => Model::Cgb,
+            _ => return Err(r.invalid()),
+        };
+        let boot_rom_enabled = r.bool()?;
+        let cycles_this_frame = r.u32()?;
+
+        let mut r = section(TAG_CPU)?;
+        let regs = RegsScalar {
+            a: r.u8()?,
+            f: r.u8()?,
+            b: r.u8()?,
+            c: r.u8()?,
+            d: r.u8()?,
+            e: r.u8()?,
+            h: r.u8()?,
+            l: r.u8()?,
+            sp: r.u16()?,
+            pc: r.u16()?,
+        };
+        let ime = r.bool()?;
+        let halted = r.bool()?;
+        let enable_ime_pending = r.bool()?;
+        let halt_bug = r.bool()?;
+        let stopped = r.bool_or_default()?;
+
+        let mut r = section(TAG_MEM)?;
+        let wram = r.array::<0x2000>()?.to_vec();
+        let vram = r.array::<0x2000>()?.to_vec();
+        let oam = r.array::<0xA0>()?.to_vec();
+        let hram = r.array::<0x7F>()?.to_vec();
+        let io = r.array::<0x80>()?.to_vec();
+        let ie = r.u8()?;
+
+        let mut r = section(TAG_TIMERS)?;
+        let timers = TimersState {
+            div_counter: r.u16()?,
+            timer_input: r.bool()?,
+            tima_state: r.u8()?,
+            pending_reload_value: r.u8()?,
+            reload_delay: r.u8()?,
+        };
+
+        let mut r = section(TAG_PPU)?;
+        let ppu = PpuState {
+            dot_in_line: r.u32()?,
+            ly: r.u8()?,
+            mode: r.u8()? & 0x03,
+            lyc_equal: r.bool()?,
+            frame_ready: r.bool()?,
+            lcd_was_on: r.bool()?,
+            window_line: r.u8()?,
+            wy_triggered: r.bool()?,
+        };
+
+        let mut r = section(TAG_CART)?;
+        let mapper = MapperState {
+            ram_enabled: r.bool()?,
+            rom_bank: r.u16()?,
+            ram_bank: r.u8()?,
+            mode: r.bool()?,
+        };
+        let ram = r.bytes()?.to_vec();
+        let rtc = if r.bool()? {
+            Some(RtcState {
+                live: read_rtc_regs(&mut r)?,
+                latched: read_rtc_regs(&mut r)?,
+                latch_armed: r.bool()?,
+                subsecond_cycles: r.u32()?,
+                wall_anchor: r.u64()?,
+            })
+        } else {
+            None
+        };
+
+        let mut r = section(TAG_SERIAL)?;
+        let serial = SerialState {
+            counter: r.u32()?,
+            active: r.bool()?,
+            pending_data: r.u8()?,
+            shift_reg: r.u8()?,
+            bits_remaining: r.u8()?,
+            internal_clock: r.bool()?,
+        };
+
+        let mut r = section(TAG_JOYPAD)?;
+        let joypad = JoypadState {
+            select: r.u8()?,
+            buttons: r.u8()?,
+            dpad: r.u8()?,
+        };
+
+        let apu = Apu::read_state(&mut section(TAG_APU)?)?;
+
+        let cgb = match find(TAG_CGB) {
+            Some(mut r) => Cgb {
+                enabled: r.bool()?,
+                vram1: Box::new(r.array()?),
+                wram_banks: Box::new(r.array()?),
+                vbk: r.u8()? & 0x01,
+                svbk: r.u8()? & 0x07,
+                speed_prepare: r.bool()?,
+                double_speed: r.bool()?,
+                bg_palette: r.array::<PALETTE_RAM_LEN>()?,
+                obj_palette: r.array::<PALETTE_RAM_LEN>()?,
+                bcps: r.u8()?,
+                ocps: r.u8()?,
+                hdma_src: r.u16()?,
+                hdma_dst: r.u16()?,
+                hdma_remaining: r.u8()? & 0x7F,
+                hdma_active: r.bool()?,
+                dma_stall: r.u32()?,
+            },
+            None => Cgb::default(),
+        };
+
+        let oam_dma = match find(TAG_OAM_DMA) {
+            Some(mut r) => {
+                let dma = OamDma {
+                    source: r.u16()?,
+                    index: r.u8()?,
+                    active: r.bool()?,
+                    countdown: r.u32()?,
+                    blocking: r.bool()?,
+                };
+                if dma.index >= OAM_DMA_LEN && dma.active {
+                    return Err(r.invalid());
+                }
+                dma
+            }
+            None => OamDma::default(),
+        };
+
+        Ok(Self {
+            rom_checksum,
+            model,
+            regs,
+            wram,
+            vram,
+            oam,
+            hram,
+            io,
+            ie,
+            timers,
+            ppu,
+            cycles_this_frame,
+            ime,
+            halted,
+            enable_ime_pending,
+            halt_bug,
+            stopped,
+            boot_rom_enabled,
+            cart: CartState { mapper, ram, rtc },
+            serial,
+            joypad,
+            apu,
+            cgb,
+            oam_dma,
+        })
+    }
+}
+
+fn write_section(out: &mut Vec<u8>, tag: SectionTag, fill: impl FnOnce(&mut SectionWriter)) {
+    let mut w = SectionWriter::default();
+    fill(&mut w);
+    out.extend_from_slice(&tag);
+    out.extend_from_slice(&(w.buf.len() as u32).to_le_bytes());
+    out.extend_from_slice(&w.buf);
+}
+
+fn write_rtc_regs(w: &mut SectionWriter, regs: &RtcRegs) {
+    for field in [
+        regs.seconds,
+        regs.minutes,
+        regs.hours,
+        regs.day_low,
+        regs.day_high,
+    ] {
+        w.u8(field);
+    }
+}
+
+fn read_rtc_regs(r: &mut SectionReader<'_>) -> Result<RtcRegs, StateError> {
+    Ok(RtcRegs {
+        seconds: r.u8()?,
+        minutes: r.u8()?,
+        hours: r.u8()?,
+        day_low: r.u8()?,
+        day_high: r.u8()?,
+    })
+}
+
+/// Little-endian field writer for one save-state section.
+#[derive(Default)]
+pub(crate) struct SectionWriter {
+    buf: Vec<u8>,
+}
+
+impl SectionWriter {
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    pub(crate) fn bool(&mut self, value: bool) {
+        self.buf.push(u8::from(value));
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a `u32` length prefix followed by the bytes.
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        self.u32(bytes.len() as u32);
+        self.buf.extend_from_slice(bytes);
+    }
+}
+
+/// Little-endian field reader over one save-state section.
+///
+/// Reading past the end reports [`StateError::InvalidSection`]; unread
+/// trailing bytes are ignored so sections can grow new fields.
+pub(crate) struct SectionReader<'a> {
+    tag: SectionTag,
+    bytes: &'a [u8],
+}
+
+impl<'a> SectionReader<'a> {
+    fn new(tag: SectionTag, bytes: &'a [u8]) -> Self {
+        Self { tag, bytes }
+    }
+
+    fn invalid(&self) -> StateError {
+        StateError::InvalidSection(self.tag)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        if self.bytes.len() < len {
+            return Err(self.invalid());
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn le<const N: usize>(&mut self) -> Result<[u8; N], StateError> {
+        Ok(self.take(N)?.try_into().expect("exact length"))
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, StateError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Reads a `bool` appended after the first format release, defaulting to
+    /// `false` when an older state ends before it.
+    pub(crate) fn bool_or_default(&mut self) -> Result<bool, StateError> {
+        if self.bytes.is_empty() {
+            Ok(false)
+        } else {
+            self.bool()
+        }
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, StateError> {
+        Ok(u16::from_le_bytes(self.le()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, StateError> {
+        Ok(u32::from_le_bytes(self.le()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, StateError> {
+        Ok(u64::from_le_bytes(self.le()?))
+    }
+
+    pub(crate) fn i64(&mut self) -> Result<i64, StateError> {
+        Ok(i64::from_le_bytes(self.le()?))
+    }
+
+    /// Reads a `u32` length-prefixed byte string.
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8], StateError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Reads a length-prefixed byte string that must be exactly `N` bytes long.
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], StateError> {
+        self.bytes()?.try_into().map_err(|_| self.invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    type ScalarCore = Core<Scalar, BusScalar>;
+    type SimdCore2 = Core<SimdExec<2>, BusSimd<2>>;
+
+    const PROGRAM: &[u8] = &[
+        0x3E, 0x0A, 0xEA, 0x00, 0x00, // LD A,0x0A; LD (0x0000),A — enable RAM
+        0x3E, 0x03, 0xEA, 0x00, 0x20, // LD A,3; LD (0x2000),A — ROM bank 3
+        0x3E, 0x01, 0xEA, 0x00, 0x40, // LD A,1; LD (0x4000),A — RAM bank 1
+        0x3E, 0x80, 0xE0, 0x26, // NR52: power on
+        0x3E, 0xF0, 0xE0, 0x12, // NR12: volume 15
+        0x3E, 0x87, 0xE0, 0x14, // NR14: trigger channel 1
+        0x3E, 0x55, 0xE0, 0x01, // SB = 0x55
+        0x3E, 0x81, 0xE0, 0x02, // SC: start internal-clock transfer
+        0x21, 0x00, 0xA0, 0x34, // loop: INC (0xA000)
+        0x21, 0x00, 0xC0, 0x34, // INC (0xC000)
+        0x18, 0xF6, // JR loop
+    ];
+
+    fn mbc3_rom() -> Arc<[u8]> {
+        let mut rom = vec![0u8; 0x10000];
+        rom[0x0100..0x0100 + PROGRAM.len()].copy_from_slice(PROGRAM);
+        rom[0x0147] = 0x10; // MBC3 + timer + RAM + battery
+        rom[0x0148] = 0x01;
+        rom[0x0149] = 0x03;
+        rom[0x014D] = 0x42;
+        rom[0x014E] = 0x12;
+        rom[0x014F] = 0x34;
+        Arc::from(rom.into_boxed_slice())
+    }
+
+    fn running_core(rom: Arc<[u8]>, cycles: u32) -> ScalarCore {
+        let mut core = ScalarCore::from_rom(rom);
+        core.step_cycles(cycles);
+        core
+    }
+
+    #[test]
+    fn encode_decode_round_trips_every_section() {
+        let core = running_core(mbc3_rom(), 200_000);
+        let state = CoreState::from(&core);
+        assert_eq!(state.cart.mapper.rom_bank, 3);
+        assert!(state.cart.rtc.is_some());
+        assert!(state.apu.read(0x26) & 0x80 != 0);
+
+        let decoded = CoreState::decode(&state.encode()).expect("decodes");
+        assert_eq!(decoded, state);
+    }
+
+    #[test]
+    fn restored_core_continues_identically() {
+        let rom = mbc3_rom();
+        let mut original = running_core(Arc::clone(&rom), 2_000);
+        let bytes = original.save_state_bytes();
+
+        let mut restored = ScalarCore::from_rom(rom);
+        restored.load_state_bytes(&bytes).expect("loads");
+        assert_eq!(CoreState::from(&restored), CoreState::from(&original));
+
+        original.step_cycles(300_000);
+        restored.step_cycles(300_000);
+        assert_eq!(CoreState::from(&restored), CoreState::from(&original));
+        assert_eq!(restored.bus.take_serial(), original.bus.take_serial());
+    }
+
+    #[test]
+    fn cgb_state_round_trips() {
+        let mut rom = vec![0u8; 0x8000];
+        rom[0x0143] = 0x80;
+        rom[0x0100..0x0102].copy_from_slice(&[0x18, 0xFE]); // JR -2
+        let rom: Arc<[u8]> = Arc::from(rom.into_boxed_slice());
+        let mut core = Core::new(
+            BusScalar::new(Arc::clone(&rom), None),
+            Default::default(),
+            Model::Cgb,
+        );
+        core.reset_post_boot(Model::Cgb);
+        core.bus.cgb.svbk = 3;
+        core.bus.cgb.wram_banks[0x1234] = 0xAB;
+        core.bus.cgb.bg_palette[5] = 0x1F;
+
+        let bytes = core.save_state_bytes();
+        let mut restored = ScalarCore::from_rom(rom);
+        restored.load_state_bytes(&bytes).expect("loads");
+        assert_eq!(restored.model(), Model::Cgb);
+        assert_eq!(restored.bus.cgb, core.bus.cgb);
+    }
+
+    #[test]
+    fn unknown_sections_are_skipped() {
+        let state = CoreState::from(&running_core(mbc3_rom(), 10_000));
+        let encoded = state.encode();
+        let mut bytes = encoded[..HEADER_LEN].to_vec();
+        bytes.extend_from_slice(b"XTRA");
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+        bytes.extend_from_slice(&encoded[HEADER_LEN..]);
+
+        assert_eq!(CoreState::decode(&bytes).expect("decodes"), state);
+    }
+
+    #[test]
+    fn rejects_malformed_and_foreign_states() {
+        let rom = mbc3_rom();
+        let bytes = running_core(Arc::clone(&rom), 10_000).save_state_bytes();
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(CoreState::decode(&bad), Err(StateError::BadMagic));
+
+        let mut bad = bytes.clone();
+        bad[4..6].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            CoreState::decode(&bad),
+            Err(StateError::UnsupportedVersion(STATE_VERSION + 1))
+        );
+
+        assert_eq!(
+            CoreState::decode(&bytes[..bytes.len() - 1]),
+            Err(StateError::Truncated)
+        );
+        assert_eq!(
+            CoreState::decode(&bytes[..HEADER_LEN]),
+            Err(StateError::MissingSection(TAG_SYSTEM))
+        );
+
+        let mut other = rom.to_vec();
+        other[0x014D] ^= 0xFF;
+        let mut core = ScalarCore::from_rom(Arc::from(other.into_boxed_slice()));
+        assert!(matches!(
+            core.load_state_bytes(&bytes),
+            Err(StateError::RomMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn simd_lane_loads_scalar_state_and_captures_it_back() {
+        let rom = mbc3_rom();
+        let scalar = running_core(Arc::clone(&rom), 2_000);
+        let expected = CoreState::from(&scalar);
+
+        let mut simd = SimdCore2::from_rom(rom);
+        let untouched = simd.lane_state(0);
+        simd.load_lane_state_bytes(1, &scalar.save_state_bytes())
+            .expect("loads");
+
+        assert_eq!(simd.lane_state(1), expected);
+        assert_eq!(simd.lane_state(0), untouched);
+        assert_eq!(
+            simd.divergence().split_mask,
+            0b10,
+            "lane 1 keeps its own timing"
+        );
+    }
+
+    #[test]
+    fn simd_lane_continues_like_the_scalar_core_it_was_loaded_from() {
+        let rom = mbc3_rom();
+        for lane in [0, 1] {
+            let mut scalar = running_core(Arc::clone(&rom), 2_000);
+            let mut simd = SimdCore2::from_rom(Arc::clone(&rom));
+            simd.step_cycles(500);
+            let other = 1 - lane;
+            let mut other_scalar = ScalarCore::from_rom(Arc::clone(&rom));
+            other_scalar.load_state(&simd.lane_state(other));
+
+            simd.load_lane_state_bytes(lane, &scalar.save_state_bytes())
+                .expect("loads");
+            for _ in 0..50 {
+                simd.step_cycles(4);
+                scalar.step_cycles(4);
+                other_scalar.step_cycles(4);
+            }
+            assert_eq!(
+                simd.lane_state(lane),
+                CoreState::from(&scalar),
+                "lane {lane}"
+            );
+            assert_eq!(
+                simd.lane_state(other),
+                CoreState::from(&other_scalar),
+                "lane {other} after loading lane {lane}"
+            );
+        }
+    }
+
+    #[test]
+    fn simd_lane_rejects_state_from_other_model() {
+        let mut rom = mbc3_rom().to_vec();
+        rom[0x0143] = 0x80;
+        let rom: Arc<[u8]> = Arc::from(rom.into_boxed_slice());
+        let mut cgb = Core::new(
+            BusScalar::new(Arc::clone(&rom), None),
+            Default::default(),
+            Model::Cgb,
+        );
+        cgb.reset_post_boot(Model::Cgb);
+
+        let mut simd = SimdCore2::from_rom(rom);
+        assert_eq!(
+            simd.load_lane_state_bytes(0, &cgb.save_state_bytes()),
+            Err(StateError::ModelMismatch {
+                expected: Model::Dmg,
+                found: Model::Cgb,
+            })
+        );
     }
 }
@@ -124,3 +124,118 @@ shared_backend_module!(
     backend_bus_simd::<4>,
     backend_bus_mut_simd::<4>
 );
+
+mod simd_lanes {
+    use super::*;
+    use crate::core::Model;
+    use crate::cpu::Register;
+
+    type SimdCore2 = Core<SimdExec<2>, BusSimd<2>>;
+
+    fn rom_with_program(program: &[u8]) -> Arc<[u8]> {
+        let mut rom = vec![0u8; 0x8000];
+        rom[0x0100..0x0100 + program.len()].copy_from_slice(program);
+        rom[0x0147] = 0x03; // MBC1 + RAM + battery
+        rom[0x0149] = 0x02; // 8 KiB RAM
+        Arc::from(rom.into_boxed_slice())
+    }
+
+    fn booted_core() -> SimdCore2 {
+        // INC A; JR -3
+        let mut core = SimdCore2::from_rom(rom_with_program(&[0x3C, 0x18, 0xFD]));
+        core.reset_post_boot(Model::Dmg);
+        core
+    }
+
+    #[test]
+    fn lane_inputs_only_reach_the_addressed_lane() {
+        let mut core = booted_core();
+        core.set_lane_inputs(1, 0x7F);
+        assert_eq!(core.bus.lane(0).joyp_buttons, 0x0F);
+        assert_eq!(core.bus.lane(1).joyp_buttons, 0x07);
+    }
+
+    #[test]
+    fn reset_lane_restores_post_boot_state_and_keeps_battery_ram() {
+        let mut core = booted_core();
+        core.step_cycles(400);
+        core.bus.lane_mut(1).wram[0x10] = 0xAA;
+        core.bus.lane_mut(1).cart.ram_mut()[0] = 0x5A;
+        let lane0 = core.cpu.lane_register(0, Register::A);
+        assert_ne!(lane0, 0x01, "the program incremented A");
+
+        core.reset_lane(1);
+        assert_eq!(core.cpu.lane_register(1, Register::A), 0x01);
+        assert_eq!(core.cpu.lane_register(1, Register::Pc), 0x0100);
+        assert_eq!(core.bus.lane(1).wram[0x10], 0x00);
+        assert_eq!(core.bus.lane(1).cart.ram()[0], 0x5A);
+        assert_eq!(core.cpu.lane_register(0, Register::A), lane0);
+    }
+
+    #[test]
+    fn load_lane_rom_swaps_a_single_cartridge() {
+        let mut core = booted_core();
+        let other = rom_with_program(&[0x00, 0x18, 0xFD]);
+        core.load_lane_rom(1, Arc::clone(&other));
+        assert!(Arc::ptr_eq(core.bus.lane(1).cart.rom(), &other));
+        assert!(!Arc::ptr_eq(core.bus.lane(0).cart.rom(), &other));
+        assert_eq!(core.cpu.lane_register(1, Register::Pc), 0x0100);
+    }
+    #[test]
+    fn diverging_lanes_split_off_and_merge_back() {
+        // Store 2 at C000 if A is held, else 0, then wait for VBlank forever.
+        let program = [
+            0xF3, // DI
+            0x3E, 0x10, // LD A,$10 (select buttons)
+            0xE0, 0x00, // LDH (P1),A
+            0xF0, 0x00, // LDH A,(P1)
+            0xE6, 0x01, // AND $01
+            0x20, 0x02, // JR NZ,+2
+            0x04, // INC B
+            0x04, // INC B
+            0x78, // LD A,B
+            0xEA, 0x00, 0xC0, // LD ($C000),A
+            0xAF, // XOR A
+            0x47, // LD B,A
+            0x3C, // INC A
+            0xE0, 0xFF, // LDH (IE),A
+            0xAF, // XOR A
+            0xE0, 0x0F, // LDH (IF),A
+            0x76, // HALT
+            0x18, 0xFB, // JR -5
+        ];
+        let rom = rom_with_program(&program);
+        let mut core = SimdCore2::from_rom(Arc::clone(&rom));
+        core.reset_post_boot(Model::Dmg);
+        core.set_lane_inputs(1, 0xEF);
+        let mut scalar = Core::<Scalar, BusScalar>::from_rom(rom);
+        scalar.reset_post_boot(Model::Dmg);
+        scalar.set_inputs(0xEF);
+
+        let mut frame = vec![0u8; 160 * 144 * 4];
+        for _ in 0..4 {
+            while !core.ppu.frame_ready() {
+                core.step_cycles(70_224);
+            }
+            core.take_frame_lane(0, &mut frame);
+            core.take_frame_lane(1, &mut frame);
+            while !scalar.ppu.frame_ready() {
+                scalar.step_cycles(70_224);
+            }
+            scalar.take_frame(&mut frame);
+        }
+
+        assert_eq!(core.bus.lane(0).wram[0], 0);
+        assert_eq!(core.bus.lane(1).wram[0], 2);
+        assert_eq!(core.lane_state(1), crate::state::CoreState::from(&scalar));
+
+        let stats = core.divergence();
+        assert_eq!(stats.splits, 1);
+        assert_eq!(
+            stats.merges, 1,
+            "lanes reconverge once both wait for VBlank"
+        );
+        assert_eq!(stats.split_mask, 0);
+        assert!(stats.split_cycles > 0);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::bus::{Bus, BusScalar, IoRegs};
 use crate::exec::Exec;
-use crate::ppu_stub::CYCLES_PER_FRAME;
+use crate::ppu::{Ppu, CYCLES_PER_FRAME};
 use crate::{mmu, Core, Model, Scalar};
 use proptest::prelude::*;
 use std::sync::Arc;
@@ -8,6 +8,7 @@ use std::sync::Arc;
 const LINE_CYCLES: u32 = 456;
 const MODE2_CYCLES: u32 = 80;
 const MODE3_CYCLES: u32 = 172;
+const TOTAL_LINES: u32 = 154;
 const FRAME_WIDTH: usize = 160;
 const FRAME_HEIGHT: usize = 144;
 const FRAME_BYTES: usize = FRAME_WIDTH * FRAME_HEIGHT * 4;
@@ -106,12 +107,30 @@ fn vram_mut(core: &mut BackendCore) -> &mut [u8; 0x2000] {
     &mut bus_mut(core).vram
 }
 
-fn vram(core: &BackendCore) -> &[u8; 0x2000] {
-    &bus(core).vram
+fn oam_mut(core: &mut BackendCore) -> &mut [u8; 0xA0] {
+    &mut bus_mut(core).oam
 }
 
-fn oam(core: &BackendCore) -> &[u8; 0xA0] {
-    &bus(core).oam
+/// Runs a fresh PPU over one full frame of the canonical lane and copies it out.
+fn render_frame(core: &mut BackendCore, buf: &mut [u8]) {
+    let mut ppu = Ppu::new();
+    for _ in 0..TOTAL_LINES {
+        ppu.step(LINE_CYCLES, bus_mut(core));
+    }
+    ppu.write_frame_rgba(0, buf, FRAME_WIDTH as u16, FRAME_HEIGHT as u16);
+}
+
+/// Steps a fresh PPU through the first line and returns how many dots mode 3 lasted.
+fn measure_mode3(core: &mut BackendCore) -> u32 {
+    let mut ppu = Ppu::new();
+    let mut dots = 0;
+    for _ in 0..LINE_CYCLES {
+        ppu.step(1, bus_mut(core));
+        if io(core).read(IoRegs::STAT) & 0x03 == 0x03 {
+            dots += 1;
+        }
+    }
+    dots
 }
 
 /// Verifies `ADD A,r` updates flags according to fixture cases.
@@ -456,7 +475,7 @@ fn timers_wrap() {
     );
 }
 
-/// Confirms the PPU stub signals a frame boundary after one frame of cycles.
+/// Confirms the PPU signals a frame boundary after one frame of cycles.
 #[test]
 fn frame_boundary() {
     if BACKEND_LANES > 1 {
@@ -706,14 +725,7 @@ fn bg_render_blanks_when_disabled() {
     let mut buf = vec![0xAA; FRAME_BYTES];
 
     io_mut(&mut core).write(IoRegs::LCDC, 0x00);
-    core.ppu.render_frame(
-        io(&core),
-        vram(&core),
-        oam(&core),
-        &mut buf,
-        FRAME_WIDTH as u16,
-        FRAME_HEIGHT as u16,
-    );
+    render_frame(&mut core, &mut buf);
     for px in buf.chunks_exact(4) {
         assert_eq!(px[0], DMG_SHADES[0]);
         assert_eq!(px[1], DMG_SHADES[0]);
@@ -722,14 +734,7 @@ fn bg_render_blanks_when_disabled() {
     }
 
     io_mut(&mut core).write(IoRegs::LCDC, 0x80);
-    core.ppu.render_frame(
-        io(&core),
-        vram(&core),
-        oam(&core),
-        &mut buf,
-        FRAME_WIDTH as u16,
-        FRAME_HEIGHT as u16,
-    );
+    render_frame(&mut core, &mut buf);
     for px in buf.chunks_exact(4) {
         assert_eq!(px[0], DMG_SHADES[0]);
         assert_eq!(px[1], DMG_SHADES[0]);
@@ -752,14 +757,7 @@ fn bg_render_unsigned_tile_data() {
     vram_mut(&mut core)[map_offset] = 0x01;
 
     let mut buf = vec![0u8; FRAME_BYTES];
-    core.ppu.render_frame(
-        io(&core),
-        vram(&core),
-        oam(&core),
-        &mut buf,
-        FRAME_WIDTH as u16,
-        FRAME_HEIGHT as u16,
-    );
+    render_frame(&mut core, &mut buf);
 
     for x in 0..8 {
         let idx = (x * 4) as usize;
@@ -785,14 +783,7 @@ fn bg_render_signed_tile_data() {
     vram_mut(&mut core)[map_offset] = 0xFF;
 
     let mut buf = vec![0u8; FRAME_BYTES];
-    core.ppu.render_frame(
-        io(&core),
-        vram(&core),
-        oam(&core),
-        &mut buf,
-        FRAME_WIDTH as u16,
-        FRAME_HEIGHT as u16,
-    );
+    render_frame(&mut core, &mut buf);
 
     assert_eq!(buf[0], DMG_SHADES[2], "first pixel should use shade 2");
     assert_eq!(buf[1], DMG_SHADES[2]);
@@ -826,14 +817,7 @@ fn bg_render_scroll_wraps() {
     vram_mut(&mut core)[map_base + 31] = 0x03;
 
     let mut buf = vec![0u8; FRAME_BYTES];
-    core.ppu.render_frame(
-        io(&core),
-        vram(&core),
-        oam(&core),
-        &mut buf,
-        FRAME_WIDTH as u16,
-        FRAME_HEIGHT as u16,
-    );
+    render_frame(&mut core, &mut buf);
 
     let top_left = &buf[0..4];
     assert_eq!(top_left[0], DMG_SHADES[1]);
@@ -846,6 +830,136 @@ fn bg_render_scroll_wraps() {
     assert_eq!(wrap_y[0], DMG_SHADES[3]);
 }
 
+/// Mode 3 stretches by the discarded fine-scroll pixels and by sprite fetches.
+#[test]
+fn mode3_length_tracks_scx_and_sprites() {
+    let mut core = core_with_rom(Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()));
+    io_mut(&mut core).write(IoRegs::LCDC, 0x93);
+    assert_eq!(measure_mode3(&mut core), MODE3_CYCLES);
+
+    io_mut(&mut core).write(IoRegs::SCX, 3);
+    assert_eq!(
+        measure_mode3(&mut core),
+        MODE3_CYCLES + 3,
+        "fine scroll pixels are shifted out and dropped"
+    );
+
+    io_mut(&mut core).write(IoRegs::SCX, 0);
+    oam_mut(&mut core)[..4].copy_from_slice(&[16, 48, 0, 0]);
+    assert_eq!(
+        measure_mode3(&mut core),
+        MODE3_CYCLES + 11,
+        "tile-aligned sprite waits for the background fetch"
+    );
+
+    oam_mut(&mut core)[1] = 53;
+    assert_eq!(
+        measure_mode3(&mut core),
+        MODE3_CYCLES + 6,
+        "sprite fetch alone costs six dots"
+    );
+
+    io_mut(&mut core).write(IoRegs::LCDC, 0x91);
+    assert_eq!(
+        measure_mode3(&mut core),
+        MODE3_CYCLES,
+        "disabled objects are not fetched"
+    );
+}
+
+/// Palette writes during mode 3 only affect pixels shifted out afterwards.
+#[test]
+fn mid_scanline_bgp_write_splits_line() {
+    let mut core = core_with_rom(Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()));
+    io_mut(&mut core).write(IoRegs::LCDC, 0x91);
+    io_mut(&mut core).write(IoRegs::BGP, 0x00);
+
+    let mut ppu = Ppu::new();
+    ppu.step(MODE2_CYCLES + 12 + 80, bus_mut(&mut core));
+    io_mut(&mut core).write(IoRegs::BGP, 0x03);
+    for _ in 0..TOTAL_LINES {
+        ppu.step(LINE_CYCLES, bus_mut(&mut core));
+    }
+    io_mut(&mut core).write(IoRegs::BGP, 0x00);
+
+    let mut buf = vec![0u8; FRAME_BYTES];
+    ppu.write_frame_rgba(0, &mut buf, FRAME_WIDTH as u16, FRAME_HEIGHT as u16);
+    // Line 0 was rendered across the write; the rest of the frame saw BGP=0x03.
+    assert_eq!(buf[0], DMG_SHADES[0], "left half keeps the old palette");
+    assert_eq!(buf[159 * 4], DMG_SHADES[3], "right half uses the new palette");
+    assert_eq!(buf[FRAME_WIDTH * 4], DMG_SHADES[3]);
+}
+
+/// The window line counter only advances on lines where the window was drawn.
+#[test]
+fn window_line_counter_skips_disabled_lines() {
+    let mut core = core_with_rom(Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()));
+    io_mut(&mut core).write(IoRegs::BGP, 0xE4);
+    io_mut(&mut core).write(IoRegs::WY, 0);
+    io_mut(&mut core).write(IoRegs::WX, 7);
+
+    // Tile 1 is solid colour 3; window map row 1 uses it, rows 0 and 2 stay blank.
+    vram_mut(&mut core)[16..32].fill(0xFF);
+    let window_map = 0x1C00usize;
+    vram_mut(&mut core)[window_map + 32..window_map + 64].fill(0x01);
+
+    let mut ppu = Ppu::new();
+    for ly in 0..TOTAL_LINES {
+        let window = !(10..20).contains(&ly);
+        let lcdc = if window { 0xF1 } else { 0xD1 };
+        io_mut(&mut core).write(IoRegs::LCDC, lcdc);
+        ppu.step(LINE_CYCLES, bus_mut(&mut core));
+    }
+
+    let mut buf = vec![0u8; FRAME_BYTES];
+    ppu.write_frame_rgba(0, &mut buf, FRAME_WIDTH as u16, FRAME_HEIGHT as u16);
+    let shade_at = |y: usize| buf[y * FRAME_WIDTH * 4];
+    assert_eq!(shade_at(7), DMG_SHADES[0], "window line 7 is map row 0");
+    assert_eq!(shade_at(8), DMG_SHADES[3], "window line 8 is map row 1");
+    assert_eq!(
+        shade_at(20),
+        DMG_SHADES[3],
+        "window resumes at line 10, not LY - WY"
+    );
+    assert_eq!(shade_at(26), DMG_SHADES[0], "window line 16 is map row 2");
+}
+
+/// Only the first ten sprites on a line in OAM order are drawn.
+#[test]
+fn sprite_line_limit_is_ten() {
+    let mut core = core_with_rom(Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()));
+    io_mut(&mut core).write(IoRegs::LCDC, 0x83);
+    io_mut(&mut core).write(IoRegs::OBP0, 0xE4);
+    vram_mut(&mut core)[16..32].fill(0xFF);
+    for sprite in 0..11 {
+        let x = 8 + sprite as u8 * 10;
+        oam_mut(&mut core)[sprite * 4..sprite * 4 + 4].copy_from_slice(&[16, x, 1, 0]);
+    }
+
+    let mut buf = vec![0u8; FRAME_BYTES];
+    render_frame(&mut core, &mut buf);
+    for sprite in 0..10 {
+        assert_eq!(buf[sprite * 10 * 4], DMG_SHADES[3], "sprite {sprite} drawn");
+    }
+    assert_eq!(buf[100 * 4], DMG_SHADES[0], "eleventh sprite dropped");
+}
+
+/// On overlapping sprites the one with the smaller X wins, regardless of OAM order.
+#[test]
+fn overlapping_sprites_prefer_lower_x() {
+    let mut core = core_with_rom(Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()));
+    io_mut(&mut core).write(IoRegs::LCDC, 0x83);
+    io_mut(&mut core).write(IoRegs::OBP0, 0xE4);
+    io_mut(&mut core).write(IoRegs::OBP1, 0x00);
+    vram_mut(&mut core)[16..32].fill(0xFF);
+    oam_mut(&mut core)[..8].copy_from_slice(&[16, 12, 1, 0x10, 16, 8, 1, 0]);
+
+    let mut buf = vec![0u8; FRAME_BYTES];
+    render_frame(&mut core, &mut buf);
+    assert_eq!(buf[5 * 4], DMG_SHADES[3], "lower X sprite drawn on top");
+    assert_eq!(buf[9 * 4], DMG_SHADES[0], "second sprite fills its tail");
+}
+
 /// Verifies consecutive `EI` instructions enable IME after the second opcode.
 #[test]
 fn double_ei_sets_ime_after_second_instruction() {
@@ -899,6 +1013,389 @@ fn ei_followed_by_nop_clears_pending() {
     );
 }
 
+/// Builds a CGB-flagged core reset to CGB post-boot state running `bytes` at 0x0100.
+fn cgb_core_with_program(bytes: &[u8]) -> BackendCore {
+    let mut rom = vec![0u8; 0x8000];
+    rom[0x0143] = 0x80;
+    rom[0x0100..0x0100 + bytes.len()].copy_from_slice(bytes);
+    let rom: Arc<[u8]> = Arc::from(rom.into_boxed_slice());
+    assert_eq!(Model::for_rom(&rom), Model::Cgb);
+    let mut core = Core::new(backend_new_bus(Arc::clone(&rom)), backend_core_config(), Model::Cgb);
+    core.reset_post_boot(Model::Cgb);
+    core
+}
+
+fn write_mem(core: &mut BackendCore, addr: u16, value: u8) {
+    core.bus.write8(exec_from_u16(addr), exec_from_u8(value));
+}
+
+fn read_mem(core: &mut BackendCore, addr: u16) -> u8 {
+    exec_to_u8(core.bus.read8(exec_from_u16(addr)))
+}
+
+#[test]
+fn cgb_post_boot_registers() {
+    let mut core = cgb_core_with_program(&[]);
+    assert_eq!(exec_to_u8(core.cpu.a), 0x11);
+    assert_eq!(core.model(), Model::Cgb);
+    assert_eq!(read_mem(&mut core, 0xFF4D), 0x7E, "KEY1 starts at normal speed");
+    assert_eq!(read_mem(&mut core, 0xFF4F), 0xFE, "VBK selects bank 0");
+    assert_eq!(read_mem(&mut core, 0xFF70), 0xF8, "SVBK selects bank 1 via 0");
+}
+
+#[test]
+fn cgb_banks_switch_vram_and_wram() {
+    let mut core = cgb_core_with_program(&[]);
+
+    write_mem(&mut core, 0xFF4F, 0x01);
+    write_mem(&mut core, 0x8000, 0xAB);
+    write_mem(&mut core, 0xFF4F, 0x00);
+    assert_eq!(read_mem(&mut core, 0x8000), 0x00);
+    assert_eq!(bus(&core).cgb.vram1[0], 0xAB);
+
+    write_mem(&mut core, 0xD000, 0x11);
+    write_mem(&mut core, 0xFF70, 0x03);
+    write_mem(&mut core, 0xD000, 0x33);
+    assert_eq!(read_mem(&mut core, 0xF000), 0x33, "echo RAM follows SVBK");
+    write_mem(&mut core, 0xFF70, 0x00);
+    assert_eq!(read_mem(&mut core, 0xD000), 0x11);
+    assert_eq!(read_mem(&mut core, 0xC000), 0x00, "bank 0 is fixed");
+}
+
+#[test]
+fn dmg_mode_ignores_cgb_registers() {
+    let mut core = core_with_program(&[]);
+    core.reset_post_boot(Model::Dmg);
+    write_mem(&mut core, 0xFF4F, 0x01);
+    write_mem(&mut core, 0x8000, 0xAB);
+    assert_eq!(bus(&core).vram[0], 0xAB);
+    assert_eq!(bus(&core).cgb.vram1[0], 0x00);
+}
+
+#[test]
+fn cgb_palette_data_auto_increments() {
+    let mut core = cgb_core_with_program(&[]);
+    write_mem(&mut core, 0xFF68, 0x80 | 0x3F);
+    write_mem(&mut core, 0xFF69, 0x12);
+    write_mem(&mut core, 0xFF69, 0x34);
+    assert_eq!(read_mem(&mut core, 0xFF68), 0xC1, "index wraps within 64 bytes");
+    assert_eq!(bus(&core).cgb.bg_palette[0x3F], 0x12);
+    assert_eq!(bus(&core).cgb.bg_palette[0x00], 0x34);
+
+    write_mem(&mut core, 0xFF6A, 0x02);
+    write_mem(&mut core, 0xFF6B, 0x56);
+    write_mem(&mut core, 0xFF6B, 0x78);
+    assert_eq!(bus(&core).cgb.obj_palette[0x02], 0x78, "no increment without bit 7");
+}
+
+#[test]
+fn cgb_gdma_copies_blocks_and_stalls() {
+    let mut core = cgb_core_with_program(&[]);
+    for offset in 0..0x20u16 {
+        write_mem(&mut core, 0xC000 + offset, offset as u8 + 1);
+    }
+    write_mem(&mut core, 0xFF51, 0xC0);
+    write_mem(&mut core, 0xFF52, 0x00);
+    write_mem(&mut core, 0xFF53, 0x01);
+    write_mem(&mut core, 0xFF54, 0x00);
+    write_mem(&mut core, 0xFF55, 0x01);
+
+    let copied: Vec<u8> = bus(&core).vram[0x100..0x120].to_vec();
+    assert_eq!(copied, (1..=0x20).collect::<Vec<u8>>());
+    assert_eq!(read_mem(&mut core, 0xFF55), 0xFF, "GDMA completes at once");
+    assert_eq!(bus(&core).cgb.dma_stall, 64);
+}
+
+#[test]
+fn cgb_hblank_dma_moves_one_block_per_line() {
+    let mut core = cgb_core_with_program(&[]);
+    for offset in 0..0x20u16 {
+        write_mem(&mut core, 0xC000 + offset, 0xEE);
+    }
+    write_mem(&mut core, 0xFF51, 0xC0);
+    write_mem(&mut core, 0xFF52, 0x00);
+    write_mem(&mut core, 0xFF53, 0x00);
+    write_mem(&mut core, 0xFF54, 0x00);
+    write_mem(&mut core, 0xFF55, 0x81);
+    assert_eq!(read_mem(&mut core, 0xFF55), 0x01);
+
+    core.step_cycles(LINE_CYCLES);
+    assert_eq!(bus(&core).vram[..0x10], [0xEE; 0x10]);
+    assert_eq!(bus(&core).vram[0x10], 0x00, "second block waits for next HBlank");
+    assert_eq!(read_mem(&mut core, 0xFF55), 0x00);
+
+    core.step_cycles(LINE_CYCLES);
+    assert_eq!(bus(&core).vram[0x10..0x20], [0xEE; 0x10]);
+    assert_eq!(read_mem(&mut core, 0xFF55), 0xFF);
+}
+
+#[test]
+fn cgb_stop_switches_to_double_speed() {
+    // LD A,$01; LDH ($4D),A; STOP; NOP
+    let mut core = cgb_core_with_program(&[0x3E, 0x01, 0xE0, 0x4D, 0x10, 0x00, 0x00]);
+    core.step_instruction();
+    core.step_instruction();
+    core.step_instruction();
+    assert!(!core.cpu.halted, "armed STOP switches speed instead of halting");
+    assert_eq!(read_mem(&mut core, 0xFF4D), 0xFE);
+
+    let (dots, _) = core.step_instruction();
+    assert_eq!(dots, 2, "a 4-cycle NOP spans two dots at double speed");
+}
+
+#[test]
+fn cgb_render_uses_tile_attributes_and_color_palettes() {
+    let mut core = cgb_core_with_program(&[]);
+    // BG palette 1, colour 1 = pure red (RGB555 0x001F).
+    write_mem(&mut core, 0xFF68, 0x80 | 0x0A);
+    write_mem(&mut core, 0xFF69, 0x1F);
+    write_mem(&mut core, 0xFF69, 0x00);
+
+    let cgb = &mut bus_mut(&mut core).cgb;
+    // Bank 1 tile 1: only the leftmost column uses colour 1.
+    for row in 0..8 {
+        cgb.vram1[16 + row * 2] = 0x80;
+    }
+    // Map entries 0 and 1 both use tile 1 from bank 1 with palette 1; entry 1 flips X.
+    cgb.vram1[0x1800] = 0x09;
+    cgb.vram1[0x1801] = 0x29;
+    vram_mut(&mut core)[0x1800] = 0x01;
+    vram_mut(&mut core)[0x1801] = 0x01;
+
+    let mut buf = vec![0u8; FRAME_BYTES];
+    render_frame(&mut core, &mut buf);
+
+    let pixel = |x: usize| &buf[x * 4..x * 4 + 4];
+    assert_eq!(pixel(0), [0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(pixel(1), [0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixel(8), [0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixel(15), [0xFF, 0x00, 0x00, 0xFF], "X flip mirrors the tile");
+}
+
+/// Checks a newly pressed button on a selected column raises the joypad interrupt.
+#[test]
+fn joypad_press_requests_interrupt() {
+    let mut core = core_with_program(&[]);
+    write_mem(&mut core, 0xFF00, 0x20); // select the d-pad
+    assert_eq!(read_mem(&mut core, 0xFF00), 0xEF, "released lines read high");
+    let initial_if = io(&core).if_reg();
+
+    assert!(core.set_inputs(!0x04), "pressing Up pulls a selected line low");
+    assert_eq!(read_mem(&mut core, 0xFF00) & 0x0F, 0x0B);
+    assert_eq!(io(&core).if_reg() & 0x10, 0x10);
+
+    io_mut(&mut core).set_if(initial_if);
+    assert!(
+        !core.set_inputs(!0x10),
+        "A sits on the deselected column and releasing Up is a rising edge"
+    );
+    assert_eq!(io(&core).if_reg() & 0x10, 0);
+
+    write_mem(&mut core, 0xFF00, 0x10); // select the buttons while A is held
+    assert_eq!(io(&core).if_reg() & 0x10, 0x10);
+}
+
+/// Checks STOP resets DIV, freezes the clock and resumes on a button press.
+#[test]
+fn stop_waits_for_joypad_press() {
+    // LD A,$10; LDH ($00),A; STOP; INC B
+    let mut core = core_with_program(&[0x3E, 0x10, 0xE0, 0x00, 0x10, 0x00, 0x04]);
+    core.step_instruction();
+    core.step_instruction();
+    io_mut(&mut core).set_div(0x55);
+    core.step_instruction();
+    assert!(core.cpu.stopped);
+    assert_eq!(read_mem(&mut core, 0xFF04), 0x00, "STOP resets DIV");
+
+    for _ in 0..1_000 {
+        let (_, pc) = core.step_instruction();
+        assert_eq!(pc, 0x0106, "PC skips the STOP padding byte and waits");
+    }
+    assert_eq!(read_mem(&mut core, 0xFF04), 0x00, "DIV is frozen in STOP");
+
+    assert!(core.set_inputs(!0x10), "pressing A wakes the CPU");
+    assert!(!core.cpu.stopped);
+    core.step_instruction();
+    assert_eq!(exec_to_u8(core.cpu.b), 0x01);
+}
+
+/// Checks every base opcode advances PC by its table length (debug builds also
+/// check its cycle count against the table).
+#[test]
+fn opcode_table_lengths_match_execution() {
+    for opcode in 0..=0xFFu8 {
+        let info = crate::opcodes::OPCODES[usize::from(opcode)];
+        let mut core = core_with_program(&[opcode, 0x00, 0x00]);
+        core.cpu.sp = exec_from_u16(0xD000);
+        core.step_instruction();
+        let control_flow = ["JR", "JP", "CALL", "RET", "RST"]
+            .iter()
+            .any(|prefix| info.mnemonic.starts_with(prefix));
+        if !control_flow {
+            assert_eq!(
+                exec_to_u16(core.cpu.pc),
+                0x0100 + u16::from(info.len),
+                "opcode {opcode:02X} ({})",
+                info.mnemonic
+            );
+        }
+    }
+}
+
+/// Checks the core disassembles through the bus from an arbitrary base.
+#[test]
+fn disassemble_reads_program_through_bus() {
+    // LD A,$10; JR NZ,-4; SET 3,(HL)
+    let mut core = core_with_program(&[0x3E, 0x10, 0x20, 0xFC, 0xCB, 0xDE]);
+    let lines = core.disassemble(0x0100, 3);
+    let text: Vec<_> = lines.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(text, ["LD A,$10", "JR NZ,$0100", "SET 3,(HL)"]);
+    assert_eq!(lines[2].addr, 0x0104);
+    assert_eq!(lines[2].bytes, vec![0xCB, 0xDE]);
+}
+
+/// Checks `step_cycles` stops before a breakpoint and resumes past it.
+#[test]
+fn breakpoint_pauses_before_instruction() {
+    // INC B; INC B; INC B; JR -5
+    let mut core = core_with_program(&[0x04, 0x04, 0x04, 0x18, 0xFB]);
+    core.breakpoints.set_breakpoint(0x0102);
+
+    core.step_cycles(1_000);
+    assert_eq!(core.breakpoints.hit(), Some(crate::BreakReason::Breakpoint));
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0102);
+    assert_eq!(exec_to_u8(core.cpu.b), 2);
+
+    core.breakpoints.resume_from(0x0102);
+    core.step_cycles(1_000);
+    assert_eq!(core.breakpoints.hit(), Some(crate::BreakReason::Breakpoint));
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0102);
+    assert_eq!(exec_to_u8(core.cpu.b), 5, "one full loop before trapping again");
+
+    assert!(core.breakpoints.clear_breakpoint(0x0102));
+    core.breakpoints.resume_from(0x0102);
+    assert!(core.step_cycles(400) >= 400);
+    assert_eq!(core.breakpoints.hit(), None);
+}
+
+/// Checks watchpoints stop after the accessing instruction, including stack traffic.
+#[test]
+fn watchpoints_trap_reads_writes_and_stack() {
+    use crate::{BreakReason, WatchAccess};
+
+    // LD A,$42; LD ($C010),A; LD A,($C010); PUSH BC; HALT
+    let mut core = core_with_program(&[
+        0x3E, 0x42, 0xEA, 0x10, 0xC0, 0xFA, 0x10, 0xC0, 0xC5, 0x76,
+    ]);
+    core.cpu.sp = exec_from_u16(0xD000);
+    core.breakpoints
+        .set_watchpoint(0xC000..=0xC0FF, WatchAccess::Write);
+    core.breakpoints
+        .set_watchpoint(0xC010..=0xC010, WatchAccess::Read);
+    core.breakpoints
+        .set_watchpoint(0xCFFE..=0xCFFF, WatchAccess::Write);
+
+    core.step_cycles(1_000);
+    assert_eq!(
+        core.breakpoints.take_hit(),
+        Some(BreakReason::Watchpoint {
+            addr: 0xC010,
+            access: WatchAccess::Write
+        })
+    );
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0105);
+    assert_eq!(read_mem(&mut core, 0xC010), 0x42, "the write completes");
+
+    core.step_cycles(1_000);
+    assert_eq!(
+        core.breakpoints.take_hit(),
+        Some(BreakReason::Watchpoint {
+            addr: 0xC010,
+            access: WatchAccess::Read
+        })
+    );
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0108);
+
+    core.step_cycles(1_000);
+    assert_eq!(
+        core.breakpoints.take_hit(),
+        Some(BreakReason::Watchpoint {
+            addr: 0xCFFF,
+            access: WatchAccess::Write
+        })
+    );
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0109);
+}
+
+#[test]
+fn set_lane_register_patches_lane_zero() {
+    use crate::Register;
+
+    // LD A,B
+    let mut core = core_with_program(&[0x78]);
+    core.cpu.set_lane_register(0, Register::B, 0x5A);
+    core.cpu.set_lane_register(0, Register::F, 0xFF);
+    core.cpu.set_lane_register(0, Register::Sp, 0xD000);
+    core.step_instruction();
+
+    assert_eq!(exec_to_u8(core.cpu.a), 0x5A);
+    assert_eq!(core.cpu.f.to_byte(), 0xF0, "low flag bits stay clear");
+    assert_eq!(exec_to_u16(core.cpu.sp), 0xD000);
+
+    core.cpu.set_lane_register(0, Register::Pc, 0x0100);
+    core.step_instruction();
+    assert_eq!(exec_to_u16(core.cpu.pc), 0x0101);
+}
+
+#[test]
+fn trace_records_state_before_each_instruction() {
+    use crate::{MemAccess, WatchAccess};
+
+    // LD A,$42; LD ($C010),A; PUSH BC
+    let mut core = core_with_program(&[0x3E, 0x42, 0xEA, 0x10, 0xC0, 0xC5]);
+    core.cpu.sp = exec_from_u16(0xD000);
+    core.cpu.b = exec_from_u8(0x12);
+    core.cpu.c = exec_from_u8(0x34);
+    core.trace.enable(8);
+    for _ in 0..3 {
+        core.step_instruction();
+    }
+
+    let entries = core.trace.drain(usize::MAX);
+    let pcs: Vec<u16> = entries.iter().map(|entry| entry.pc).collect();
+    assert_eq!(pcs, [0x0100, 0x0102, 0x0105]);
+    let cycles: Vec<u64> = entries.iter().map(|entry| entry.cycle).collect();
+    assert_eq!(cycles, [0, 8, 24]);
+
+    assert_eq!(entries[0].pcmem, [0x3E, 0x42, 0xEA, 0x10]);
+    assert!(entries[0].accesses.is_empty(), "opcode fetches are not traced");
+    assert_eq!(entries[1].a, 0x42);
+    assert_eq!(
+        entries[1].accesses,
+        [MemAccess {
+            addr: 0xC010,
+            value: 0x42,
+            kind: WatchAccess::Write
+        }]
+    );
+    assert_eq!((entries[2].b, entries[2].c, entries[2].sp), (0x12, 0x34, 0xD000));
+    assert_eq!(
+        entries[2].accesses,
+        [
+            MemAccess {
+                addr: 0xCFFF,
+                value: 0x12,
+                kind: WatchAccess::Write
+            },
+            MemAccess {
+                addr: 0xCFFE,
+                value: 0x34,
+                kind: WatchAccess::Write
+            },
+        ]
+    );
+}
+
 // Property-based tests verifying ALU/carry behaviour.
 proptest! {
     #[test]
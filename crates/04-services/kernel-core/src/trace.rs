@@ -0,0 +1,206 @@
+use crate::breakpoints::WatchAccess;
+use std::collections::VecDeque;
+
+/// Data accesses recorded per instruction; no SM83 instruction makes more.
+pub const MAX_TRACE_ACCESSES: usize = 2;
+
+/// Default number of instructions kept in the trace ring.
+pub const DEFAULT_TRACE_CAPACITY: usize = 4096;
+
+/// A data read or write made by a traced instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemAccess {
+    /// Address accessed.
+    pub addr: u16,
+    /// Byte read or written.
+    pub value: u8,
+    /// Whether the access was a read or a write.
+    pub kind: WatchAccess,
+}
+
+/// CPU state captured just before an instruction executes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// CPU cycles elapsed since tracing was enabled.
+    pub cycle: u64,
+    /// Address of the opcode.
+    pub pc: u16,
+    /// The four bytes at `pc`, opcode first.
+    pub pcmem: [u8; 4],
+    /// Accumulator.
+    pub a: u8,
+    /// Flags register.
+    pub f: u8,
+    /// Register B.
+    pub b: u8,
+    /// Register C.
+    pub c: u8,
+    /// Register D.
+    pub d: u8,
+    /// Register E.
+    pub e: u8,
+    /// Register H.
+    pub h: u8,
+    /// Register L.
+    pub l: u8,
+    /// Stack pointer.
+    pub sp: u16,
+    /// Data accesses made by the instruction, in order.
+    pub accesses: Vec<MemAccess>,
+}
+
+impl TraceEntry {
+    /// Returns the opcode byte.
+    #[inline]
+    pub fn opcode(&self) -> u8 {
+        self.pcmem[0]
+    }
+}
+
+/// Ring buffer of recently executed instructions.
+///
+/// Tracing is off by default; while enabled every instruction the core
+/// executes (stepped or free-running) appends an entry, evicting the oldest
+/// once the ring is full. Interrupt dispatch is not recorded as an entry.
+#[derive(Clone, Debug)]
+pub struct Trace {
+    enabled: bool,
+    capacity: usize,
+    entries: VecDeque<TraceEntry>,
+    clock: u64,
+    recording: bool,
+    dropped: u64,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: DEFAULT_TRACE_CAPACITY,
+            entries: VecDeque::new(),
+            clock: 0,
+            recording: false,
+            dropped: 0,
+        }
+    }
+}
+
+impl Trace {
+    /// Starts recording into a ring of `capacity` entries, clearing any history.
+    pub fn enable(&mut self, capacity: usize) {
+        self.enabled = true;
+        self.capacity = capacity.max(1);
+        self.entries.clear();
+        self.clock = 0;
+        self.dropped = 0;
+    }
+
+    /// Stops recording; entries already captured stay readable.
+    pub fn disable(&mut self) {
+        self.enabled = false;
+        self.recording = false;
+    }
+
+    /// Returns whether instructions are being recorded.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the number of buffered entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no entries are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns how many entries were evicted unread since tracing was enabled.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Removes and returns up to `max` of the oldest buffered entries.
+    pub fn drain(&mut self, max: usize) -> Vec<TraceEntry> {
+        let take = max.min(self.entries.len());
+        self.entries.drain(..take).collect()
+    }
+
+    #[inline]
+    pub(crate) fn advance(&mut self, cycles: u32) {
+        if self.enabled {
+            self.clock = self.clock.wrapping_add(u64::from(cycles));
+        }
+    }
+
+    pub(crate) fn begin(&mut self, mut entry: TraceEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.dropped = self.dropped.wrapping_add(1);
+        }
+        entry.cycle = self.clock;
+        self.entries.push_back(entry);
+        self.recording = true;
+    }
+
+    #[inline]
+    pub(crate) fn end(&mut self) {
+        self.recording = false;
+    }
+
+    #[inline]
+    pub(crate) fn note_access(&mut self, addr: u16, value: u8, kind: WatchAccess) {
+        if !self.recording {
+            return;
+        }
+        if let Some(entry) = self.entries.back_mut() {
+            if entry.accesses.len() < MAX_TRACE_ACCESSES {
+                entry.accesses.push(MemAccess { addr, value, kind });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_evicts_oldest_and_counts_drops() {
+        let mut trace = Trace::default();
+        trace.enable(2);
+        for pc in 0..3u16 {
+            trace.begin(TraceEntry {
+                pc,
+                ..TraceEntry::default()
+            });
+            trace.end();
+        }
+        assert_eq!(trace.dropped(), 1);
+        let pcs: Vec<u16> = trace.drain(8).iter().map(|entry| entry.pc).collect();
+        assert_eq!(pcs, [1, 2]);
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn accesses_attach_only_while_recording() {
+        let mut trace = Trace::default();
+        trace.enable(4);
+        trace.begin(TraceEntry::default());
+        trace.note_access(0xC000, 0x12, WatchAccess::Write);
+        trace.end();
+        trace.note_access(0xFFFE, 0x34, WatchAccess::Write);
+
+        let entry = &trace.drain(1)[0];
+        assert_eq!(
+            entry.accesses,
+            [MemAccess {
+                addr: 0xC000,
+                value: 0x12,
+                kind: WatchAccess::Write
+            }]
+        );
+    }
+}
@@ -13,6 +13,7 @@ fn run_serial_rom(path: &str, max_cycles: u64) -> (String, u64) {
         .unwrap_or(max_cycles);
     let rom = testdata::bytes(path);
     let mut core = Core::<Scalar, BusScalar>::from_rom(rom);
+    core.set_audio_capture(false);
     let mut serial = String::new();
     let mut elapsed = 0u64;
     let break_on_fail = std::env::var_os("GBX_BREAK_ON_TEST_FAIL").is_some();
@@ -16,6 +16,7 @@ fn run_serial_rom(path: &str, max_cycles: u64) -> ([String; LANES], u64, [u16; L
         .unwrap_or(max_cycles);
     let rom = testdata::bytes(path);
     let mut core: SimdCore<LANES> = SimdCore::from_rom(Arc::clone(&rom));
+    core.set_audio_capture(false);
     let mut serial: [String; LANES] = std::array::from_fn(|_| String::new());
     let mut elapsed = 0u64;
 
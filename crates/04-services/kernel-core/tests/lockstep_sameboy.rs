@@ -68,6 +68,7 @@ struct OurCore {
 impl OurCore {
     fn new(rom: Arc<[u8]>) -> Self {
         let mut core = Core::<Scalar, BusScalar>::from_rom(rom);
+        core.set_audio_capture(false);
         core.reset_post_boot(Model::Dmg);
         Self {
             core,
@@ -1,4 +1,4 @@
-//! Mooneye timer and interrupt acceptance ROMs checked via the Fibonacci oracle.
+//! Mooneye timer, OAM DMA and interrupt acceptance ROMs checked via the Fibonacci oracle.
 
 use kernel_core::{BusScalar, Core, Exec, Model, Scalar};
 
@@ -34,6 +34,7 @@ enum Outcome {
 fn run_mooneye(path: &str) -> Outcome {
     let rom = testdata::bytes(path);
     let mut core = Core::<Scalar, BusScalar>::from_rom(rom);
+    core.set_audio_capture(false);
     core.reset_post_boot(Model::Dmg);
 
     let mut cycles = 0u64;
@@ -204,6 +205,21 @@ fn mooneye_timer_suite() {
     }
 }
 
+#[test]
+fn mooneye_oam_dma_suite() {
+    const ROMS: &[&str] = &[
+        "mooneye-test-suite/acceptance/oam_dma/basic.gb",
+        "mooneye-test-suite/acceptance/oam_dma/reg_read.gb",
+        "mooneye-test-suite/acceptance/oam_dma_restart.gb",
+        "mooneye-test-suite/acceptance/oam_dma_start.gb",
+        "mooneye-test-suite/acceptance/oam_dma_timing.gb",
+    ];
+
+    for &rom in ROMS {
+        assert_mooneye(rom);
+    }
+}
+
 #[test]
 #[ignore = "Mooneye timer suite (wilbertpol fork) under bring-up"]
 fn mooneye_timer_suite_wilbertpol() {
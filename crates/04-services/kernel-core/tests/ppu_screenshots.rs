@@ -0,0 +1,145 @@
+//! dmg-acid2 and Mealybug Tearoom PPU ROMs compared against their reference screenshots.
+
+use kernel_core::mmu::peek8_scalar;
+use kernel_core::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use kernel_core::{BusScalar, Core, Exec, Model, Scalar};
+use png::{ColorType, Decoder, Transformations};
+use testdata::RomModel;
+
+/// `LD B,B`, the breakpoint screenshot ROMs execute once the picture is final.
+const LD_B_B: u8 = 0x40;
+const CYCLES_PER_FRAME: u64 = 70_224;
+const MAX_CYCLES: u64 = 60 * CYCLES_PER_FRAME;
+
+/// Mealybug PPU ROMs with a DMG reference; the manifest runs them on a DMG.
+/// The rest only ship CGB pictures, which depend on the boot ROM's DMG
+/// compatibility palettes.
+const MEALYBUG_PPU_ROMS: &[&str] = &[
+    "mealybug-tearoom-tests/ppu/m2_win_en_toggle.gb",
+    "mealybug-tearoom-tests/ppu/m3_bgp_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_bgp_change_sprites.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_bg_en_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_bg_map_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_obj_en_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_obj_en_change_variant.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_obj_size_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_obj_size_change_scx.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_tile_sel_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_tile_sel_win_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_win_en_change_multiple.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_win_en_change_multiple_wx.gb",
+    "mealybug-tearoom-tests/ppu/m3_lcdc_win_map_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_obp0_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_scx_high_5_bits.gb",
+    "mealybug-tearoom-tests/ppu/m3_scx_low_3_bits.gb",
+    "mealybug-tearoom-tests/ppu/m3_scy_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_window_timing.gb",
+    "mealybug-tearoom-tests/ppu/m3_window_timing_wx_0.gb",
+    "mealybug-tearoom-tests/ppu/m3_wx_4_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_wx_4_change_sprites.gb",
+    "mealybug-tearoom-tests/ppu/m3_wx_5_change.gb",
+    "mealybug-tearoom-tests/ppu/m3_wx_6_change.gb",
+];
+
+/// Runs `path` on the model recorded in the manifest until it executes `LD B,B`,
+/// then returns the next complete frame as RGBA.
+fn capture(path: &str) -> Vec<u8> {
+    let meta = testdata::metadata(path).unwrap_or_else(|| panic!("unknown ROM path {path}"));
+    let model = match meta.model {
+        RomModel::Cgb => Model::Cgb,
+        _ => Model::Dmg,
+    };
+    let mut core = Core::<Scalar, BusScalar>::from_rom(testdata::bytes(path));
+    core.set_audio_capture(false);
+    core.reset_post_boot(model);
+
+    let mut cycles = 0u64;
+    while core.cpu.halted || peek8_scalar(&core.bus, Scalar::to_u16(core.cpu.pc)) != LD_B_B {
+        assert!(
+            cycles < MAX_CYCLES,
+            "{path}: no LD B,B within {cycles} cycles"
+        );
+        let (step, _) = core.step_instruction();
+        cycles += u64::from(step);
+    }
+
+    let mut frame = vec![0u8; SCREEN_WIDTH * SCREEN_HEIGHT * 4];
+    // The frame in flight may predate the breakpoint; compare the next full one.
+    core.take_frame(&mut frame);
+    let deadline = cycles + 2 * CYCLES_PER_FRAME;
+    while !core.frame_ready() && cycles < deadline {
+        let (step, _) = core.step_instruction();
+        cycles += u64::from(step);
+    }
+    core.take_frame(&mut frame);
+    frame
+}
+
+/// Counts pixels of `frame` whose RGB differs from the reference PNG.
+fn mismatched_pixels(frame: &[u8], png_bytes: &[u8]) -> usize {
+    let mut decoder = Decoder::new(png_bytes);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().expect("reference PNG header");
+    let mut pixels = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).expect("reference PNG data");
+    assert_eq!(
+        (info.width as usize, info.height as usize),
+        (SCREEN_WIDTH, SCREEN_HEIGHT)
+    );
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => panic!("reference palette was not expanded"),
+    };
+
+    let mut mismatched = 0;
+    for y in 0..SCREEN_HEIGHT {
+        let row = &pixels[y * info.line_size..];
+        for x in 0..SCREEN_WIDTH {
+            let px = &row[x * channels..];
+            let expected = if channels < 3 {
+                [px[0]; 3]
+            } else {
+                [px[0], px[1], px[2]]
+            };
+            let offset = (y * SCREEN_WIDTH + x) * 4;
+            if frame[offset..offset + 3] != expected {
+                mismatched += 1;
+            }
+        }
+    }
+    mismatched
+}
+
+fn check(path: &str) -> Result<(), String> {
+    let reference =
+        testdata::reference_png(path).ok_or_else(|| format!("{path}: no reference screenshot"))?;
+    match mismatched_pixels(&capture(path), &reference) {
+        0 => Ok(()),
+        n => Err(format!("{path}: {n} pixel(s) differ from the reference")),
+    }
+}
+
+#[test]
+fn dmg_acid2_matches_reference() {
+    if let Err(err) = check("dmg-acid2/dmg-acid2.gb") {
+        panic!("{err}");
+    }
+}
+
+#[test]
+fn mealybug_ppu_suite_matches_references() {
+    let failures: Vec<String> = MEALYBUG_PPU_ROMS
+        .iter()
+        .filter_map(|path| check(path).err())
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} Mealybug ROMs failed:\n{}",
+        failures.len(),
+        MEALYBUG_PPU_ROMS.len(),
+        failures.join("\n")
+    );
+}
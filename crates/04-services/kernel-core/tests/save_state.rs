@@ -0,0 +1,73 @@
+//! Save-state round trips against Blargg ROMs: a core restored mid-run must
+//! continue bit-identically to the one it was captured from.
+
+use kernel_core::state::CoreState;
+use kernel_core::{BusScalar, Core, Scalar};
+
+const STEP_BUDGET: u32 = 1_000_000;
+
+fn run_until_done(core: &mut Core<Scalar, BusScalar>, serial: &mut String, max_cycles: u64) {
+    let mut elapsed = 0u64;
+    while elapsed < max_cycles && !serial.contains("Passed") && !serial.contains("Failed") {
+        let consumed = core.step_cycles(STEP_BUDGET);
+        if consumed == 0 {
+            break;
+        }
+        elapsed += u64::from(consumed);
+        serial.push_str(&core.bus.take_serial());
+    }
+}
+
+fn assert_round_trip(path: &str, split_cycles: u64, max_cycles: u64) {
+    let rom = testdata::bytes(path);
+    let mut original = Core::<Scalar, BusScalar>::from_rom(rom.clone());
+    original.set_audio_capture(false);
+    let mut prefix = String::new();
+    run_until_done(&mut original, &mut prefix, split_cycles);
+    assert!(
+        !prefix.contains("Passed"),
+        "{path} finished before the split point; lower split_cycles"
+    );
+
+    let bytes = original.save_state_bytes();
+    let mut restored = Core::<Scalar, BusScalar>::from_rom(rom);
+    restored.set_audio_capture(false);
+    restored
+        .load_state_bytes(&bytes)
+        .unwrap_or_else(|err| panic!("{path}: {err}"));
+    assert_eq!(CoreState::from(&restored), CoreState::from(&original));
+
+    let mut expected = prefix.clone();
+    let mut observed = prefix;
+    run_until_done(&mut original, &mut expected, max_cycles);
+    run_until_done(&mut restored, &mut observed, max_cycles);
+    assert_eq!(observed, expected, "{path}: serial output diverged");
+    assert!(
+        observed.contains("Passed"),
+        "{path}: restored core did not pass: {observed:?}"
+    );
+    assert_eq!(CoreState::from(&restored), CoreState::from(&original));
+}
+
+#[test]
+fn blargg_01_special_survives_save_state() {
+    assert_round_trip(
+        "blargg/cpu_instrs/individual/01-special.gb",
+        2_000_000,
+        40_000_000,
+    );
+}
+
+#[test]
+fn blargg_02_interrupts_survives_save_state() {
+    assert_round_trip(
+        "blargg/cpu_instrs/individual/02-interrupts.gb",
+        1_000_000,
+        60_000_000,
+    );
+}
+
+#[test]
+fn blargg_instr_timing_survives_save_state() {
+    assert_round_trip("blargg/instr_timing/instr_timing.gb", 1_000_000, 80_000_000);
+}
@@ -1,10 +1,19 @@
-use crate::sink_transport::TransportFrameSink;
+use crate::sink_transport::{TransportAudioSink, TransportFrameSink};
 use core::num::NonZeroUsize;
 use core::simd::{LaneCount, SupportedLaneCount};
+use kernel_core::apu;
 use kernel_core::bus::IoRegs;
+use kernel_core::mmu::{peek8_scalar, write8_scalar};
+use kernel_core::ppu::CYCLES_PER_FRAME;
 use kernel_core::Exec;
-use kernel_core::{BusScalar, BusSimd, Core, Model, Scalar, SimdCore, SimdExec};
-use service_abi::{CpuVM, InspectorVMMinimal, MemSpace, PpuVM, SlotSpan, TimersVM};
+use kernel_core::{
+    BreakReason, Breakpoints, BusScalar, BusSimd, Cheats, Core, DisasmLine, DivergenceStats, Model,
+    Register, Scalar, SimdCore, SimdExec, Trace,
+};
+use service_abi::{
+    AudioSpan, CpuVM, DisasmLineVM, InspectorVMMinimal, LanesVM, MemSpace, PpuVM, RegistersPatch,
+    SlotSpan, TimersVM,
+};
 use std::sync::Arc;
 
 /// Execution backend container.
@@ -38,6 +47,77 @@ impl AnyCore {
         }
     }
 
+    pub fn disassemble(&mut self, base: u16, count: usize) -> Vec<DisasmLine> {
+        match self {
+            AnyCore::Scalar(core) => core.disassemble(base, count),
+            AnyCore::Simd2(core) => core.disassemble(base, count),
+            AnyCore::Simd4(core) => core.disassemble(base, count),
+            AnyCore::Simd8(core) => core.disassemble(base, count),
+        }
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut Breakpoints {
+        match self {
+            AnyCore::Scalar(core) => &mut core.breakpoints,
+            AnyCore::Simd2(core) => &mut core.breakpoints,
+            AnyCore::Simd4(core) => &mut core.breakpoints,
+            AnyCore::Simd8(core) => &mut core.breakpoints,
+        }
+    }
+
+    pub fn set_lane_register(&mut self, lane: usize, reg: Register, value: u16) {
+        match self {
+            AnyCore::Scalar(core) => core.cpu.set_lane_register(lane, reg, value),
+            AnyCore::Simd2(core) => core.set_lane_register(lane, reg, value),
+            AnyCore::Simd4(core) => core.set_lane_register(lane, reg, value),
+            AnyCore::Simd8(core) => core.set_lane_register(lane, reg, value),
+        }
+    }
+
+    pub fn set_ime(&mut self, ime: bool) {
+        match self {
+            AnyCore::Scalar(core) => core.cpu.ime = ime,
+            AnyCore::Simd2(core) => core.cpu.ime = ime,
+            AnyCore::Simd4(core) => core.cpu.ime = ime,
+            AnyCore::Simd8(core) => core.cpu.ime = ime,
+        }
+    }
+
+    pub fn set_lane_inputs(&mut self, lane: usize, joypad: u8) {
+        match self {
+            AnyCore::Scalar(core) => {
+                core.set_inputs(joypad);
+            }
+            AnyCore::Simd2(core) => {
+                core.set_lane_inputs(lane, joypad);
+            }
+            AnyCore::Simd4(core) => {
+                core.set_lane_inputs(lane, joypad);
+            }
+            AnyCore::Simd8(core) => {
+                core.set_lane_inputs(lane, joypad);
+            }
+        }
+    }
+
+    pub fn set_cheats(&mut self, cheats: &Cheats) {
+        match self {
+            AnyCore::Scalar(core) => core.set_cheats(cheats),
+            AnyCore::Simd2(core) => core.set_cheats(cheats),
+            AnyCore::Simd4(core) => core.set_cheats(cheats),
+            AnyCore::Simd8(core) => core.set_cheats(cheats),
+        }
+    }
+
+    pub fn trace_mut(&mut self) -> &mut Trace {
+        match self {
+            AnyCore::Scalar(core) => &mut core.trace,
+            AnyCore::Simd2(core) => &mut core.trace,
+            AnyCore::Simd4(core) => &mut core.trace,
+            AnyCore::Simd8(core) => &mut core.trace,
+        }
+    }
+
     pub fn frame_ready(&self) -> bool {
         match self {
             AnyCore::Scalar(core) => core.frame_ready(),
@@ -47,6 +127,15 @@ impl AnyCore {
         }
     }
 
+    pub fn skip_frame(&mut self) {
+        match self {
+            AnyCore::Scalar(core) => core.skip_frame(),
+            AnyCore::Simd2(core) => core.skip_frame(),
+            AnyCore::Simd4(core) => core.skip_frame(),
+            AnyCore::Simd8(core) => core.skip_frame(),
+        }
+    }
+
     pub fn load_rom(&mut self, rom: Arc<[u8]>) {
         match self {
             AnyCore::Scalar(core) => core.load_rom(rom),
@@ -56,6 +145,62 @@ impl AnyCore {
         }
     }
 
+    pub fn load_save_ram(&mut self, bytes: &[u8]) {
+        match self {
+            AnyCore::Scalar(core) => core.load_save_ram(bytes),
+            AnyCore::Simd2(core) => core.load_save_ram(bytes),
+            AnyCore::Simd4(core) => core.load_save_ram(bytes),
+            AnyCore::Simd8(core) => core.load_save_ram(bytes),
+        }
+    }
+
+    pub fn sync_rtc(&mut self, unix_secs: u64) {
+        match self {
+            AnyCore::Scalar(core) => core.sync_rtc(unix_secs),
+            AnyCore::Simd2(core) => core.sync_rtc(unix_secs),
+            AnyCore::Simd4(core) => core.sync_rtc(unix_secs),
+            AnyCore::Simd8(core) => core.sync_rtc(unix_secs),
+        }
+    }
+
+    /// Returns the lanes whose battery RAM changed, with their contents.
+    pub fn take_dirty_save_ram(&mut self) -> Vec<(u16, Arc<[u8]>)> {
+        let lanes = match self {
+            AnyCore::Scalar(core) => {
+                return core
+                    .take_dirty_save_ram()
+                    .map(|bytes| (0, bytes))
+                    .into_iter()
+                    .collect()
+            }
+            AnyCore::Simd2(core) => core.take_dirty_save_ram(),
+            AnyCore::Simd4(core) => core.take_dirty_save_ram(),
+            AnyCore::Simd8(core) => core.take_dirty_save_ram(),
+        };
+        lanes
+            .into_iter()
+            .map(|(lane, bytes)| (lane as u16, bytes))
+            .collect()
+    }
+
+    pub fn set_audio_capture(&mut self, capture: bool) {
+        match self {
+            AnyCore::Scalar(core) => core.set_audio_capture(capture),
+            AnyCore::Simd2(core) => core.set_audio_capture(capture),
+            AnyCore::Simd4(core) => core.set_audio_capture(capture),
+            AnyCore::Simd8(core) => core.set_audio_capture(capture),
+        }
+    }
+
+    pub fn take_audio(&mut self) -> (Vec<i16>, u32) {
+        match self {
+            AnyCore::Scalar(core) => (core.take_audio(), core.audio_sample_rate_hz()),
+            AnyCore::Simd2(core) => (core.take_audio(), core.audio_sample_rate_hz()),
+            AnyCore::Simd4(core) => (core.take_audio(), core.audio_sample_rate_hz()),
+            AnyCore::Simd8(core) => (core.take_audio(), core.audio_sample_rate_hz()),
+        }
+    }
+
     pub fn reset_post_boot(&mut self, model: Model) {
         match self {
             AnyCore::Scalar(core) => core.reset_post_boot(model),
@@ -153,21 +298,78 @@ where
     }
 }
 
-fn mem_window_simd<const LANES: usize>(
-    core: &Core<SimdExec<LANES>, BusSimd<LANES>>,
-    space: MemSpace,
-    base: u16,
-    len: u16,
-) -> Vec<u8>
-where
-    LaneCount<LANES>: SupportedLaneCount,
-{
-    let bus = core.bus.lane(0);
+fn lanes_from_stats(divergence: DivergenceStats) -> LanesVM {
+    LanesVM {
+        split_mask: divergence.split_mask,
+        splits: divergence.splits,
+        merges: divergence.merges,
+        split_cycles: divergence.split_cycles,
+    }
+}
+
+fn mem_window_bus(bus: &BusScalar, space: MemSpace, base: u16, len: u16) -> Vec<u8> {
     match space {
         MemSpace::Vram => window_slice(bus.vram.as_ref(), 0x8000, base, len),
         MemSpace::Wram => window_slice(bus.wram.as_ref(), 0xC000, base, len),
         MemSpace::Oam => window_slice(bus.oam.as_ref(), 0xFE00, base, len),
         MemSpace::Io => window_slice(bus.io.regs(), 0xFF00, base, len),
+        MemSpace::Hram => {
+            let mut hram = bus.hram.to_vec();
+            hram.push(bus.ie);
+            window_slice(&hram, 0xFF80, base, len)
+        }
+        MemSpace::Rom { bank } => {
+            let region_base = if bank == 0 { 0x0000 } else { 0x4000 };
+            bus.cart
+                .rom_bank_bytes(usize::from(bank))
+                .map_or_else(Vec::new, |bytes| {
+                    window_slice(bytes, region_base, base, len)
+                })
+        }
+        MemSpace::CartRam { bank } => bus
+            .cart
+            .ram_bank_bytes(usize::from(bank))
+            .map_or_else(Vec::new, |bytes| window_slice(bytes, 0xA000, base, len)),
+        MemSpace::Bus => {
+            let end = u32::from(base) + u32::from(len);
+            (u32::from(base)..end.min(0x1_0000))
+                .map(|addr| peek8_scalar(bus, addr as u16))
+                .collect()
+        }
+    }
+}
+
+fn write_mem_bus(bus: &mut BusScalar, space: MemSpace, base: u16, bytes: &[u8]) {
+    match space {
+        MemSpace::Vram => window_write(bus.vram.as_mut(), 0x8000, base, bytes),
+        MemSpace::Wram => window_write(bus.wram.as_mut(), 0xC000, base, bytes),
+        MemSpace::Oam => window_write(bus.oam.as_mut(), 0xFE00, base, bytes),
+        MemSpace::Io => window_write(bus.io.regs_mut(), 0xFF00, base, bytes),
+        MemSpace::Hram => {
+            let mut hram = [0u8; 0x80];
+            hram[..0x7F].copy_from_slice(&bus.hram);
+            hram[0x7F] = bus.ie;
+            window_write(&mut hram, 0xFF80, base, bytes);
+            bus.hram.copy_from_slice(&hram[..0x7F]);
+            bus.ie = hram[0x7F];
+        }
+        MemSpace::Rom { bank } => {
+            let region_base = if bank == 0 { 0x0000 } else { 0x4000 };
+            if let Some(offset) = base.checked_sub(region_base) {
+                bus.cart
+                    .patch_rom(usize::from(bank), usize::from(offset), bytes);
+            }
+        }
+        MemSpace::CartRam { bank } => {
+            if let Some(ram) = bus.cart.ram_bank_bytes_mut(usize::from(bank)) {
+                window_write(ram, 0xA000, base, bytes);
+            }
+        }
+        MemSpace::Bus => {
+            for (addr, byte) in (base..=0xFFFF).zip(bytes) {
+                write8_scalar(bus, addr, *byte);
+            }
+        }
     }
 }
 
@@ -175,10 +377,18 @@ where
 pub struct Instance {
     pub core: AnyCore,
     pub sink: TransportFrameSink,
+    /// Writes audio into the audio slot pool when one is attached.
+    pub audio_sink: Option<TransportAudioSink>,
     pub next_frame_id: u64,
-    pub joypad: u8,
+    /// Host joypad state of each lane, re-applied after state loads and resets.
+    pub joypads: Vec<u8>,
     pub lanes: NonZeroUsize,
     boot: Option<BootSequence>,
+    /// Set while a breakpoint or watchpoint holds the instance paused.
+    paused: bool,
+    pending_break: Option<BreakReason>,
+    /// PC of the breakpoint that paused the instance, stepped over on resume.
+    break_pc: Option<u16>,
 }
 
 impl Instance {
@@ -186,10 +396,14 @@ impl Instance {
         Self {
             core: AnyCore::Scalar(Box::new(core)),
             sink,
+            audio_sink: None,
             next_frame_id: 0,
-            joypad: 0xFF,
+            joypads: vec![0xFF; 1],
             lanes: NonZeroUsize::new(1).unwrap(),
             boot: None,
+            paused: false,
+            pending_break: None,
+            break_pc: None,
         }
     }
 
@@ -197,10 +411,14 @@ impl Instance {
         Self {
             core: AnyCore::Simd2(Box::new(core)),
             sink,
+            audio_sink: None,
             next_frame_id: 0,
-            joypad: 0xFF,
+            joypads: vec![0xFF; 2],
             lanes: NonZeroUsize::new(2).unwrap(),
             boot: None,
+            paused: false,
+            pending_break: None,
+            break_pc: None,
         }
     }
 
@@ -208,10 +426,14 @@ impl Instance {
         Self {
             core: AnyCore::Simd4(Box::new(core)),
             sink,
+            audio_sink: None,
             next_frame_id: 0,
-            joypad: 0xFF,
+            joypads: vec![0xFF; 4],
             lanes: NonZeroUsize::new(4).unwrap(),
             boot: None,
+            paused: false,
+            pending_break: None,
+            break_pc: None,
         }
     }
 
@@ -219,10 +441,14 @@ impl Instance {
         Self {
             core: AnyCore::Simd8(Box::new(core)),
             sink,
+            audio_sink: None,
             next_frame_id: 0,
-            joypad: 0xFF,
+            joypads: vec![0xFF; 8],
             lanes: NonZeroUsize::new(8).unwrap(),
             boot: None,
+            paused: false,
+            pending_break: None,
+            break_pc: None,
         }
     }
 
@@ -230,7 +456,71 @@ impl Instance {
         if let Some(boot) = self.boot.as_mut() {
             return boot.step(budget);
         }
-        self.core.step_cycles(budget)
+        if self.paused {
+            return 0;
+        }
+        let cycles = self.core.step_cycles(budget);
+        if let Some(reason) = self.core.breakpoints_mut().take_hit() {
+            self.paused = true;
+            self.pending_break = Some(reason);
+            if reason == BreakReason::Breakpoint {
+                self.break_pc = Some(self.pc());
+            }
+        }
+        cycles
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut Breakpoints {
+        self.core.breakpoints_mut()
+    }
+
+    pub fn trace_mut(&mut self) -> &mut Trace {
+        self.core.trace_mut()
+    }
+
+    pub fn set_cheats(&mut self, cheats: &Cheats) {
+        self.core.set_cheats(cheats);
+    }
+
+    pub fn disassemble(&mut self, base: u16, count: usize) -> Vec<DisasmLineVM> {
+        self.core
+            .disassemble(base, count)
+            .into_iter()
+            .map(|line| DisasmLineVM {
+                addr: line.addr,
+                bytes: line.bytes,
+                text: line.text,
+            })
+            .collect()
+    }
+
+    /// Returns the mnemonic of the instruction at the current PC.
+    pub fn disasm_at_pc(&mut self) -> String {
+        let pc = self.pc();
+        self.core
+            .disassemble(pc, 1)
+            .pop()
+            .map(|line| line.text)
+            .unwrap_or_default()
+    }
+
+    /// Returns the break that paused the instance since the last call, if any.
+    pub fn take_break(&mut self) -> Option<BreakReason> {
+        self.pending_break.take()
+    }
+
+    /// Lifts a break pause.
+    ///
+    /// Steps over the breakpoint at the current PC only when that breakpoint
+    /// caused the pause; watchpoint pauses and running instances resume
+    /// without skipping anything.
+    pub fn resume(&mut self) {
+        let pc = self.pc();
+        self.paused = false;
+        self.pending_break = None;
+        if self.break_pc.take() == Some(pc) {
+            self.core.breakpoints_mut().resume_from(pc);
+        }
     }
 
     pub fn step_instructions(&mut self, count: u32) -> (u32, u16) {
@@ -262,17 +552,25 @@ impl Instance {
         self.next_frame_id
     }
 
-    pub fn load_rom(&mut self, rom: Arc<[u8]>) {
+    pub fn load_rom(&mut self, rom: Arc<[u8]>, save_ram: Option<&[u8]>) {
         let blank_rom = rom.iter().all(|&byte| byte == 0);
+        self.paused = false;
+        self.pending_break = None;
+        self.break_pc = None;
         self.core.load_rom(Arc::clone(&rom));
+        if let Some(bytes) = save_ram {
+            self.core.load_save_ram(bytes);
+        }
         let using_boot_rom = self.core.has_boot_rom();
+        let model = Model::for_rom(&rom);
         if using_boot_rom && !blank_rom {
-            self.core.reset_power_on(Model::Dmg);
+            self.core.reset_power_on(model);
             self.core.set_boot_rom_enabled(true);
             self.boot = None;
         } else {
-            self.core.reset_post_boot(Model::Dmg);
-            self.boot = if blank_rom {
+            self.core.reset_post_boot(model);
+            // The scripted logo scroll mimics the DMG boot ROM only.
+            self.boot = if blank_rom || model == Model::Cgb {
                 None
             } else {
                 match &mut self.core {
@@ -284,13 +582,156 @@ impl Instance {
         self.next_frame_id = 0;
     }
 
-    pub fn set_inputs(&mut self, joypad: u8) {
-        self.joypad = joypad;
-        match &mut self.core {
-            AnyCore::Scalar(core) => core.bus.set_inputs(joypad),
-            AnyCore::Simd2(core) => core.bus.set_inputs(joypad),
-            AnyCore::Simd4(core) => core.bus.set_inputs(joypad),
-            AnyCore::Simd8(core) => core.bus.set_inputs(joypad),
+    pub fn take_dirty_save_ram(&mut self) -> Vec<(u16, Arc<[u8]>)> {
+        self.core.take_dirty_save_ram()
+    }
+
+    /// Turns APU output buffering on or off for every lane.
+    pub fn set_audio_capture(&mut self, capture: bool) {
+        self.core.set_audio_capture(capture);
+    }
+
+    /// Drains the samples generated since the last call, written into the
+    /// audio slot pool when it has room and inline otherwise.
+    ///
+    /// Returns `None` when no samples were generated.
+    pub fn take_audio(&mut self) -> Option<AudioSpan> {
+        let (samples, sample_rate_hz) = self.core.take_audio();
+        if samples.is_empty() {
+            return None;
+        }
+        let channels = apu::OUTPUT_CHANNELS;
+        let slot_span = self
+            .audio_sink
+            .as_ref()
+            .and_then(|sink| sink.produce_audio(&samples));
+        Some(match slot_span {
+            Some(slot_span) => AudioSpan {
+                samples: Arc::from([]),
+                channels,
+                sample_rate_hz,
+                slot_span: Some(slot_span),
+                slot_frames: (samples.len() / usize::from(channels)) as u32,
+            },
+            None => AudioSpan {
+                samples: Arc::from(samples),
+                channels,
+                sample_rate_hz,
+                slot_span: None,
+                slot_frames: 0,
+            },
+        })
+    }
+
+    /// Encodes the state of `lane`, or returns `None` if the lane does not exist.
+    ///
+    /// SIMD lanes share the group's timers and PPU position, which the image
+    /// captures as seen by that lane.
+    pub fn save_state(&self, lane: u16) -> Option<Arc<[u8]>> {
+        let lane = usize::from(lane);
+        let bytes = match &self.core {
+            AnyCore::Scalar(core) if lane == 0 => core.save_state_bytes(),
+            AnyCore::Simd2(core) if lane < 2 => core.save_lane_state_bytes(lane),
+            AnyCore::Simd4(core) if lane < 4 => core.save_lane_state_bytes(lane),
+            AnyCore::Simd8(core) if lane < 8 => core.save_lane_state_bytes(lane),
+            _ => return None,
+        };
+        Some(Arc::from(bytes))
+    }
+
+    /// Restores `lane` from a save-state image, returning whether it was accepted.
+    ///
+    /// A restored state supersedes the scripted boot sequence; host inputs are
+    /// re-applied so held buttons survive the load. A SIMD lane whose image runs
+    /// on different timers or PPU position than the group continues on its own
+    /// scalar core until it reconverges.
+    pub fn load_state(&mut self, lane: u16, bytes: &[u8]) -> bool {
+        let lane = usize::from(lane);
+        let loaded = match &mut self.core {
+            AnyCore::Scalar(core) if lane == 0 => core.load_state_bytes(bytes).is_ok(),
+            AnyCore::Simd2(core) if lane < 2 => core.load_lane_state_bytes(lane, bytes).is_ok(),
+            AnyCore::Simd4(core) if lane < 4 => core.load_lane_state_bytes(lane, bytes).is_ok(),
+            AnyCore::Simd8(core) if lane < 8 => core.load_lane_state_bytes(lane, bytes).is_ok(),
+            _ => false,
+        };
+        if loaded {
+            self.boot = None;
+            self.core.set_lane_inputs(lane, self.joypads[lane]);
+        }
+        loaded
+    }
+
+    /// Restores every lane, and the timing they share, from one save-state image.
+    pub fn load_state_all(&mut self, bytes: &[u8]) -> bool {
+        let loaded = match &mut self.core {
+            AnyCore::Scalar(core) => core.load_state_bytes(bytes).is_ok(),
+            AnyCore::Simd2(core) => core.load_group_state_bytes(bytes).is_ok(),
+            AnyCore::Simd4(core) => core.load_group_state_bytes(bytes).is_ok(),
+            AnyCore::Simd8(core) => core.load_group_state_bytes(bytes).is_ok(),
+        };
+        if loaded {
+            self.boot = None;
+            for (lane, &joypad) in self.joypads.iter().enumerate() {
+                self.core.set_lane_inputs(lane, joypad);
+            }
+        }
+        loaded
+    }
+
+    /// Returns one lane to the post-boot state, optionally swapping its cartridge.
+    ///
+    /// Without a new ROM the lane keeps its battery RAM; `save_ram` seeds the
+    /// cartridge RAM afterwards either way. The scalar backend reloads its
+    /// cartridge, while SIMD lanes rejoin the running group without disturbing
+    /// their siblings. Returns `false` if the lane does not exist.
+    pub fn reset_lane(
+        &mut self,
+        lane: u16,
+        rom: Option<Arc<[u8]>>,
+        save_ram: Option<&[u8]>,
+    ) -> bool {
+        let lane = usize::from(lane);
+        if lane >= self.lanes.get() {
+            return false;
+        }
+        if let AnyCore::Scalar(core) = &self.core {
+            let current_ram;
+            let (rom, save_ram) = match rom {
+                Some(rom) => (rom, save_ram),
+                None => {
+                    current_ram = core.bus.cart.save_data();
+                    let rom = Arc::clone(core.bus.cart.rom());
+                    (rom, Some(save_ram.unwrap_or(&current_ram)))
+                }
+            };
+            self.load_rom(rom, save_ram);
+        } else {
+            match (&mut self.core, rom) {
+                (AnyCore::Simd2(core), Some(rom)) => core.load_lane_rom(lane, rom),
+                (AnyCore::Simd4(core), Some(rom)) => core.load_lane_rom(lane, rom),
+                (AnyCore::Simd8(core), Some(rom)) => core.load_lane_rom(lane, rom),
+                (AnyCore::Simd2(core), None) => core.reset_lane(lane),
+                (AnyCore::Simd4(core), None) => core.reset_lane(lane),
+                (AnyCore::Simd8(core), None) => core.reset_lane(lane),
+                (AnyCore::Scalar(_), _) => unreachable!("scalar handled above"),
+            }
+            if let (Some(bytes), Some(bus)) = (save_ram, self.lane_bus_mut(lane)) {
+                bus.load_save_ram(bytes);
+            }
+        }
+        self.core.set_lane_inputs(lane, self.joypads[lane]);
+        true
+    }
+
+    /// Applies host joypad inputs to the lanes selected by `lanes_mask`.
+    ///
+    /// Lanes outside the group are ignored.
+    pub fn set_inputs(&mut self, lanes_mask: u32, joypad: u8) {
+        for lane in 0..self.lanes.get().min(u32::BITS as usize) {
+            if lanes_mask & (1 << lane) != 0 {
+                self.joypads[lane] = joypad;
+                self.core.set_lane_inputs(lane, joypad);
+            }
         }
     }
 
@@ -353,21 +794,100 @@ impl Instance {
         }
     }
 
-    pub fn mem_window(&self, space: MemSpace, base: u16, len: u16) -> Vec<u8> {
+    /// Returns the SIMD lane divergence counters; scalar groups report zeros.
+    pub fn lanes_snapshot(&self) -> LanesVM {
         match &self.core {
-            AnyCore::Scalar(core) => match space {
-                MemSpace::Vram => window_slice(core.bus.vram.as_ref(), 0x8000, base, len),
-                MemSpace::Wram => window_slice(core.bus.wram.as_ref(), 0xC000, base, len),
-                MemSpace::Oam => window_slice(core.bus.oam.as_ref(), 0xFE00, base, len),
-                MemSpace::Io => window_slice(core.bus.io.regs(), 0xFF00, base, len),
-            },
-            AnyCore::Simd2(core) => mem_window_simd::<2>(core, space, base, len),
-            AnyCore::Simd4(core) => mem_window_simd::<4>(core, space, base, len),
-            AnyCore::Simd8(core) => mem_window_simd::<8>(core, space, base, len),
+            AnyCore::Scalar(_) => LanesVM::default(),
+            AnyCore::Simd2(core) => lanes_from_stats(core.divergence()),
+            AnyCore::Simd4(core) => lanes_from_stats(core.divergence()),
+            AnyCore::Simd8(core) => lanes_from_stats(core.divergence()),
+        }
+    }
+
+    /// Returns the bus of one lane, or `None` if the lane does not exist.
+    fn lane_bus(&self, lane: usize) -> Option<&BusScalar> {
+        match &self.core {
+            AnyCore::Scalar(core) => (lane == 0).then_some(&core.bus),
+            AnyCore::Simd2(core) => (lane < 2).then(|| core.bus.lane(lane)),
+            AnyCore::Simd4(core) => (lane < 4).then(|| core.bus.lane(lane)),
+            AnyCore::Simd8(core) => (lane < 8).then(|| core.bus.lane(lane)),
+        }
+    }
+
+    /// Returns the bus of one lane, or `None` if the lane does not exist.
+    fn lane_bus_mut(&mut self, lane: usize) -> Option<&mut BusScalar> {
+        match &mut self.core {
+            AnyCore::Scalar(core) => (lane == 0).then_some(&mut core.bus),
+            AnyCore::Simd2(core) => (lane < 2).then(|| core.bus.lane_mut(lane)),
+            AnyCore::Simd4(core) => (lane < 4).then(|| core.bus.lane_mut(lane)),
+            AnyCore::Simd8(core) => (lane < 8).then(|| core.bus.lane_mut(lane)),
+        }
+    }
+
+    /// Pokes `bytes` into `space` on one lane; out-of-range lanes and addresses are ignored.
+    ///
+    /// Region spaces are written directly without side effects, while `Bus`
+    /// writes go through the MMU exactly like CPU stores.
+    pub fn write_mem(&mut self, lane: usize, space: MemSpace, base: u16, bytes: &[u8]) {
+        if let Some(bus) = self.lane_bus_mut(lane) {
+            write_mem_bus(bus, space, base, bytes);
+        }
+    }
+
+    /// Overwrites the registers present in `regs` on one lane.
+    pub fn set_registers(&mut self, lane: usize, regs: &RegistersPatch) {
+        if lane >= self.lanes.get() {
+            return;
+        }
+        let writes = [
+            (Register::A, regs.a.map(u16::from)),
+            (Register::F, regs.f.map(u16::from)),
+            (Register::B, regs.b.map(u16::from)),
+            (Register::C, regs.c.map(u16::from)),
+            (Register::D, regs.d.map(u16::from)),
+            (Register::E, regs.e.map(u16::from)),
+            (Register::H, regs.h.map(u16::from)),
+            (Register::L, regs.l.map(u16::from)),
+            (Register::Sp, regs.sp),
+            (Register::Pc, regs.pc),
+        ];
+        for (reg, value) in writes {
+            if let Some(value) = value {
+                self.core.set_lane_register(lane, reg, value);
+            }
+        }
+        if let Some(ime) = regs.ime {
+            self.core.set_ime(ime);
         }
     }
 
-    fn render_lane_into(&mut self, lane: usize, buf: &mut [u8]) {
+    /// Reads a debug memory window from one lane; out-of-range lanes read nothing.
+    pub fn mem_window(&self, lane: usize, space: MemSpace, base: u16, len: u16) -> Vec<u8> {
+        self.lane_bus(lane)
+            .map(|bus| mem_window_bus(bus, space, base, len))
+            .unwrap_or_default()
+    }
+
+    /// Returns the cartridge ROM inserted into one lane.
+    pub fn lane_rom(&self, lane: u16) -> Option<Arc<[u8]>> {
+        self.lane_bus(usize::from(lane))
+            .map(|bus| Arc::clone(bus.cart.rom()))
+    }
+
+    /// Runs until the next frame is ready or a frame's worth of cycles has passed,
+    /// whichever comes first, so a disabled LCD cannot stall the caller.
+    pub fn run_frame(&mut self) {
+        let mut remaining = CYCLES_PER_FRAME;
+        while remaining > 0 && !self.frame_ready() {
+            let cycles = self.step_cycles(remaining);
+            if cycles == 0 {
+                break;
+            }
+            remaining = remaining.saturating_sub(cycles);
+        }
+    }
+
+    pub fn render_lane_into(&mut self, lane: usize, buf: &mut [u8]) {
         let total_lanes = self.lanes.get();
         assert!(
             lane < total_lanes,
@@ -405,6 +925,14 @@ impl Instance {
         }
     }
 
+    /// Drops the completed frame of every lane so the next one can start.
+    ///
+    /// Only valid once the boot animation is over, which loading a state ensures.
+    pub fn skip_frame(&mut self) {
+        debug_assert!(self.boot.is_none(), "boot animation renders every frame");
+        self.core.skip_frame();
+    }
+
     pub fn render_into(&mut self, buf: &mut [u8]) {
         self.render_lane_into(0, buf);
     }
@@ -708,6 +1236,17 @@ fn vram_index_checked(addr: u16, len: usize) -> Option<usize> {
         None
     }
 }
+fn window_write(data: &mut [u8], region_base: u16, base: u16, bytes: &[u8]) {
+    let Some(start) = base.checked_sub(region_base).map(usize::from) else {
+        return;
+    };
+    if let Some(window) = data.get_mut(start..) {
+        for (dst, src) in window.iter_mut().zip(bytes) {
+            *dst = *src;
+        }
+    }
+}
+
 fn window_slice(data: &[u8], region_base: u16, base: u16, len: u16) -> Vec<u8> {
     if len == 0 {
         return Vec::new();
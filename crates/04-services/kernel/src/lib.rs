@@ -6,18 +6,20 @@ mod instance;
 mod sink_transport;
 
 use crate::instance::Instance;
-use crate::sink_transport::TransportFrameSink;
-use kernel_core::ppu_stub::CYCLES_PER_FRAME;
-use kernel_core::{BusScalar, BusSimd, Core, CoreConfig, Model, SimdCore};
+use crate::sink_transport::{TransportAudioSink, TransportFrameSink};
+use kernel_core::ppu::CYCLES_PER_FRAME;
+use kernel_core::{BusScalar, BusSimd, Cheat, Cheats, Core, CoreConfig, Model, RtcClock, SimdCore};
 use log::{debug, trace};
 use service_abi::{
-    DebugCmd, DebugRep, FrameSpan, KernelCmd, KernelRep, KernelServiceHandle, Service, StepKind,
-    SubmitOutcome, SubmitPolicy, TickPurpose,
+    BreakReason, DebugCmd, DebugRep, ForkRejection, ForkedLane, FrameSpan, KernelCmd, KernelRep,
+    KernelServiceHandle, MemAccessVM, Service, StepKind, SubmitOutcome, SubmitPolicy, TickPurpose,
+    TraceEntryVM, WatchAccess,
 };
 use services_common::{drain_queue, try_submit_queue, LocalQueue};
 use smallvec::{smallvec, SmallVec};
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use transport::{SlotPool, SlotPoolConfig, SlotPoolHandle};
 #[cfg(target_arch = "wasm32")]
@@ -36,6 +38,21 @@ fn load_default_rom() -> Arc<[u8]> {
     Arc::from(vec![0u8; 0x8000].into_boxed_slice())
 }
 
+/// Returns the host wall-clock time in whole seconds since the UNIX epoch.
+fn host_unix_seconds() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)
+    }
+}
+
 struct SingleThreadCell<T> {
     value: UnsafeCell<T>,
 }
@@ -65,6 +82,7 @@ impl<T> SingleThreadCell<T> {
 pub struct KernelFarm {
     instances: HashMap<u16, Instance>,
     frame_pool: Arc<SlotPoolHandle>,
+    audio_pool: Option<Arc<SlotPoolHandle>>,
     core_config: CoreConfig,
     default_rom: Arc<[u8]>,
     boot_rom: Option<Arc<[u8]>>,
@@ -77,6 +95,7 @@ impl KernelFarm {
         Self {
             instances: HashMap::new(),
             frame_pool,
+            audio_pool: None,
             core_config,
             default_rom,
             boot_rom,
@@ -85,70 +104,80 @@ impl KernelFarm {
 
     fn ensure_instance(&mut self, id: u16) -> &mut Instance {
         if !self.instances.contains_key(&id) {
-            let sink = TransportFrameSink::new(
-                Arc::clone(&self.frame_pool),
-                self.core_config.frame_width,
-                self.core_config.frame_height,
-            );
-            let lanes = self.core_config.lanes.get();
-            let instance = match lanes {
-                1 => {
-                    let mut core = Core::new(
-                        BusScalar::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
-                        self.core_config,
-                        Model::Dmg,
-                    );
-                    if self.boot_rom.is_some() {
-                        core.reset_power_on(Model::Dmg);
-                    } else {
-                        core.reset_post_boot(Model::Dmg);
-                    }
-                    Instance::new_scalar(core, sink)
+            let instance = self.build_instance(self.core_config.lanes.get());
+            self.instances.insert(id, instance);
+        }
+        self.instances.get_mut(&id).expect("instance exists")
+    }
+
+    /// Builds a group with `lanes` lanes running the default cartridge.
+    fn build_instance(&self, lanes: usize) -> Instance {
+        let sink = TransportFrameSink::new(
+            Arc::clone(&self.frame_pool),
+            self.core_config.frame_width,
+            self.core_config.frame_height,
+        );
+        let core_config = CoreConfig {
+            lanes: NonZeroUsize::new(lanes).expect("group lanes must be non-zero"),
+            ..self.core_config
+        };
+        let mut instance = match lanes {
+            1 => {
+                let mut core = Core::new(
+                    BusScalar::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
+                    core_config,
+                    Model::Dmg,
+                );
+                if self.boot_rom.is_some() {
+                    core.reset_power_on(Model::Dmg);
+                } else {
+                    core.reset_post_boot(Model::Dmg);
                 }
-                2 => {
-                    let mut core = SimdCore::<2>::new(
-                        BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
-                        self.core_config,
-                        Model::Dmg,
-                    );
-                    if self.boot_rom.is_some() {
-                        core.reset_power_on(Model::Dmg);
-                    } else {
-                        core.reset_post_boot(Model::Dmg);
-                    }
-                    Instance::new_simd2(core, sink)
+                Instance::new_scalar(core, sink)
+            }
+            2 => {
+                let mut core = SimdCore::<2>::new(
+                    BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
+                    core_config,
+                    Model::Dmg,
+                );
+                if self.boot_rom.is_some() {
+                    core.reset_power_on(Model::Dmg);
+                } else {
+                    core.reset_post_boot(Model::Dmg);
                 }
-                4 => {
-                    let mut core = SimdCore::<4>::new(
-                        BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
-                        self.core_config,
-                        Model::Dmg,
-                    );
-                    if self.boot_rom.is_some() {
-                        core.reset_power_on(Model::Dmg);
-                    } else {
-                        core.reset_post_boot(Model::Dmg);
-                    }
-                    Instance::new_simd4(core, sink)
+                Instance::new_simd2(core, sink)
+            }
+            4 => {
+                let mut core = SimdCore::<4>::new(
+                    BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
+                    core_config,
+                    Model::Dmg,
+                );
+                if self.boot_rom.is_some() {
+                    core.reset_power_on(Model::Dmg);
+                } else {
+                    core.reset_post_boot(Model::Dmg);
                 }
-                8 => {
-                    let mut core = SimdCore::<8>::new(
-                        BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
-                        self.core_config,
-                        Model::Dmg,
-                    );
-                    if self.boot_rom.is_some() {
-                        core.reset_power_on(Model::Dmg);
-                    } else {
-                        core.reset_post_boot(Model::Dmg);
-                    }
-                    Instance::new_simd8(core, sink)
+                Instance::new_simd4(core, sink)
+            }
+            8 => {
+                let mut core = SimdCore::<8>::new(
+                    BusSimd::new(Arc::clone(&self.default_rom), self.boot_rom.clone()),
+                    core_config,
+                    Model::Dmg,
+                );
+                if self.boot_rom.is_some() {
+                    core.reset_power_on(Model::Dmg);
+                } else {
+                    core.reset_post_boot(Model::Dmg);
                 }
-                _ => panic!("unsupported SIMD lane count {}", lanes),
-            };
-            self.instances.insert(id, instance);
-        }
-        self.instances.get_mut(&id).expect("instance exists")
+                Instance::new_simd8(core, sink)
+            }
+            _ => panic!("unsupported SIMD lane count {}", lanes),
+        };
+        instance.audio_sink = self.audio_pool.clone().map(TransportAudioSink::new);
+        instance
     }
 
     fn publish_lane_frames(
@@ -214,7 +243,11 @@ impl KernelFarm {
     }
 
     pub fn tick(&mut self, id: u16, budget: u32, out: &mut Vec<KernelRep>) -> u32 {
+        let wall_clock = self.core_config.rtc_clock == RtcClock::WallClock;
         let inst = self.ensure_instance(id);
+        if wall_clock {
+            inst.core.sync_rtc(host_unix_seconds());
+        }
         #[cfg(target_arch = "wasm32")]
         web_sys::console::log_1(&JsValue::from_str(&format!(
             "kernel::tick wasm start group={id}"
@@ -313,6 +346,29 @@ impl KernelFarm {
             (1u32 << lanes) - 1
         };
 
+        for (lane, bytes) in inst.take_dirty_save_ram() {
+            out.push(KernelRep::SaveRamDirty {
+                group: id,
+                lane,
+                bytes,
+            });
+        }
+
+        if let Some(span) = inst.take_audio() {
+            out.push(KernelRep::AudioReady { group: id, span });
+        }
+
+        if let Some(reason) = inst.take_break() {
+            out.push(KernelRep::Debug {
+                group: id,
+                rep: DebugRep::BreakpointHit {
+                    group: id,
+                    pc: inst.pc(),
+                    reason: break_reason_to_abi(reason),
+                },
+            });
+        }
+
         out.push(KernelRep::TickDone {
             group: id,
             lanes_mask,
@@ -332,16 +388,143 @@ impl KernelFarm {
         cycles
     }
 
-    pub fn load_rom(&mut self, id: u16, rom: Arc<[u8]>) -> usize {
+    /// Returns the lane count of group `id`, or of the group a tick would create.
+    fn group_lanes(&self, id: u16) -> usize {
+        self.instances
+            .get(&id)
+            .map_or(self.core_config.lanes.get(), |inst| inst.lanes.get())
+    }
+
+    pub fn load_rom(&mut self, id: u16, rom: Arc<[u8]>, save_ram: Option<&[u8]>) -> usize {
         let inst = self.ensure_instance(id);
-        inst.load_rom(rom.clone());
+        inst.load_rom(rom.clone(), save_ram);
         rom.len()
     }
 
-    pub fn set_inputs(&mut self, id: u16, joypad: u8) {
+    pub fn save_state(&self, id: u16, lane: u16) -> Option<Arc<[u8]>> {
+        self.instances.get(&id)?.save_state(lane)
+    }
+
+    pub fn load_state(&mut self, id: u16, lane: u16, bytes: &[u8]) -> bool {
+        self.instances
+            .get_mut(&id)
+            .is_some_and(|inst| inst.load_state(lane, bytes))
+    }
+
+    pub fn set_inputs(&mut self, id: u16, lanes_mask: u32, joypad: u8) {
         if let Some(inst) = self.instances.get_mut(&id) {
-            inst.set_inputs(joypad);
+            inst.set_inputs(lanes_mask, joypad);
+        }
+    }
+
+    /// Resets one lane of group `id`, optionally inserting a different cartridge.
+    ///
+    /// Returns `false` if the group or lane does not exist.
+    pub fn reset_lane(
+        &mut self,
+        id: u16,
+        lane: u16,
+        rom: Option<Arc<[u8]>>,
+        save_ram: Option<&[u8]>,
+    ) -> bool {
+        self.instances
+            .get_mut(&id)
+            .is_some_and(|inst| inst.reset_lane(lane, rom, save_ram))
+    }
+
+    /// Decodes `codes` and activates them on every lane of group `id`.
+    ///
+    /// Returns the number of active codes and the codes that failed to decode.
+    pub fn set_cheats(&mut self, id: u16, codes: &[String]) -> (u16, Vec<String>) {
+        let mut rejected = Vec::new();
+        let cheats = Cheats::new(codes.iter().filter_map(|code| match code.parse::<Cheat>() {
+            Ok(cheat) => Some(cheat),
+            Err(_) => {
+                rejected.push(code.clone());
+                None
+            }
+        }));
+        let Some(inst) = self.instances.get_mut(&id) else {
+            return (0, rejected);
+        };
+        inst.set_cheats(&cheats);
+        (
+            u16::try_from(cheats.codes().len()).unwrap_or(u16::MAX),
+            rejected,
+        )
+    }
+
+    /// Branches `source_lane` of group `id` into a new group `target`, one lane
+    /// per input script, and plays every script to its end.
+    ///
+    /// Returns the frames run and each scripted lane's end state with a
+    /// thumbnail of its last frame. Refuses to replace a running `target`, and
+    /// to run more than `MAX_FORK_FRAMES` frames in one call.
+    pub fn fork(
+        &mut self,
+        id: u16,
+        source_lane: u16,
+        target: u16,
+        scripts: &[Arc<[u8]>],
+    ) -> Result<(u32, Vec<ForkedLane>), ForkRejection> {
+        if target == id {
+            return Err(ForkRejection::TargetIsSource);
+        }
+        if self.instances.contains_key(&target) {
+            return Err(ForkRejection::TargetInUse);
+        }
+        if scripts.is_empty() || scripts.len() > MAX_FORK_LANES {
+            return Err(ForkRejection::ScriptCount);
+        }
+        let frames = scripts.iter().map(|script| script.len()).max().unwrap_or(0);
+        if frames > MAX_FORK_FRAMES {
+            return Err(ForkRejection::ScriptTooLong);
+        }
+        let source = self
+            .instances
+            .get(&id)
+            .ok_or(ForkRejection::SourceMissing)?;
+        let state = source
+            .save_state(source_lane)
+            .ok_or(ForkRejection::SourceMissing)?;
+        let rom = source
+            .lane_rom(source_lane)
+            .ok_or(ForkRejection::SourceMissing)?;
+
+        let mut inst = self.build_instance(scripts.len().next_power_of_two());
+        // Scripted frames are never played back, so skip buffering their audio.
+        inst.set_audio_capture(false);
+        inst.load_rom(rom, None);
+        if !inst.load_state_all(&state) {
+            return Err(ForkRejection::StateRejected);
+        }
+
+        for frame in 0..frames {
+            for (lane, script) in scripts.iter().enumerate() {
+                if let Some(&joypad) = script.get(frame) {
+                    inst.set_inputs(1 << lane, joypad);
+                }
+            }
+            inst.run_frame();
+            inst.skip_frame();
+        }
+
+        let (width, height) = (self.core_config.frame_width, self.core_config.frame_height);
+        let mut pixels = vec![0u8; usize::from(width) * usize::from(height) * 4];
+        let mut lanes = Vec::with_capacity(scripts.len());
+        for lane in 0..scripts.len() {
+            inst.render_lane_into(lane, &mut pixels);
+            lanes.push(ForkedLane {
+                lane: lane as u16,
+                state: inst
+                    .save_state(lane as u16)
+                    .ok_or(ForkRejection::StateRejected)?,
+                thumbnail: thumbnail(&pixels, width, height),
+            });
         }
+        inst.set_audio_capture(true);
+        self.instances.insert(target, inst);
+        Ok((u32::try_from(frames).unwrap_or(u32::MAX), lanes))
     }
 
     pub fn terminate(&mut self, id: u16) {
@@ -353,46 +536,240 @@ impl KernelFarm {
             DebugCmd::Snapshot { group } => {
                 let inst = self.ensure_instance(*group);
                 let snapshot = inst.inspector_snapshot();
-                out.push(KernelRep::Debug(DebugRep::Snapshot(snapshot)));
+                let lanes = inst.lanes_snapshot();
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Snapshot(snapshot),
+                });
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Lanes(lanes),
+                });
             }
             DebugCmd::MemWindow {
                 group,
+                lane,
                 space,
                 base,
                 len,
             } => {
                 let inst = self.ensure_instance(*group);
-                let bytes = inst.mem_window(*space, *base, *len);
-                out.push(KernelRep::Debug(DebugRep::MemWindow {
-                    space: *space,
-                    base: *base,
-                    bytes: Arc::<[u8]>::from(bytes.into_boxed_slice()),
-                }));
+                let bytes = inst.mem_window(usize::from(*lane), *space, *base, *len);
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::MemWindow {
+                        space: *space,
+                        base: *base,
+                        bytes: Arc::<[u8]>::from(bytes.into_boxed_slice()),
+                    },
+                });
             }
             DebugCmd::StepInstruction { group, count } => {
                 let inst = self.ensure_instance(*group);
                 let (cycles, pc) = inst.step_instructions(*count);
-                out.push(KernelRep::Debug(DebugRep::Stepped {
-                    kind: StepKind::Instruction,
-                    cycles,
-                    pc,
-                    disasm: None,
-                }));
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Stepped {
+                        kind: StepKind::Instruction,
+                        cycles,
+                        pc,
+                        disasm: Some(inst.disasm_at_pc()),
+                    },
+                });
             }
             DebugCmd::StepFrame { group } => {
                 let mut intermediate = Vec::new();
                 let cycles = self.tick(*group, CYCLES_PER_FRAME, &mut intermediate);
-                let pc = self.instances.get(group).map(|inst| inst.pc()).unwrap_or(0);
+                let (pc, disasm) = self
+                    .instances
+                    .get_mut(group)
+                    .map(|inst| (inst.pc(), Some(inst.disasm_at_pc())))
+                    .unwrap_or((0, None));
                 out.extend(intermediate);
-                out.push(KernelRep::Debug(DebugRep::Stepped {
-                    kind: StepKind::Frame,
-                    cycles,
-                    pc,
-                    disasm: None,
-                }));
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Stepped {
+                        kind: StepKind::Frame,
+                        cycles,
+                        pc,
+                        disasm,
+                    },
+                });
+            }
+            DebugCmd::Disassemble { group, base, count } => {
+                let inst = self.ensure_instance(*group);
+                let lines = inst.disassemble(*base, usize::from(*count));
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Disassembly { base: *base, lines },
+                });
+            }
+            DebugCmd::SetBreakpoint { group, pc } => {
+                let inst = self.ensure_instance(*group);
+                inst.breakpoints_mut().set_breakpoint(*pc);
             }
+            DebugCmd::ClearBreakpoint { group, pc } => {
+                let inst = self.ensure_instance(*group);
+                inst.breakpoints_mut().clear_breakpoint(*pc);
+            }
+            DebugCmd::SetWatchpoint {
+                group,
+                addr_range,
+                access,
+            } => {
+                let inst = self.ensure_instance(*group);
+                inst.breakpoints_mut()
+                    .set_watchpoint(addr_range.clone(), watch_access_from_abi(*access));
+            }
+            DebugCmd::ClearWatchpoint {
+                group,
+                addr_range,
+                access,
+            } => {
+                let inst = self.ensure_instance(*group);
+                inst.breakpoints_mut()
+                    .clear_watchpoint(addr_range.clone(), watch_access_from_abi(*access));
+            }
+            DebugCmd::Continue { group } => {
+                if let Some(inst) = self.instances.get_mut(group) {
+                    inst.resume();
+                }
+            }
+            DebugCmd::SetTracing {
+                group,
+                enabled,
+                capacity,
+            } => {
+                let trace = self.ensure_instance(*group).trace_mut();
+                if *enabled {
+                    trace.enable(*capacity as usize);
+                } else {
+                    trace.disable();
+                }
+            }
+            DebugCmd::WriteMem {
+                group,
+                lane,
+                space,
+                base,
+                bytes,
+            } => {
+                let inst = self.ensure_instance(*group);
+                inst.write_mem(usize::from(*lane), *space, *base, bytes);
+            }
+            DebugCmd::SetRegisters { group, lane, regs } => {
+                let inst = self.ensure_instance(*group);
+                inst.set_registers(usize::from(*lane), regs);
+            }
+            DebugCmd::ReadTrace { group, max } => {
+                let trace = self.ensure_instance(*group).trace_mut();
+                let entries = trace
+                    .drain(usize::from(*max))
+                    .into_iter()
+                    .map(trace_entry_to_abi)
+                    .collect();
+                out.push(KernelRep::Debug {
+                    group: *group,
+                    rep: DebugRep::Trace {
+                        entries,
+                        remaining: trace.len() as u32,
+                        dropped: trace.dropped(),
+                    },
+                });
+            }
+        }
+    }
+}
+
+fn watch_access_from_abi(access: WatchAccess) -> kernel_core::WatchAccess {
+    match access {
+        WatchAccess::Read => kernel_core::WatchAccess::Read,
+        WatchAccess::Write => kernel_core::WatchAccess::Write,
+    }
+}
+
+fn watch_access_to_abi(access: kernel_core::WatchAccess) -> WatchAccess {
+    match access {
+        kernel_core::WatchAccess::Read => WatchAccess::Read,
+        kernel_core::WatchAccess::Write => WatchAccess::Write,
+    }
+}
+
+fn break_reason_to_abi(reason: kernel_core::BreakReason) -> BreakReason {
+    match reason {
+        kernel_core::BreakReason::Breakpoint => BreakReason::Breakpoint,
+        kernel_core::BreakReason::Watchpoint { addr, access } => BreakReason::Watchpoint {
+            addr,
+            access: watch_access_to_abi(access),
+        },
+    }
+}
+
+fn trace_entry_to_abi(entry: kernel_core::TraceEntry) -> TraceEntryVM {
+    TraceEntryVM {
+        cycle: entry.cycle,
+        pc: entry.pc,
+        pcmem: entry.pcmem,
+        a: entry.a,
+        f: entry.f,
+        b: entry.b,
+        c: entry.c,
+        d: entry.d,
+        e: entry.e,
+        h: entry.h,
+        l: entry.l,
+        sp: entry.sp,
+        accesses: entry
+            .accesses
+            .into_iter()
+            .map(|access| MemAccessVM {
+                addr: access.addr,
+                value: access.value,
+                access: watch_access_to_abi(access.kind),
+            })
+            .collect(),
+    }
+}
+
+/// Widest group a `Fork` can create.
+const MAX_FORK_LANES: usize = 8;
+
+/// Longest script a single `Fork` runs, about five seconds of play; the fork
+/// runs inside `try_submit`, so this bounds how long one command can stall it.
+const MAX_FORK_FRAMES: usize = 300;
+
+/// Factor by which fork thumbnails shrink each frame dimension.
+const THUMBNAIL_SCALE: u16 = 4;
+
+/// Box-filters an RGBA frame down by [`THUMBNAIL_SCALE`] in each dimension.
+fn thumbnail(pixels: &[u8], width: u16, height: u16) -> FrameSpan {
+    let scale = usize::from(THUMBNAIL_SCALE);
+    let (src_width, thumb_width, thumb_height) = (
+        usize::from(width),
+        usize::from(width / THUMBNAIL_SCALE),
+        usize::from(height / THUMBNAIL_SCALE),
+    );
+    let mut thumb = Vec::with_capacity(thumb_width * thumb_height * 4);
+    for ty in 0..thumb_height {
+        for tx in 0..thumb_width {
+            let mut sum = [0u32; 4];
+            for y in ty * scale..(ty + 1) * scale {
+                for x in tx * scale..(tx + 1) * scale {
+                    let offset = (y * src_width + x) * 4;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += u32::from(pixels[offset + channel]);
+                    }
+                }
+            }
+            thumb.extend(sum.map(|total| (total / (scale * scale) as u32) as u8));
         }
     }
+    FrameSpan {
+        width: width / THUMBNAIL_SCALE,
+        height: height / THUMBNAIL_SCALE,
+        pixels: Arc::from(thumb),
+        slot_span: None,
+    }
 }
 
 const DEFAULT_CAPACITY: usize = 64;
@@ -400,6 +777,9 @@ const DEFAULT_CAPACITY: usize = 64;
 /// Kernel service implementation backed by [`KernelFarm`].
 pub struct KernelService {
     reports: LocalQueue<KernelRep>,
+    /// Latest `SaveRamDirty` per group lane. Kept out of `reports` so coalescing a
+    /// tick can never evict a battery save; each snapshot supersedes the last.
+    save_ram: LocalQueue<KernelRep>,
     capacity: usize,
     farm: SingleThreadCell<KernelFarm>,
 }
@@ -437,6 +817,20 @@ impl KernelService {
         Self::build(capacity, frame_pool, core_config)
     }
 
+    /// Constructs a kernel service that also writes audio into `audio_pool`.
+    pub fn new_with_pools(
+        capacity: usize,
+        frame_pool: Arc<SlotPoolHandle>,
+        audio_pool: Arc<SlotPoolHandle>,
+        core_config: CoreConfig,
+    ) -> Self {
+        let service = Self::build(capacity, frame_pool, core_config);
+        service
+            .farm
+            .with_mut(|farm| farm.audio_pool = Some(audio_pool));
+        service
+    }
+
     /// Constructs a kernel service using the default frame pool but custom core configuration.
     pub fn new_with_config(core_config: CoreConfig) -> Self {
         Self::build(DEFAULT_CAPACITY, default_frame_pool(), core_config)
@@ -445,21 +839,54 @@ impl KernelService {
     fn build(capacity: usize, frame_pool: Arc<SlotPoolHandle>, core_config: CoreConfig) -> Self {
         Self {
             reports: LocalQueue::with_capacity(capacity),
+            save_ram: LocalQueue::with_capacity(4),
             capacity,
             farm: SingleThreadCell::new(KernelFarm::new(frame_pool, core_config)),
         }
     }
 
-    fn reports_for(cmd: &KernelCmd) -> usize {
+    fn reports_for(&self, cmd: &KernelCmd) -> usize {
         match cmd {
-            KernelCmd::Tick { .. } => 2,
+            // One frame per lane, then SaveRamDirty, AudioReady, a possible
+            // BreakpointHit and TickDone.
+            KernelCmd::Tick { group, .. } => {
+                self.farm.with_mut(|farm| farm.group_lanes(*group)) + 4
+            }
             KernelCmd::LoadRom { .. } => 1,
             KernelCmd::SetInputs { .. } => 0,
             KernelCmd::Terminate { .. } => 0,
+            KernelCmd::SaveState { .. } | KernelCmd::LoadState { .. } => 1,
+            KernelCmd::ResetLane { .. } => 1,
+            KernelCmd::SetCheats { .. } => 1,
+            KernelCmd::Fork { .. } => 1,
             KernelCmd::Debug(debug) => debug.expected_reports(),
         }
     }
 
+    /// Moves `SaveRamDirty` reports into the pending save slot, replacing any
+    /// older snapshot of the same lane, and returns the remaining reports.
+    fn stash_save_ram(&self, reports: SmallVec<[KernelRep; 8]>) -> SmallVec<[KernelRep; 8]> {
+        reports
+            .into_iter()
+            .filter_map(|rep| match rep {
+                KernelRep::SaveRamDirty { group, lane, .. } => {
+                    self.save_ram.with_mut(|pending| {
+                        pending.retain(|old| {
+                            !matches!(
+                                old,
+                                KernelRep::SaveRamDirty { group: g, lane: l, .. }
+                                    if *g == group && *l == lane
+                            )
+                        });
+                        pending.push_back(rep);
+                    });
+                    None
+                }
+                rep => Some(rep),
+            })
+            .collect()
+    }
+
     fn submit_policy(cmd: &KernelCmd) -> SubmitPolicy {
         match cmd {
             KernelCmd::Tick { purpose, .. } => match purpose {
@@ -469,6 +896,10 @@ impl KernelService {
             KernelCmd::LoadRom { .. } => SubmitPolicy::Lossless,
             KernelCmd::SetInputs { .. } => SubmitPolicy::Lossless,
             KernelCmd::Terminate { .. } => SubmitPolicy::Lossless,
+            KernelCmd::SaveState { .. } | KernelCmd::LoadState { .. } => SubmitPolicy::Lossless,
+            KernelCmd::ResetLane { .. } => SubmitPolicy::Lossless,
+            KernelCmd::SetCheats { .. } => SubmitPolicy::Lossless,
+            KernelCmd::Fork { .. } => SubmitPolicy::BestEffort,
             KernelCmd::Debug(debug) => debug.submit_policy(),
         }
     }
@@ -482,17 +913,26 @@ impl KernelService {
                 });
                 SmallVec::from_vec(out)
             }
-            KernelCmd::LoadRom { group, bytes } => {
+            KernelCmd::LoadRom {
+                group,
+                bytes,
+                save_ram,
+            } => {
                 let len = self
                     .farm
-                    .with_mut(|farm| farm.load_rom(*group, Arc::clone(bytes)));
+                    .with_mut(|farm| farm.load_rom(*group, Arc::clone(bytes), save_ram.as_deref()));
                 smallvec![KernelRep::RomLoaded {
                     group: *group,
                     bytes_len: len,
                 }]
             }
-            KernelCmd::SetInputs { group, joypad, .. } => {
-                self.farm.with_mut(|farm| farm.set_inputs(*group, *joypad));
+            KernelCmd::SetInputs {
+                group,
+                lanes_mask,
+                joypad,
+            } => {
+                self.farm
+                    .with_mut(|farm| farm.set_inputs(*group, *lanes_mask, *joypad));
                 SmallVec::new()
             }
             KernelCmd::Terminate { group } => {
@@ -505,6 +945,69 @@ impl KernelService {
                     .with_mut(|farm| farm.handle_debug(debug, &mut out));
                 SmallVec::from_vec(out)
             }
+            KernelCmd::SaveState { group, lane } => {
+                let bytes = self.farm.with_mut(|farm| farm.save_state(*group, *lane));
+                smallvec![KernelRep::StateSaved {
+                    group: *group,
+                    lane: *lane,
+                    bytes,
+                }]
+            }
+            KernelCmd::LoadState { group, lane, bytes } => {
+                let ok = self
+                    .farm
+                    .with_mut(|farm| farm.load_state(*group, *lane, bytes));
+                smallvec![KernelRep::StateLoaded {
+                    group: *group,
+                    lane: *lane,
+                    ok,
+                }]
+            }
+            KernelCmd::ResetLane {
+                group,
+                lane,
+                rom,
+                save_ram,
+            } => {
+                let ok = self.farm.with_mut(|farm| {
+                    farm.reset_lane(*group, *lane, rom.clone(), save_ram.as_deref())
+                });
+                smallvec![KernelRep::LaneReset {
+                    group: *group,
+                    lane: *lane,
+                    ok,
+                }]
+            }
+            KernelCmd::SetCheats { group, codes } => {
+                let (active, rejected) = self.farm.with_mut(|farm| farm.set_cheats(*group, codes));
+                smallvec![KernelRep::CheatsSet {
+                    group: *group,
+                    active,
+                    rejected,
+                }]
+            }
+            KernelCmd::Fork {
+                group,
+                source_lane,
+                target_group,
+                input_scripts,
+            } => {
+                let forked = self
+                    .farm
+                    .with_mut(|farm| farm.fork(*group, *source_lane, *target_group, input_scripts));
+                let rep = match forked {
+                    Ok((frames, lanes)) => KernelRep::Forked {
+                        group: *target_group,
+                        frames,
+                        lanes,
+                    },
+                    Err(reason) => KernelRep::ForkRejected {
+                        group: *target_group,
+                        reason,
+                    },
+                };
+                smallvec![rep]
+            }
         }
     }
 }
@@ -515,14 +1018,19 @@ impl Service for KernelService {
 
     fn try_submit(&self, cmd: &Self::Cmd) -> SubmitOutcome {
         let policy = Self::submit_policy(cmd);
-        let needed = Self::reports_for(cmd);
-        try_submit_queue(&self.reports, self.capacity, policy, needed, || {
-            self.materialise_reports(cmd)
+        let needed = self.reports_for(cmd);
+        let capacity = self
+            .capacity
+            .saturating_sub(self.save_ram.with_mut(|pending| pending.len()));
+        try_submit_queue(&self.reports, capacity, policy, needed, || {
+            self.stash_save_ram(self.materialise_reports(cmd))
         })
     }
 
     fn drain(&self, max: usize) -> SmallVec<[Self::Rep; 8]> {
-        drain_queue(&self.reports, max)
+        let mut out = drain_queue(&self.save_ram, max);
+        out.extend(drain_queue(&self.reports, max - out.len()));
+        out
     }
 }
 
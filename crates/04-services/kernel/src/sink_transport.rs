@@ -68,3 +68,64 @@ impl TransportFrameSink {
         }
     }
 }
+
+/// Audio sink writing interleaved `f32` samples into a transport slot pool.
+pub struct TransportAudioSink {
+    pool: Arc<SlotPoolHandle>,
+}
+
+impl TransportAudioSink {
+    pub fn new(pool: Arc<SlotPoolHandle>) -> Self {
+        Self { pool }
+    }
+
+    /// Converts `samples` to `f32` in as many slots as they need and marks the
+    /// slots ready in order.
+    ///
+    /// Returns `None` when `samples` is empty or the pool is short of slots, in
+    /// which case the caller keeps the samples inline.
+    pub fn produce_audio(&self, samples: &[i16]) -> Option<SlotSpan> {
+        if samples.is_empty() {
+            return None;
+        }
+        self.pool.with_mut(|pool| {
+            let per_slot = pool.slot_size() / size_of::<f32>();
+            let chunks = samples.chunks(per_slot);
+            let mut slots = Vec::with_capacity(chunks.len());
+            for _ in 0..chunks.len() {
+                let Some(idx) = pool.try_acquire_free() else {
+                    log::trace!(
+                        "TransportAudioSink::produce_audio: free slot unavailable (samples={}) – falling back to inline samples",
+                        samples.len()
+                    );
+                    for idx in slots {
+                        pool.release_free(idx);
+                    }
+                    return None;
+                };
+                slots.push(idx);
+            }
+
+            for (&idx, chunk) in slots.iter().zip(chunks) {
+                let slot = pool.slot_mut(idx);
+                for (out, &sample) in slot.chunks_exact_mut(size_of::<f32>()).zip(chunk) {
+                    out.copy_from_slice(&(f32::from(sample) / 32768.0).to_le_bytes());
+                }
+            }
+
+            for (pushed, &idx) in slots.iter().enumerate() {
+                if pool.push_ready(idx) == SlotPush::WouldBlock {
+                    // Slots already pushed are skipped by the consumer's resync.
+                    for &idx in &slots[pushed..] {
+                        pool.release_free(idx);
+                    }
+                    return None;
+                }
+            }
+            Some(SlotSpan {
+                start_idx: slots[0],
+                count: slots.len() as u32,
+            })
+        })
+    }
+}
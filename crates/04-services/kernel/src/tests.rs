@@ -1,11 +1,12 @@
-use super::{instance::AnyCore, KernelFarm, KernelService};
+use super::{instance::AnyCore, KernelFarm, KernelService, MAX_FORK_FRAMES};
 use kernel_core::bus::IoRegs;
-use kernel_core::ppu_stub::CYCLES_PER_FRAME;
+use kernel_core::ppu::CYCLES_PER_FRAME;
+use kernel_core::state::CoreState;
 use kernel_core::BusScalar;
 use kernel_core::CoreConfig;
 use service_abi::{
-    DebugCmd, DebugRep, FrameSpan, KernelCmd, KernelRep, KernelServiceHandle, MemSpace, StepKind,
-    SubmitOutcome, TickPurpose,
+    BreakReason, DebugCmd, DebugRep, ForkRejection, FrameSpan, KernelCmd, KernelRep,
+    KernelServiceHandle, MemSpace, RegistersPatch, Service, StepKind, SubmitOutcome, TickPurpose,
 };
 use std::env;
 use std::fs;
@@ -62,6 +63,7 @@ fn load_blank_rom(service: &KernelServiceHandle, group: u16) {
     let load_cmd = KernelCmd::LoadRom {
         group,
         bytes: Arc::clone(&rom),
+        save_ram: None,
     };
     assert_eq!(service.try_submit(&load_cmd), SubmitOutcome::Accepted);
     let reports = collect_reports(service.drain(4));
@@ -124,6 +126,7 @@ fn tick_produces_frame() {
     let load_cmd = KernelCmd::LoadRom {
         group: 1,
         bytes: Arc::clone(&rom),
+        save_ram: None,
     };
     assert_eq!(service.try_submit(&load_cmd), SubmitOutcome::Accepted);
     let reports = collect_reports(service.drain(4));
@@ -143,6 +146,55 @@ fn tick_produces_frame() {
     });
     assert!(matches!(reports[0], KernelRep::LaneFrame { .. }));
     assert!(matches!(reports[1], KernelRep::TickDone { .. }));
+    match &reports[2] {
+        KernelRep::AudioReady { group: 1, span } => {
+            assert_eq!(span.channels, 2);
+            assert_eq!(span.sample_rate_hz, 48_000);
+            let frames = span.samples.len() / 2;
+            assert!(
+                (790..=820).contains(&frames),
+                "unexpected frame count {frames}"
+            );
+        }
+        other => panic!("expected AudioReady, got {other:?}"),
+    }
+}
+
+#[test]
+fn silent_tick_reports_no_audio() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let config = CoreConfig {
+        audio_capture: false,
+        ..Default::default()
+    };
+    let service = KernelService::new_with_frame_pool(8, frame_pool, config);
+    let load_cmd = KernelCmd::LoadRom {
+        group: 1,
+        bytes: Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()),
+        save_ram: None,
+    };
+    assert_eq!(service.try_submit(&load_cmd), SubmitOutcome::Accepted);
+    collect_reports(service.drain(4));
+
+    let tick_cmd = KernelCmd::Tick {
+        group: 1,
+        purpose: TickPurpose::Display,
+        budget: CYCLES_PER_FRAME,
+    };
+    assert_eq!(service.try_submit(&tick_cmd), SubmitOutcome::Accepted);
+    let reports = collect_reports(service.drain(8));
+    assert!(reports
+        .iter()
+        .any(|rep| matches!(rep, KernelRep::TickDone { .. })));
+    assert!(!reports
+        .iter()
+        .any(|rep| matches!(rep, KernelRep::AudioReady { .. })));
 }
 
 #[test]
@@ -162,6 +214,7 @@ fn frame_reports_slot_span_when_ready() {
     let load_cmd = KernelCmd::LoadRom {
         group: 7,
         bytes: Arc::clone(&rom),
+        save_ram: None,
     };
     assert_eq!(service.try_submit(&load_cmd), SubmitOutcome::Accepted);
     collect_reports(service.drain(4));
@@ -205,6 +258,80 @@ fn frame_reports_slot_span_when_ready() {
     );
 }
 
+#[test]
+fn audio_is_written_into_audio_pool_as_f32() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 2,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let audio_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: 4096,
+        })
+        .expect("slot pool"),
+    ));
+    let service: KernelServiceHandle = Arc::new(KernelService::new_with_pools(
+        8,
+        frame_pool,
+        Arc::clone(&audio_pool),
+        CoreConfig::default(),
+    ));
+    let load_cmd = KernelCmd::LoadRom {
+        group: 3,
+        bytes: Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()),
+        save_ram: None,
+    };
+    assert_eq!(service.try_submit(&load_cmd), SubmitOutcome::Accepted);
+    collect_reports(service.drain(4));
+
+    let tick_cmd = KernelCmd::Tick {
+        group: 3,
+        purpose: TickPurpose::Display,
+        budget: CYCLES_PER_FRAME,
+    };
+    assert_eq!(service.try_submit(&tick_cmd), SubmitOutcome::Accepted);
+    let span = collect_reports(service.drain(8))
+        .into_iter()
+        .find_map(|rep| match rep {
+            KernelRep::AudioReady { span, .. } => Some(span),
+            _ => None,
+        })
+        .expect("expected audio report");
+
+    assert!(span.samples.is_empty(), "pooled audio embeds no samples");
+    let slot_span = span
+        .slot_span
+        .clone()
+        .expect("audio should carry a slot span");
+    assert!((790..=820).contains(&span.frames()));
+    let bytes = span.frames() * usize::from(span.channels) * 4;
+    assert_eq!(slot_span.count as usize, bytes.div_ceil(4096));
+
+    audio_pool.with_mut(|p| {
+        for chunk in 0..slot_span.count {
+            let SlotPop::Ok { slot_idx } = p.pop_ready() else {
+                panic!("expected ready audio slot");
+            };
+            if chunk == 0 {
+                assert_eq!(slot_idx, slot_span.start_idx);
+            }
+            for sample in p.slot_mut(slot_idx).chunks_exact(4) {
+                let sample = f32::from_le_bytes(sample.try_into().unwrap());
+                assert!(
+                    (-1.0..1.0).contains(&sample),
+                    "sample {sample} out of range"
+                );
+            }
+            p.release_free(slot_idx);
+        }
+        assert!(matches!(p.pop_ready(), SlotPop::Empty));
+    });
+}
+
 #[test]
 fn simd8_lane_frames_are_unique_and_release_slots() {
     let frame_pool = Arc::new(SlotPoolHandle::new(
@@ -221,7 +348,7 @@ fn simd8_lane_frames_are_unique_and_release_slots() {
     };
     let mut farm = KernelFarm::new(Arc::clone(&frame_pool), config);
     let blank_rom = Arc::<[u8]>::from(vec![0x00u8; 0x8000].into_boxed_slice());
-    farm.load_rom(0, Arc::clone(&blank_rom));
+    farm.load_rom(0, Arc::clone(&blank_rom), None);
 
     {
         let inst = farm.ensure_instance(0);
@@ -319,6 +446,7 @@ fn backpressure_prevents_frame_without_slot() {
     let load_cmd = KernelCmd::LoadRom {
         group: 1,
         bytes: Arc::clone(&rom),
+        save_ram: None,
     };
     service.try_submit(&load_cmd);
     service.drain(4);
@@ -351,6 +479,182 @@ fn backpressure_prevents_frame_without_slot() {
         .any(|rep| matches!(rep, KernelRep::LaneFrame { .. })));
 }
 
+#[test]
+fn battery_ram_is_seeded_and_reported_when_dirty() {
+    let mut rom = vec![0x00u8; 0x8000];
+    rom[0x0147] = 0x03; // MBC1 + RAM + battery
+    rom[0x0149] = 0x02; // 8 KiB
+    let rom = Arc::<[u8]>::from(rom.into_boxed_slice());
+
+    let mut farm = KernelFarm::new(super::default_frame_pool(), CoreConfig::default());
+    farm.load_rom(0, rom, Some(&[0xAB, 0xCD]));
+
+    let inst = farm.ensure_instance(0);
+    let AnyCore::Scalar(core) = &mut inst.core else {
+        panic!("expected scalar backend");
+    };
+    assert_eq!(&core.bus.cart.ram()[..2], &[0xAB, 0xCD]);
+
+    let mut out = Vec::new();
+    farm.tick(0, 4, &mut out);
+    assert!(
+        !out.iter()
+            .any(|rep| matches!(rep, KernelRep::SaveRamDirty { .. })),
+        "seeding must not mark RAM dirty"
+    );
+
+    let inst = farm.ensure_instance(0);
+    let AnyCore::Scalar(core) = &mut inst.core else {
+        panic!("expected scalar backend");
+    };
+    core.bus.cart.write_control(0x0000, 0x0A);
+    core.bus.cart.write_ram(0xA001, 0xEF);
+
+    out.clear();
+    farm.tick(0, 4, &mut out);
+    let saved = out
+        .iter()
+        .find_map(|rep| match rep {
+            KernelRep::SaveRamDirty {
+                group: 0,
+                lane: 0,
+                bytes,
+            } => Some(Arc::clone(bytes)),
+            _ => None,
+        })
+        .expect("SaveRamDirty report");
+    assert_eq!(saved.len(), 0x2000);
+    assert_eq!(&saved[..2], &[0xAB, 0xEF]);
+}
+
+#[test]
+fn dirty_save_ram_survives_coalesced_ticks() {
+    let mut rom = vec![0x00u8; 0x8000];
+    rom[0x0147] = 0x03; // MBC1 + RAM + battery
+    rom[0x0149] = 0x02; // 8 KiB
+    let service =
+        KernelService::new_with_frame_pool(6, super::default_frame_pool(), CoreConfig::default());
+    service.try_submit(&KernelCmd::LoadRom {
+        group: 0,
+        bytes: Arc::from(rom.into_boxed_slice()),
+        save_ram: None,
+    });
+    service.drain(4);
+
+    service.farm.with_mut(|farm| {
+        let AnyCore::Scalar(core) = &mut farm.ensure_instance(0).core else {
+            panic!("expected scalar backend");
+        };
+        core.bus.cart.write_control(0x0000, 0x0A);
+        core.bus.cart.write_ram(0xA000, 0x5A);
+    });
+
+    let tick = KernelCmd::Tick {
+        group: 0,
+        purpose: TickPurpose::Display,
+        budget: 4,
+    };
+    assert_eq!(service.try_submit(&tick), SubmitOutcome::Accepted);
+    for _ in 0..3 {
+        assert_eq!(service.try_submit(&tick), SubmitOutcome::Coalesced);
+    }
+
+    let saves: Vec<_> = collect_reports(service.drain(16))
+        .into_iter()
+        .filter_map(|rep| match rep {
+            KernelRep::SaveRamDirty {
+                group: 0,
+                lane: 0,
+                bytes,
+            } => Some(bytes),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        saves.len(),
+        1,
+        "the dirty snapshot is reported exactly once"
+    );
+    assert_eq!(saves[0][0], 0x5A);
+}
+
+#[test]
+fn battery_ram_is_reported_per_lane() {
+    let mut rom = vec![0x00u8; 0x8000];
+    rom[0x0147] = 0x03; // MBC1 + RAM + battery
+    rom[0x0149] = 0x02; // 8 KiB
+    let rom = Arc::<[u8]>::from(rom.into_boxed_slice());
+    let config = CoreConfig {
+        lanes: NonZeroUsize::new(2).expect("non-zero lanes"),
+        ..Default::default()
+    };
+    let mut farm = KernelFarm::new(super::default_frame_pool(), config);
+    farm.load_rom(0, rom, None);
+
+    let write_lane = |farm: &mut KernelFarm, lane: usize, value: u8| {
+        let AnyCore::Simd2(core) = &mut farm.ensure_instance(0).core else {
+            panic!("expected 2-lane backend");
+        };
+        let cart = &mut core.bus.lane_mut(lane).cart;
+        cart.write_control(0x0000, 0x0A);
+        cart.write_ram(0xA000, value);
+    };
+    let dirty = |out: &[KernelRep]| -> Vec<(u16, u8)> {
+        out.iter()
+            .filter_map(|rep| match rep {
+                KernelRep::SaveRamDirty { lane, bytes, .. } => Some((*lane, bytes[0])),
+                _ => None,
+            })
+            .collect()
+    };
+
+    write_lane(&mut farm, 1, 0x11);
+    let mut out = Vec::new();
+    farm.tick(0, 4, &mut out);
+    assert_eq!(dirty(&out), [(1, 0x11)]);
+
+    write_lane(&mut farm, 0, 0x22);
+    write_lane(&mut farm, 1, 0x33);
+    out.clear();
+    farm.tick(0, 4, &mut out);
+    assert_eq!(dirty(&out), [(0, 0x22), (1, 0x33)]);
+}
+
+#[test]
+fn tick_reserves_a_report_per_lane() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 16,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let config = CoreConfig {
+        lanes: NonZeroUsize::new(8).expect("non-zero lanes"),
+        ..Default::default()
+    };
+    let tick = KernelCmd::Tick {
+        group: 0,
+        purpose: TickPurpose::Display,
+        budget: CYCLES_PER_FRAME,
+    };
+
+    let small = KernelService::new_with_frame_pool(11, Arc::clone(&frame_pool), config);
+    assert_eq!(small.try_submit(&tick), SubmitOutcome::WouldBlock);
+
+    let service = KernelService::new_with_frame_pool(12, frame_pool, config);
+    assert_eq!(service.try_submit(&tick), SubmitOutcome::Accepted);
+    let reports = collect_reports(service.drain(16));
+    assert_eq!(
+        reports
+            .iter()
+            .filter(|rep| matches!(rep, KernelRep::LaneFrame { .. }))
+            .count(),
+        8
+    );
+    assert!(reports.len() <= 12);
+}
+
 #[test]
 fn debug_snapshot_reports_initial_cpu_state() {
     let service = KernelService::new_handle(8);
@@ -364,7 +668,10 @@ fn debug_snapshot_reports_initial_cpu_state() {
     let snapshot = reports
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(DebugRep::Snapshot(s)) => Some(s),
+            KernelRep::Debug {
+                rep: DebugRep::Snapshot(s),
+                ..
+            } => Some(s),
             _ => None,
         })
         .expect("snapshot report");
@@ -382,6 +689,7 @@ fn debug_mem_window_respects_requested_length() {
 
     let outcome = service.try_submit(&KernelCmd::Debug(DebugCmd::MemWindow {
         group,
+        lane: 0,
         space: MemSpace::Vram,
         base: 0x8000,
         len: 0x20,
@@ -392,7 +700,10 @@ fn debug_mem_window_respects_requested_length() {
     let window = reports
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(DebugRep::MemWindow { bytes, .. }) => Some(bytes),
+            KernelRep::Debug {
+                rep: DebugRep::MemWindow { bytes, .. },
+                ..
+            } => Some(bytes),
             _ => None,
         })
         .expect("mem window report");
@@ -400,6 +711,69 @@ fn debug_mem_window_respects_requested_length() {
     assert_eq!(window.len(), 0x20);
 }
 
+fn request_mem_window(
+    service: &KernelServiceHandle,
+    group: u16,
+    space: MemSpace,
+    base: u16,
+    len: u16,
+) -> Arc<[u8]> {
+    service.try_submit(&KernelCmd::Debug(DebugCmd::MemWindow {
+        group,
+        lane: 0,
+        space,
+        base,
+        len,
+    }));
+    drain_debug(service, 4)
+        .into_iter()
+        .find_map(|rep| match rep {
+            KernelRep::Debug {
+                rep: DebugRep::MemWindow { bytes, .. },
+                ..
+            } => Some(bytes),
+            _ => None,
+        })
+        .expect("mem window report")
+}
+
+#[test]
+fn debug_mem_window_reaches_banks_hram_and_bus() {
+    let service = KernelService::new_handle(8);
+    let group = 6;
+    // MBC1 with 4 ROM banks and 4 RAM banks; each ROM bank starts with its number.
+    let mut rom = vec![0u8; 4 * 0x4000];
+    for bank in 0..4 {
+        rom[bank * 0x4000] = 0xB0 | bank as u8;
+    }
+    rom[0x0147] = 0x03;
+    rom[0x0149] = 0x03;
+    let load = KernelCmd::LoadRom {
+        group,
+        bytes: Arc::from(rom),
+        save_ram: None,
+    };
+    assert_eq!(service.try_submit(&load), SubmitOutcome::Accepted);
+    drain_debug(&service, 4);
+
+    let bank = request_mem_window(&service, group, MemSpace::Rom { bank: 2 }, 0x4000, 2);
+    assert_eq!(bank.as_ref(), [0xB2, 0x00], "unmapped bank is readable");
+    let bank0 = request_mem_window(&service, group, MemSpace::Rom { bank: 0 }, 0x0000, 1);
+    assert_eq!(bank0.as_ref(), [0xB0]);
+    let ram = request_mem_window(&service, group, MemSpace::CartRam { bank: 3 }, 0xA000, 0x10);
+    assert_eq!(ram.len(), 0x10);
+    let missing = request_mem_window(&service, group, MemSpace::Rom { bank: 9 }, 0x4000, 4);
+    assert!(missing.is_empty());
+
+    let hram = request_mem_window(&service, group, MemSpace::Hram, 0xFF80, 0x100);
+    assert_eq!(hram.len(), 0x80, "HRAM window ends with IE");
+
+    let bus = request_mem_window(&service, group, MemSpace::Bus, 0x4000, 1);
+    assert_eq!(bus.as_ref(), [0xB1], "bus view follows the mapped bank");
+    let tail = request_mem_window(&service, group, MemSpace::Bus, 0xFFFE, 8);
+    assert_eq!(tail.len(), 2, "bus view stops at 0xFFFF");
+}
+
 #[test]
 fn debug_step_instruction_advances_pc() {
     let service = KernelService::new_handle(8);
@@ -410,7 +784,10 @@ fn debug_step_instruction_advances_pc() {
     let pc_before = drain_debug(&service, 4)
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(DebugRep::Snapshot(snapshot)) => Some(snapshot.cpu.pc),
+            KernelRep::Debug {
+                rep: DebugRep::Snapshot(snapshot),
+                ..
+            } => Some(snapshot.cpu.pc),
             _ => None,
         })
         .expect("snapshot after load");
@@ -425,17 +802,22 @@ fn debug_step_instruction_advances_pc() {
     let stepped = reports
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(DebugRep::Stepped {
-                kind: StepKind::Instruction,
-                cycles,
-                pc,
+            KernelRep::Debug {
+                rep:
+                    DebugRep::Stepped {
+                        kind: StepKind::Instruction,
+                        cycles,
+                        pc,
+                        disasm,
+                    },
                 ..
-            }) => Some((cycles, pc)),
+            } => Some((cycles, pc, disasm)),
             _ => None,
         })
         .expect("stepped report");
 
     assert!(stepped.0 > 0, "instruction step should consume cycles");
+    assert_eq!(stepped.2.as_deref(), Some("NOP"), "blank ROM is a NOP sled");
     assert_eq!(
         stepped.1,
         pc_before.wrapping_add(1),
@@ -447,13 +829,139 @@ fn debug_step_instruction_advances_pc() {
     let snapshot = drain_debug(&service, 4)
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(DebugRep::Snapshot(s)) => Some(s),
+            KernelRep::Debug {
+                rep: DebugRep::Snapshot(s),
+                ..
+            } => Some(s),
             _ => None,
         })
         .expect("snapshot after step");
     assert_eq!(snapshot.cpu.pc, pc_before.wrapping_add(1));
 }
 
+#[test]
+fn save_state_restores_lane_after_stepping() {
+    let service = KernelService::new_handle(8);
+    let group = 4;
+    load_blank_rom(&service, group);
+
+    let pc_of = |service: &KernelServiceHandle| {
+        service.try_submit(&KernelCmd::Debug(DebugCmd::Snapshot { group }));
+        drain_debug(service, 4)
+            .into_iter()
+            .find_map(|rep| match rep {
+                KernelRep::Debug {
+                    rep: DebugRep::Snapshot(snapshot),
+                    ..
+                } => Some(snapshot.cpu.pc),
+                _ => None,
+            })
+            .expect("snapshot")
+    };
+    let pc_saved = pc_of(&service);
+
+    assert_eq!(
+        service.try_submit(&KernelCmd::SaveState { group, lane: 0 }),
+        SubmitOutcome::Accepted
+    );
+    let bytes = drain_debug(&service, 4)
+        .into_iter()
+        .find_map(|rep| match rep {
+            KernelRep::StateSaved {
+                group: g,
+                lane: 0,
+                bytes,
+            } if g == group => bytes,
+            _ => None,
+        })
+        .expect("state bytes");
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::StepInstruction {
+        group,
+        count: 10,
+    }));
+    drain_debug(&service, 4);
+    assert_eq!(pc_of(&service), pc_saved.wrapping_add(10));
+
+    service.try_submit(&KernelCmd::LoadState {
+        group,
+        lane: 0,
+        bytes,
+    });
+    assert_eq!(
+        drain_debug(&service, 4),
+        vec![KernelRep::StateLoaded {
+            group,
+            lane: 0,
+            ok: true
+        }]
+    );
+    assert_eq!(pc_of(&service), pc_saved);
+
+    service.try_submit(&KernelCmd::LoadState {
+        group,
+        lane: 0,
+        bytes: Arc::from(*b"not a state"),
+    });
+    assert_eq!(
+        drain_debug(&service, 4),
+        vec![KernelRep::StateLoaded {
+            group,
+            lane: 0,
+            ok: false
+        }]
+    );
+    assert_eq!(
+        pc_of(&service),
+        pc_saved,
+        "rejected state leaves lane intact"
+    );
+}
+
+#[test]
+fn set_cheats_patches_rom_reads_and_reports_rejects() {
+    let service = KernelService::new_handle(8);
+    let group = 5;
+    load_blank_rom(&service, group);
+
+    let cpu_of = |service: &KernelServiceHandle| {
+        service.try_submit(&KernelCmd::Debug(DebugCmd::Snapshot { group }));
+        drain_debug(service, 4)
+            .into_iter()
+            .find_map(|rep| match rep {
+                KernelRep::Debug {
+                    rep: DebugRep::Snapshot(snapshot),
+                    ..
+                } => Some(snapshot.cpu),
+                _ => None,
+            })
+            .expect("snapshot")
+    };
+    let before = cpu_of(&service);
+
+    // Game Genie code turning the NOP at PC into `INC A`.
+    let code = format!("3C{:03X}{:X}", before.pc & 0x0FFF, (before.pc >> 12) ^ 0xF);
+    service.try_submit(&KernelCmd::SetCheats {
+        group,
+        codes: vec![code, "not-a-code".to_string()],
+    });
+    assert_eq!(
+        drain_debug(&service, 4),
+        vec![KernelRep::CheatsSet {
+            group,
+            active: 1,
+            rejected: vec!["not-a-code".to_string()],
+        }]
+    );
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::StepInstruction {
+        group,
+        count: 1,
+    }));
+    drain_debug(&service, 4);
+    assert_eq!(cpu_of(&service).a, before.a.wrapping_add(1));
+}
+
 #[test]
 fn debug_step_frame_produces_frame_and_tick() {
     let service = KernelService::new_handle(16);
@@ -469,7 +977,10 @@ fn debug_step_frame_produces_frame_and_tick() {
 
     for rep in reports {
         match rep {
-            KernelRep::Debug(DebugRep::Stepped { kind, cycles, .. }) => {
+            KernelRep::Debug {
+                rep: DebugRep::Stepped { kind, cycles, .. },
+                ..
+            } => {
                 if matches!(kind, StepKind::Frame) {
                     assert!(cycles > 0, "frame step should consume cycles");
                     saw_debug = true;
@@ -494,6 +1005,457 @@ fn debug_step_frame_produces_frame_and_tick() {
     assert!(saw_tick_done, "expected TickDone alongside frame step");
 }
 
+#[test]
+fn debug_disassemble_lists_instructions_from_base() {
+    let service = KernelService::new_handle(8);
+    let group = 3;
+    load_blank_rom(&service, group);
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::Disassemble {
+        group,
+        base: 0x0100,
+        count: 4,
+    }));
+    let (base, lines) = drain_debug(&service, 4)
+        .into_iter()
+        .find_map(|rep| match rep {
+            KernelRep::Debug {
+                rep: DebugRep::Disassembly { base, lines },
+                ..
+            } => Some((base, lines)),
+            _ => None,
+        })
+        .expect("disassembly report");
+
+    assert_eq!(base, 0x0100);
+    let addrs: Vec<u16> = lines.iter().map(|line| line.addr).collect();
+    assert_eq!(addrs, [0x0100, 0x0101, 0x0102, 0x0103]);
+    assert!(lines.iter().all(|line| line.text == "NOP"));
+}
+
+#[test]
+fn debug_trace_records_stepped_instructions() {
+    let service = KernelService::new_handle(8);
+    let group = 4;
+    load_blank_rom(&service, group);
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::SetTracing {
+        group,
+        enabled: true,
+        capacity: 2,
+    }));
+    service.try_submit(&KernelCmd::Debug(DebugCmd::StepInstruction {
+        group,
+        count: 3,
+    }));
+    service.try_submit(&KernelCmd::Debug(DebugCmd::ReadTrace { group, max: 1 }));
+    let (entries, remaining, dropped) = drain_debug(&service, 8)
+        .into_iter()
+        .find_map(|rep| match rep {
+            KernelRep::Debug {
+                rep:
+                    DebugRep::Trace {
+                        entries,
+                        remaining,
+                        dropped,
+                    },
+                ..
+            } => Some((entries, remaining, dropped)),
+            _ => None,
+        })
+        .expect("trace report");
+
+    assert_eq!((remaining, dropped), (1, 1));
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].pc, 0x0101, "oldest entry was evicted");
+    assert_eq!(entries[0].cycle, 4);
+    assert_eq!(entries[0].pcmem, [0x00; 4]);
+}
+
+#[test]
+fn debug_writes_target_a_single_simd_lane() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let config = CoreConfig {
+        lanes: NonZeroUsize::new(2).expect("non-zero lanes"),
+        ..Default::default()
+    };
+    let mut farm = KernelFarm::new(frame_pool, config);
+    let blank_rom = Arc::<[u8]>::from(vec![0x00u8; 0x8000].into_boxed_slice());
+    farm.load_rom(0, blank_rom, None);
+
+    let mut out = Vec::new();
+    farm.handle_debug(
+        &DebugCmd::WriteMem {
+            group: 0,
+            lane: 1,
+            space: MemSpace::Wram,
+            base: 0xC010,
+            bytes: Arc::from([0xDE, 0xAD].as_slice()),
+        },
+        &mut out,
+    );
+    farm.handle_debug(
+        &DebugCmd::WriteMem {
+            group: 0,
+            lane: 0,
+            space: MemSpace::Hram,
+            base: 0xFFFE,
+            bytes: Arc::from([0x42, 0x1F].as_slice()),
+        },
+        &mut out,
+    );
+    farm.handle_debug(
+        &DebugCmd::SetRegisters {
+            group: 0,
+            lane: 1,
+            regs: RegistersPatch {
+                b: Some(0x77),
+                sp: Some(0xD000),
+                ..RegistersPatch::default()
+            },
+        },
+        &mut out,
+    );
+    assert!(out.is_empty(), "writes produce no reports");
+
+    let inst = farm.ensure_instance(0);
+    let AnyCore::Simd2(core) = &inst.core else {
+        panic!("expected Simd2 backend");
+    };
+    assert_eq!(core.bus.lane(1).wram[0x10..0x12], [0xDE, 0xAD]);
+    assert_eq!(core.bus.lane(0).wram[0x10..0x12], [0x00, 0x00]);
+    assert_eq!(core.bus.lane(0).hram[0x7E], 0x42);
+    assert_eq!(core.bus.lane(0).ie, 0x1F, "last HRAM window byte is IE");
+    assert_eq!(core.cpu.b.to_array(), [0x00, 0x77]);
+    assert_eq!(core.cpu.sp.to_array(), [0xFFFE, 0xD000]);
+
+    for (lane, expected) in [(1, [0xDE, 0xAD]), (0, [0x00, 0x00])] {
+        let mut out = Vec::new();
+        farm.handle_debug(
+            &DebugCmd::MemWindow {
+                group: 0,
+                lane,
+                space: MemSpace::Wram,
+                base: 0xC010,
+                len: 2,
+            },
+            &mut out,
+        );
+        match out.as_slice() {
+            [KernelRep::Debug {
+                rep: DebugRep::MemWindow { bytes, .. },
+                ..
+            }] => assert_eq!(
+                bytes.as_ref(),
+                expected,
+                "lane {lane} reads back its own bytes"
+            ),
+            other => panic!("expected a mem window, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn simd_lanes_take_independent_inputs_states_and_roms() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let config = CoreConfig {
+        lanes: NonZeroUsize::new(2).expect("non-zero lanes"),
+        ..Default::default()
+    };
+    let mut farm = KernelFarm::new(frame_pool, config);
+    let blank_rom = Arc::<[u8]>::from(vec![0x00u8; 0x8000].into_boxed_slice());
+    farm.load_rom(0, blank_rom, None);
+
+    // Start held on lane 1 only.
+    farm.set_inputs(0, 0b10, 0x7F);
+    let saved = farm.save_state(0, 1).expect("lane 1 state");
+    farm.handle_debug(
+        &DebugCmd::WriteMem {
+            group: 0,
+            lane: 1,
+            space: MemSpace::Wram,
+            base: 0xC000,
+            bytes: Arc::from([0x5A].as_slice()),
+        },
+        &mut Vec::new(),
+    );
+    assert!(farm.load_state(0, 1, &saved));
+    assert!(farm.save_state(0, 2).is_none(), "lane 2 does not exist");
+
+    let mut other_rom = vec![0x00u8; 0x8000];
+    other_rom[0x0134] = b'B';
+    let other_rom = Arc::<[u8]>::from(other_rom.into_boxed_slice());
+    assert!(farm.reset_lane(0, 0, Some(Arc::clone(&other_rom)), None));
+    assert!(!farm.reset_lane(0, 2, None, None));
+
+    let inst = farm.ensure_instance(0);
+    assert_eq!(inst.joypads, [0xFF, 0x7F]);
+    let AnyCore::Simd2(core) = &inst.core else {
+        panic!("expected Simd2 backend");
+    };
+    assert_eq!(core.bus.lane(0).joyp_buttons, 0x0F);
+    assert_eq!(core.bus.lane(1).joyp_buttons, 0x07);
+    assert_eq!(core.bus.lane(1).wram[0], 0x00, "state load undid the poke");
+    assert!(Arc::ptr_eq(core.bus.lane(0).cart.rom(), &other_rom));
+    assert!(!Arc::ptr_eq(core.bus.lane(1).cart.rom(), &other_rom));
+}
+
+#[test]
+fn fork_plays_one_input_script_per_lane() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let mut farm = KernelFarm::new(frame_pool, CoreConfig::default());
+    // Select the buttons, then keep writing 1 to C000 once A has been seen held.
+    let mut rom = vec![0x00u8; 0x8000];
+    rom[0x0100..0x0112].copy_from_slice(&[
+        0x3E, 0x10, 0xE0, 0x00, // LD A,$10; LDH (P1),A
+        0xF0, 0x00, 0xE6, 0x01, // LDH A,(P1); AND $01
+        0x20, 0x02, 0x06, 0x01, // JR NZ,+2; LD B,1
+        0x78, 0xEA, 0x00, 0xC0, // LD A,B; LD ($C000),A
+        0x18, 0xF2, // JR -14
+    ]);
+    farm.load_rom(0, Arc::from(rom.into_boxed_slice()), None);
+
+    let held = Arc::<[u8]>::from([0xFF, 0xEF, 0xFF].as_slice());
+    let idle = Arc::<[u8]>::from([0xFF; 3].as_slice());
+    let empty = Arc::<[u8]>::from(Vec::new());
+    let (frames, lanes) = farm
+        .fork(0, 0, 1, &[held, idle, empty])
+        .expect("fork accepted");
+
+    assert_eq!(frames, 3);
+    assert_eq!(lanes.len(), 3);
+    for (index, lane) in lanes.iter().enumerate() {
+        assert_eq!(lane.lane, index as u16);
+        assert_eq!(farm.save_state(1, lane.lane).as_ref(), Some(&lane.state));
+        assert_eq!((lane.thumbnail.width, lane.thumbnail.height), (40, 36));
+        assert_eq!(lane.thumbnail.pixels.len(), 40 * 36 * 4);
+    }
+    let inst = farm.ensure_instance(1);
+    assert_eq!(
+        inst.lanes.get(),
+        4,
+        "three scripts round up to a 4-lane group"
+    );
+    let AnyCore::Simd4(core) = &inst.core else {
+        panic!("expected Simd4 backend");
+    };
+    assert_eq!(core.bus.lane(0).wram[0], 1);
+    assert_eq!(core.bus.lane(1).wram[0], 0);
+    assert_eq!(core.bus.lane(2).wram[0], 0);
+}
+
+#[test]
+fn forked_lanes_keep_the_source_interrupt_and_timing_state() {
+    let mut farm = KernelFarm::new(super::default_frame_pool(), CoreConfig::default());
+    // Count VBlank interrupts at C000 while halting in between.
+    let mut rom = vec![0x00u8; 0x8000];
+    rom[0x0040..0x0042].copy_from_slice(&[0x34, 0xD9]); // INC (HL); RETI
+    rom[0x0100..0x010A].copy_from_slice(&[
+        0x21, 0x00, 0xC0, // LD HL,$C000
+        0x3E, 0x01, 0xE0, 0xFF, // LD A,$01; LDH (IE),A
+        0xFB, // EI
+        0x76, 0x18, // HALT; JR -3
+    ]);
+    rom[0x010A] = 0xFD;
+    let rom = Arc::<[u8]>::from(rom.into_boxed_slice());
+    farm.load_rom(0, Arc::clone(&rom), None);
+    let source = farm.ensure_instance(0);
+    source.step_instructions(1); // Skips the boot animation.
+    for _ in 0..2 {
+        source.run_frame();
+        source.skip_frame();
+    }
+    source.step_cycles(5_000);
+    let state = farm.save_state(0, 0).expect("source state");
+
+    let idle = Arc::<[u8]>::from([0xFF; 3].as_slice());
+    let (frames, lanes) = farm
+        .fork(0, 0, 1, &[Arc::clone(&idle), idle])
+        .expect("fork accepted");
+
+    farm.load_rom(2, rom, None);
+    let reference = farm.ensure_instance(2);
+    reference.set_audio_capture(false); // Forks skip audio, like the scripted frames.
+    assert!(reference.load_state(0, &state));
+    for _ in 0..frames {
+        reference.run_frame();
+        reference.skip_frame();
+    }
+    let expected = reference.save_state(0).expect("reference state");
+    let AnyCore::Scalar(core) = &reference.core else {
+        panic!("expected scalar backend");
+    };
+    assert_eq!(core.bus.wram[0], 5, "one interrupt per frame");
+
+    let expected = CoreState::decode(&expected).expect("decodes");
+    for lane in &lanes {
+        let state = CoreState::decode(&lane.state).expect("decodes");
+        assert_eq!(state, expected, "lane {}", lane.lane);
+    }
+}
+
+#[test]
+fn fork_rejects_bad_requests_without_touching_groups() {
+    let frame_pool = Arc::new(SlotPoolHandle::new(
+        SlotPool::new(SlotPoolConfig {
+            slot_count: 4,
+            slot_size: DMG_FRAME_BYTES,
+        })
+        .expect("slot pool"),
+    ));
+    let mut farm = KernelFarm::new(frame_pool, CoreConfig::default());
+    farm.load_rom(0, Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()), None);
+    farm.load_rom(3, Arc::from(vec![0x00u8; 0x8000].into_boxed_slice()), None);
+    let script = || Arc::<[u8]>::from([0xFF].as_slice());
+
+    assert_eq!(
+        farm.fork(0, 0, 0, &[script()]),
+        Err(ForkRejection::TargetIsSource)
+    );
+    assert_eq!(
+        farm.fork(0, 0, 3, &[script()]),
+        Err(ForkRejection::TargetInUse),
+        "a running group is never replaced"
+    );
+    assert_eq!(
+        farm.fork(0, 1, 2, &[script()]),
+        Err(ForkRejection::SourceMissing)
+    );
+    assert_eq!(farm.fork(0, 0, 2, &[]), Err(ForkRejection::ScriptCount));
+    assert_eq!(
+        farm.fork(0, 0, 2, &vec![script(); 9]),
+        Err(ForkRejection::ScriptCount)
+    );
+    let long = Arc::<[u8]>::from(vec![0xFF; MAX_FORK_FRAMES + 1]);
+    assert_eq!(
+        farm.fork(0, 0, 2, &[long]),
+        Err(ForkRejection::ScriptTooLong)
+    );
+    assert!(farm.save_state(2, 0).is_none(), "no group was created");
+}
+
+#[test]
+fn rejected_fork_is_reported() {
+    let service = KernelService::new_handle(16);
+    load_blank_rom(&service, 0);
+    load_blank_rom(&service, 1);
+    let fork = KernelCmd::Fork {
+        group: 0,
+        source_lane: 0,
+        target_group: 1,
+        input_scripts: vec![Arc::from([0xFF].as_slice())],
+    };
+    assert_eq!(service.try_submit(&fork), SubmitOutcome::Accepted);
+    let reports = service.drain(4);
+    assert_eq!(
+        reports.as_slice(),
+        &[KernelRep::ForkRejected {
+            group: 1,
+            reason: ForkRejection::TargetInUse,
+        }]
+    );
+}
+
+#[test]
+fn breakpoint_pauses_group_until_continue() {
+    let service = KernelService::new_handle(16);
+    let group = 7;
+    load_blank_rom(&service, group);
+    let tick = KernelCmd::Tick {
+        group,
+        purpose: TickPurpose::Display,
+        budget: CYCLES_PER_FRAME,
+    };
+    let tick_cycles = |reports: &[KernelRep]| {
+        reports
+            .iter()
+            .find_map(|rep| match rep {
+                KernelRep::TickDone { cycles_done, .. } => Some(*cycles_done),
+                _ => None,
+            })
+            .expect("tick done")
+    };
+    let hit = |reports: &[KernelRep]| {
+        reports.iter().find_map(|rep| match rep {
+            KernelRep::Debug {
+                rep:
+                    DebugRep::BreakpointHit {
+                        group: g,
+                        pc,
+                        reason,
+                    },
+                ..
+            } if *g == group => Some((*pc, *reason)),
+            _ => None,
+        })
+    };
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::SetBreakpoint {
+        group,
+        pc: 0x0200,
+    }));
+    service.try_submit(&tick);
+    let reports = drain_debug(&service, 16);
+    assert_eq!(hit(&reports), Some((0x0200, BreakReason::Breakpoint)));
+
+    service.try_submit(&tick);
+    let reports = drain_debug(&service, 16);
+    assert_eq!(tick_cycles(&reports), 0, "paused group does not advance");
+    assert_eq!(hit(&reports), None, "hit is reported once");
+
+    service.try_submit(&KernelCmd::Debug(DebugCmd::ClearBreakpoint {
+        group,
+        pc: 0x0200,
+    }));
+    service.try_submit(&KernelCmd::Debug(DebugCmd::Continue { group }));
+    service.try_submit(&tick);
+    let reports = drain_debug(&service, 16);
+    assert!(tick_cycles(&reports) > 0, "continue resumes the group");
+    assert_eq!(hit(&reports), None);
+}
+
+#[test]
+fn continue_only_steps_over_the_breakpoint_that_paused() {
+    let mut farm = KernelFarm::new(super::default_frame_pool(), CoreConfig::default());
+    let inst = farm.ensure_instance(0);
+    inst.step_cycles(64);
+    let pc = inst.pc();
+
+    // Continue on a running instance must not swallow a breakpoint at its PC.
+    inst.breakpoints_mut().set_breakpoint(pc);
+    inst.resume();
+    assert_eq!(inst.step_cycles(64), 0);
+    assert_eq!(
+        inst.take_break(),
+        Some(kernel_core::BreakReason::Breakpoint)
+    );
+    assert_eq!(inst.pc(), pc);
+
+    // Continue after that breakpoint paused the instance steps over it.
+    inst.resume();
+    assert!(inst.step_cycles(64) > 0);
+    assert_eq!(inst.take_break(), None);
+    assert_ne!(inst.pc(), pc);
+}
+
 fn optional_tetris_rom() -> Option<Arc<[u8]>> {
     if let Ok(path) = env::var("GBX_TETRIS_ROM") {
         match fs::read(&path) {
@@ -550,10 +1512,10 @@ fn default_rom_matches_blargg_bundle() {
     let expected = vec![0u8; 0x8000];
     let expected_ref: &[u8] = expected.as_ref();
     let actual = match &inst.core {
-        AnyCore::Scalar(core) => core.bus.rom.as_ref(),
-        AnyCore::Simd2(core) => core.bus.lane(0).rom.as_ref(),
-        AnyCore::Simd4(core) => core.bus.lane(0).rom.as_ref(),
-        AnyCore::Simd8(core) => core.bus.lane(0).rom.as_ref(),
+        AnyCore::Scalar(core) => core.bus.cart.rom().as_ref(),
+        AnyCore::Simd2(core) => core.bus.lane(0).cart.rom().as_ref(),
+        AnyCore::Simd4(core) => core.bus.lane(0).cart.rom().as_ref(),
+        AnyCore::Simd8(core) => core.bus.lane(0).cart.rom().as_ref(),
     };
 
     assert_eq!(
@@ -582,7 +1544,7 @@ fn tetris_rom_produces_multiple_frames() {
     };
 
     let mut farm = KernelFarm::new(Arc::clone(&frame_pool), CoreConfig::default());
-    let _ = farm.load_rom(0, Arc::clone(&rom));
+    let _ = farm.load_rom(0, Arc::clone(&rom), None);
 
     const MAX_TICKS: usize = 2048;
     const TARGET_FRAMES_WITH_BOOT: usize = 120;
@@ -713,7 +1675,7 @@ fn tetris_rom_advances_without_boot_rom() {
     let mut farm = KernelFarm::new(Arc::clone(&frame_pool), CoreConfig::default());
     let rom =
         Arc::<[u8]>::from(include_bytes!("../../../../third_party/testroms/tetris.gb").as_slice());
-    let _ = farm.load_rom(0, Arc::clone(&rom));
+    let _ = farm.load_rom(0, Arc::clone(&rom), None);
 
     let mut total_frames = 0usize;
     let mut ticks_run = 0usize;
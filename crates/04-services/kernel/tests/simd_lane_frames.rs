@@ -5,7 +5,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
 
-use kernel_core::ppu_stub::CYCLES_PER_FRAME;
+use kernel_core::ppu::CYCLES_PER_FRAME;
 use services_kernel::KernelFarm;
 use transport::{SlotPool, SlotPoolConfig, SlotPoolHandle, SlotPop};
 
@@ -31,7 +31,7 @@ fn simd8_emits_lane_frames_beyond_initial_tick() {
         Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../third_party/testroms/tetris.gb");
     let rom_bytes = fs::read(rom_path).expect("read tetris rom");
     let rom = Arc::<[u8]>::from(rom_bytes.into_boxed_slice());
-    farm.load_rom(0, Arc::clone(&rom));
+    farm.load_rom(0, Arc::clone(&rom), None);
 
     // First few ticks should eventually produce a lane frame (boot path).
     assert!(
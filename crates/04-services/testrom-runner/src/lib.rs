@@ -0,0 +1,273 @@
+//! Headless runner for the vendored test ROM bundle.
+//!
+//! Each ROM runs on a scalar core until the completion signal recorded in its
+//! [`testdata::Expected`] metadata fires or its [`Budget`] runs out. Results are
+//! grouped per suite and render as JUnit XML or JSON (see [`Report`]) so CI can
+//! track accuracy regressions suite by suite.
+
+mod report;
+mod screenshot;
+
+pub use report::{CaseReport, Report, Status, SuiteReport, Tally};
+
+use kernel_core::mmu::peek8_scalar;
+use kernel_core::ppu::{CYCLES_PER_FRAME, SCREEN_HEIGHT, SCREEN_WIDTH};
+use kernel_core::{BusScalar, Core, Exec, Model, Scalar};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use testdata::{Expected, RomMeta, RomModel};
+
+/// `LD B,B`, the software breakpoint Mooneye and screenshot ROMs execute when done.
+const LD_B_B: u8 = 0x40;
+
+/// Registers B, C, D, E, H and L after a passing Mooneye test.
+const MOONEYE_PASS: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+/// Registers B, C, D, E, H and L after a failing Mooneye test.
+const MOONEYE_FAIL: [u8; 6] = [0x42; 6];
+
+/// Instructions executed between wall-clock checks.
+const CLOCK_CHECK_INTERVAL: u32 = 4096;
+
+/// Limits applied to every ROM in a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Budget {
+    /// Emulated CPU cycles before the ROM is reported as timed out.
+    pub max_cycles: u64,
+    /// Wall-clock time before the ROM is reported as timed out.
+    pub timeout: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            max_cycles: 300_000_000,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Runs one ROM from the bundle and evaluates its expected completion signal.
+///
+/// ROMs without a recorded expectation are skipped without being loaded.
+pub fn run_rom(meta: &RomMeta, budget: Budget) -> CaseReport {
+    if meta.expected == Expected::Unknown {
+        return CaseReport::new(meta, Status::Skipped, 0, Duration::ZERO)
+            .with_message("no completion signal recorded");
+    }
+    let rom = testdata::bytes(meta.path);
+    let reference = testdata::reference_png(meta.path);
+    run_bytes(meta, rom, reference.as_deref(), budget)
+}
+
+/// Runs `rom` against the expectation in `meta`.
+///
+/// `reference` is the PNG compared against the final frame of screenshot ROMs.
+/// Panics raised by the core are caught and reported as [`Status::Error`].
+pub fn run_bytes(
+    meta: &RomMeta,
+    rom: Arc<[u8]>,
+    reference: Option<&[u8]>,
+    budget: Budget,
+) -> CaseReport {
+    let started = Instant::now();
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        Run::new(meta, rom, budget, started).execute(reference)
+    }));
+    let elapsed = started.elapsed();
+    match outcome {
+        Ok((status, cycles, message)) => {
+            CaseReport::new(meta, status, cycles, elapsed).with_message(message)
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_owned())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "core panicked".to_owned());
+            CaseReport::new(meta, Status::Error, 0, elapsed).with_message(message)
+        }
+    }
+}
+
+/// Runs every ROM in `roms` and groups the results by suite.
+///
+/// Suites appear in the order their first ROM is listed. `on_case` sees each
+/// result as soon as it is available, e.g. for progress output.
+pub fn run_suite<'a>(
+    roms: impl IntoIterator<Item = &'a RomMeta>,
+    budget: Budget,
+    mut on_case: impl FnMut(&CaseReport),
+) -> Report {
+    let mut report = Report {
+        bundle: testdata::source_bundle(),
+        suites: Vec::new(),
+    };
+    for meta in roms {
+        let case = run_rom(meta, budget);
+        on_case(&case);
+        report.record(meta.suite, case);
+    }
+    report
+}
+
+/// Raw verdict of a run: status, cycles executed and a human-readable detail.
+type Verdict = (Status, u64, String);
+
+/// A single ROM execution in progress.
+struct Run<'a> {
+    meta: &'a RomMeta,
+    core: Core<Scalar, BusScalar>,
+    budget: Budget,
+    started: Instant,
+    cycles: u64,
+}
+
+impl<'a> Run<'a> {
+    fn new(meta: &'a RomMeta, rom: Arc<[u8]>, budget: Budget, started: Instant) -> Self {
+        let model = if meta.model == RomModel::Cgb {
+            Model::Cgb
+        } else {
+            Model::for_rom(&rom)
+        };
+        let mut core = Core::<Scalar, BusScalar>::from_rom(rom);
+        core.set_audio_capture(false);
+        core.reset_post_boot(model);
+        Self {
+            meta,
+            core,
+            budget,
+            started,
+            cycles: 0,
+        }
+    }
+
+    fn execute(mut self, reference: Option<&[u8]>) -> Verdict {
+        let mut serial_len = 0;
+        let mut instructions = 0u32;
+        loop {
+            if self.cycles >= self.budget.max_cycles {
+                return self.timeout(format!("no result within {} cycles", self.cycles));
+            }
+            instructions = instructions.wrapping_add(1);
+            if instructions.is_multiple_of(CLOCK_CHECK_INTERVAL)
+                && self.started.elapsed() >= self.budget.timeout
+            {
+                return self.timeout(format!(
+                    "no result within {:?} ({} cycles)",
+                    self.budget.timeout, self.cycles
+                ));
+            }
+
+            if !self.core.cpu.halted && self.opcode_at_pc() == LD_B_B {
+                match self.meta.expected {
+                    Expected::MooneyeFib => return self.mooneye_registers(),
+                    Expected::Screenshot => return self.screenshot(reference),
+                    _ => {}
+                }
+            }
+
+            let (cycles, _) = self.core.step_instruction();
+            self.cycles += u64::from(cycles);
+
+            if self.core.bus.serial_out.len() != serial_len {
+                serial_len = self.core.bus.serial_out.len();
+                if let Some(verdict) = self.serial() {
+                    return verdict;
+                }
+            }
+        }
+    }
+
+    fn opcode_at_pc(&self) -> u8 {
+        peek8_scalar(&self.core.bus, Scalar::to_u16(self.core.cpu.pc))
+    }
+
+    fn timeout(&self, mut message: String) -> Verdict {
+        if !self.core.bus.serial_out.is_empty() {
+            message.push_str("; serial: ");
+            message.push_str(&String::from_utf8_lossy(&self.core.bus.serial_out));
+        }
+        (Status::Timeout, self.cycles, message)
+    }
+
+    /// Checks the serial log after it grew.
+    fn serial(&self) -> Option<Verdict> {
+        let log = &self.core.bus.serial_out;
+        match self.meta.expected {
+            Expected::SerialAscii(marker) => {
+                let text = String::from_utf8_lossy(log);
+                if text.contains("Failed") {
+                    Some((Status::Failed, self.cycles, text.into_owned()))
+                } else if text.contains(marker.trim_end()) {
+                    Some((Status::Passed, self.cycles, String::new()))
+                } else {
+                    None
+                }
+            }
+            Expected::MooneyeFib => {
+                let tail = log.get(log.len().checked_sub(MOONEYE_PASS.len())?..)?;
+                if tail == MOONEYE_PASS {
+                    Some((Status::Passed, self.cycles, String::new()))
+                } else if tail == MOONEYE_FAIL {
+                    Some((
+                        Status::Failed,
+                        self.cycles,
+                        "serial reported the failure pattern".to_owned(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates the Mooneye register contract at the final `LD B,B`.
+    fn mooneye_registers(&self) -> Verdict {
+        let cpu = &self.core.cpu;
+        let regs = [cpu.b, cpu.c, cpu.d, cpu.e, cpu.h, cpu.l].map(Scalar::to_u8);
+        if regs == MOONEYE_PASS {
+            return (Status::Passed, self.cycles, String::new());
+        }
+        let message = if regs == MOONEYE_FAIL {
+            "registers reported the failure pattern".to_owned()
+        } else {
+            format!("unexpected registers at LD B,B: {regs:02X?}")
+        };
+        (Status::Failed, self.cycles, message)
+    }
+
+    /// Renders the frame after the final `LD B,B` and compares it to `reference`.
+    fn screenshot(&mut self, reference: Option<&[u8]>) -> Verdict {
+        let Some(reference) = reference else {
+            return (
+                Status::Skipped,
+                self.cycles,
+                "no reference screenshot in the bundle".to_owned(),
+            );
+        };
+        let mut frame = vec![0u8; SCREEN_WIDTH * SCREEN_HEIGHT * 4];
+        // Drop the frame in flight so the comparison sees one drawn after the breakpoint.
+        self.core.take_frame(&mut frame);
+        let deadline = self.cycles + 2 * u64::from(CYCLES_PER_FRAME);
+        while !self.core.frame_ready() && self.cycles < deadline {
+            let (cycles, _) = self.core.step_instruction();
+            self.cycles += u64::from(cycles);
+        }
+        self.core.take_frame(&mut frame);
+        match screenshot::compare(&frame, reference) {
+            Ok(0) => (Status::Passed, self.cycles, String::new()),
+            Ok(mismatched) => (
+                Status::Failed,
+                self.cycles,
+                format!("{mismatched} pixel(s) differ from the reference screenshot"),
+            ),
+            Err(err) => (Status::Error, self.cycles, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
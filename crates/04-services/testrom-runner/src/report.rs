@@ -0,0 +1,241 @@
+//! Per-ROM results and their JUnit/JSON renderings.
+
+use serde::Serialize;
+use std::fmt::Write;
+use std::time::Duration;
+use testdata::RomMeta;
+
+/// Outcome of a single ROM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// The ROM produced its expected completion signal.
+    Passed,
+    /// The ROM reported a failure or its output did not match.
+    Failed,
+    /// The budget ran out before the ROM reported a result.
+    Timeout,
+    /// The core panicked or the reference could not be evaluated.
+    Error,
+    /// The ROM has no evaluable expectation.
+    Skipped,
+}
+
+/// Result of running one ROM.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CaseReport {
+    /// Normalized bundle path of the ROM.
+    pub path: &'static str,
+    /// Friendly ROM name.
+    pub name: &'static str,
+    /// Verdict for the ROM.
+    pub status: Status,
+    /// Emulated CPU cycles executed.
+    pub cycles: u64,
+    /// Wall-clock seconds spent on the ROM.
+    pub seconds: f64,
+    /// Failure, timeout or skip detail; empty for passing ROMs.
+    pub message: String,
+}
+
+impl CaseReport {
+    pub(crate) fn new(meta: &RomMeta, status: Status, cycles: u64, elapsed: Duration) -> Self {
+        Self {
+            path: meta.path,
+            name: meta.name,
+            status,
+            cycles,
+            seconds: elapsed.as_secs_f64(),
+            message: String::new(),
+        }
+    }
+
+    pub(crate) fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+/// Status counts for a suite or a whole run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Tally {
+    /// ROMs that passed.
+    pub passed: usize,
+    /// ROMs that failed.
+    pub failed: usize,
+    /// ROMs that ran out of budget.
+    pub timed_out: usize,
+    /// ROMs that could not be evaluated.
+    pub errors: usize,
+    /// ROMs without an evaluable expectation.
+    pub skipped: usize,
+}
+
+impl Tally {
+    /// Total number of ROMs counted.
+    pub fn tests(&self) -> usize {
+        self.passed + self.failed + self.timed_out + self.errors + self.skipped
+    }
+
+    /// ROMs reported as JUnit failures (failed or timed out).
+    pub fn failures(&self) -> usize {
+        self.failed + self.timed_out
+    }
+
+    fn count(&mut self, status: Status) {
+        match status {
+            Status::Passed => self.passed += 1,
+            Status::Failed => self.failed += 1,
+            Status::Timeout => self.timed_out += 1,
+            Status::Error => self.errors += 1,
+            Status::Skipped => self.skipped += 1,
+        }
+    }
+
+    fn add(&mut self, other: &Tally) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.timed_out += other.timed_out;
+        self.errors += other.errors;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Results for every ROM of one suite.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SuiteReport {
+    /// Suite name (top-level directory in the bundle).
+    pub name: &'static str,
+    /// Status counts across `cases`.
+    #[serde(flatten)]
+    pub tally: Tally,
+    /// Wall-clock seconds spent on the suite.
+    pub seconds: f64,
+    /// Per-ROM results in run order.
+    pub cases: Vec<CaseReport>,
+}
+
+/// Results of a runner invocation, grouped by suite.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// Upstream bundle identifier the ROMs came from.
+    pub bundle: &'static str,
+    /// Per-suite results in run order.
+    pub suites: Vec<SuiteReport>,
+}
+
+impl Report {
+    /// Appends `case` to `suite`, creating the suite on first use.
+    pub fn record(&mut self, suite: &'static str, case: CaseReport) {
+        let idx = match self.suites.iter().position(|s| s.name == suite) {
+            Some(idx) => idx,
+            None => {
+                self.suites.push(SuiteReport {
+                    name: suite,
+                    tally: Tally::default(),
+                    seconds: 0.0,
+                    cases: Vec::new(),
+                });
+                self.suites.len() - 1
+            }
+        };
+        let entry = &mut self.suites[idx];
+        entry.tally.count(case.status);
+        entry.seconds += case.seconds;
+        entry.cases.push(case);
+    }
+
+    /// Status counts across every suite.
+    pub fn tally(&self) -> Tally {
+        let mut total = Tally::default();
+        for suite in &self.suites {
+            total.add(&suite.tally);
+        }
+        total
+    }
+
+    /// Renders the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serializes to JSON")
+    }
+
+    /// Renders the report as JUnit XML, one `<testsuite>` per suite.
+    ///
+    /// Timeouts are failures with `type="timeout"`; core panics are errors.
+    pub fn to_junit(&self) -> String {
+        let total = self.tally();
+        let seconds: f64 = self.suites.iter().map(|s| s.seconds).sum();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writeln!(
+            out,
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{seconds:.3}\">",
+            xml_escape(self.bundle),
+            total.tests(),
+            total.failures(),
+            total.errors,
+            total.skipped,
+        )
+        .expect("write testsuites");
+        for suite in &self.suites {
+            writeln!(
+                out,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+                xml_escape(suite.name),
+                suite.tally.tests(),
+                suite.tally.failures(),
+                suite.tally.errors,
+                suite.tally.skipped,
+                suite.seconds,
+            )
+            .expect("write testsuite");
+            for case in &suite.cases {
+                write!(
+                    out,
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                    xml_escape(suite.name),
+                    xml_escape(case.path),
+                    case.seconds,
+                )
+                .expect("write testcase");
+                let message = xml_escape(&case.message);
+                let child = match case.status {
+                    Status::Passed => None,
+                    Status::Failed => {
+                        Some(format!("<failure type=\"failed\" message=\"{message}\"/>"))
+                    }
+                    Status::Timeout => {
+                        Some(format!("<failure type=\"timeout\" message=\"{message}\"/>"))
+                    }
+                    Status::Error => Some(format!("<error message=\"{message}\"/>")),
+                    Status::Skipped => Some(format!("<skipped message=\"{message}\"/>")),
+                };
+                match child {
+                    Some(child) => writeln!(out, ">\n      {child}\n    </testcase>"),
+                    None => writeln!(out, "/>"),
+                }
+                .expect("write testcase body");
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        out.push_str("</testsuites>\n");
+        out
+    }
+}
+
+/// Escapes text for use inside an XML attribute value.
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
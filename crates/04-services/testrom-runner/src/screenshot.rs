@@ -0,0 +1,51 @@
+//! Frame comparison against upstream PNG reference screenshots.
+
+use kernel_core::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use png::{ColorType, Decoder, Transformations};
+
+/// Counts pixels of `frame_rgba` whose RGB differs from the reference PNG.
+///
+/// References are decoded to 8-bit colour; grayscale and palette images are
+/// expanded so DMG and CGB references compare the same way. Alpha is ignored.
+pub(crate) fn compare(frame_rgba: &[u8], png_bytes: &[u8]) -> Result<usize, String> {
+    let mut decoder = Decoder::new(png_bytes);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|err| format!("invalid reference screenshot: {err}"))?;
+    let mut pixels = vec![0u8; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut pixels)
+        .map_err(|err| format!("invalid reference screenshot: {err}"))?;
+    let (width, height) = (info.width as usize, info.height as usize);
+    if (width, height) != (SCREEN_WIDTH, SCREEN_HEIGHT) {
+        return Err(format!(
+            "reference screenshot is {width}x{height}, expected {SCREEN_WIDTH}x{SCREEN_HEIGHT}"
+        ));
+    }
+
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => return Err("reference screenshot palette was not expanded".into()),
+    };
+    let mut mismatched = 0;
+    for y in 0..height {
+        let row = &pixels[y * info.line_size..];
+        for x in 0..width {
+            let px = &row[x * channels..];
+            let expected = if channels < 3 {
+                [px[0]; 3]
+            } else {
+                [px[0], px[1], px[2]]
+            };
+            let offset = (y * width + x) * 4;
+            if frame_rgba[offset..offset + 3] != expected {
+                mismatched += 1;
+            }
+        }
+    }
+    Ok(mismatched)
+}
@@ -0,0 +1,180 @@
+use super::*;
+use testdata::RomKind;
+
+fn meta(expected: Expected) -> RomMeta {
+    RomMeta {
+        suite: "unit",
+        name: "program",
+        path: "unit/program.gb",
+        model: RomModel::Dmg,
+        kind: RomKind::Cpu,
+        expected,
+        reference: None,
+        sha256: "",
+        size: 0x8000,
+        embed: false,
+    }
+}
+
+/// Builds a 32 KiB ROM with `program` at the reset address 0x0100.
+fn rom_with_program(program: &[u8]) -> Arc<[u8]> {
+    let mut rom = vec![0u8; 0x8000];
+    rom[0x0100..0x0100 + program.len()].copy_from_slice(program);
+    Arc::from(rom.into_boxed_slice())
+}
+
+/// Loads B, C, D, E, H and L with `regs`, then executes `LD B,B` in a loop.
+fn fib_program(regs: [u8; 6]) -> Arc<[u8]> {
+    let mut program = Vec::new();
+    for (opcode, value) in [0x06, 0x0E, 0x16, 0x1E, 0x26, 0x2E].into_iter().zip(regs) {
+        program.extend_from_slice(&[opcode, value]);
+    }
+    program.extend_from_slice(&[0x40, 0x18, 0xFD]); // LD B,B; JR -3
+    rom_with_program(&program)
+}
+
+/// Sends `text` over the serial port with the internal clock, then spins.
+fn serial_program(text: &str) -> Arc<[u8]> {
+    let mut program = Vec::new();
+    for byte in text.bytes() {
+        program.extend_from_slice(&[
+            0x3E, byte, // LD A,byte
+            0xE0, 0x01, // LDH (SB),A
+            0x3E, 0x81, // LD A,$81
+            0xE0, 0x02, // LDH (SC),A
+            0xF0, 0x02, // LDH A,(SC)
+            0xCB, 0x7F, // BIT 7,A
+            0x20, 0xFA, // JR NZ,-6
+        ]);
+    }
+    program.extend_from_slice(&[0x18, 0xFE]); // JR -2
+    rom_with_program(&program)
+}
+
+/// Encodes a 160x144 grayscale PNG that is white except for `black` pixels.
+fn white_png(black: &[(usize, usize)]) -> Vec<u8> {
+    let mut pixels = vec![0xFFu8; SCREEN_WIDTH * SCREEN_HEIGHT];
+    for &(x, y) in black {
+        pixels[y * SCREEN_WIDTH + x] = 0x00;
+    }
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("png header");
+    writer.write_image_data(&pixels).expect("png data");
+    writer.finish().expect("png finish");
+    out
+}
+
+#[test]
+fn mooneye_registers_pass_and_fail_at_ld_b_b() {
+    let meta = meta(Expected::MooneyeFib);
+    let pass = run_bytes(&meta, fib_program(MOONEYE_PASS), None, Budget::default());
+    assert_eq!(pass.status, Status::Passed, "{}", pass.message);
+    assert!(pass.cycles > 0);
+
+    let fail = run_bytes(&meta, fib_program(MOONEYE_FAIL), None, Budget::default());
+    assert_eq!(fail.status, Status::Failed);
+    assert!(fail.message.contains("failure pattern"), "{}", fail.message);
+}
+
+#[test]
+fn serial_marker_passes_and_failed_text_fails() {
+    let meta = meta(Expected::SerialAscii("Passed\n"));
+    let pass = run_bytes(
+        &meta,
+        serial_program("ok\nPassed\n"),
+        None,
+        Budget::default(),
+    );
+    assert_eq!(pass.status, Status::Passed, "{}", pass.message);
+
+    let fail = run_bytes(&meta, serial_program("Failed #3"), None, Budget::default());
+    assert_eq!(fail.status, Status::Failed);
+    assert!(fail.message.starts_with("Failed"), "{}", fail.message);
+}
+
+#[test]
+fn budget_exhaustion_reports_timeout() {
+    let budget = Budget {
+        max_cycles: 10_000,
+        ..Budget::default()
+    };
+    let case = run_bytes(
+        &meta(Expected::SerialAscii("Passed\n")),
+        rom_with_program(&[0x18, 0xFE]),
+        None,
+        budget,
+    );
+    assert_eq!(case.status, Status::Timeout);
+    assert!(case.cycles >= 10_000);
+}
+
+#[test]
+fn screenshot_compares_frame_after_ld_b_b() {
+    let meta = meta(Expected::Screenshot);
+    let rom = rom_with_program(&[0x40, 0x18, 0xFD]);
+
+    let matching = white_png(&[]);
+    let pass = run_bytes(&meta, Arc::clone(&rom), Some(&matching), Budget::default());
+    assert_eq!(pass.status, Status::Passed, "{}", pass.message);
+
+    let differing = white_png(&[(0, 0), (159, 143)]);
+    let fail = run_bytes(&meta, Arc::clone(&rom), Some(&differing), Budget::default());
+    assert_eq!(fail.status, Status::Failed);
+    assert!(fail.message.starts_with("2 pixel(s)"), "{}", fail.message);
+
+    let missing = run_bytes(&meta, rom, None, Budget::default());
+    assert_eq!(missing.status, Status::Skipped);
+}
+
+#[test]
+fn unknown_expectation_is_skipped_without_loading() {
+    let case = run_rom(&meta(Expected::Unknown), Budget::default());
+    assert_eq!(case.status, Status::Skipped);
+    assert_eq!(case.cycles, 0);
+}
+
+#[test]
+fn report_renders_junit_and_json_per_suite() {
+    let unit = meta(Expected::MooneyeFib);
+    let mut report = Report {
+        bundle: "bundle",
+        suites: Vec::new(),
+    };
+    report.record(
+        "alpha",
+        CaseReport::new(&unit, Status::Passed, 100, Duration::from_millis(5)),
+    );
+    report.record(
+        "beta",
+        CaseReport::new(&unit, Status::Timeout, 200, Duration::ZERO)
+            .with_message("no <result> & \"done\""),
+    );
+    report.record(
+        "alpha",
+        CaseReport::new(&unit, Status::Skipped, 0, Duration::ZERO),
+    );
+
+    let tally = report.tally();
+    assert_eq!((tally.tests(), tally.failures(), tally.skipped), (3, 1, 1));
+    assert_eq!(report.suites.len(), 2);
+    assert_eq!(report.suites[0].tally.tests(), 2);
+
+    let junit = report.to_junit();
+    assert!(junit
+        .contains(r#"<testsuites name="bundle" tests="3" failures="1" errors="0" skipped="1""#));
+    assert!(
+        junit.contains(r#"<testsuite name="alpha" tests="2" failures="0" errors="0" skipped="1""#)
+    );
+    assert!(junit.contains(
+        r#"<failure type="timeout" message="no &lt;result&gt; &amp; &quot;done&quot;"/>"#
+    ));
+    assert_eq!(junit.matches("<testcase ").count(), 3);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).expect("valid json");
+    assert_eq!(json["suites"][1]["name"], "beta");
+    assert_eq!(json["suites"][1]["timed_out"], 1);
+    assert_eq!(json["suites"][1]["cases"][0]["status"], "timeout");
+}
@@ -2,7 +2,8 @@
 
 use serde::Serialize;
 use service_abi::{
-    CpuVM, DebugRep, InspectorVMMinimal, MemSpace, PpuVM, StepKind, TimersVM, TraceVM,
+    BreakReason, CpuVM, DebugRep, DisasmLineVM, InspectorVMMinimal, LanesVM, MemSpace, PpuVM,
+    StepKind, TimersVM, TraceEntryVM, TraceVM,
 };
 
 /// Inspector view-model shared across CLI, logfile, and web frontends.
@@ -14,6 +15,8 @@ pub struct InspectorVM {
     pub ppu: PpuVM,
     /// Timer register snapshot.
     pub timers: TimersVM,
+    /// SIMD lane divergence counters.
+    pub lanes: LanesVM,
     /// Memory windows exposed to the front ends.
     pub mem: MemVM,
     /// Aggregated world performance counters.
@@ -22,6 +25,23 @@ pub struct InspectorVM {
     pub transport: TransportVM,
     /// Last disassembly trace emitted by stepping.
     pub disasm: Option<TraceVM>,
+    /// Most recent code listing requested by the UI.
+    pub listing: Vec<DisasmLineVM>,
+    /// Breakpoint or watchpoint that most recently paused a group.
+    pub last_break: Option<BreakVM>,
+    /// Most recent chunk of the execution trace.
+    pub trace: Vec<TraceEntryVM>,
+}
+
+/// Where and why a group last paused on a breakpoint or watchpoint.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BreakVM {
+    /// Kernel group that paused.
+    pub group: u16,
+    /// PC the group is paused at.
+    pub pc: u16,
+    /// Breakpoint or watchpoint that fired.
+    pub reason: BreakReason,
 }
 
 impl Default for InspectorVM {
@@ -59,10 +79,14 @@ impl Default for InspectorVM {
                 tma: 0,
                 tac: 0,
             },
+            lanes: LanesVM::default(),
             mem: MemVM::default(),
             perf: PerfVM::default(),
             transport: TransportVM::default(),
             disasm: None,
+            listing: Vec::new(),
+            last_break: None,
+            trace: Vec::new(),
         }
     }
 }
@@ -113,6 +137,23 @@ impl InspectorVM {
                     }
                 }
             }
+            DebugRep::Disassembly { lines, .. } => {
+                self.listing = lines.clone();
+            }
+            DebugRep::BreakpointHit { group, pc, reason } => {
+                self.cpu.pc = *pc;
+                self.last_break = Some(BreakVM {
+                    group: *group,
+                    pc: *pc,
+                    reason: *reason,
+                });
+            }
+            DebugRep::Trace { entries, .. } => {
+                self.trace = entries.clone();
+            }
+            DebugRep::Lanes(lanes) => {
+                self.lanes = lanes.clone();
+            }
         }
     }
 
@@ -135,6 +176,14 @@ pub struct MemVM {
     pub oam_window: Option<MemWindow>,
     /// Optional WRAM window requested by the UI.
     pub wram_window: Option<MemWindow>,
+    /// Optional HRAM/IE window requested by the UI.
+    pub hram_window: Option<MemWindow>,
+    /// Optional cartridge ROM bank window requested by the UI.
+    pub rom_window: Option<BankedMemWindow>,
+    /// Optional cartridge RAM bank window requested by the UI.
+    pub cart_ram_window: Option<BankedMemWindow>,
+    /// Optional flat bus window requested by the UI.
+    pub bus_window: Option<MemWindow>,
 }
 
 impl MemVM {
@@ -148,6 +197,15 @@ impl MemVM {
             MemSpace::Vram => self.vram_window = Some(window),
             MemSpace::Wram => self.wram_window = Some(window),
             MemSpace::Oam => self.oam_window = Some(window),
+            MemSpace::Hram => self.hram_window = Some(window),
+            MemSpace::Rom { bank } => self.rom_window = Some(BankedMemWindow { bank, window }),
+            MemSpace::CartRam { bank } => {
+                self.cart_ram_window = Some(BankedMemWindow {
+                    bank: u16::from(bank),
+                    window,
+                })
+            }
+            MemSpace::Bus => self.bus_window = Some(window),
             MemSpace::Io => {
                 let start = base.saturating_sub(0xFF00) as usize;
                 for (idx, value) in bytes.iter().enumerate() {
@@ -169,6 +227,10 @@ impl Default for MemVM {
             vram_window: None,
             oam_window: None,
             wram_window: None,
+            hram_window: None,
+            rom_window: None,
+            cart_ram_window: None,
+            bus_window: None,
         }
     }
 }
@@ -182,6 +244,15 @@ pub struct MemWindow {
     pub bytes: Vec<u8>,
 }
 
+/// Memory window read from a specific cartridge bank.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BankedMemWindow {
+    /// Bank the window was read from.
+    pub bank: u16,
+    /// Window contents.
+    pub window: MemWindow,
+}
+
 /// Aggregated performance counters from the world.
 #[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct PerfVM {
@@ -217,6 +288,18 @@ pub struct PortMetricsVM {
     pub would_block: u32,
 }
 
+/// Formats a trace entry as a Gameboy Doctor log line.
+///
+/// The output matches the format the Gameboy Doctor tool compares against,
+/// e.g. `A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02`.
+pub fn gameboy_doctor_line(entry: &TraceEntryVM) -> String {
+    let [m0, m1, m2, m3] = entry.pcmem;
+    format!(
+        "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{m0:02X},{m1:02X},{m2:02X},{m3:02X}",
+        entry.a, entry.f, entry.b, entry.c, entry.d, entry.e, entry.h, entry.l, entry.sp, entry.pc,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +357,20 @@ mod tests {
         assert_eq!(vm.mem.io[0], 0xAA);
     }
 
+    #[test]
+    fn apply_lanes_replaces_divergence_counters() {
+        let mut vm = InspectorVM::default();
+        vm.apply_debug_rep(&DebugRep::Lanes(LanesVM {
+            split_mask: 0b10,
+            splits: 3,
+            merges: 2,
+            split_cycles: 1_024,
+        }));
+
+        assert_eq!(vm.lanes.split_mask, 0b10);
+        assert_eq!(vm.lanes.split_cycles, 1_024);
+    }
+
     #[test]
     fn apply_mem_window_tracks_spaces() {
         let mut mem = MemVM::default();
@@ -287,6 +384,19 @@ mod tests {
         assert_eq!(mem.io[0x11], 7);
     }
 
+    #[test]
+    fn apply_mem_window_keeps_bank_numbers() {
+        let mut mem = MemVM::default();
+        mem.apply_window(MemSpace::Rom { bank: 5 }, 0x4000, &[1, 2]);
+        mem.apply_window(MemSpace::CartRam { bank: 2 }, 0xA000, &[3]);
+        mem.apply_window(MemSpace::Bus, 0xFFFE, &[4, 5]);
+
+        let rom = mem.rom_window.as_ref().unwrap();
+        assert_eq!((rom.bank, rom.window.base), (5, 0x4000));
+        assert_eq!(mem.cart_ram_window.as_ref().unwrap().bank, 2);
+        assert_eq!(mem.bus_window.as_ref().unwrap().bytes, vec![4, 5]);
+    }
+
     #[test]
     fn apply_debug_rep_updates_disasm_and_mem() {
         let mut vm = InspectorVM::default();
@@ -310,6 +420,75 @@ mod tests {
         assert_eq!(trace.disasm_line, "NOP");
     }
 
+    #[test]
+    fn apply_disassembly_replaces_listing() {
+        let mut vm = InspectorVM::default();
+        let line = DisasmLineVM {
+            addr: 0x0150,
+            bytes: vec![0x00],
+            text: "NOP".into(),
+        };
+        vm.apply_debug_rep(&DebugRep::Disassembly {
+            base: 0x0150,
+            lines: vec![line.clone()],
+        });
+
+        assert_eq!(vm.listing, vec![line]);
+    }
+
+    #[test]
+    fn apply_breakpoint_hit_records_break() {
+        let mut vm = InspectorVM::default();
+        vm.apply_debug_rep(&DebugRep::BreakpointHit {
+            group: 2,
+            pc: 0x0150,
+            reason: BreakReason::Breakpoint,
+        });
+
+        assert_eq!(vm.cpu.pc, 0x0150);
+        let hit = vm.last_break.as_ref().expect("break");
+        assert_eq!((hit.group, hit.pc), (2, 0x0150));
+        assert_eq!(hit.reason, BreakReason::Breakpoint);
+    }
+
+    fn sample_trace_entry() -> TraceEntryVM {
+        TraceEntryVM {
+            cycle: 0,
+            pc: 0x0100,
+            pcmem: [0x00, 0xC3, 0x13, 0x02],
+            a: 0x01,
+            f: 0xB0,
+            b: 0x00,
+            c: 0x13,
+            d: 0x00,
+            e: 0xD8,
+            h: 0x01,
+            l: 0x4D,
+            sp: 0xFFFE,
+            accesses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn gameboy_doctor_line_matches_reference_format() {
+        assert_eq!(
+            gameboy_doctor_line(&sample_trace_entry()),
+            "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02"
+        );
+    }
+
+    #[test]
+    fn apply_trace_keeps_latest_chunk() {
+        let mut vm = InspectorVM::default();
+        vm.apply_debug_rep(&DebugRep::Trace {
+            entries: vec![sample_trace_entry()],
+            remaining: 0,
+            dropped: 0,
+        });
+
+        assert_eq!(vm.trace, vec![sample_trace_entry()]);
+    }
+
     #[test]
     fn to_ndjson_line_contains_newline() {
         let line = InspectorVM::default().to_ndjson_line().unwrap();
@@ -22,5 +22,7 @@ pub use crate::types::{
     Intent, IntentPriority, KernelCmd, KernelRep, Report, SlotSpan, SubmitOutcome, SubmitPolicy,
     TickPurpose, WorkCmd,
 };
-pub use crate::world::{ViewMode, World, WorldHealth, WorldPerf};
+pub use crate::world::{
+    PendingSave, PendingSlotLoad, ViewMode, World, WorldHealth, WorldPerf, SAVE_RAM_DEBOUNCE_TICKS,
+};
 pub use inspector::InspectorState;
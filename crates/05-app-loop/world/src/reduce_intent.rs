@@ -1,8 +1,10 @@
 //! Pure intent reducer implementation for the Wave B world state.
 
-use crate::types::{DebugCmd, Intent, KernelCmd, TickPurpose, WorkCmd};
-use crate::world::World;
+use crate::types::{DebugCmd, FsCmd, Intent, KernelCmd, TickPurpose, WorkCmd};
+use crate::world::{PendingSlotLoad, World};
 use smallvec::{smallvec, SmallVec};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Trait for handling intents and producing work commands.
 pub trait IntentReducer {
@@ -18,8 +20,92 @@ impl IntentReducer for World {
                 purpose: TickPurpose::Display,
                 budget: display_cycle_budget(self.speed),
             })],
-            Intent::LoadRom { group, bytes } => {
-                smallvec![WorkCmd::Kernel(KernelCmd::LoadRom { group, bytes })]
+            Intent::LoadRom {
+                group,
+                bytes,
+                save_ram,
+            } => {
+                self.save_paths.insert(group, save_path_for_rom(&bytes));
+                self.pending_saves.retain(|pending| pending.group != group);
+                self.pending_state_slots.retain(|&(g, _), _| g != group);
+                self.state_slots.retain(|&(g, _), _| g != group);
+                self.pending_slot_loads
+                    .retain(|pending| pending.group != group);
+                smallvec![WorkCmd::Kernel(KernelCmd::LoadRom {
+                    group,
+                    bytes,
+                    save_ram,
+                })]
+            }
+            Intent::PersistSaveRam { group, bytes } => {
+                let path = self
+                    .save_paths
+                    .get(&group)
+                    .cloned()
+                    .unwrap_or_else(|| PathBuf::from(format!("group-{group}.sav")));
+                smallvec![WorkCmd::Fs(FsCmd::Persist { path, bytes })]
+            }
+            Intent::SaveStateSlot { group, lane, slot } => {
+                self.pending_state_slots.insert((group, lane), slot);
+                smallvec![WorkCmd::Kernel(KernelCmd::SaveState { group, lane })]
+            }
+            Intent::LoadStateSlot { group, lane, slot } => {
+                if let Some(bytes) = self.state_slots.get(&(group, slot)) {
+                    return smallvec![WorkCmd::Kernel(KernelCmd::LoadState {
+                        group,
+                        lane,
+                        bytes: Arc::clone(bytes),
+                    })];
+                }
+                let path = self.state_slot_path(group, slot);
+                self.pending_slot_loads.push(PendingSlotLoad {
+                    path: path.clone(),
+                    group,
+                    lane,
+                    slot,
+                });
+                smallvec![WorkCmd::Fs(FsCmd::Load { path })]
+            }
+            Intent::LoadState { group, lane, bytes } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::LoadState { group, lane, bytes })]
+            }
+            Intent::SetInputs {
+                group,
+                lanes_mask,
+                joypad,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::SetInputs {
+                group,
+                lanes_mask,
+                joypad,
+            })],
+            Intent::ResetLane {
+                group,
+                lane,
+                rom,
+                save_ram,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::ResetLane {
+                group,
+                lane,
+                rom,
+                save_ram,
+            })],
+            Intent::SetCheats { group, codes } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::SetCheats { group, codes })]
+            }
+            Intent::Fork {
+                group,
+                source_lane,
+                target_group,
+                input_scripts,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::Fork {
+                group,
+                source_lane,
+                target_group,
+                input_scripts,
+            })],
+            Intent::PersistStateSlot { group, slot, bytes } => {
+                let path = self.state_slot_path(group, slot);
+                smallvec![WorkCmd::Fs(FsCmd::Persist { path, bytes })]
             }
             Intent::TogglePause => {
                 self.paused = !self.paused;
@@ -40,11 +126,13 @@ impl IntentReducer for World {
             }
             Intent::DebugMem {
                 group,
+                lane,
                 space,
                 base,
                 len,
             } => smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::MemWindow {
                 group,
+                lane,
                 space,
                 base,
                 len,
@@ -59,6 +147,85 @@ impl IntentReducer for World {
                     group
                 }))]
             }
+            Intent::DebugSetBreakpoint { group, pc } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetBreakpoint {
+                    group,
+                    pc
+                }))]
+            }
+            Intent::DebugClearBreakpoint { group, pc } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(
+                    DebugCmd::ClearBreakpoint { group, pc }
+                ))]
+            }
+            Intent::DebugSetWatchpoint {
+                group,
+                addr_range,
+                access,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetWatchpoint {
+                group,
+                addr_range,
+                access,
+            }))],
+            Intent::DebugClearWatchpoint {
+                group,
+                addr_range,
+                access,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::Debug(
+                DebugCmd::ClearWatchpoint {
+                    group,
+                    addr_range,
+                    access,
+                }
+            ))],
+            Intent::DebugDisassemble { group, base, count } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::Disassemble {
+                    group,
+                    base,
+                    count
+                }))]
+            }
+            Intent::DebugSetTracing {
+                group,
+                enabled,
+                capacity,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetTracing {
+                group,
+                enabled,
+                capacity
+            }))],
+            Intent::DebugReadTrace { group, max } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::ReadTrace {
+                    group,
+                    max
+                }))]
+            }
+            Intent::DebugWriteMem {
+                group,
+                lane,
+                space,
+                base,
+                bytes,
+            } => smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::WriteMem {
+                group,
+                lane,
+                space,
+                base,
+                bytes
+            }))],
+            Intent::DebugSetRegisters { group, lane, regs } => {
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetRegisters {
+                    group,
+                    lane,
+                    regs
+                }))]
+            }
+            Intent::DebugContinue(group) => {
+                self.paused = false;
+                smallvec![WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::Continue {
+                    group
+                }))]
+            }
         }
     }
 }
@@ -75,3 +242,25 @@ fn display_cycle_budget(speed: f32) -> u32 {
 fn clamp_speed(speed: f32) -> f32 {
     speed.clamp(MIN_SPEED, MAX_SPEED)
 }
+
+const HEADER_TITLE: core::ops::Range<usize> = 0x0134..0x0144;
+
+/// Derives the battery save file name from the cartridge header title.
+fn save_path_for_rom(rom: &[u8]) -> PathBuf {
+    let title: String = rom
+        .get(HEADER_TITLE)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|&&byte| byte != 0)
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' => byte as char,
+            _ => '_',
+        })
+        .collect();
+    let stem = if title.is_empty() {
+        "cartridge"
+    } else {
+        &title
+    };
+    PathBuf::from(format!("{stem}.sav"))
+}
@@ -1,6 +1,9 @@
 //! Pure report reducer implementation for the Wave B world state.
 
-use crate::types::{AudioRep, AvCmd, FollowUps, GpuCmd, Intent, IntentPriority, KernelRep, Report};
+use crate::types::{
+    AudioCmd, AudioRep, AvCmd, DebugRep, FollowUps, FsRep, GpuCmd, Intent, IntentPriority,
+    KernelRep, Report,
+};
 use crate::world::{ViewMode, World};
 
 /// Trait for handling reports and producing follow-up actions.
@@ -29,16 +32,47 @@ impl ReportReducer for World {
                         self.record_present(frame_id);
                     }
                 }
-                KernelRep::TickDone { .. } => {
+                KernelRep::TickDone { group, .. } => {
                     if self.auto_pump {
                         follow_ups.push_deferred_intent(IntentPriority::P1, Intent::PumpFrame);
                     }
+                    if let Some((group, bytes)) = self.settle_save_ram(group) {
+                        follow_ups.push_deferred_intent(
+                            IntentPriority::P2,
+                            Intent::PersistSaveRam { group, bytes },
+                        );
+                    }
+                }
+                KernelRep::AudioReady { span, .. } => {
+                    if span.frames() > 0 {
+                        follow_ups.push_immediate_av(AvCmd::Audio(AudioCmd::Submit { span }));
+                    }
+                }
+                // The group's save file mirrors lane 0; other lanes' cartridges
+                // are scratch copies and are not persisted.
+                KernelRep::SaveRamDirty {
+                    group,
+                    lane: 0,
+                    bytes,
+                } => {
+                    self.queue_save_ram(group, bytes);
+                }
+                KernelRep::StateSaved { group, lane, bytes } => {
+                    if let Some((slot, bytes)) = self.store_state_slot(group, lane, bytes) {
+                        follow_ups.push_deferred_intent(
+                            IntentPriority::P2,
+                            Intent::PersistStateSlot { group, slot, bytes },
+                        );
+                    }
                 }
                 KernelRep::RomLoaded { .. } => {
                     self.rom_loaded = true;
                     self.rom_events = self.rom_events.saturating_add(1);
                 }
-                KernelRep::Debug(debug) => {
+                KernelRep::Debug { rep: debug, .. } => {
+                    if matches!(debug, DebugRep::BreakpointHit { .. }) {
+                        self.paused = true;
+                    }
                     self.inspector.apply_debug_rep(&debug);
                 }
                 _ => {}
@@ -49,6 +83,14 @@ impl ReportReducer for World {
                 }
                 AudioRep::Played { .. } => {}
             },
+            Report::Fs(FsRep::Loaded { path, bytes }) => {
+                for (group, lane, bytes) in self.finish_slot_loads(&path, bytes) {
+                    follow_ups.push_deferred_intent(
+                        IntentPriority::P0,
+                        Intent::LoadState { group, lane, bytes },
+                    );
+                }
+            }
             Report::Gpu(_) | Report::Fs(_) => {}
         }
 
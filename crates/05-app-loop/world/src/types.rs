@@ -6,13 +6,15 @@
 //! message definitions while higher layers are still under construction.
 
 use smallvec::SmallVec;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 // Re-export service ABI types that world uses
 pub use service_abi::{
-    AudioCmd, AudioRep, AudioSpan, CpuVM, DebugCmd, DebugRep, FrameSpan, FsCmd, FsRep, GpuCmd,
-    GpuRep, InspectorVMMinimal, KernelCmd, KernelRep, MemSpace, PpuVM, SlotSpan, StepKind,
-    SubmitOutcome, SubmitPolicy, TickPurpose, TimersVM, TraceVM,
+    AudioCmd, AudioRep, AudioSpan, BreakReason, CpuVM, DebugCmd, DebugRep, FrameSpan, FsCmd, FsRep,
+    GpuCmd, GpuRep, InspectorVMMinimal, KernelCmd, KernelRep, MemSpace, PpuVM, RegistersPatch,
+    SlotSpan, StepKind, SubmitOutcome, SubmitPolicy, TickPurpose, TimersVM, TraceEntryVM, TraceVM,
+    WatchAccess,
 };
 
 /// Priority level for intent scheduling (P0 is highest).
@@ -41,14 +43,98 @@ pub enum Intent {
         group: u16,
         /// Raw ROM bytes.
         bytes: Arc<[u8]>,
+        /// Previously persisted battery RAM to seed the cartridge with.
+        save_ram: Option<Arc<[u8]>>,
+    },
+    /// Persist settled battery RAM for a kernel group.
+    PersistSaveRam {
+        /// Kernel group identifier.
+        group: u16,
+        /// Cartridge RAM contents to write.
+        bytes: Arc<[u8]>,
+    },
+    /// Capture a lane into a quick-save slot.
+    SaveStateSlot {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to capture.
+        lane: u16,
+        /// Quick-save slot number.
+        slot: u8,
+    },
+    /// Restore a lane from a quick-save slot, reading the slot file if it is not cached.
+    LoadStateSlot {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to restore.
+        lane: u16,
+        /// Quick-save slot number.
+        slot: u8,
+    },
+    /// Restore a lane from a save-state image supplied by the frontend.
+    LoadState {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to restore.
+        lane: u16,
+        /// Encoded save-state image.
+        bytes: Arc<[u8]>,
+    },
+    /// Persist a captured quick-save slot.
+    PersistStateSlot {
+        /// Kernel group identifier.
+        group: u16,
+        /// Quick-save slot number.
+        slot: u8,
+        /// Encoded save-state image.
+        bytes: Arc<[u8]>,
+    },
+    /// Apply joypad inputs to the selected lanes of a kernel group.
+    SetInputs {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lanes receiving the inputs.
+        lanes_mask: u32,
+        /// Raw joypad state (active-low).
+        joypad: u8,
+    },
+    /// Reset one lane of a kernel group, optionally with a different cartridge.
+    ResetLane {
+        /// Kernel group identifier.
+        group: u16,
+        /// Lane to reset.
+        lane: u16,
+        /// ROM to insert into the lane; `None` keeps the current cartridge.
+        rom: Option<Arc<[u8]>>,
+        /// Battery RAM to seed the lane's cartridge with.
+        save_ram: Option<Arc<[u8]>>,
+    },
+    /// Replace the cheat codes active on a kernel group.
+    SetCheats {
+        /// Kernel group identifier.
+        group: u16,
+        /// Game Genie or GameShark codes; empty disables cheats.
+        codes: Vec<String>,
+    },
+    /// Branch a lane into a new kernel group driven by per-lane input scripts.
+    Fork {
+        /// Kernel group holding the lane to branch from.
+        group: u16,
+        /// Lane whose state seeds the forked lanes.
+        source_lane: u16,
+        /// Kernel group created for the forked lanes.
+        target_group: u16,
+        /// Raw joypad state per frame, one script per forked lane.
+        input_scripts: Vec<Arc<[u8]>>,
     },
     /// Select which display lane should be presented.
     SelectDisplayLane(u16),
     /// Request a debug snapshot for the given kernel group.
     DebugSnapshot(u16),
-    /// Request a memory window from the kernel.
+    /// Request a memory window of one lane from the kernel.
     DebugMem {
         group: u16,
+        lane: u16,
         space: MemSpace,
         base: u16,
         len: u16,
@@ -57,6 +143,48 @@ pub enum Intent {
     DebugStepInstruction { group: u16, count: u32 },
     /// Step the kernel forward by exactly one frame.
     DebugStepFrame(u16),
+    /// Pause the group before the instruction at `pc` runs.
+    DebugSetBreakpoint { group: u16, pc: u16 },
+    /// Remove a breakpoint from the group.
+    DebugClearBreakpoint { group: u16, pc: u16 },
+    /// Pause the group after an instruction accesses `addr_range`.
+    DebugSetWatchpoint {
+        group: u16,
+        addr_range: RangeInclusive<u16>,
+        access: WatchAccess,
+    },
+    /// Remove a watchpoint from the group.
+    DebugClearWatchpoint {
+        group: u16,
+        addr_range: RangeInclusive<u16>,
+        access: WatchAccess,
+    },
+    /// Resume a group paused by a breakpoint or watchpoint.
+    DebugContinue(u16),
+    /// Request a code listing of `count` instructions from `base`.
+    DebugDisassemble { group: u16, base: u16, count: u16 },
+    /// Start or stop recording an execution trace into a ring of `capacity` entries.
+    DebugSetTracing {
+        group: u16,
+        enabled: bool,
+        capacity: u32,
+    },
+    /// Fetch up to `max` of the oldest recorded trace entries.
+    DebugReadTrace { group: u16, max: u16 },
+    /// Poke bytes into a memory space of one lane.
+    DebugWriteMem {
+        group: u16,
+        lane: u16,
+        space: MemSpace,
+        base: u16,
+        bytes: Arc<[u8]>,
+    },
+    /// Overwrite CPU registers of one lane.
+    DebugSetRegisters {
+        group: u16,
+        lane: u16,
+        regs: RegistersPatch,
+    },
 }
 
 impl Intent {
@@ -67,11 +195,29 @@ impl Intent {
             Intent::TogglePause => IntentPriority::P0,
             Intent::SetSpeed(_) => IntentPriority::P0,
             Intent::LoadRom { .. } => IntentPriority::P0,
+            Intent::PersistSaveRam { .. } => IntentPriority::P2,
+            Intent::SaveStateSlot { .. } => IntentPriority::P0,
+            Intent::LoadStateSlot { .. } => IntentPriority::P0,
+            Intent::LoadState { .. } => IntentPriority::P0,
+            Intent::SetInputs { .. } => IntentPriority::P0,
+            Intent::ResetLane { .. } => IntentPriority::P0,
+            Intent::SetCheats { .. } => IntentPriority::P0,
+            Intent::Fork { .. } => IntentPriority::P2,
+            Intent::PersistStateSlot { .. } => IntentPriority::P2,
             Intent::SelectDisplayLane(_) => IntentPriority::P1,
             Intent::DebugSnapshot(_) => IntentPriority::P1,
             Intent::DebugMem { .. } => IntentPriority::P1,
             Intent::DebugStepInstruction { .. } => IntentPriority::P0,
             Intent::DebugStepFrame(_) => IntentPriority::P0,
+            Intent::DebugSetBreakpoint { .. } => IntentPriority::P0,
+            Intent::DebugClearBreakpoint { .. } => IntentPriority::P0,
+            Intent::DebugSetWatchpoint { .. } => IntentPriority::P0,
+            Intent::DebugClearWatchpoint { .. } => IntentPriority::P0,
+            Intent::DebugContinue(_) => IntentPriority::P0,
+            Intent::DebugDisassemble { .. } => IntentPriority::P1,
+            Intent::DebugSetTracing { .. } => IntentPriority::P0,
+            Intent::DebugReadTrace { .. } => IntentPriority::P1,
+            Intent::DebugWriteMem { .. } | Intent::DebugSetRegisters { .. } => IntentPriority::P0,
         }
     }
 }
@@ -96,8 +242,15 @@ impl WorkCmd {
             WorkCmd::Kernel(KernelCmd::LoadRom { .. }) => SubmitPolicy::Lossless,
             WorkCmd::Kernel(KernelCmd::SetInputs { .. }) => SubmitPolicy::Lossless,
             WorkCmd::Kernel(KernelCmd::Terminate { .. }) => SubmitPolicy::Lossless,
+            WorkCmd::Kernel(KernelCmd::SaveState { .. } | KernelCmd::LoadState { .. }) => {
+                SubmitPolicy::Lossless
+            }
+            WorkCmd::Kernel(KernelCmd::ResetLane { .. }) => SubmitPolicy::Lossless,
+            WorkCmd::Kernel(KernelCmd::SetCheats { .. }) => SubmitPolicy::Lossless,
+            WorkCmd::Kernel(KernelCmd::Fork { .. }) => SubmitPolicy::BestEffort,
             WorkCmd::Kernel(KernelCmd::Debug(cmd)) => cmd.submit_policy(),
             WorkCmd::Fs(FsCmd::Persist { .. }) => SubmitPolicy::Coalesce,
+            WorkCmd::Fs(FsCmd::Load { .. }) => SubmitPolicy::Lossless,
         }
     }
 }
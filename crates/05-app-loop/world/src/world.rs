@@ -5,6 +5,36 @@ use crate::types::{
     AudioCmd, AudioRep, AudioSpan, AvCmd, FollowUps, Intent, KernelCmd, KernelRep, Report,
     TickPurpose, WorkCmd,
 };
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Number of completed ticks without further writes before battery RAM is persisted.
+pub const SAVE_RAM_DEBOUNCE_TICKS: u32 = 60;
+
+/// Battery RAM snapshot waiting for writes to settle before persisting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSave {
+    /// Kernel group that produced the snapshot.
+    pub group: u16,
+    /// Latest cartridge RAM contents.
+    pub bytes: Arc<[u8]>,
+    /// Ticks remaining before the snapshot is flushed.
+    pub ticks_left: u32,
+}
+
+/// Quick-save slot load waiting for the filesystem to read the slot file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSlotLoad {
+    /// Slot file requested from the filesystem service.
+    pub path: PathBuf,
+    /// Kernel group to restore.
+    pub group: u16,
+    /// Lane to restore.
+    pub lane: u16,
+    /// Quick-save slot number.
+    pub slot: u8,
+}
 
 /// Aggregated performance counters (placeholder for future metrics).
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -56,6 +86,16 @@ pub struct World {
     pub health: WorldHealth,
     /// Inspector view-model state.
     pub inspector: InspectorState,
+    /// Battery RAM snapshots awaiting the debounce window.
+    pub pending_saves: Vec<PendingSave>,
+    /// Battery save file paths keyed by kernel group.
+    pub save_paths: BTreeMap<u16, PathBuf>,
+    /// Quick-save slots awaiting a `StateSaved` report, keyed by `(group, lane)`.
+    pub pending_state_slots: BTreeMap<(u16, u16), u8>,
+    /// Save-state images captured or loaded this session, keyed by `(group, slot)`.
+    pub state_slots: BTreeMap<(u16, u8), Arc<[u8]>>,
+    /// Quick-save slots being read back from disk.
+    pub pending_slot_loads: Vec<PendingSlotLoad>,
 }
 
 impl World {
@@ -118,6 +158,87 @@ impl World {
         self.perf.audio_underruns = self.perf.audio_underruns.saturating_add(1);
     }
 
+    /// Records dirty battery RAM, restarting the debounce window for its group.
+    pub fn queue_save_ram(&mut self, group: u16, bytes: Arc<[u8]>) {
+        match self
+            .pending_saves
+            .iter_mut()
+            .find(|pending| pending.group == group)
+        {
+            Some(pending) => {
+                pending.bytes = bytes;
+                pending.ticks_left = SAVE_RAM_DEBOUNCE_TICKS;
+            }
+            None => self.pending_saves.push(PendingSave {
+                group,
+                bytes,
+                ticks_left: SAVE_RAM_DEBOUNCE_TICKS,
+            }),
+        }
+    }
+
+    /// Advances the debounce window for `group`, returning the snapshot once it settles.
+    pub fn settle_save_ram(&mut self, group: u16) -> Option<(u16, Arc<[u8]>)> {
+        let idx = self
+            .pending_saves
+            .iter()
+            .position(|pending| pending.group == group)?;
+        let pending = &mut self.pending_saves[idx];
+        pending.ticks_left = pending.ticks_left.saturating_sub(1);
+        if pending.ticks_left > 0 {
+            return None;
+        }
+        let pending = self.pending_saves.swap_remove(idx);
+        Some((pending.group, pending.bytes))
+    }
+
+    /// Stores a captured save state in the slot requested for `(group, lane)`.
+    ///
+    /// Returns the slot number when a quick-save was pending; unsolicited or
+    /// failed captures only clear the request.
+    pub fn store_state_slot(
+        &mut self,
+        group: u16,
+        lane: u16,
+        bytes: Option<Arc<[u8]>>,
+    ) -> Option<(u8, Arc<[u8]>)> {
+        let slot = self.pending_state_slots.remove(&(group, lane))?;
+        let bytes = bytes?;
+        self.state_slots.insert((group, slot), Arc::clone(&bytes));
+        Some((slot, bytes))
+    }
+
+    /// Returns the file a quick-save slot of `group` is persisted to.
+    pub fn state_slot_path(&self, group: u16, slot: u8) -> PathBuf {
+        self.save_paths
+            .get(&group)
+            .map(|path| path.with_extension(format!("ss{slot}")))
+            .unwrap_or_else(|| PathBuf::from(format!("group-{group}.ss{slot}")))
+    }
+
+    /// Completes the slot loads waiting on `path`, returning the lanes to restore.
+    ///
+    /// A successful read is cached in `state_slots`; a missing file restores nothing.
+    pub fn finish_slot_loads(
+        &mut self,
+        path: &Path,
+        bytes: Option<Arc<[u8]>>,
+    ) -> Vec<(u16, u16, Arc<[u8]>)> {
+        let mut restores = Vec::new();
+        self.pending_slot_loads.retain(|pending| {
+            if pending.path != path {
+                return true;
+            }
+            if let Some(bytes) = &bytes {
+                self.state_slots
+                    .insert((pending.group, pending.slot), Arc::clone(bytes));
+                restores.push((pending.group, pending.lane, Arc::clone(bytes)));
+            }
+            false
+        });
+        restores
+    }
+
     /// Applies a minimal report reducer hook, used only by doctests.
     pub fn reduce_report_stub(&mut self, report: Report) -> FollowUps {
         match report {
@@ -128,7 +249,7 @@ impl World {
                 self.rom_loaded = true;
                 self.rom_events = self.rom_events.saturating_add(1);
             }
-            Report::Kernel(KernelRep::Debug(debug)) => {
+            Report::Kernel(KernelRep::Debug { rep: debug, .. }) => {
                 self.inspector.apply_debug_rep(&debug);
                 self.inspector.sync_perf(&self.perf);
             }
@@ -163,6 +284,11 @@ impl Default for World {
             perf: WorldPerf::default(),
             health: WorldHealth::default(),
             inspector: InspectorState::default(),
+            pending_saves: Vec::new(),
+            save_paths: BTreeMap::new(),
+            pending_state_slots: BTreeMap::new(),
+            state_slots: BTreeMap::new(),
+            pending_slot_loads: Vec::new(),
         }
     }
 }
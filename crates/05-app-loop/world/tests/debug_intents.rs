@@ -1,6 +1,7 @@
 //! Intent reducer coverage for debug inspector flows.
 
-use service_abi::{DebugCmd, MemSpace};
+use service_abi::{DebugCmd, MemSpace, RegistersPatch, WatchAccess};
+use std::sync::Arc;
 use world::{Intent, IntentReducer, KernelCmd, WorkCmd, World};
 
 fn single_cmd(intent: Intent) -> WorkCmd {
@@ -24,17 +25,20 @@ fn debug_snapshot_intent_emits_kernel_command() {
 fn debug_mem_intent_emits_window_params() {
     match single_cmd(Intent::DebugMem {
         group: 1,
+        lane: 2,
         space: MemSpace::Wram,
         base: 0xC120,
         len: 0x40,
     }) {
         WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::MemWindow {
             group,
+            lane,
             space,
             base,
             len,
         })) => {
             assert_eq!(group, 1);
+            assert_eq!(lane, 2);
             assert_eq!(space, MemSpace::Wram);
             assert_eq!(base, 0xC120);
             assert_eq!(len, 0x40);
@@ -63,3 +67,114 @@ fn debug_step_frame_targets_group() {
         other => panic!("unexpected command: {other:?}"),
     }
 }
+
+#[test]
+fn debug_disassemble_intent_targets_range() {
+    match single_cmd(Intent::DebugDisassemble {
+        group: 1,
+        base: 0x0150,
+        count: 8,
+    }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::Disassemble { group, base, count })) => {
+            assert_eq!((group, base, count), (1, 0x0150, 8));
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn debug_trace_intents_toggle_and_drain() {
+    match single_cmd(Intent::DebugSetTracing {
+        group: 2,
+        enabled: true,
+        capacity: 1024,
+    }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetTracing {
+            group,
+            enabled,
+            capacity,
+        })) => {
+            assert_eq!((group, enabled, capacity), (2, true, 1024));
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+    match single_cmd(Intent::DebugReadTrace { group: 2, max: 64 }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::ReadTrace { group, max })) => {
+            assert_eq!((group, max), (2, 64));
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn debug_write_intents_target_a_lane() {
+    match single_cmd(Intent::DebugWriteMem {
+        group: 1,
+        lane: 2,
+        space: MemSpace::Wram,
+        base: 0xC000,
+        bytes: Arc::from([0xAA].as_slice()),
+    }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::WriteMem {
+            group,
+            lane,
+            space,
+            base,
+            bytes,
+        })) => {
+            assert_eq!((group, lane, space, base), (1, 2, MemSpace::Wram, 0xC000));
+            assert_eq!(bytes.as_ref(), [0xAA]);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+    let regs = RegistersPatch {
+        pc: Some(0x0150),
+        ..RegistersPatch::default()
+    };
+    match single_cmd(Intent::DebugSetRegisters {
+        group: 1,
+        lane: 0,
+        regs,
+    }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetRegisters {
+            lane, regs: sent, ..
+        })) => {
+            assert_eq!((lane, sent), (0, regs));
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn debug_watchpoint_intent_carries_range_and_access() {
+    match single_cmd(Intent::DebugSetWatchpoint {
+        group: 3,
+        addr_range: 0xC000..=0xC0FF,
+        access: WatchAccess::Write,
+    }) {
+        WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::SetWatchpoint {
+            group,
+            addr_range,
+            access,
+        })) => {
+            assert_eq!(group, 3);
+            assert_eq!(addr_range, 0xC000..=0xC0FF);
+            assert_eq!(access, WatchAccess::Write);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn debug_continue_unpauses_world() {
+    let mut world = World::new();
+    world.paused = true;
+    let cmds = world.reduce_intent(Intent::DebugContinue(1));
+    assert!(!world.paused);
+    assert_eq!(
+        cmds.as_slice(),
+        [WorkCmd::Kernel(KernelCmd::Debug(DebugCmd::Continue {
+            group: 1
+        }))]
+    );
+}
@@ -1,7 +1,11 @@
 //! Integration tests validating world intent reducer behavior.
 
+use std::path::PathBuf;
 use std::sync::Arc;
-use world::{Intent, IntentReducer, KernelCmd, TickPurpose, WorkCmd, World};
+use world::{
+    FsCmd, Intent, IntentPriority, IntentReducer, KernelCmd, SubmitPolicy, TickPurpose, WorkCmd,
+    World,
+};
 
 /// PumpFrame should enqueue a display tick whose budget scales with the speed multiplier.
 #[test]
@@ -50,6 +54,7 @@ fn load_rom_forwards_group_and_payload() {
     let commands = world.reduce_intent(Intent::LoadRom {
         group: 2,
         bytes: Arc::clone(&payload),
+        save_ram: None,
     });
 
     assert_eq!(
@@ -61,11 +66,187 @@ fn load_rom_forwards_group_and_payload() {
         commands[0],
         WorkCmd::Kernel(KernelCmd::LoadRom {
             group: 2,
-            bytes: payload
+            bytes: payload,
+            save_ram: None,
         })
     );
 }
 
+/// SetCheats should forward the codes untouched; decoding happens in the kernel.
+#[test]
+fn set_cheats_forwards_codes_to_kernel() {
+    let mut world = World::new();
+    let codes = vec!["3EA-5FB-EEA".to_string(), "0163D1C0".to_string()];
+
+    let commands = world.reduce_intent(Intent::SetCheats {
+        group: 1,
+        codes: codes.clone(),
+    });
+
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Kernel(KernelCmd::SetCheats { group: 1, codes })]
+    );
+}
+
+/// Per-lane inputs and resets should reach the kernel with their lane selection intact.
+#[test]
+fn lane_inputs_and_resets_forward_to_kernel() {
+    let mut world = World::new();
+    let rom: Arc<[u8]> = Arc::from(vec![0u8; 0x8000]);
+
+    let inputs = world.reduce_intent(Intent::SetInputs {
+        group: 0,
+        lanes_mask: 0b0100,
+        joypad: 0xF7,
+    });
+    let reset = world.reduce_intent(Intent::ResetLane {
+        group: 0,
+        lane: 2,
+        rom: Some(Arc::clone(&rom)),
+        save_ram: None,
+    });
+
+    assert_eq!(
+        inputs.as_slice(),
+        &[WorkCmd::Kernel(KernelCmd::SetInputs {
+            group: 0,
+            lanes_mask: 0b0100,
+            joypad: 0xF7,
+        })]
+    );
+    assert_eq!(
+        reset.as_slice(),
+        &[WorkCmd::Kernel(KernelCmd::ResetLane {
+            group: 0,
+            lane: 2,
+            rom: Some(rom),
+            save_ram: None,
+        })]
+    );
+}
+
+/// Forks are background exploration work and pass their scripts through untouched.
+#[test]
+fn fork_forwards_scripts_to_kernel() {
+    let mut world = World::new();
+    let scripts: Vec<Arc<[u8]>> = vec![
+        Arc::from([0xFF, 0xEF].as_slice()),
+        Arc::from([0xFE].as_slice()),
+    ];
+    let intent = Intent::Fork {
+        group: 0,
+        source_lane: 0,
+        target_group: 9,
+        input_scripts: scripts.clone(),
+    };
+    assert_eq!(intent.priority(), IntentPriority::P2);
+
+    let work = world.reduce_intent(intent);
+    let expected = WorkCmd::Kernel(KernelCmd::Fork {
+        group: 0,
+        source_lane: 0,
+        target_group: 9,
+        input_scripts: scripts,
+    });
+    assert_eq!(expected.default_policy(), SubmitPolicy::BestEffort);
+    assert_eq!(work.as_slice(), &[expected]);
+}
+
+/// Settled battery RAM should be persisted under a name derived from the ROM title.
+#[test]
+fn persist_save_ram_targets_rom_title() {
+    let mut world = World::new();
+    let mut rom = vec![0u8; 0x150];
+    rom[0x134..0x13D].copy_from_slice(b"POKEMON R");
+    world.reduce_intent(Intent::LoadRom {
+        group: 1,
+        bytes: Arc::from(rom),
+        save_ram: None,
+    });
+
+    let bytes: Arc<[u8]> = Arc::from([0xAAu8; 4]);
+    let commands = world.reduce_intent(Intent::PersistSaveRam {
+        group: 1,
+        bytes: Arc::clone(&bytes),
+    });
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Fs(FsCmd::Persist {
+            path: PathBuf::from("POKEMON_R.sav"),
+            bytes,
+        })]
+    );
+}
+
+/// Quick-save slots capture through the kernel and reload from memory once captured.
+#[test]
+fn state_slot_intents_round_trip_through_kernel_commands() {
+    let mut world = World::new();
+    let mut rom = vec![0u8; 0x150];
+    rom[0x134..0x139].copy_from_slice(b"ZELDA");
+    world.reduce_intent(Intent::LoadRom {
+        group: 0,
+        bytes: Arc::from(rom),
+        save_ram: None,
+    });
+
+    let commands = world.reduce_intent(Intent::LoadStateSlot {
+        group: 0,
+        lane: 0,
+        slot: 1,
+    });
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Fs(FsCmd::Load {
+            path: PathBuf::from("ZELDA.ss1"),
+        })],
+        "uncached slots are read back from disk"
+    );
+
+    let commands = world.reduce_intent(Intent::SaveStateSlot {
+        group: 0,
+        lane: 0,
+        slot: 1,
+    });
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Kernel(KernelCmd::SaveState { group: 0, lane: 0 })]
+    );
+
+    let state: Arc<[u8]> = Arc::from(*b"GBXS");
+    assert_eq!(
+        world.store_state_slot(0, 0, Some(Arc::clone(&state))),
+        Some((1, Arc::clone(&state)))
+    );
+    let commands = world.reduce_intent(Intent::LoadStateSlot {
+        group: 0,
+        lane: 0,
+        slot: 1,
+    });
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Kernel(KernelCmd::LoadState {
+            group: 0,
+            lane: 0,
+            bytes: Arc::clone(&state),
+        })]
+    );
+
+    let commands = world.reduce_intent(Intent::PersistStateSlot {
+        group: 0,
+        slot: 1,
+        bytes: Arc::clone(&state),
+    });
+    assert_eq!(
+        commands.as_slice(),
+        &[WorkCmd::Fs(FsCmd::Persist {
+            path: PathBuf::from("ZELDA.ss1"),
+            bytes: state,
+        })]
+    );
+}
+
 /// Toggle, speed, and display lane intents mutate the world without emitting commands.
 #[test]
 fn stateful_intents_update_world_without_emitting_commands() {
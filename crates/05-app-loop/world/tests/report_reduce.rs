@@ -1,9 +1,11 @@
 //! Integration-style coverage for the world report reducer.
 
-use service_abi::{CpuVM, DebugRep, InspectorVMMinimal, PpuVM, TimersVM};
+use service_abi::{BreakReason, CpuVM, DebugRep, InspectorVMMinimal, PpuVM, TimersVM};
+use std::path::PathBuf;
+use std::sync::Arc;
 use world::{
-    AudioRep, AvCmd, FrameSpan, GpuCmd, Intent, IntentPriority, KernelRep, Report, ReportReducer,
-    ViewMode, World,
+    AudioCmd, AudioRep, AudioSpan, AvCmd, FrameSpan, FsRep, GpuCmd, Intent, IntentPriority,
+    IntentReducer, KernelRep, Report, ReportReducer, ViewMode, World, SAVE_RAM_DEBOUNCE_TICKS,
 };
 
 /// Frames for the active display lane should be forwarded to the GPU immediately.
@@ -93,6 +95,147 @@ fn tick_done_without_auto_pump_is_noop() {
     assert!(follow_ups.deferred_intents.is_empty());
 }
 
+/// Dirty battery RAM should only be persisted once writes stop for the debounce window.
+#[test]
+fn save_ram_dirty_is_debounced_into_persist_intent() {
+    let mut world = World::new();
+    world.auto_pump = false;
+    let tick = || {
+        Report::Kernel(KernelRep::TickDone {
+            group: 0,
+            lanes_mask: 0b1,
+            cycles_done: 10,
+        })
+    };
+
+    let first: Arc<[u8]> = Arc::from([1u8, 2, 3]);
+    world.reduce_report(Report::Kernel(KernelRep::SaveRamDirty {
+        group: 0,
+        lane: 0,
+        bytes: Arc::clone(&first),
+    }));
+    for _ in 0..SAVE_RAM_DEBOUNCE_TICKS / 2 {
+        assert!(world.reduce_report(tick()).deferred_intents.is_empty());
+    }
+
+    let latest: Arc<[u8]> = Arc::from([4u8, 5, 6]);
+    world.reduce_report(Report::Kernel(KernelRep::SaveRamDirty {
+        group: 0,
+        lane: 0,
+        bytes: Arc::clone(&latest),
+    }));
+    for _ in 1..SAVE_RAM_DEBOUNCE_TICKS {
+        assert!(
+            world.reduce_report(tick()).deferred_intents.is_empty(),
+            "a new write restarts the debounce window"
+        );
+    }
+
+    let follow_ups = world.reduce_report(tick());
+    assert_eq!(
+        follow_ups.deferred_intents.as_slice(),
+        &[(
+            IntentPriority::P2,
+            Intent::PersistSaveRam {
+                group: 0,
+                bytes: latest
+            }
+        )]
+    );
+    assert!(world.pending_saves.is_empty());
+    assert!(world.reduce_report(tick()).deferred_intents.is_empty());
+
+    world.reduce_report(Report::Kernel(KernelRep::SaveRamDirty {
+        group: 0,
+        lane: 1,
+        bytes: Arc::from([7u8, 8, 9]),
+    }));
+    assert!(
+        world.pending_saves.is_empty(),
+        "only lane 0 backs the group's save file"
+    );
+}
+
+/// A captured quick-save is stored in its slot and queued for persistence.
+#[test]
+fn state_saved_fills_requested_slot() {
+    let mut world = World::new();
+    world.reduce_intent(Intent::SaveStateSlot {
+        group: 0,
+        lane: 2,
+        slot: 3,
+    });
+
+    let bytes: Arc<[u8]> = Arc::from([9u8; 8]);
+    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::StateSaved {
+        group: 0,
+        lane: 2,
+        bytes: Some(Arc::clone(&bytes)),
+    }));
+    assert_eq!(
+        follow_ups.deferred_intents.as_slice(),
+        &[(
+            IntentPriority::P2,
+            Intent::PersistStateSlot {
+                group: 0,
+                slot: 3,
+                bytes: Arc::clone(&bytes),
+            }
+        )]
+    );
+    assert_eq!(world.state_slots.get(&(0, 3)), Some(&bytes));
+
+    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::StateSaved {
+        group: 0,
+        lane: 2,
+        bytes: Some(bytes),
+    }));
+    assert!(
+        follow_ups.deferred_intents.is_empty(),
+        "unsolicited captures are not persisted"
+    );
+}
+
+/// Slot files read back from disk restore the requesting lane; missing ones restore nothing.
+#[test]
+fn loaded_slot_file_restores_lane() {
+    let mut world = World::new();
+    for slot in [1, 2] {
+        world.reduce_intent(Intent::LoadStateSlot {
+            group: 0,
+            lane: 1,
+            slot,
+        });
+    }
+    assert_eq!(world.pending_slot_loads.len(), 2);
+
+    let bytes: Arc<[u8]> = Arc::from([7u8; 8]);
+    let follow_ups = world.reduce_report(Report::Fs(FsRep::Loaded {
+        path: PathBuf::from("group-0.ss1"),
+        bytes: Some(Arc::clone(&bytes)),
+    }));
+    assert_eq!(
+        follow_ups.deferred_intents.as_slice(),
+        &[(
+            IntentPriority::P0,
+            Intent::LoadState {
+                group: 0,
+                lane: 1,
+                bytes: Arc::clone(&bytes),
+            }
+        )]
+    );
+    assert_eq!(world.state_slots.get(&(0, 1)), Some(&bytes));
+
+    let follow_ups = world.reduce_report(Report::Fs(FsRep::Loaded {
+        path: PathBuf::from("group-0.ss2"),
+        bytes: None,
+    }));
+    assert!(follow_ups.deferred_intents.is_empty());
+    assert!(world.pending_slot_loads.is_empty());
+    assert!(!world.state_slots.contains_key(&(0, 2)));
+}
+
 /// Successful ROM load reports should update world tracking flags.
 #[test]
 fn rom_loaded_updates_world_state() {
@@ -109,6 +252,31 @@ fn rom_loaded_updates_world_state() {
     assert_eq!(world.rom_events, 1);
 }
 
+/// Kernel audio should be submitted to the audio service; silent ticks are dropped.
+#[test]
+fn audio_ready_submits_samples() {
+    let mut world = World::new();
+    let span = AudioSpan {
+        samples: Arc::from(vec![0i16, 1, -1, 2]),
+        ..AudioSpan::default()
+    };
+
+    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::AudioReady {
+        group: 0,
+        span: span.clone(),
+    }));
+    assert_eq!(
+        follow_ups.immediate_av.as_slice(),
+        &[AvCmd::Audio(AudioCmd::Submit { span })]
+    );
+
+    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::AudioReady {
+        group: 0,
+        span: AudioSpan::default(),
+    }));
+    assert!(follow_ups.immediate_av.is_empty());
+}
+
 /// Audio underruns should be reflected in the world's performance metrics.
 #[test]
 fn audio_underrun_increments_metric() {
@@ -160,9 +328,30 @@ fn debug_snapshot_report_updates_inspector_state() {
         io: vec![0; 0x80],
     });
 
-    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::Debug(snapshot)));
+    let follow_ups = world.reduce_report(Report::Kernel(KernelRep::Debug {
+        group: 0,
+        rep: snapshot,
+    }));
 
     assert!(follow_ups.immediate_av.is_empty());
     assert_eq!(world.inspector.vm.cpu.a, 0x42);
     assert_eq!(world.inspector.vm.cpu.pc, 0x0200);
 }
+
+/// A breakpoint hit pauses the world and surfaces in the inspector.
+#[test]
+fn breakpoint_hit_pauses_world() {
+    let mut world = World::new();
+    world.reduce_report(Report::Kernel(KernelRep::Debug {
+        group: 0,
+        rep: DebugRep::BreakpointHit {
+            group: 0,
+            pc: 0x0150,
+            reason: BreakReason::Breakpoint,
+        },
+    }));
+
+    assert!(world.paused);
+    let hit = world.inspector.vm.last_break.as_ref().expect("break");
+    assert_eq!(hit.pc, 0x0150);
+}
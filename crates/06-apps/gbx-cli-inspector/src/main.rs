@@ -1,18 +1,30 @@
 //! Command-line utility for exercising the Phase A debug inspector.
 
 use anyhow::{anyhow, bail, Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use inspector_vm::InspectorVM;
-use service_abi::{DebugCmd, DebugRep, KernelCmd, KernelRep, MemSpace, SubmitOutcome};
+use kernel_core::ppu::CYCLES_PER_FRAME;
+use service_abi::{
+    DebugCmd, DebugRep, KernelCmd, KernelRep, MemSpace, RegistersPatch, SubmitOutcome, TickPurpose,
+    TraceEntryVM, WatchAccess,
+};
 use services_kernel::default_service;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use testrom_runner::Budget;
+
+/// Trace entries fetched per `ReadTrace` request.
+const TRACE_CHUNK: u16 = 1024;
 
 /// Text rendering helpers used by the CLI commands.
 mod render {
-    use inspector_vm::InspectorVM;
+    use inspector_vm::{gameboy_doctor_line, InspectorVM};
+    use service_abi::{BreakReason, DisasmLineVM, TraceEntryVM, WatchAccess};
     use std::fmt::Write;
+    use testrom_runner::{CaseReport, Report, Status};
 
     /// Format the CPU/PPU/timer portion of an inspector snapshot.
     pub fn snapshot(vm: &InspectorVM) -> String {
@@ -79,15 +91,98 @@ mod render {
     pub fn step_frame(cycles: u32, pc: u16) -> String {
         format!("Advanced one frame -> PC={pc:04X} cycles={cycles}\n")
     }
+
+    /// Format a code listing, marking the instruction at `pc`.
+    pub fn disassembly(lines: &[DisasmLineVM], pc: u16) -> String {
+        let mut out = String::new();
+        for line in lines {
+            let marker = if line.addr == pc { "=>" } else { "  " };
+            let bytes: Vec<String> = line.bytes.iter().map(|b| format!("{b:02X}")).collect();
+            writeln!(
+                out,
+                "{marker} {:04X}: {:<9} {}",
+                line.addr,
+                bytes.join(" "),
+                line.text
+            )
+            .expect("write disasm line");
+        }
+        out
+    }
+
+    /// Format the output for a run that stopped on a breakpoint or watchpoint.
+    pub fn break_hit(pc: u16, reason: BreakReason, frames: u32) -> String {
+        let what = match reason {
+            BreakReason::Breakpoint => "Breakpoint".to_string(),
+            BreakReason::Watchpoint { addr, access } => {
+                let access = match access {
+                    WatchAccess::Read => "read",
+                    WatchAccess::Write => "write",
+                };
+                format!("Watchpoint ({access} {addr:04X})")
+            }
+        };
+        format!("{what} hit -> PC={pc:04X} after {frames} frame(s)\n")
+    }
+
+    /// Format one test-ROM result as a progress line.
+    pub fn test_case(case: &CaseReport) -> String {
+        let label = match case.status {
+            Status::Passed => "PASS",
+            Status::Failed => "FAIL",
+            Status::Timeout => "TIME",
+            Status::Error => "ERR ",
+            Status::Skipped => "SKIP",
+        };
+        if case.message.is_empty() {
+            format!("{label} {} ({} cycles)\n", case.path, case.cycles)
+        } else {
+            format!(
+                "{label} {} ({} cycles): {}\n",
+                case.path, case.cycles, case.message
+            )
+        }
+    }
+
+    /// Format the per-suite pass/fail table of a test-ROM run.
+    pub fn test_summary(report: &Report) -> String {
+        let mut out = String::new();
+        let mut row = |name: &str, tally: testrom_runner::Tally| {
+            writeln!(
+                out,
+                "{name:<32} {:>5} passed {:>5} failed {:>5} timeout {:>5} error {:>5} skipped",
+                tally.passed, tally.failed, tally.timed_out, tally.errors, tally.skipped
+            )
+            .expect("write summary row");
+        };
+        for suite in &report.suites {
+            row(suite.name, suite.tally);
+        }
+        row("total", report.tally());
+        out
+    }
+
+    /// Format trace entries as a Gameboy Doctor log, one line per instruction.
+    pub fn doctor_log(entries: &[TraceEntryVM]) -> String {
+        let mut out = String::new();
+        for entry in entries {
+            writeln!(out, "{}", gameboy_doctor_line(entry)).expect("write trace line");
+        }
+        out
+    }
 }
 
 /// Inspect GBX kernels via the Phase A debug pipeline.
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Interact with the GBX inspector", long_about = None)]
 struct Cli {
-    /// Path to the ROM to load.
+    /// Path to the ROM to load (not used by `test-roms`).
     #[arg(value_name = "ROM")]
-    rom: PathBuf,
+    rom: Option<PathBuf>,
+
+    /// Game Genie or GameShark code to activate after loading (repeatable).
+    #[arg(long = "cheat", value_name = "CODE", global = true)]
+    cheats: Vec<String>,
 
     #[command(subcommand)]
     command: Command,
@@ -101,14 +196,20 @@ enum Command {
         #[arg(short, long, default_value_t = 0)]
         group: u16,
     },
-    /// Dump a memory window from VRAM/OAM/WRAM/IO.
+    /// Dump a memory window from VRAM/OAM/WRAM/IO/HRAM, a cartridge bank or the bus.
     Mem {
         /// Kernel group identifier (defaults to 0).
         #[arg(short, long, default_value_t = 0)]
         group: u16,
+        /// Lane to read (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        lane: u16,
         /// Memory space to read.
         #[arg(value_enum)]
         space: MemSpaceArg,
+        /// Cartridge bank for the rom and cart-ram spaces (defaults to 0).
+        #[arg(long, value_parser = parse_u16, default_value_t = 0)]
+        bank: u16,
         /// Start address (decimal or hex, e.g. 0x8000).
         #[arg(value_parser = parse_u16, value_name = "BASE")]
         base: u16,
@@ -131,15 +232,151 @@ enum Command {
         #[arg(short, long, default_value_t = 0)]
         group: u16,
     },
+    /// Disassemble instructions starting at BASE (defaults to PC).
+    Disasm {
+        /// Kernel group identifier (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        group: u16,
+        /// Start address (decimal or hex); defaults to the current PC.
+        #[arg(value_parser = parse_u16, value_name = "BASE")]
+        base: Option<u16>,
+        /// Number of instructions to list (defaults to 16).
+        #[arg(short, long, value_parser = parse_u16, default_value_t = 16)]
+        count: u16,
+    },
+    /// Run until a breakpoint or watchpoint fires and print the resulting snapshot.
+    Run {
+        /// Kernel group identifier (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        group: u16,
+        /// Break before executing the instruction at PC (repeatable).
+        #[arg(short = 'b', long = "break", value_parser = parse_u16, value_name = "PC")]
+        breakpoints: Vec<u16>,
+        /// Break after an access to START[-END][:r|w]; writes by default (repeatable).
+        #[arg(short = 'w', long = "watch", value_parser = parse_watch, value_name = "RANGE")]
+        watchpoints: Vec<(RangeInclusive<u16>, WatchAccess)>,
+        /// Give up after this many frames (defaults to 600).
+        #[arg(long, value_parser = parse_u32, default_value_t = 600, value_name = "FRAMES")]
+        max_frames: u32,
+    },
+    /// Trace N CPU instructions and print a Gameboy Doctor compatible log.
+    Trace {
+        /// Kernel group identifier (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        group: u16,
+        /// Number of instructions to execute and trace.
+        #[arg(value_parser = parse_u32, value_name = "COUNT")]
+        count: u32,
+        /// Write the log to this file instead of stdout.
+        #[arg(short, long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Write bytes into a memory space and print the patched window.
+    Poke {
+        /// Kernel group identifier (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        group: u16,
+        /// Lane to patch (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        lane: u16,
+        /// Memory space to write.
+        #[arg(value_enum)]
+        space: MemSpaceArg,
+        /// Cartridge bank for the rom and cart-ram spaces (defaults to 0).
+        #[arg(long, value_parser = parse_u16, default_value_t = 0)]
+        bank: u16,
+        /// Start address (decimal or hex, e.g. 0xC000).
+        #[arg(value_parser = parse_u16, value_name = "BASE")]
+        base: u16,
+        /// Bytes to write as hex, e.g. `3E01` or `3e:01`.
+        #[arg(value_parser = parse_hex_bytes, value_name = "BYTES")]
+        bytes: HexBytes,
+    },
+    /// Overwrite CPU registers and print the resulting snapshot.
+    SetRegs {
+        /// Kernel group identifier (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        group: u16,
+        /// Lane to patch (defaults to 0).
+        #[arg(short, long, default_value_t = 0)]
+        lane: u16,
+        /// Accumulator.
+        #[arg(long, value_parser = parse_u8)]
+        a: Option<u8>,
+        /// Flags register; the low nibble is always cleared.
+        #[arg(long, value_parser = parse_u8)]
+        f: Option<u8>,
+        /// Register B.
+        #[arg(long, value_parser = parse_u8)]
+        b: Option<u8>,
+        /// Register C.
+        #[arg(long, value_parser = parse_u8)]
+        c: Option<u8>,
+        /// Register D.
+        #[arg(long, value_parser = parse_u8)]
+        d: Option<u8>,
+        /// Register E.
+        #[arg(long, value_parser = parse_u8)]
+        e: Option<u8>,
+        /// Register H.
+        #[arg(long, value_parser = parse_u8)]
+        h: Option<u8>,
+        /// Register L.
+        #[arg(long, value_parser = parse_u8)]
+        l: Option<u8>,
+        /// Stack pointer.
+        #[arg(long, value_parser = parse_u16)]
+        sp: Option<u16>,
+        /// Program counter.
+        #[arg(long, value_parser = parse_u16)]
+        pc: Option<u16>,
+        /// Interrupt master enable.
+        #[arg(long)]
+        ime: Option<bool>,
+    },
+    /// Run the vendored test-ROM bundle headlessly and report results per suite.
+    TestRoms(TestRomsArgs),
 }
 
+/// Selection, budget and report outputs for `test-roms`.
+#[derive(Args, Debug)]
+struct TestRomsArgs {
+    /// Only run ROMs from this suite (repeatable; defaults to every suite).
+    #[arg(short, long = "suite", value_name = "SUITE")]
+    suites: Vec<String>,
+    /// Only run ROMs whose bundle path contains this text.
+    #[arg(short, long, value_name = "TEXT")]
+    filter: Option<String>,
+    /// Emulated cycle budget per ROM.
+    #[arg(long, default_value_t = Budget::default().max_cycles, value_name = "CYCLES")]
+    max_cycles: u64,
+    /// Wall-clock budget per ROM in seconds.
+    #[arg(long, default_value_t = Budget::default().timeout.as_secs(), value_name = "SECS")]
+    timeout: u64,
+    /// Write a JUnit XML report to this file.
+    #[arg(long, value_name = "FILE")]
+    junit: Option<PathBuf>,
+    /// Write a JSON report to this file.
+    #[arg(long, value_name = "FILE")]
+    json: Option<PathBuf>,
+}
+
+/// Byte string parsed from a `poke` argument.
+type HexBytes = Vec<u8>;
+
 impl Command {
     fn group(&self) -> u16 {
         match *self {
             Command::Snapshot { group }
             | Command::Mem { group, .. }
             | Command::Step { group, .. }
-            | Command::StepFrame { group } => group,
+            | Command::StepFrame { group }
+            | Command::Disasm { group, .. }
+            | Command::Run { group, .. }
+            | Command::Trace { group, .. }
+            | Command::Poke { group, .. }
+            | Command::SetRegs { group, .. } => group,
+            Command::TestRoms(_) => 0,
         }
     }
 }
@@ -154,35 +391,59 @@ enum MemSpaceArg {
     Oam,
     /// I/O register window (0xFF00-0xFF7F).
     Io,
+    /// High RAM and the IE register (0xFF80-0xFFFF).
+    Hram,
+    /// Cartridge ROM bank selected with --bank (0x0000-0x3FFF for bank 0, else 0x4000-0x7FFF).
+    Rom,
+    /// Cartridge RAM bank selected with --bank (0xA000-0xBFFF).
+    CartRam,
+    /// Whole CPU address map as currently mapped (0x0000-0xFFFF).
+    Bus,
 }
 
-impl From<MemSpaceArg> for MemSpace {
-    fn from(arg: MemSpaceArg) -> Self {
-        match arg {
+impl MemSpaceArg {
+    fn with_bank(self, bank: u16) -> Result<MemSpace> {
+        Ok(match self {
             MemSpaceArg::Vram => MemSpace::Vram,
             MemSpaceArg::Wram => MemSpace::Wram,
             MemSpaceArg::Oam => MemSpace::Oam,
             MemSpaceArg::Io => MemSpace::Io,
-        }
+            MemSpaceArg::Hram => MemSpace::Hram,
+            MemSpaceArg::Rom => MemSpace::Rom { bank },
+            MemSpaceArg::CartRam => MemSpace::CartRam {
+                bank: u8::try_from(bank)
+                    .map_err(|_| anyhow!("cartridge RAM bank {bank} out of range"))?,
+            },
+            MemSpaceArg::Bus => MemSpace::Bus,
+        })
     }
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let bytes = load_rom_bytes(&cli.rom)?;
+    if let Command::TestRoms(args) = cli.command {
+        return handle_test_roms(args);
+    }
+    let rom = cli.rom.context("a ROM path is required for this command")?;
+    let bytes = load_rom_bytes(&rom)?;
     let kernel = default_service();
 
     let group = cli.command.group();
     load_rom(&kernel, group, Arc::clone(&bytes))?;
+    if !cli.cheats.is_empty() {
+        set_cheats(&kernel, group, cli.cheats)?;
+    }
 
     match cli.command {
         Command::Snapshot { group } => handle_snapshot(&kernel, group)?,
         Command::Mem {
             group,
+            lane,
             space,
+            bank,
             base,
             len,
-        } => handle_mem(&kernel, group, space.into(), base, len)?,
+        } => handle_mem(&kernel, group, lane, space.with_bank(bank)?, base, len)?,
         Command::Step { group, count } => {
             handle_step_instructions(&kernel, group, count)?;
             handle_snapshot(&kernel, group)?;
@@ -191,6 +452,74 @@ fn main() -> Result<()> {
             handle_step_frame(&kernel, group)?;
             handle_snapshot(&kernel, group)?;
         }
+        Command::Disasm { group, base, count } => handle_disasm(&kernel, group, base, count)?,
+        Command::Run {
+            group,
+            breakpoints,
+            watchpoints,
+            max_frames,
+        } => {
+            handle_run(&kernel, group, &breakpoints, watchpoints, max_frames)?;
+            handle_snapshot(&kernel, group)?;
+        }
+        Command::Trace { group, count, out } => handle_trace(&kernel, group, count, out)?,
+        Command::Poke {
+            group,
+            lane,
+            space,
+            bank,
+            base,
+            bytes,
+        } => {
+            let space = space.with_bank(bank)?;
+            let len = u16::try_from(bytes.len()).context("poke payload too long")?;
+            submit(
+                &kernel,
+                KernelCmd::Debug(DebugCmd::WriteMem {
+                    group,
+                    lane,
+                    space,
+                    base,
+                    bytes: Arc::from(bytes),
+                }),
+            )?;
+            handle_mem(&kernel, group, lane, space, base, len)?;
+        }
+        Command::SetRegs {
+            group,
+            lane,
+            a,
+            f,
+            b,
+            c,
+            d,
+            e,
+            h,
+            l,
+            sp,
+            pc,
+            ime,
+        } => {
+            let regs = RegistersPatch {
+                a,
+                f,
+                b,
+                c,
+                d,
+                e,
+                h,
+                l,
+                sp,
+                pc,
+                ime,
+            };
+            submit(
+                &kernel,
+                KernelCmd::Debug(DebugCmd::SetRegisters { group, lane, regs }),
+            )?;
+            handle_snapshot(&kernel, group)?;
+        }
+        Command::TestRoms(_) => unreachable!("test-roms runs without a kernel"),
     }
 
     Ok(())
@@ -202,7 +531,14 @@ fn load_rom_bytes(path: &Path) -> Result<Arc<[u8]>> {
 }
 
 fn load_rom(kernel: &service_abi::KernelServiceHandle, group: u16, bytes: Arc<[u8]>) -> Result<()> {
-    submit(kernel, KernelCmd::LoadRom { group, bytes })?;
+    submit(
+        kernel,
+        KernelCmd::LoadRom {
+            group,
+            bytes,
+            save_ram: None,
+        },
+    )?;
     let reports = kernel.drain(8);
     if !reports
         .iter()
@@ -213,6 +549,25 @@ fn load_rom(kernel: &service_abi::KernelServiceHandle, group: u16, bytes: Arc<[u
     Ok(())
 }
 
+fn set_cheats(
+    kernel: &service_abi::KernelServiceHandle,
+    group: u16,
+    codes: Vec<String>,
+) -> Result<()> {
+    submit(kernel, KernelCmd::SetCheats { group, codes })?;
+    match kernel
+        .drain(8)
+        .into_iter()
+        .find(|rep| matches!(rep, KernelRep::CheatsSet { .. }))
+    {
+        Some(KernelRep::CheatsSet { rejected, .. }) if rejected.is_empty() => Ok(()),
+        Some(KernelRep::CheatsSet { rejected, .. }) => {
+            bail!("invalid cheat codes: {}", rejected.join(", "))
+        }
+        _ => bail!("kernel did not acknowledge cheats"),
+    }
+}
+
 fn handle_snapshot(kernel: &service_abi::KernelServiceHandle, group: u16) -> Result<()> {
     let debug = issue_debug(kernel, DebugCmd::Snapshot { group })?;
     match debug {
@@ -229,6 +584,7 @@ fn handle_snapshot(kernel: &service_abi::KernelServiceHandle, group: u16) -> Res
 fn handle_mem(
     kernel: &service_abi::KernelServiceHandle,
     group: u16,
+    lane: u16,
     space: MemSpace,
     base: u16,
     len: u16,
@@ -237,6 +593,7 @@ fn handle_mem(
         kernel,
         DebugCmd::MemWindow {
             group,
+            lane,
             space,
             base,
             len,
@@ -258,8 +615,13 @@ fn handle_step_instructions(
 ) -> Result<()> {
     let debug = issue_debug(kernel, DebugCmd::StepInstruction { group, count })?;
     match debug {
-        DebugRep::Stepped { cycles, pc, .. } => {
+        DebugRep::Stepped {
+            cycles, pc, disasm, ..
+        } => {
             print!("{}", render::step_instruction(count, cycles, pc));
+            if let Some(text) = disasm {
+                println!("Next: {text}");
+            }
             Ok(())
         }
         other => bail!("unexpected debug payload: {other:?}"),
@@ -275,7 +637,7 @@ fn handle_step_frame(kernel: &service_abi::KernelServiceHandle, group: u16) -> R
     }
     if let Some(DebugRep::Stepped { pc, cycles, .. }) =
         reports.into_iter().find_map(|rep| match rep {
-            KernelRep::Debug(debug) => Some(debug),
+            KernelRep::Debug { rep: debug, .. } => Some(debug),
             _ => None,
         })
     {
@@ -286,6 +648,177 @@ fn handle_step_frame(kernel: &service_abi::KernelServiceHandle, group: u16) -> R
     }
 }
 
+fn handle_disasm(
+    kernel: &service_abi::KernelServiceHandle,
+    group: u16,
+    base: Option<u16>,
+    count: u16,
+) -> Result<()> {
+    let pc = match issue_debug(kernel, DebugCmd::Snapshot { group })? {
+        DebugRep::Snapshot(snapshot) => snapshot.cpu.pc,
+        other => bail!("unexpected debug payload: {other:?}"),
+    };
+    let base = base.unwrap_or(pc);
+    match issue_debug(kernel, DebugCmd::Disassemble { group, base, count })? {
+        DebugRep::Disassembly { lines, .. } => {
+            print!("{}", render::disassembly(&lines, pc));
+            Ok(())
+        }
+        other => bail!("unexpected debug payload: {other:?}"),
+    }
+}
+
+fn handle_run(
+    kernel: &service_abi::KernelServiceHandle,
+    group: u16,
+    breakpoints: &[u16],
+    watchpoints: Vec<(RangeInclusive<u16>, WatchAccess)>,
+    max_frames: u32,
+) -> Result<()> {
+    if breakpoints.is_empty() && watchpoints.is_empty() {
+        bail!("run needs at least one --break or --watch");
+    }
+    for &pc in breakpoints {
+        submit(
+            kernel,
+            KernelCmd::Debug(DebugCmd::SetBreakpoint { group, pc }),
+        )?;
+    }
+    for (addr_range, access) in watchpoints {
+        submit(
+            kernel,
+            KernelCmd::Debug(DebugCmd::SetWatchpoint {
+                group,
+                addr_range,
+                access,
+            }),
+        )?;
+    }
+
+    for frame in 1..=max_frames {
+        submit(
+            kernel,
+            KernelCmd::Tick {
+                group,
+                purpose: TickPurpose::Display,
+                budget: CYCLES_PER_FRAME,
+            },
+        )?;
+        let hit = kernel.drain(32).into_iter().find_map(|rep| match rep {
+            KernelRep::Debug {
+                rep: DebugRep::BreakpointHit { pc, reason, .. },
+                ..
+            } => Some((pc, reason)),
+            _ => None,
+        });
+        if let Some((pc, reason)) = hit {
+            print!("{}", render::break_hit(pc, reason, frame));
+            return Ok(());
+        }
+    }
+    println!("No breakpoint hit after {max_frames} frame(s)");
+    Ok(())
+}
+
+fn handle_trace(
+    kernel: &service_abi::KernelServiceHandle,
+    group: u16,
+    count: u32,
+    out: Option<PathBuf>,
+) -> Result<()> {
+    submit(
+        kernel,
+        KernelCmd::Debug(DebugCmd::SetTracing {
+            group,
+            enabled: true,
+            capacity: count.max(1),
+        }),
+    )?;
+    match issue_debug(kernel, DebugCmd::StepInstruction { group, count })? {
+        DebugRep::Stepped { .. } => {}
+        other => bail!("unexpected debug payload: {other:?}"),
+    }
+
+    let mut entries: Vec<TraceEntryVM> = Vec::new();
+    loop {
+        match issue_debug(
+            kernel,
+            DebugCmd::ReadTrace {
+                group,
+                max: TRACE_CHUNK,
+            },
+        )? {
+            DebugRep::Trace {
+                entries: chunk,
+                remaining,
+                ..
+            } => {
+                entries.extend(chunk);
+                if remaining == 0 {
+                    break;
+                }
+            }
+            other => bail!("unexpected debug payload: {other:?}"),
+        }
+    }
+    submit(
+        kernel,
+        KernelCmd::Debug(DebugCmd::SetTracing {
+            group,
+            enabled: false,
+            capacity: 0,
+        }),
+    )?;
+
+    let log = render::doctor_log(&entries);
+    match out {
+        Some(path) => {
+            fs::write(&path, log).with_context(|| format!("failed to write trace {path:?}"))?;
+            println!(
+                "Wrote {} trace line(s) to {}",
+                entries.len(),
+                path.display()
+            );
+        }
+        None => print!("{log}"),
+    }
+    Ok(())
+}
+
+fn handle_test_roms(args: TestRomsArgs) -> Result<()> {
+    let budget = Budget {
+        max_cycles: args.max_cycles,
+        timeout: Duration::from_secs(args.timeout),
+    };
+    let roms = testdata::list().iter().filter(|meta| {
+        (args.suites.is_empty() || args.suites.iter().any(|suite| suite == meta.suite))
+            && args
+                .filter
+                .as_deref()
+                .is_none_or(|text| meta.path.contains(text))
+    });
+    let report = testrom_runner::run_suite(roms, budget, |case| {
+        print!("{}", render::test_case(case));
+    });
+    if report.suites.is_empty() {
+        bail!(
+            "no test ROMs matched (bundle {})",
+            testdata::source_bundle()
+        );
+    }
+    print!("{}", render::test_summary(&report));
+
+    if let Some(path) = &args.junit {
+        fs::write(path, report.to_junit())
+            .with_context(|| format!("failed to write JUnit report {path:?}"))?;
+    }
+    if let Some(path) = &args.json {
+        fs::write(path, report.to_json())
+            .with_context(|| format!("failed to write JSON report {path:?}"))?;
+    }
+    Ok(())
+}
+
 fn print_snapshot(vm: &InspectorVM) {
     print!("{}", render::snapshot(vm));
 }
@@ -300,7 +833,7 @@ fn issue_debug(kernel: &service_abi::KernelServiceHandle, cmd: DebugCmd) -> Resu
     reports
         .into_iter()
         .find_map(|rep| match rep {
-            KernelRep::Debug(debug) => Some(debug),
+            KernelRep::Debug { rep: debug, .. } => Some(debug),
             _ => None,
         })
         .ok_or_else(|| anyhow!("debug command produced no payload"))
@@ -333,6 +866,54 @@ fn parse_u16(input: &str) -> Result<u16, String> {
     }
 }
 
+fn parse_u8(input: &str) -> Result<u8, String> {
+    let value = parse_u16(input)?;
+    u8::try_from(value).map_err(|_| format!("value '{input}' does not fit in a byte"))
+}
+
+fn parse_hex_bytes(input: &str) -> Result<HexBytes, String> {
+    let digits: String = input
+        .strip_prefix("0x")
+        .unwrap_or(input)
+        .chars()
+        .filter(|c| !matches!(c, ':' | ' ' | '_'))
+        .collect();
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex byte in '{input}'"));
+    }
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return Err(format!("'{input}' is not an even number of hex digits"));
+    }
+    Ok(digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let text = std::str::from_utf8(pair).expect("ascii hex digits");
+            u8::from_str_radix(text, 16).expect("validated hex digits")
+        })
+        .collect())
+}
+
+fn parse_watch(input: &str) -> Result<(RangeInclusive<u16>, WatchAccess), String> {
+    let (range, access) = match input.rsplit_once(':') {
+        Some((range, "r")) => (range, WatchAccess::Read),
+        Some((range, "w")) => (range, WatchAccess::Write),
+        Some((_, other)) => return Err(format!("invalid watch access '{other}' (use r or w)")),
+        None => (input, WatchAccess::Write),
+    };
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (parse_u16(start)?, parse_u16(end)?),
+        None => {
+            let addr = parse_u16(range)?;
+            (addr, addr)
+        }
+    };
+    if start > end {
+        return Err(format!("watch range '{range}' ends before it starts"));
+    }
+    Ok((start..=end, access))
+}
+
 fn parse_u32(input: &str) -> Result<u32, String> {
     if let Some(stripped) = input.strip_prefix("0x") {
         u32::from_str_radix(stripped, 16).map_err(|_| format!("invalid hex value '{input}'"))
@@ -345,9 +926,11 @@ fn parse_u32(input: &str) -> Result<u32, String> {
 
 #[cfg(test)]
 mod tests {
-    use super::render;
+    use super::{parse_hex_bytes, parse_watch, render};
     use inspector_vm::{InspectorVM, MemVM, PerfVM, PortMetricsVM, TransportVM};
     use insta::assert_snapshot;
+    use service_abi::{BreakReason, DisasmLineVM, TraceEntryVM, WatchAccess};
+    use testrom_runner::{CaseReport, Report, Status};
 
     fn sample_vm() -> InspectorVM {
         let mut vm = InspectorVM::default();
@@ -396,4 +979,127 @@ mod tests {
             render::step_instruction(3, 12, 0x0150)
         );
     }
+
+    #[test]
+    fn disassembly_render_matches_expectation() {
+        let lines = [
+            DisasmLineVM {
+                addr: 0x0150,
+                bytes: vec![0x3E, 0x42],
+                text: "LD A,$42".into(),
+            },
+            DisasmLineVM {
+                addr: 0x0152,
+                bytes: vec![0xEA, 0x10, 0xC0],
+                text: "LD ($C010),A".into(),
+            },
+            DisasmLineVM {
+                addr: 0x0155,
+                bytes: vec![0x18, 0xF9],
+                text: "JR $0150".into(),
+            },
+        ];
+        assert_snapshot!("disassembly_render", render::disassembly(&lines, 0x0152));
+    }
+
+    #[test]
+    fn break_hit_render_matches_expectation() {
+        assert_snapshot!(
+            "break_hit_render",
+            render::break_hit(
+                0x0153,
+                BreakReason::Watchpoint {
+                    addr: 0xC010,
+                    access: WatchAccess::Write
+                },
+                2
+            )
+        );
+    }
+
+    #[test]
+    fn doctor_log_render_matches_expectation() {
+        let first = TraceEntryVM {
+            cycle: 0,
+            pc: 0x0100,
+            pcmem: [0x00, 0xC3, 0x50, 0x01],
+            a: 0x01,
+            f: 0xB0,
+            b: 0x00,
+            c: 0x13,
+            d: 0x00,
+            e: 0xD8,
+            h: 0x01,
+            l: 0x4D,
+            sp: 0xFFFE,
+            accesses: Vec::new(),
+        };
+        let second = TraceEntryVM {
+            cycle: 4,
+            pc: 0x0101,
+            pcmem: [0xC3, 0x50, 0x01, 0xCE],
+            ..first.clone()
+        };
+        assert_snapshot!("doctor_log_render", render::doctor_log(&[first, second]));
+    }
+
+    #[test]
+    fn test_roms_render_matches_expectation() {
+        let case = |path, status, cycles, message: &str| CaseReport {
+            path,
+            name: "rom",
+            status,
+            cycles,
+            seconds: 0.5,
+            message: message.into(),
+        };
+        let mut report = Report {
+            bundle: "c-sp-v7.0",
+            suites: Vec::new(),
+        };
+        let results = [
+            (
+                "blargg",
+                case("blargg/cpu_instrs.gb", Status::Passed, 120, ""),
+            ),
+            (
+                "mooneye-test-suite",
+                case("mooneye/di_timing.gb", Status::Timeout, 900, "no result"),
+            ),
+            (
+                "gambatte",
+                case("gambatte/x.gb", Status::Skipped, 0, "no signal"),
+            ),
+        ];
+        let mut log = String::new();
+        for (suite, case) in results {
+            log.push_str(&render::test_case(&case));
+            report.record(suite, case);
+        }
+        log.push_str(&render::test_summary(&report));
+        assert_snapshot!("test_roms_render", log);
+    }
+
+    #[test]
+    fn parse_watch_accepts_ranges_and_access() {
+        assert_eq!(
+            parse_watch("0xC010"),
+            Ok((0xC010..=0xC010, WatchAccess::Write))
+        );
+        assert_eq!(
+            parse_watch("0xFF40-0xFF4B:r"),
+            Ok((0xFF40..=0xFF4B, WatchAccess::Read))
+        );
+        assert!(parse_watch("0xC010:x").is_err());
+        assert!(parse_watch("0xD000-0xC000").is_err());
+    }
+
+    #[test]
+    fn parse_hex_bytes_accepts_separators() {
+        assert_eq!(parse_hex_bytes("3E01"), Ok(vec![0x3E, 0x01]));
+        assert_eq!(parse_hex_bytes("0xc3:50:01"), Ok(vec![0xC3, 0x50, 0x01]));
+        assert!(parse_hex_bytes("3E0").is_err());
+        assert!(parse_hex_bytes("zz").is_err());
+        assert!(parse_hex_bytes("").is_err());
+    }
 }
@@ -178,6 +178,7 @@ pub fn gbx_load_rom(bytes: Uint8Array) -> Result<(), JsValue> {
             ctx.scheduler.enqueue_front_p0(Intent::LoadRom {
                 group: 0,
                 bytes: rom,
+                save_ram: None,
             });
             ctx.scheduler
                 .enqueue_intent(IntentPriority::P1, Intent::PumpFrame);
@@ -13,7 +13,7 @@ use transport::schema::{
     TAG_AUDIO_CMD, TAG_AUDIO_REP, TAG_FS_CMD, TAG_FS_REP, TAG_GPU_CMD, TAG_GPU_REP, TAG_KERNEL_CMD,
     TAG_KERNEL_REP,
 };
-use transport_codecs::{AudioCodec, FsCodec, GpuCodec, KernelCodec};
+use transport_codecs::{AudioCodec, FsCodec, GpuCodec, KernelCodec, KERNEL_AUDIO_POOL};
 use transport_fabric::{Codec, ServiceEngine, WorkerEndpoint};
 use wasm_bindgen::prelude::*;
 use web_sys::console;
@@ -109,11 +109,15 @@ pub fn worker_register_services(_layout_ptr: u32, _layout_len: u32) -> i32 {
         return ERR_BAD_LAYOUT;
     }
 
-    let kernel_endpoint =
-        match build_worker_endpoint(&endpoints[0], KernelCodec, TAG_KERNEL_CMD, TAG_KERNEL_REP) {
-            Ok(endpoint) => endpoint,
-            Err(code) => return code,
-        };
+    let kernel_endpoint = match build_worker_endpoint(
+        &endpoints[0],
+        KernelCodec::default(),
+        TAG_KERNEL_CMD,
+        TAG_KERNEL_REP,
+    ) {
+        Ok(endpoint) => endpoint,
+        Err(code) => return code,
+    };
     let fs_endpoint = match build_worker_endpoint(&endpoints[1], FsCodec, TAG_FS_CMD, TAG_FS_REP) {
         Ok(endpoint) => endpoint,
         Err(code) => return code,
@@ -123,11 +127,15 @@ pub fn worker_register_services(_layout_ptr: u32, _layout_len: u32) -> i32 {
             Ok(endpoint) => endpoint,
             Err(code) => return code,
         };
-    let audio_endpoint =
-        match build_worker_endpoint(&endpoints[3], AudioCodec, TAG_AUDIO_CMD, TAG_AUDIO_REP) {
-            Ok(endpoint) => endpoint,
-            Err(code) => return code,
-        };
+    let audio_endpoint = match build_worker_endpoint(
+        &endpoints[3],
+        AudioCodec::default(),
+        TAG_AUDIO_CMD,
+        TAG_AUDIO_REP,
+    ) {
+        Ok(endpoint) => endpoint,
+        Err(code) => return code,
+    };
 
     let status = FABRIC_RUNTIME.with(move |runtime_cell| {
         let mut guard = runtime_cell.borrow_mut();
@@ -144,6 +152,14 @@ pub fn worker_register_services(_layout_ptr: u32, _layout_len: u32) -> i32 {
             }
         };
 
+        let audio_pool = match kernel_endpoint.slot_pools().get(KERNEL_AUDIO_POOL).cloned() {
+            Some(pool) => pool,
+            None => {
+                console::error_1(&"worker_register_services: kernel audio pool missing".into());
+                return ERR_BAD_LAYOUT;
+            }
+        };
+
         let core_config = CoreConfig {
             lanes: NonZeroUsize::new(8).expect("lanes must be non-zero"),
             ..CoreConfig::default()
@@ -157,7 +173,7 @@ pub fn worker_register_services(_layout_ptr: u32, _layout_len: u32) -> i32 {
             .into(),
         );
 
-        let kernel_service = KernelService::new_with_frame_pool(64, frame_pool, core_config);
+        let kernel_service = KernelService::new_with_pools(64, frame_pool, audio_pool, core_config);
 
         runtime.register(FabricServiceEngine::new(
             kernel_endpoint,
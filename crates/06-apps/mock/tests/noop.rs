@@ -7,7 +7,7 @@ use services_audio::AudioService;
 use services_fs::FsService;
 use services_gpu::GpuService;
 use services_kernel::KernelService;
-use world::{AudioCmd, AudioSpan, FrameSpan, FsCmd, GpuCmd, KernelCmd};
+use world::{AudioCmd, AudioSpan, FrameSpan, FsCmd, FsRep, GpuCmd, KernelCmd};
 
 /// Confirms the mock service handles always accept input and yield empty drains.
 #[test]
@@ -28,6 +28,17 @@ fn noop_services_submit_and_drain() {
     };
     assert_eq!(fs.try_submit(&fs_cmd), SubmitOutcome::Accepted);
     assert!(!fs.drain(4).is_empty());
+    for (path, expected) in [("slot", Some(&b""[..])), ("missing", None)] {
+        let load = FsCmd::Load { path: path.into() };
+        assert_eq!(fs.try_submit(&load), SubmitOutcome::Accepted);
+        assert_eq!(
+            fs.drain(4).as_slice(),
+            &[FsRep::Loaded {
+                path: path.into(),
+                bytes: expected.map(Arc::from),
+            }]
+        );
+    }
 
     let gpu = GpuService::new_handle(16);
     let gpu_cmd = GpuCmd::UploadFrame {
@@ -41,6 +41,7 @@ fn record_script() -> Vec<String> {
         Intent::LoadRom {
             group: 0,
             bytes: Arc::clone(&rom),
+            save_ram: None,
         },
     );
     scheduler.run_once();
@@ -59,6 +60,7 @@ fn record_script() -> Vec<String> {
         &mut scheduler,
         Intent::DebugMem {
             group: 0,
+            lane: 0,
             space: MemSpace::Vram,
             base: 0x8000,
             len: 0x20,
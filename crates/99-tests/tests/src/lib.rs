@@ -37,6 +37,7 @@ mod tests {
             Intent::LoadRom {
                 group: 0,
                 bytes: Arc::clone(&rom_bytes),
+                save_ram: None,
             },
         );
         scheduler.run_once();
@@ -65,6 +66,7 @@ mod tests {
             Intent::LoadRom {
                 group: 0,
                 bytes: Arc::clone(&rom_bytes),
+                save_ram: None,
             },
         );
 
@@ -73,6 +75,7 @@ mod tests {
             Intent::LoadRom {
                 group: 0,
                 bytes: rom_bytes_b,
+                save_ram: None,
             },
         );
 
@@ -103,6 +106,7 @@ mod tests {
             Intent::LoadRom {
                 group: 0,
                 bytes: Arc::clone(&rom_bytes),
+                save_ram: None,
             },
         );
         scheduler.run_once();
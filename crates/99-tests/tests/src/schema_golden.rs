@@ -105,6 +105,21 @@ fn transport_schema_goldens_v1() {
         },
     );
 
+    assert_golden(
+        "fs_cmd_load_v1",
+        &FsCmdV1::Load(FsLoadCmdV1 {
+            key: "autosave".to_string(),
+        }),
+    );
+
+    assert_golden(
+        "fs_rep_loaded_v1",
+        &FsRepV1::Loaded(Box::new(FsLoadedRepV1 {
+            key: "autosave".to_string(),
+            payload: Some(vec![0xAA, 0xBB, 0xCC]),
+        })),
+    );
+
     assert_golden(
         "gpu_cmd_upload_frame_v1",
         &GpuCmdV1::UploadFrame {
@@ -131,6 +146,141 @@ fn transport_schema_goldens_v1() {
     assert_golden("audio_rep_underrun_v1", &AudioRepV1::Underrun);
 }
 
+#[test]
+fn transport_schema_goldens_v2() {
+    assert_golden(
+        "kernel_cmd_tick_v2",
+        &KernelCmdV2::Tick(KernelTickCmdV2 {
+            group: 3,
+            purpose: TickPurposeV1::Display,
+            budget: 32_768,
+        }),
+    );
+
+    assert_golden(
+        "kernel_cmd_load_rom_v2",
+        &KernelCmdV2::LoadRom(KernelLoadRomCmdV2 {
+            group: 3,
+            bytes: vec![0x00, 0x01, 0x02, 0x03, 0xFE, 0xFF],
+            save_ram: Some(vec![0xA0, 0xA1]),
+        }),
+    );
+
+    assert_golden(
+        "kernel_rep_tick_done_v2",
+        &KernelRepV2::TickDone {
+            group: 3,
+            lanes_mask: 0x0000_000F,
+            cycles_done: 70_224,
+        },
+    );
+
+    assert_golden(
+        "kernel_rep_lane_frame_v2",
+        &KernelRepV2::LaneFrame {
+            group: 3,
+            lane: 1,
+            frame_id: 0x0123_4567_89AB_CDEF,
+            span: SlotSpanV1 {
+                start_idx: 11,
+                count: 2,
+            },
+            pixels: vec![0x10, 0x20, 0x30, 0x40],
+        },
+    );
+
+    assert_golden(
+        "kernel_rep_rom_loaded_v2",
+        &KernelRepV2::RomLoaded {
+            group: 3,
+            bytes_len: 65_536,
+        },
+    );
+
+    assert_golden(
+        "kernel_rep_dropped_thumb_v2",
+        &KernelRepV2::DroppedThumb { group: 3, count: 5 },
+    );
+
+    assert_golden(
+        "kernel_cmd_load_rom_pooled_v2",
+        &KernelCmdV2::LoadRomPooled(KernelLoadRomPooledCmdV2 {
+            group: 3,
+            rom: BulkSpanV2 {
+                span: SlotSpanV1 {
+                    start_idx: 4,
+                    count: 3,
+                },
+                len: 600_000,
+            },
+            save_ram: Some(BulkSpanV2 {
+                span: SlotSpanV1 {
+                    start_idx: 7,
+                    count: 1,
+                },
+                len: 32_768,
+            }),
+        }),
+    );
+
+    assert_golden(
+        "kernel_rep_state_saved_pooled_v2",
+        &KernelRepV2::StateSavedPooled {
+            group: 3,
+            lane: 1,
+            bytes: BulkSpanV2 {
+                span: SlotSpanV1 {
+                    start_idx: 0,
+                    count: 1,
+                },
+                len: 70_000,
+            },
+        },
+    );
+
+    assert_golden(
+        "kernel_rep_forked_pooled_v2",
+        &KernelRepV2::ForkedPooled {
+            group: 4,
+            frames: 60,
+            lanes: vec![KernelForkedLanePooledV2 {
+                lane: 1,
+                state: BulkSpanV2 {
+                    span: SlotSpanV1 {
+                        start_idx: 2,
+                        count: 5,
+                    },
+                    len: 70_000,
+                },
+                thumb_width: 2,
+                thumb_height: 1,
+                thumbnail: vec![0x11; 8],
+            }],
+        },
+    );
+
+    let audio = AudioSlotSpanV2 {
+        span: SlotSpanV1 {
+            start_idx: 2,
+            count: 1,
+        },
+        frames: 800,
+        channels: 2,
+        sample_rate_hz: 48_000,
+    };
+    assert_golden(
+        "kernel_rep_audio_ready_pooled_v2",
+        &KernelRepV2::AudioReadyPooled {
+            group: 3,
+            audio: audio.clone(),
+        },
+    );
+    assert_golden(
+        "audio_cmd_submit_pooled_v2",
+        &AudioCmdV2::SubmitPooled { audio },
+    );
+}
+
 fn assert_golden<T>(stem: &str, value: &T)
 where
     T: Archive,
@@ -79,6 +79,60 @@ fn expected_expr(expected: &Expected) -> String {
     }
 }
 
+/// Model tags reference screenshot names may carry after the ROM stem.
+const MODEL_TAGS: &[&str] = &["dmg", "mgb", "sgb", "cgb", "agb"];
+
+/// Picks the upstream reference image for a screenshot ROM.
+///
+/// Bundles ship PNGs next to the ROM named after the ROM stem, optionally
+/// followed by a model tag (`dmg-acid2-dmg.png`, `m3_bgp_change_cgb_c.png`,
+/// ...). A tag naming the ROM's model wins, then the bare stem, then the
+/// first match. Pictures of other ROMs sharing the stem as a prefix
+/// (`m3_wx_4_change_sprites_dmg_blob.png` for `m3_wx_4_change`) never match.
+fn reference_screenshot(bundle_dir: &Path, rom: &RomEntry) -> Result<Option<String>> {
+    if rom.expected.kind != "screenshot" {
+        return Ok(None);
+    }
+    let rom_path = Path::new(&rom.path);
+    let (Some(dir), Some(stem)) = (rom_path.parent(), rom_path.file_stem()) else {
+        return Ok(None);
+    };
+    let stem = stem.to_string_lossy();
+    let mut candidates = Vec::new();
+    for entry in fs::read_dir(bundle_dir.join(dir))? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if let Some(tag) = model_tag(&stem, &name) {
+            candidates.push((tag, name));
+        }
+    }
+    candidates.sort();
+    let model = rom.model.to_ascii_lowercase();
+    let picked = candidates
+        .iter()
+        .find(|(tag, _)| tag.starts_with(&model))
+        .or_else(|| candidates.iter().find(|(tag, _)| tag.is_empty()))
+        .or_else(|| candidates.first());
+    Ok(picked.map(|(_, name)| dir.join(name).to_string_lossy().replace('\\', "/")))
+}
+
+/// Returns the lowercase model tag of screenshot `name` for ROM `stem`: empty
+/// for `<stem>.png`, `dmg_blob` for `<stem>_dmg_blob.png`, and `None` for
+/// names that are not a PNG of this ROM.
+fn model_tag(stem: &str, name: &str) -> Option<String> {
+    let lower = name.to_ascii_lowercase();
+    let rest = lower
+        .strip_prefix(&stem.to_ascii_lowercase())?
+        .strip_suffix(".png")?;
+    if rest.is_empty() {
+        return Some(String::new());
+    }
+    let tag = rest.strip_prefix(['_', '-'])?;
+    MODEL_TAGS
+        .iter()
+        .any(|model| tag.starts_with(model))
+        .then(|| tag.to_owned())
+}
+
 fn main() -> Result<()> {
     if env::var("GBX_SKIP_TESTROMS").is_ok() {
         println!("cargo:warning=Skipping test ROM vendoring (GBX_SKIP_TESTROMS set)");
@@ -171,9 +225,20 @@ pub(crate) static ROMS: &[RomMeta] = &[];
         }
         fs::write(&dest, &rom_bytes).with_context(|| format!("copying ROM bytes to {dest:?}"))?;
 
+        let reference = reference_screenshot(&bundle_dir, rom)?;
+        if let Some(reference) = &reference {
+            let source = bundle_dir.join(reference);
+            fs::copy(&source, rom_out_dir.join(reference))
+                .with_context(|| format!("copying reference screenshot {source:?}"))?;
+        }
+        let reference = match reference {
+            Some(path) => format!("Some({})", rust_string(&path)),
+            None => "None".to_owned(),
+        };
+
         writeln!(
             &mut generated,
-            "    RomMeta {{ suite: {}, name: {}, path: {}, model: RomModel::{}, kind: RomKind::{}, expected: {}, sha256: {}, size: {size}, embed: {} }},",
+            "    RomMeta {{ suite: {}, name: {}, path: {}, model: RomModel::{}, kind: RomKind::{}, expected: {}, reference: {reference}, sha256: {}, size: {size}, embed: {} }},",
             rust_string(&rom.suite),
             rust_string(&rom.name),
             rust_string(&rom.path),
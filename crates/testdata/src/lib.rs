@@ -71,6 +71,16 @@ pub fn bytes(path: &str) -> Arc<[u8]> {
     load_entry(idx)
 }
 
+/// Reads the PNG reference screenshot recorded for a ROM, if any.
+pub fn reference_png(path: &str) -> Option<Vec<u8>> {
+    let reference = metadata(path)?.reference?;
+    let file_path = DATA_DIR.join(reference);
+    Some(
+        std::fs::read(&file_path)
+            .unwrap_or_else(|err| panic!("failed to read screenshot {file_path:?}: {err}")),
+    )
+}
+
 fn load_entry(idx: usize) -> Arc<[u8]> {
     ENTRIES[idx]
         .cache
@@ -58,6 +58,8 @@ pub struct RomMeta {
     pub kind: RomKind,
     /// Expected success criteria.
     pub expected: Expected,
+    /// Normalized path of the upstream reference screenshot, if the bundle ships one.
+    pub reference: Option<&'static str>,
     /// SHA-256 digest of the ROM bytes.
     pub sha256: &'static str,
     /// Size of the ROM payload in bytes.
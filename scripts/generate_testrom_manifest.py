@@ -25,8 +25,17 @@ def iter_roms() -> Iterable[Path]:
             yield path
 
 
+def has_dmg_reference(path: Path) -> bool:
+    rom = BUNDLE_ROOT / path
+    return any(rom.parent.glob(f"{rom.stem}_dmg*.png"))
+
+
 def infer_model(path: Path) -> str:
     lower = str(path).lower()
+    # Mealybug pictures taken on a DMG are modelled exactly; the CGB ones
+    # depend on the boot ROM's DMG compatibility palettes.
+    if "mealybug" in lower and has_dmg_reference(path):
+        return "dmg"
     if any(token in lower for token in ("cgb", "mealybug", "color", "same-suite/cgb")):
         return "cgb"
     if "sgb" in lower: